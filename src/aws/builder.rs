@@ -26,7 +26,10 @@ use crate::aws::{
 };
 use crate::client::{http_connector, HttpConnector, TokenCredentialProvider};
 use crate::config::ConfigValue;
-use crate::{ClientConfigKey, ClientOptions, Result, RetryConfig, StaticCredentialProvider};
+use crate::{
+    ClientConfigKey, ClientOptions, CopyConfig, RangeCoalescePolicy, Result, RetryConfig,
+    StaticCredentialProvider,
+};
 use base64::prelude::BASE64_STANDARD;
 use base64::Engine;
 use itertools::Itertools;
@@ -138,6 +141,10 @@ pub struct AmazonS3Builder {
     url: Option<String>,
     /// Retry config
     retry_config: RetryConfig,
+    /// THIS FIELD IS ARAS ONLY
+    ///
+    /// Configuration for copies of large objects
+    copy_config: CopyConfig,
     /// When set to true, fallback to IMDSv1
     imdsv1_fallback: ConfigValue<bool>,
     /// When set to true, virtual hosted style request has to be used
@@ -773,6 +780,14 @@ impl AmazonS3Builder {
         self
     }
 
+    /// THIS METHOD IS ARAS ONLY
+    ///
+    /// Set the configuration for server-side copies of large objects
+    pub fn with_copy_config(mut self, copy_config: CopyConfig) -> Self {
+        self.copy_config = copy_config;
+        self
+    }
+
     /// By default instance credentials will only be fetched over [IMDSv2], as AWS recommends
     /// against having IMDSv1 enabled on EC2 instances as it is vulnerable to [SSRF attack]
     ///
@@ -826,6 +841,16 @@ impl AmazonS3Builder {
         self
     }
 
+    /// THIS METHOD IS ARAS ONLY
+    ///
+    /// Sets the [`RangeCoalescePolicy`] used by [`ObjectStore::get_ranges`]
+    ///
+    /// [`ObjectStore::get_ranges`]: crate::ObjectStore::get_ranges
+    pub fn with_range_coalesce_policy(mut self, policy: RangeCoalescePolicy) -> Self {
+        self.client_options = self.client_options.with_range_coalesce_policy(policy);
+        self
+    }
+
     /// Set the proxy_url to be used by the underlying client
     pub fn with_proxy_url(mut self, proxy_url: impl Into<String>) -> Self {
         self.client_options = self.client_options.with_proxy_url(proxy_url);
@@ -1084,6 +1109,7 @@ impl AmazonS3Builder {
             credentials,
             session_provider,
             retry_config: self.retry_config,
+            copy_config: self.copy_config,
             client_options: self.client_options,
             sign_payload: !self.unsigned_payload.get()?,
             skip_signature: self.skip_signature.get()?,
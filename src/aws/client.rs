@@ -26,18 +26,22 @@ use crate::client::builder::{HttpRequestBuilder, RequestBuilderError};
 use crate::client::get::GetClient;
 use crate::client::header::{get_etag, HeaderConfig};
 use crate::client::header::{get_put_result, get_version};
-use crate::client::list::ListClient;
+use crate::client::list::{ListClient, ListMultipartClient, ListVersionsClient};
 use crate::client::retry::{RetryContext, RetryExt};
 use crate::client::s3::{
     CompleteMultipartUpload, CompleteMultipartUploadResult, CopyPartResult,
-    InitiateMultipartUploadResult, ListResponse, PartMetadata,
+    InitiateMultipartUploadResult, ListMultipartUploadsResponse, ListPartsResponse, ListResponse,
+    ListVersionsResponse, PartMetadata,
 };
 use crate::client::{GetOptionsExt, HttpClient, HttpError, HttpResponse};
 use crate::list::{PaginatedListOptions, PaginatedListResult};
-use crate::multipart::PartId;
+use crate::multipart::{MultipartUploadInfo, PartId, PartInfo};
+use crate::tags::Tagging;
+use crate::util::STRICT_ENCODE_SET;
+use crate::version::ObjectVersion;
 use crate::{
-    Attribute, Attributes, ClientOptions, GetOptions, MultipartId, Path, PutMultipartOpts,
-    PutPayload, PutResult, Result, RetryConfig, TagSet,
+    Attribute, Attributes, ClientOptions, CopyConfig, GetOptions, MultipartId, Path,
+    PutMultipartOpts, PutPayload, PutResult, Result, RetryConfig, TagSet,
 };
 use async_trait::async_trait;
 use base64::prelude::BASE64_STANDARD;
@@ -55,6 +59,7 @@ use quick_xml::events::{self as xml_events};
 use ring::digest;
 use ring::digest::Context;
 use serde::{Deserialize, Serialize};
+use std::ops::Range;
 use std::sync::Arc;
 
 const VERSION_HEADER: &str = "x-amz-version-id";
@@ -120,6 +125,18 @@ pub(crate) enum Error {
     Metadata {
         source: crate::client::header::Error,
     },
+
+    /// THIS VARIANT IS ARAS ONLY
+    #[error("Error getting object tagging response body: {}", source)]
+    TaggingResponseBody { source: HttpError },
+
+    /// THIS VARIANT IS ARAS ONLY
+    #[error("Got invalid object tagging response: {}", source)]
+    InvalidTaggingResponse { source: quick_xml::de::DeError },
+
+    /// THIS VARIANT IS ARAS ONLY
+    #[error("Got invalid object tagging request: {}", source)]
+    InvalidTaggingRequest { source: quick_xml::se::SeError },
 }
 
 impl From<Error> for crate::Error {
@@ -137,6 +154,10 @@ impl From<Error> for crate::Error {
 pub(crate) enum PutPartPayload<'a> {
     Part(PutPayload),
     Copy(&'a Path),
+    /// THIS VARIANT IS ARAS ONLY
+    ///
+    /// Copy the given byte range of an existing object
+    CopyRange(&'a Path, Range<u64>),
 }
 
 impl Default for PutPartPayload<'_> {
@@ -197,6 +218,8 @@ pub(crate) struct S3Config {
     pub credentials: AwsCredentialProvider,
     pub session_provider: Option<AwsCredentialProvider>,
     pub retry_config: RetryConfig,
+    /// THIS FIELD IS ARAS ONLY
+    pub copy_config: CopyConfig,
     pub client_options: ClientOptions,
     pub sign_payload: bool,
     pub skip_signature: bool,
@@ -627,6 +650,25 @@ impl S3Client {
             .with_session_creds(false)
     }
 
+    /// THIS METHOD IS ARAS ONLY
+    ///
+    /// Make an S3 Copy request for the specified version of `from`
+    pub(crate) fn copy_version_request<'a>(
+        &'a self,
+        from: &Path,
+        version: &str,
+        to: &'a Path,
+    ) -> Request<'a> {
+        let version = utf8_percent_encode(version, &STRICT_ENCODE_SET);
+        let source = format!(
+            "{}/{}?versionId={version}",
+            self.config.bucket,
+            encode_path(from)
+        );
+        self.copy_request(from, to)
+            .header(&COPY_SOURCE_HEADER, &source)
+    }
+
     /// THIS METHOD IS COMMON, MODIFIED BY ARAS
     pub(crate) async fn create_multipart(
         &self,
@@ -637,7 +679,7 @@ impl S3Client {
             tags,
             attributes,
             extensions,
-            copy_and_append: _copy_and_append,
+            mode: _,
         } = opts;
 
         let mut request = self.request(Method::POST, location);
@@ -675,7 +717,10 @@ impl S3Client {
         part_idx: usize,
         data: PutPartPayload<'_>,
     ) -> Result<PartId> {
-        let is_copy = matches!(data, PutPartPayload::Copy(_));
+        let is_copy = matches!(
+            data,
+            PutPartPayload::Copy(_) | PutPartPayload::CopyRange(_, _)
+        );
         let part = (part_idx + 1).to_string();
 
         let mut request = self
@@ -689,6 +734,15 @@ impl S3Client {
                 "x-amz-copy-source",
                 &format!("{}/{}", self.config.bucket, encode_path(path)),
             ),
+            PutPartPayload::CopyRange(path, range) => request
+                .header(
+                    "x-amz-copy-source",
+                    &format!("{}/{}", self.config.bucket, encode_path(path)),
+                )
+                .header(
+                    "x-amz-copy-source-range",
+                    &format!("bytes={}-{}", range.start, range.end - 1),
+                ),
         };
 
         if self
@@ -733,6 +787,57 @@ impl S3Client {
         Ok(PartId { content_id })
     }
 
+    /// THIS METHOD IS ARAS ONLY
+    ///
+    /// Make an S3 ListParts request, fetching all pages <https://docs.aws.amazon.com/AmazonS3/latest/API/API_ListParts.html>
+    pub(crate) async fn list_parts(
+        &self,
+        location: &Path,
+        upload_id: &MultipartId,
+    ) -> Result<Vec<PartInfo>> {
+        let mut parts = Vec::new();
+        let mut marker: Option<String> = None;
+        loop {
+            let mut query = vec![("uploadId", upload_id.as_str())];
+            if let Some(marker) = &marker {
+                query.push(("part-number-marker", marker.as_str()));
+            }
+
+            let mut request = self
+                .request(Method::GET, location)
+                .query(&query)
+                .idempotent(true);
+            if self
+                .config
+                .encryption_headers
+                .0
+                .contains_key("x-amz-server-side-encryption-customer-algorithm")
+            {
+                // Parts of uploads encrypted with SSE-C can only be listed with the key
+                request = request.with_encryption_headers();
+            }
+
+            let response = request
+                .send()
+                .await?
+                .into_body()
+                .bytes()
+                .await
+                .map_err(|source| Error::ListResponseBody { source })?;
+
+            let response: ListPartsResponse = quick_xml::de::from_reader(response.reader())
+                .map_err(|source| Error::InvalidListResponse { source })?;
+
+            parts.extend(response.part.into_iter().map(PartInfo::from));
+            match response.next_part_number_marker {
+                Some(next) if response.is_truncated => marker = Some(next),
+                _ => break,
+            }
+        }
+        parts.sort_by_key(|part| part.part_idx);
+        Ok(parts)
+    }
+
     pub(crate) async fn abort_multipart(&self, location: &Path, upload_id: &str) -> Result<()> {
         self.request(Method::DELETE, location)
             .query(&[("uploadId", upload_id)])
@@ -812,8 +917,10 @@ impl S3Client {
         })
     }
 
-    #[cfg(test)]
-    pub(crate) async fn get_object_tagging(&self, path: &Path) -> Result<HttpResponse> {
+    /// THIS METHOD IS COMMON, MODIFIED BY ARAS
+    ///
+    /// Make an S3 GetObjectTagging request <https://docs.aws.amazon.com/AmazonS3/latest/API/API_GetObjectTagging.html>
+    pub(crate) async fn get_object_tagging(&self, path: &Path) -> Result<TagSet> {
         let credential = self.config.get_session_credential().await?;
         let url = format!("{}?tagging", self.config.path_url(path));
         let response = self
@@ -823,7 +930,73 @@ impl S3Client {
             .send_retry(&self.config.retry_config)
             .await
             .map_err(|e| e.error(STORE, path.to_string()))?;
-        Ok(response)
+
+        let data = response
+            .into_body()
+            .bytes()
+            .await
+            .map_err(|source| Error::TaggingResponseBody { source })?;
+
+        let tagging: Tagging = quick_xml::de::from_reader(data.reader())
+            .map_err(|source| Error::InvalidTaggingResponse { source })?;
+        Ok(tagging.into())
+    }
+
+    /// THIS METHOD IS ARAS ONLY
+    ///
+    /// Make an S3 PutObjectTagging request <https://docs.aws.amazon.com/AmazonS3/latest/API/API_PutObjectTagging.html>
+    ///
+    /// Does nothing if tagging is disabled
+    pub(crate) async fn put_object_tagging(&self, path: &Path, tags: &TagSet) -> Result<()> {
+        if self.config.disable_tagging {
+            return Ok(());
+        }
+
+        let credential = self.config.get_session_credential().await?;
+        let url = format!("{}?tagging", self.config.path_url(path));
+        let body = quick_xml::se::to_string(&Tagging::from(tags))
+            .map_err(|source| Error::InvalidTaggingRequest { source })?;
+        let body = Bytes::from(body);
+
+        let mut builder = self.client.request(Method::PUT, url);
+
+        let digest = digest::digest(&digest::SHA256, &body);
+        builder = builder.header(SHA256_CHECKSUM, BASE64_STANDARD.encode(digest));
+
+        // PutObjectTagging requires an integrity check, like DeleteObjects
+        let mut hasher = Md5::new();
+        hasher.update(&body);
+        builder = builder.header("Content-MD5", BASE64_STANDARD.encode(hasher.finalize()));
+
+        builder
+            .header(CONTENT_TYPE, "application/xml")
+            .body(body)
+            .with_aws_sigv4(credential.authorizer(), Some(digest.as_ref()))
+            .send_retry(&self.config.retry_config)
+            .await
+            .map_err(|e| e.error(STORE, path.to_string()))?;
+        Ok(())
+    }
+
+    /// THIS METHOD IS ARAS ONLY
+    ///
+    /// Make an S3 DeleteObjectTagging request <https://docs.aws.amazon.com/AmazonS3/latest/API/API_DeleteObjectTagging.html>
+    ///
+    /// Does nothing if tagging is disabled
+    pub(crate) async fn delete_object_tagging(&self, path: &Path) -> Result<()> {
+        if self.config.disable_tagging {
+            return Ok(());
+        }
+
+        let credential = self.config.get_session_credential().await?;
+        let url = format!("{}?tagging", self.config.path_url(path));
+        self.client
+            .request(Method::DELETE, url)
+            .with_aws_sigv4(credential.authorizer(), None)
+            .send_retry(&self.config.retry_config)
+            .await
+            .map_err(|e| e.error(STORE, path.to_string()))?;
+        Ok(())
     }
 }
 
@@ -945,6 +1118,116 @@ impl ListClient for Arc<S3Client> {
     }
 }
 
+/// THIS IMPL IS ARAS ONLY
+#[async_trait]
+impl ListVersionsClient for Arc<S3Client> {
+    /// Make an S3 ListObjectVersions request <https://docs.aws.amazon.com/AmazonS3/latest/API/API_ListObjectVersions.html>
+    ///
+    /// The page token encodes both the version id marker and the key marker
+    async fn list_versions_request(
+        &self,
+        prefix: Option<&str>,
+        page_token: Option<String>,
+    ) -> Result<(Vec<ObjectVersion>, Option<String>)> {
+        let credential = self.config.get_session_credential().await?;
+        let url = self.config.bucket_endpoint.clone();
+
+        let mut query = Vec::with_capacity(4);
+        query.push(("versions", ""));
+
+        if let Some(prefix) = prefix {
+            query.push(("prefix", prefix))
+        }
+
+        if let Some((version_id_marker, key_marker)) =
+            page_token.as_deref().and_then(|x| x.split_once('\n'))
+        {
+            query.push(("key-marker", key_marker));
+            if !version_id_marker.is_empty() {
+                query.push(("version-id-marker", version_id_marker));
+            }
+        }
+
+        let response = self
+            .client
+            .request(Method::GET, &url)
+            .query(&query)
+            .with_aws_sigv4(credential.authorizer(), None)
+            .send_retry(&self.config.retry_config)
+            .await
+            .map_err(|source| Error::ListRequest { source })?
+            .into_body()
+            .bytes()
+            .await
+            .map_err(|source| Error::ListResponseBody { source })?;
+
+        let mut response: ListVersionsResponse = quick_xml::de::from_reader(response.reader())
+            .map_err(|source| Error::InvalidListResponse { source })?;
+
+        let token = match (
+            response.next_key_marker.take(),
+            response.next_version_id_marker.take(),
+        ) {
+            (Some(key), Some(version)) => Some(format!("{version}\n{key}")),
+            (Some(key), None) => Some(format!("\n{key}")),
+            _ => None,
+        };
+        Ok((response.try_into()?, token))
+    }
+}
+
+/// THIS IMPL IS ARAS ONLY
+#[async_trait]
+impl ListMultipartClient for Arc<S3Client> {
+    /// Make an S3 ListMultipartUploads request <https://docs.aws.amazon.com/AmazonS3/latest/API/API_ListMultipartUploads.html>
+    ///
+    /// The page token encodes both the upload id marker and the key marker
+    async fn list_multipart_uploads_request(
+        &self,
+        prefix: Option<&str>,
+        page_token: Option<String>,
+    ) -> Result<(Vec<MultipartUploadInfo>, Option<String>)> {
+        let credential = self.config.get_session_credential().await?;
+        let url = self.config.bucket_endpoint.clone();
+
+        let mut query = Vec::with_capacity(4);
+        query.push(("uploads", ""));
+
+        if let Some(prefix) = prefix {
+            query.push(("prefix", prefix))
+        }
+
+        if let Some((upload_id_marker, key_marker)) =
+            page_token.as_deref().and_then(|x| x.split_once('\n'))
+        {
+            query.push(("key-marker", key_marker));
+            if !upload_id_marker.is_empty() {
+                query.push(("upload-id-marker", upload_id_marker));
+            }
+        }
+
+        let response = self
+            .client
+            .request(Method::GET, &url)
+            .query(&query)
+            .with_aws_sigv4(credential.authorizer(), None)
+            .send_retry(&self.config.retry_config)
+            .await
+            .map_err(|source| Error::ListRequest { source })?
+            .into_body()
+            .bytes()
+            .await
+            .map_err(|source| Error::ListResponseBody { source })?;
+
+        let mut response: ListMultipartUploadsResponse =
+            quick_xml::de::from_reader(response.reader())
+                .map_err(|source| Error::InvalidListResponse { source })?;
+
+        let token = response.page_token();
+        Ok((response.try_into()?, token))
+    }
+}
+
 fn encode_path(path: &Path) -> PercentEncode<'_> {
     utf8_percent_encode(path.as_ref(), &STRICT_PATH_ENCODE_SET)
 }
@@ -26,31 +26,65 @@
 //! these unneeded parts, however, it is recommended that you consider implementing
 //! [automatic cleanup] of unused parts that are older than some threshold.
 //!
+//! Alternatively, incomplete uploads can be enumerated with
+//! [`MultipartStore::list_multipart_uploads`] and those older than some threshold
+//! aborted with [`abort_stale_multipart_uploads`].
+//!
+//! [`abort_stale_multipart_uploads`]: crate::multipart::abort_stale_multipart_uploads
 //! [automatic cleanup]: https://aws.amazon.com/blogs/aws/s3-lifecycle-management-update-support-for-multipart-uploads-and-delete-markers/
 
 use async_trait::async_trait;
+use bytes::Bytes;
 use futures::stream::BoxStream;
 use futures::{StreamExt, TryStreamExt};
 use reqwest::header::{HeaderName, IF_MATCH, IF_NONE_MATCH};
 use reqwest::{Method, StatusCode};
+use std::ops::Range;
 use std::{sync::Arc, time::Duration};
 use url::Url;
 
 use crate::aws::client::{CompleteMultipartMode, PutPartPayload, RequestError, S3Client};
 use crate::client::get::GetClientExt;
-use crate::client::list::{ListClient, ListClientExt};
+use crate::client::list::{
+    ListClient, ListClientExt, ListMultipartClientExt, ListVersionsClientExt,
+};
 use crate::client::CredentialProvider;
-use crate::multipart::{MultipartStore, PartId};
+use crate::multipart::{MultipartStore, MultipartUploadInfo, PartId, PartInfo};
 use crate::signer::Signer;
+use crate::upload::AppendUpload;
 use crate::util::STRICT_ENCODE_SET;
+use crate::version::{ObjectVersion, VersionedStore};
 use crate::{
-    Error, GetOptions, GetResult, ListResult, MultipartId, MultipartUpload, ObjectMeta,
-    ObjectStore, Path, PutMode, PutMultipartOpts, PutOptions, PutPayload, PutResult, Result,
-    UploadPart,
+    Attributes, DeleteOptions, Error, GetOptions, GetResult, ListResult, MultipartId,
+    MultipartUpload, ObjectMeta, ObjectStore, Path, PutMode, PutMultipartOpts, PutOptions,
+    PutPayload, PutPayloadMut, PutResult, Result, TagSet, TaggingStore, UploadPart,
 };
 
+/// THIS CONSTANT IS ARAS ONLY
+///
+/// The minimum size of every part of a multipart upload but the last
+const MIN_PART_SIZE: u64 = 5 * 1024 * 1024;
+
+/// THIS CONSTANT IS ARAS ONLY
+///
+/// The maximum size of a part of a multipart upload
+const MAX_PART_SIZE: u64 = 5 * 1024 * 1024 * 1024;
+
+/// THIS CONSTANT IS ARAS ONLY
+///
+/// The maximum number of parts of a multipart upload
+const MAX_PARTS: u64 = 10_000;
+
+/// THIS CONSTANT IS ARAS ONLY
+///
+/// The message of the error returned when copying a source larger than 5 GiB
+/// with a single request
+const COPY_SOURCE_TOO_LARGE: &str = "copy source is larger than the maximum allowable size";
+
 static TAGS_HEADER: HeaderName = HeaderName::from_static("x-amz-tagging");
 static COPY_SOURCE_HEADER: HeaderName = HeaderName::from_static("x-amz-copy-source");
+/// THIS STATIC IS ARAS ONLY
+static WRITE_OFFSET_HEADER: HeaderName = HeaderName::from_static("x-amz-write-offset-bytes");
 
 mod builder;
 mod checksum;
@@ -101,6 +135,131 @@ impl AmazonS3 {
     fn path_url(&self, path: &Path) -> String {
         self.client.config.path_url(path)
     }
+
+    /// THIS METHOD IS ARAS ONLY
+    ///
+    /// Returns true if the size of copy sources must be determined before copying, as
+    /// [`CopyConfig::multipart_threshold`] is below the size copied with a single request.
+    /// Otherwise sources are only copied in ranges once a single request was rejected
+    ///
+    /// [`CopyConfig::multipart_threshold`]: crate::CopyConfig::multipart_threshold
+    fn probe_copy_source(&self) -> bool {
+        self.client.config.copy_config.probe_size()
+    }
+
+    /// THIS METHOD IS ARAS ONLY
+    ///
+    /// Returns the attributes, tags and size of the object at `from` if it is large
+    /// enough to be copied with [`Self::copy_multipart`], or `None` otherwise
+    async fn multipart_copy_source(&self, from: &Path) -> Result<Option<MultipartCopySource>> {
+        let config = &self.client.config.copy_config;
+        if config.multipart_threshold == u64::MAX {
+            return Ok(None);
+        }
+
+        let options = GetOptions {
+            head: true,
+            ..Default::default()
+        };
+        let result = self.get_opts(from, options).await?;
+        if !config.is_multipart(result.meta.size) {
+            return Ok(None);
+        }
+
+        let tags = match self.client.config.disable_tagging {
+            true => TagSet::default(),
+            false => self.client.get_object_tagging(from).await?,
+        };
+        Ok(Some(MultipartCopySource {
+            size: result.meta.size,
+            attributes: result.attributes,
+            tags,
+        }))
+    }
+
+    /// THIS METHOD IS ARAS ONLY
+    ///
+    /// Copies `from` to `to` as a multipart upload of ranges copied with `UploadPartCopy`,
+    /// retaining the attributes and tags of the source
+    async fn copy_multipart(
+        &self,
+        source: MultipartCopySource,
+        from: &Path,
+        to: &Path,
+        mode: CompleteMultipartMode,
+    ) -> Result<PutResult> {
+        let config = &self.client.config.copy_config;
+        let opts = PutMultipartOpts {
+            tags: source.tags,
+            attributes: source.attributes,
+            ..Default::default()
+        };
+        let upload_id = self.client.create_multipart(to, opts).await?;
+
+        let ranges = config.part_ranges(source.size, MIN_PART_SIZE, MAX_PART_SIZE, MAX_PARTS);
+        let res = async {
+            let parts = futures::stream::iter(ranges.into_iter().enumerate())
+                .map(|(idx, range)| {
+                    let part = PutPartPayload::CopyRange(from, range);
+                    self.client.put_part(to, &upload_id, idx, part)
+                })
+                .buffered(config.max_concurrency.max(1))
+                .try_collect()
+                .await?;
+            self.client
+                .complete_multipart(to, &upload_id, parts, mode)
+                .await
+        }
+        .await;
+
+        // If the multipart upload failed, make a best effort attempt to clean it up
+        if res.is_err() {
+            let _ = self.client.abort_multipart(to, &upload_id).await;
+        }
+        res
+    }
+}
+
+/// THIS FUNCTION IS ARAS ONLY
+///
+/// Returns true if `e` is the rejection of a copy source larger than 5 GiB, the maximum
+/// size copied with a single `CopyObject` or `UploadPartCopy`
+fn is_copy_source_too_large(e: &Error) -> bool {
+    let source = match e {
+        Error::Generic { source, .. } => source,
+        _ => return false,
+    };
+    match source.downcast_ref::<crate::client::retry::RetryError>() {
+        Some(e) => {
+            e.status() == Some(StatusCode::BAD_REQUEST)
+                && e.body()
+                    .map(|b| b.contains(COPY_SOURCE_TOO_LARGE))
+                    .unwrap_or(false)
+        }
+        None => false,
+    }
+}
+
+/// THIS FUNCTION IS ARAS ONLY
+///
+/// Maps the failure of a conditional copy to [`Error::AlreadyExists`]
+fn copy_if_not_exists_error(to: &Path, e: Error) -> Error {
+    match e {
+        Error::Precondition { .. } => Error::AlreadyExists {
+            path: to.to_string(),
+            source: Box::new(e),
+        },
+        e => e,
+    }
+}
+
+/// THIS STRUCT IS ARAS ONLY
+///
+/// The source of a copy performed with [`AmazonS3::copy_multipart`]
+struct MultipartCopySource {
+    size: u64,
+    attributes: Attributes,
+    tags: TagSet,
 }
 
 #[async_trait]
@@ -168,9 +327,20 @@ impl ObjectStore for AmazonS3 {
             tags,
             attributes,
             extensions,
-            copy_and_append: _copy_and_append,
         } = opts;
 
+        // S3 Express One Zone requires the current size of the object as the write offset
+        let append_offset = match mode {
+            PutMode::Append if self.client.config.is_s3_express() => {
+                match self.head(location).await {
+                    Ok(meta) => Some(meta.size),
+                    Err(Error::NotFound { .. }) => None,
+                    Err(e) => return Err(e),
+                }
+            }
+            _ => None,
+        };
+
         let request = self
             .client
             .request(Method::PUT, location)
@@ -227,14 +397,43 @@ impl ObjectStore for AmazonS3 {
                     S3ConditionalPut::Disabled => Err(Error::NotImplemented),
                 }
             }
+            (PutMode::Append, _) if self.client.config.is_s3_express() => match append_offset {
+                Some(offset) => {
+                    let offset = offset.to_string();
+                    request.header(&WRITE_OFFSET_HEADER, &offset).do_put().await
+                }
+                None => request.header(&IF_NONE_MATCH, "*").do_put().await,
+            },
+            (PutMode::Append, _) => Err(Error::NotSupported {
+                source: "S3 only supports appends to S3 Express One Zone buckets"
+                    .to_string()
+                    .into(),
+            }),
         }
     }
 
+    /// THIS METHOD IS COMMON, MODIFIED BY ARAS
     async fn put_multipart_opts(
         &self,
         location: &Path,
         opts: PutMultipartOpts,
     ) -> Result<Box<dyn MultipartUpload>> {
+        match opts.mode {
+            PutMode::Overwrite => {}
+            PutMode::Append if !self.client.config.is_s3_express() => {
+                return Err(Error::NotSupported {
+                    source: "S3 only supports appends to S3 Express One Zone buckets"
+                        .to_string()
+                        .into(),
+                })
+            }
+            PutMode::Append => {
+                let store = Arc::new(self.clone());
+                return Ok(Box::new(AppendUpload::new(store, location.clone(), opts)));
+            }
+            PutMode::Create | PutMode::Update(_) => return Err(Error::NotImplemented),
+        }
+
         let upload_id = self.client.create_multipart(location, opts).await?;
 
         Ok(Box::new(S3MultiPartUpload {
@@ -252,11 +451,38 @@ impl ObjectStore for AmazonS3 {
         self.client.get_opts(location, options).await
     }
 
+    /// THIS METHOD IS ARAS ONLY
+    async fn get_ranges(&self, location: &Path, ranges: &[Range<u64>]) -> Result<Vec<Bytes>> {
+        let policy = self.client.config.client_options.range_coalesce_policy();
+        self.get_ranges_opts(location, ranges, policy).await
+    }
+
     async fn delete(&self, location: &Path) -> Result<()> {
         self.client.request(Method::DELETE, location).send().await?;
         Ok(())
     }
 
+    /// THIS METHOD IS ARAS ONLY
+    ///
+    /// S3 supports conditional deletes via `If-Match`, and therefore requires `if_match`,
+    /// returning [`Error::NotImplemented`] if only `version` is provided
+    async fn delete_opts(&self, location: &Path, opts: DeleteOptions) -> Result<()> {
+        let etag = match (opts.if_match, opts.version) {
+            (None, None) => return self.delete(location).await,
+            (Some(etag), _) => etag,
+            (None, Some(_)) => return Err(Error::NotImplemented),
+        };
+
+        let request = self.client.request(Method::DELETE, location);
+        let result = request.header(&IF_MATCH, etag.as_str()).send().await;
+        match result.map_err(Error::from) {
+            // Real S3 reports NotFound rather than PreconditionFailed when the
+            // object doesn't exist, convert for consistency with conditional puts
+            Err(Error::NotFound { path, source }) => Err(Error::Precondition { path, source }),
+            r => r.map(|_| ()),
+        }
+    }
+
     fn delete_stream<'a>(
         &'a self,
         locations: BoxStream<'a, Result<Path>>,
@@ -303,13 +529,33 @@ impl ObjectStore for AmazonS3 {
         self.client.list_with_delimiter(prefix).await
     }
 
+    /// THIS METHOD IS COMMON, MODIFIED BY ARAS
     async fn copy(&self, from: &Path, to: &Path) -> Result<()> {
-        self.client
+        let mode = CompleteMultipartMode::Overwrite;
+        if self.probe_copy_source() {
+            if let Some(source) = self.multipart_copy_source(from).await? {
+                self.copy_multipart(source, from, to, mode).await?;
+                return Ok(());
+            }
+        }
+
+        let result = self
+            .client
             .copy_request(from, to)
             .idempotent(true)
             .send()
-            .await?;
-        Ok(())
+            .await;
+        match result.map_err(Error::from) {
+            Ok(_) => Ok(()),
+            Err(e) if is_copy_source_too_large(&e) => {
+                match self.multipart_copy_source(from).await? {
+                    Some(source) => self.copy_multipart(source, from, to, mode).await?,
+                    None => return Err(e),
+                };
+                Ok(())
+            }
+            Err(e) => Err(e),
+        }
     }
 
     async fn copy_if_not_exists(&self, from: &Path, to: &Path) -> Result<()> {
@@ -317,6 +563,16 @@ impl ObjectStore for AmazonS3 {
             Some(S3CopyIfNotExists::Header(k, v)) => (k, v, StatusCode::PRECONDITION_FAILED),
             Some(S3CopyIfNotExists::HeaderWithStatus(k, v, status)) => (k, v, *status),
             Some(S3CopyIfNotExists::Multipart) => {
+                let mode = CompleteMultipartMode::Create;
+                if self.probe_copy_source() {
+                    if let Some(source) = self.multipart_copy_source(from).await? {
+                        return match self.copy_multipart(source, from, to, mode).await {
+                            Ok(_) => Ok(()),
+                            Err(e) => Err(copy_if_not_exists_error(to, e)),
+                        };
+                    }
+                }
+
                 let upload_id = self
                     .client
                     .create_multipart(to, PutMultipartOpts::default())
@@ -355,7 +611,19 @@ impl ObjectStore for AmazonS3 {
                     let _ = self.client.abort_multipart(to, &upload_id).await;
                 }
 
-                return res;
+                return match res {
+                    Err(e) if is_copy_source_too_large(&e) => {
+                        match self.multipart_copy_source(from).await? {
+                            Some(source) => match self.copy_multipart(source, from, to, mode).await
+                            {
+                                Ok(_) => Ok(()),
+                                Err(e) => Err(copy_if_not_exists_error(to, e)),
+                            },
+                            None => Err(e),
+                        }
+                    }
+                    res => res,
+                };
             }
             None => {
                 return Err(Error::NotSupported {
@@ -376,6 +644,120 @@ impl ObjectStore for AmazonS3 {
             Ok(_) => Ok(()),
         }
     }
+
+    /// THIS METHOD IS ARAS ONLY
+    ///
+    /// Composes the sources into a multipart upload, copying sources of at least
+    /// 5 MiB with `UploadPartCopy` and buffering smaller sources into regular parts,
+    /// as every part but the last must be at least 5 MiB. Sources larger than the
+    /// 5 GiB maximum part size are copied in ranges
+    async fn compose(&self, sources: &[Path], dest: &Path) -> Result<PutResult> {
+        // A multipart upload cannot be completed without any parts
+        if sources.is_empty() {
+            return self.put(dest, PutPayload::default()).await;
+        }
+
+        let heads = futures::future::try_join_all(sources.iter().map(|s| self.head(s))).await?;
+        let sizes = heads.into_iter().map(|m| m.size);
+
+        let upload_id = self
+            .client
+            .create_multipart(dest, PutMultipartOpts::default())
+            .await?;
+
+        let res = async {
+            let mut parts = vec![];
+            let mut buffer = PutPayloadMut::new();
+            for (source, size) in sources.iter().zip(sizes) {
+                // Empty sources contribute nothing, and would be rejected as a part
+                if size == 0 {
+                    continue;
+                }
+
+                let mut start = 0;
+                if buffer.content_length() > 0 || size < MIN_PART_SIZE {
+                    // Top up the buffer to MIN_PART_SIZE, taking the whole source if the
+                    // remainder would be too small to be copied as a part of its own
+                    let end = match size < MIN_PART_SIZE {
+                        true => size,
+                        false => {
+                            let end = MIN_PART_SIZE - buffer.content_length() as u64;
+                            match size - end < MIN_PART_SIZE {
+                                true => size,
+                                false => end,
+                            }
+                        }
+                    };
+                    if end > 0 {
+                        buffer.push(self.get_range(source, 0..end).await?);
+                    }
+                    start = end;
+
+                    if buffer.content_length() as u64 >= MIN_PART_SIZE {
+                        let payload = std::mem::take(&mut buffer).freeze();
+                        let part = PutPartPayload::Part(payload);
+                        parts.push(
+                            self.client
+                                .put_part(dest, &upload_id, parts.len(), part)
+                                .await?,
+                        );
+                    }
+                }
+
+                // The whole source was buffered
+                if start >= size {
+                    continue;
+                }
+
+                if start == 0 && size <= MAX_PART_SIZE {
+                    let part = PutPartPayload::Copy(source);
+                    parts.push(
+                        self.client
+                            .put_part(dest, &upload_id, parts.len(), part)
+                            .await?,
+                    );
+                    continue;
+                }
+
+                // Split the remainder into equal ranges of at most MAX_PART_SIZE, each
+                // of which is at least MIN_PART_SIZE as the remainder is
+                let remaining = size - start;
+                let count = (remaining + MAX_PART_SIZE - 1) / MAX_PART_SIZE;
+                let part_size = (remaining + count - 1) / count;
+                while start < size {
+                    let end = size.min(start + part_size);
+                    let part = PutPartPayload::CopyRange(source, start..end);
+                    parts.push(
+                        self.client
+                            .put_part(dest, &upload_id, parts.len(), part)
+                            .await?,
+                    );
+                    start = end;
+                }
+            }
+
+            // The last part may be smaller than MIN_PART_SIZE, or empty if all sources are
+            if buffer.content_length() > 0 || parts.is_empty() {
+                let part = PutPartPayload::Part(buffer.freeze());
+                parts.push(
+                    self.client
+                        .put_part(dest, &upload_id, parts.len(), part)
+                        .await?,
+                );
+            }
+
+            self.client
+                .complete_multipart(dest, &upload_id, parts, CompleteMultipartMode::Overwrite)
+                .await
+        }
+        .await;
+
+        // If the multipart upload failed, make a best effort attempt to clean it up
+        if res.is_err() {
+            let _ = self.client.abort_multipart(dest, &upload_id).await;
+        }
+        res
+    }
 }
 
 #[derive(Debug)]
@@ -440,6 +822,51 @@ impl MultipartUpload for S3MultiPartUpload {
     }
 }
 
+/// THIS IMPL IS ARAS ONLY
+#[async_trait]
+impl VersionedStore for AmazonS3 {
+    fn list_versions(&self, prefix: Option<&Path>) -> BoxStream<'static, Result<ObjectVersion>> {
+        self.client.list_versions(prefix)
+    }
+
+    async fn delete_version(&self, location: &Path, version: &str) -> Result<()> {
+        self.client
+            .request(Method::DELETE, location)
+            .query(&[("versionId", version)])
+            .send()
+            .await?;
+        Ok(())
+    }
+
+    async fn copy_version(&self, from: &Path, version: &str, to: &Path) -> Result<()> {
+        self.client
+            .copy_version_request(from, version, to)
+            .idempotent(true)
+            .send()
+            .await?;
+        Ok(())
+    }
+}
+
+/// THIS IMPL IS ARAS ONLY
+///
+/// If tagging is disabled with [`AmazonS3ConfigKey::DisableTagging`], tags are not
+/// updated, consistent with tags being ignored by puts
+#[async_trait]
+impl TaggingStore for AmazonS3 {
+    async fn get_tags(&self, location: &Path) -> Result<TagSet> {
+        self.client.get_object_tagging(location).await
+    }
+
+    async fn put_tags(&self, location: &Path, tags: TagSet) -> Result<()> {
+        self.client.put_object_tagging(location, &tags).await
+    }
+
+    async fn delete_tags(&self, location: &Path) -> Result<()> {
+        self.client.delete_object_tagging(location).await
+    }
+}
+
 #[async_trait]
 impl MultipartStore for AmazonS3 {
     async fn create_multipart(&self, path: &Path) -> Result<MultipartId> {
@@ -479,6 +906,19 @@ impl MultipartStore for AmazonS3 {
             .await?;
         Ok(())
     }
+
+    /// THIS METHOD IS ARAS ONLY
+    fn list_multipart_uploads(
+        &self,
+        prefix: Option<&Path>,
+    ) -> BoxStream<'static, Result<MultipartUploadInfo>> {
+        self.client.list_multipart_uploads(prefix)
+    }
+
+    /// THIS METHOD IS ARAS ONLY
+    async fn list_parts(&self, path: &Path, id: &MultipartId) -> Result<Vec<PartInfo>> {
+        self.client.list_parts(path, id).await
+    }
 }
 
 #[async_trait]
@@ -586,6 +1026,7 @@ mod tests {
         rename_and_copy(&integration).await;
         stream_get(&integration).await;
         multipart(&integration, &integration).await;
+        list_multipart_uploads(&integration).await;
         multipart_race_condition(&integration, true).await;
         multipart_out_of_order(&integration).await;
         signing(&integration).await;
@@ -878,4 +1319,363 @@ mod tests {
         shutdown_tx.send(()).ok();
         thread_handle.join().expect("runtime thread panicked");
     }
+
+    /// THIS TEST IS ARAS ONLY
+    #[tokio::test]
+    async fn test_copy_multipart() {
+        use crate::client::mock_server::MockServer;
+        use crate::CopyConfig;
+        use http::header::{CONTENT_LENGTH, CONTENT_TYPE, ETAG, LAST_MODIFIED};
+        use http::Response;
+
+        let server = MockServer::new().await;
+        let store = AmazonS3Builder::new()
+            .with_endpoint(server.url())
+            .with_bucket_name("bucket")
+            .with_region("us-east-1")
+            .with_access_key_id("key")
+            .with_secret_access_key("secret")
+            .with_allow_http(true)
+            .with_copy_config(CopyConfig {
+                multipart_threshold: MIN_PART_SIZE,
+                part_size: MIN_PART_SIZE,
+                max_concurrency: 1,
+            })
+            .build()
+            .unwrap();
+
+        let size = 2 * MIN_PART_SIZE + 10;
+        server.push(
+            Response::builder()
+                .header(CONTENT_LENGTH, size)
+                .header(CONTENT_TYPE, "text/plain")
+                .header(ETAG, "\"source\"")
+                .header(LAST_MODIFIED, "Tue, 15 Nov 1994 12:45:26 GMT")
+                .body(String::new())
+                .unwrap(),
+        );
+        server.push_fn(|req| {
+            assert_eq!(req.uri().query(), Some("tagging"));
+            let body =
+                "<Tagging><TagSet><Tag><Key>k</Key><Value>v</Value></Tag></TagSet></Tagging>";
+            Response::new(body.to_string())
+        });
+        server.push_fn(|req| {
+            assert_eq!(req.uri().path(), "/bucket/to");
+            assert_eq!(req.headers()[CONTENT_TYPE], "text/plain");
+            assert_eq!(req.headers()["x-amz-tagging"], "k=v");
+            let body = "<InitiateMultipartUploadResult><UploadId>upload</UploadId></InitiateMultipartUploadResult>";
+            Response::new(body.to_string())
+        });
+        let ranges = [
+            format!("bytes=0-{}", MIN_PART_SIZE - 1),
+            format!("bytes={}-{}", MIN_PART_SIZE, 2 * MIN_PART_SIZE - 1),
+            format!("bytes={}-{}", 2 * MIN_PART_SIZE, size - 1),
+        ];
+        for (idx, range) in ranges.into_iter().enumerate() {
+            server.push_fn(move |req| {
+                let query = format!("partNumber={}&uploadId=upload", idx + 1);
+                assert_eq!(req.uri().query(), Some(query.as_str()));
+                assert_eq!(req.headers()["x-amz-copy-source"], "bucket/from");
+                assert_eq!(req.headers()["x-amz-copy-source-range"], range.as_str());
+                let body = format!("<CopyPartResult><ETag>\"{idx}\"</ETag></CopyPartResult>");
+                Response::new(body)
+            });
+        }
+        server.push_fn(|req| {
+            assert_eq!(req.uri().query(), Some("uploadId=upload"));
+            let body = "<CompleteMultipartUploadResult><ETag>\"dest\"</ETag></CompleteMultipartUploadResult>";
+            Response::new(body.to_string())
+        });
+
+        store
+            .copy(&Path::from("from"), &Path::from("to"))
+            .await
+            .unwrap();
+
+        // Without a threshold below the maximum copy size, the size of the source is
+        // only determined once copying it with a single request failed
+        let store = AmazonS3Builder::new()
+            .with_endpoint(server.url())
+            .with_bucket_name("bucket")
+            .with_region("us-east-1")
+            .with_access_key_id("key")
+            .with_secret_access_key("secret")
+            .with_allow_http(true)
+            .with_copy_config(CopyConfig {
+                part_size: MAX_PART_SIZE,
+                max_concurrency: 1,
+                ..Default::default()
+            })
+            .build()
+            .unwrap();
+
+        server.push_fn(|req| {
+            assert_eq!(req.uri().path(), "/bucket/to");
+            assert_eq!(req.uri().query(), None);
+            assert_eq!(req.headers()["x-amz-copy-source"], "bucket/from");
+            let body = "<Error><Code>InvalidRequest</Code><Message>The specified copy source \
+                is larger than the maximum allowable size for a copy source: 5368709120\
+                </Message></Error>";
+            Response::builder()
+                .status(StatusCode::BAD_REQUEST)
+                .body(body.to_string())
+                .unwrap()
+        });
+        let size = MAX_PART_SIZE + 10;
+        server.push(
+            Response::builder()
+                .header(CONTENT_LENGTH, size)
+                .header(ETAG, "\"source\"")
+                .header(LAST_MODIFIED, "Tue, 15 Nov 1994 12:45:26 GMT")
+                .body(String::new())
+                .unwrap(),
+        );
+        server.push_fn(|req| {
+            assert_eq!(req.uri().query(), Some("tagging"));
+            Response::new("<Tagging><TagSet></TagSet></Tagging>".to_string())
+        });
+        server.push_fn(|req| {
+            assert_eq!(req.uri().query(), Some("uploads="));
+            let body = "<InitiateMultipartUploadResult><UploadId>upload</UploadId></InitiateMultipartUploadResult>";
+            Response::new(body.to_string())
+        });
+        let ranges = [
+            format!("bytes=0-{}", MAX_PART_SIZE - 1),
+            format!("bytes={}-{}", MAX_PART_SIZE, size - 1),
+        ];
+        for (idx, range) in ranges.into_iter().enumerate() {
+            server.push_fn(move |req| {
+                assert_eq!(req.headers()["x-amz-copy-source-range"], range.as_str());
+                let body = format!("<CopyPartResult><ETag>\"{idx}\"</ETag></CopyPartResult>");
+                Response::new(body)
+            });
+        }
+        server.push_fn(|req| {
+            assert_eq!(req.uri().query(), Some("uploadId=upload"));
+            let body = "<CompleteMultipartUploadResult><ETag>\"dest\"</ETag></CompleteMultipartUploadResult>";
+            Response::new(body.to_string())
+        });
+
+        store
+            .copy(&Path::from("from"), &Path::from("to"))
+            .await
+            .unwrap();
+        server.shutdown().await;
+    }
+
+    /// THIS TEST IS ARAS ONLY
+    #[tokio::test]
+    async fn test_compose_small() {
+        use crate::client::mock_server::MockServer;
+        use http::header::{CONTENT_LENGTH, CONTENT_RANGE, ETAG, LAST_MODIFIED, RANGE};
+        use http::{Method, Response, StatusCode};
+        use http_body_util::BodyExt;
+
+        fn content(path: &str) -> &'static str {
+            match path {
+                "/bucket/a" => "aaa",
+                "/bucket/b" => "",
+                "/bucket/c" => "cccc",
+                _ => unreachable!("{path}"),
+            }
+        }
+
+        let server = MockServer::new().await;
+        let store = AmazonS3Builder::new()
+            .with_endpoint(server.url())
+            .with_bucket_name("bucket")
+            .with_region("us-east-1")
+            .with_access_key_id("key")
+            .with_secret_access_key("secret")
+            .with_allow_http(true)
+            .build()
+            .unwrap();
+
+        // The sources are sent HEAD requests concurrently
+        for _ in 0..3 {
+            server.push_fn(|req| {
+                assert_eq!(req.method(), Method::HEAD);
+                Response::builder()
+                    .header(CONTENT_LENGTH, content(req.uri().path()).len())
+                    .header(ETAG, "\"source\"")
+                    .header(LAST_MODIFIED, "Tue, 15 Nov 1994 12:45:26 GMT")
+                    .body(String::new())
+                    .unwrap()
+            });
+        }
+        server.push_fn(|req| {
+            assert_eq!(req.uri().query(), Some("uploads="));
+            let body = "<InitiateMultipartUploadResult><UploadId>upload</UploadId></InitiateMultipartUploadResult>";
+            Response::new(body.to_string())
+        });
+        // Sources below the minimum part size are buffered, skipping the empty source
+        for path in ["/bucket/a", "/bucket/c"] {
+            server.push_fn(move |req| {
+                assert_eq!(req.method(), Method::GET);
+                assert_eq!(req.uri().path(), path);
+                let data = content(path);
+                let range = format!("bytes=0-{}", data.len() - 1);
+                assert_eq!(req.headers()[RANGE], range.as_str());
+                Response::builder()
+                    .status(StatusCode::PARTIAL_CONTENT)
+                    .header(
+                        CONTENT_RANGE,
+                        format!("bytes 0-{}/{}", data.len() - 1, data.len()),
+                    )
+                    .header(ETAG, "\"source\"")
+                    .header(LAST_MODIFIED, "Tue, 15 Nov 1994 12:45:26 GMT")
+                    .body(data.to_string())
+                    .unwrap()
+            });
+        }
+        server.push_async_fn(|req| async move {
+            assert_eq!(req.uri().query(), Some("partNumber=1&uploadId=upload"));
+            assert!(!req.headers().contains_key("x-amz-copy-source"));
+            let body = req.into_body().collect().await.unwrap().to_bytes();
+            assert_eq!(body.as_ref(), b"aaacccc");
+            Response::builder()
+                .header(ETAG, "\"0\"")
+                .body(String::new())
+                .unwrap()
+        });
+        server.push_fn(|req| {
+            assert_eq!(req.uri().query(), Some("uploadId=upload"));
+            let body = "<CompleteMultipartUploadResult><ETag>\"dest\"</ETag></CompleteMultipartUploadResult>";
+            Response::new(body.to_string())
+        });
+
+        let sources = ["a", "b", "c"].map(Path::from);
+        store.compose(&sources, &Path::from("dest")).await.unwrap();
+        server.shutdown().await;
+    }
+
+    /// THIS TEST IS ARAS ONLY
+    #[tokio::test]
+    async fn test_list_multipart_uploads() {
+        use crate::client::mock_server::MockServer;
+        use http::Response;
+
+        let server = MockServer::new().await;
+        let store = AmazonS3Builder::new()
+            .with_endpoint(server.url())
+            .with_bucket_name("bucket")
+            .with_region("us-east-1")
+            .with_access_key_id("key")
+            .with_secret_access_key("secret")
+            .with_allow_http(true)
+            .build()
+            .unwrap();
+
+        server.push_fn(|req| {
+            assert_eq!(req.uri().path(), "/bucket");
+            assert_eq!(req.uri().query(), Some("uploads=&prefix=data%2F"));
+            let body = "<ListMultipartUploadsResult>\
+                <IsTruncated>true</IsTruncated>\
+                <NextKeyMarker>data/a</NextKeyMarker>\
+                <NextUploadIdMarker>1</NextUploadIdMarker>\
+                <Upload><Key>data/a</Key><UploadId>1</UploadId>\
+                <Initiated>2024-01-01T00:00:00.000Z</Initiated></Upload>\
+                </ListMultipartUploadsResult>";
+            Response::new(body.to_string())
+        });
+        server.push_fn(|req| {
+            let query = "uploads=&prefix=data%2F&key-marker=data%2Fa&upload-id-marker=1";
+            assert_eq!(req.uri().query(), Some(query));
+            let body = "<ListMultipartUploadsResult>\
+                <IsTruncated>false</IsTruncated>\
+                <Upload><Key>data/b</Key><UploadId>2</UploadId>\
+                <Initiated>2024-01-02T00:00:00.000Z</Initiated></Upload>\
+                </ListMultipartUploadsResult>";
+            Response::new(body.to_string())
+        });
+
+        let prefix = Path::from("data");
+        let uploads: Vec<_> = store
+            .list_multipart_uploads(Some(&prefix))
+            .try_collect()
+            .await
+            .unwrap();
+        let listed: Vec<_> = uploads
+            .iter()
+            .map(|u| (u.location.as_ref(), u.id.as_str()))
+            .collect();
+        assert_eq!(listed, vec![("data/a", "1"), ("data/b", "2")]);
+        assert_eq!(
+            uploads[1].initiated.unwrap().to_rfc3339(),
+            "2024-01-02T00:00:00+00:00"
+        );
+
+        server.push_fn(|req| {
+            assert_eq!(req.uri().path(), "/bucket/data/a");
+            assert_eq!(req.uri().query(), Some("uploadId=1"));
+            let body = "<ListPartsResult>\
+                <IsTruncated>true</IsTruncated>\
+                <NextPartNumberMarker>2</NextPartNumberMarker>\
+                <Part><PartNumber>2</PartNumber><ETag>\"b\"</ETag><Size>5</Size>\
+                <LastModified>2024-01-01T00:00:00.000Z</LastModified></Part>\
+                </ListPartsResult>";
+            Response::new(body.to_string())
+        });
+        server.push_fn(|req| {
+            assert_eq!(req.uri().query(), Some("uploadId=1&part-number-marker=2"));
+            let body = "<ListPartsResult>\
+                <IsTruncated>false</IsTruncated>\
+                <Part><PartNumber>1</PartNumber><ETag>\"a\"</ETag><Size>10</Size>\
+                <LastModified>2024-01-01T00:00:00.000Z</LastModified></Part>\
+                </ListPartsResult>";
+            Response::new(body.to_string())
+        });
+
+        let parts = store
+            .list_parts(&Path::from("data/a"), &"1".to_string())
+            .await
+            .unwrap();
+        let parts: Vec<_> = parts
+            .iter()
+            .map(|p| (p.part_idx, p.part_id.content_id.as_str(), p.size))
+            .collect();
+        assert_eq!(parts, vec![(0, "\"a\"", 10), (1, "\"b\"", 5)]);
+        server.shutdown().await;
+    }
+
+    /// THIS TEST IS ARAS ONLY
+    #[tokio::test]
+    async fn test_delete_opts() {
+        use crate::client::mock_server::MockServer;
+        use http::{Method, Response};
+
+        let server = MockServer::new().await;
+        let store = AmazonS3Builder::new()
+            .with_endpoint(server.url())
+            .with_bucket_name("bucket")
+            .with_region("us-east-1")
+            .with_access_key_id("key")
+            .with_secret_access_key("secret")
+            .with_allow_http(true)
+            .build()
+            .unwrap();
+        let path = Path::from("a");
+
+        // A version alone is not supported, and sends no request
+        let opts = DeleteOptions {
+            version: Some("1".to_string()),
+            ..Default::default()
+        };
+        let err = store.delete_opts(&path, opts).await.unwrap_err();
+        assert!(matches!(err, Error::NotImplemented), "{err}");
+
+        server.push_fn(|req| {
+            assert_eq!(req.method(), Method::DELETE);
+            assert_eq!(req.headers()["if-match"], "\"e\"");
+            Response::new(String::new())
+        });
+        let opts = DeleteOptions {
+            if_match: Some("\"e\"".to_string()),
+            version: Some("1".to_string()),
+            ..Default::default()
+        };
+        store.delete_opts(&path, opts).await.unwrap();
+        server.shutdown().await;
+    }
 }
@@ -15,7 +15,7 @@
 // specific language governing permissions and limitations
 // under the License.
 
-use crate::azure::client::{AzureClient, AzureConfig};
+use crate::azure::client::{AzureClient, AzureConfig, DEFAULT_LIST_UPLOADS_CONCURRENCY};
 use crate::azure::credential::{
     AzureAccessKey, AzureCliCredential, ClientSecretOAuthProvider, FabricTokenOAuthProvider,
     ImdsManagedIdentityProvider, WorkloadIdentityOAuthProvider,
@@ -23,7 +23,10 @@ use crate::azure::credential::{
 use crate::azure::{AzureCredential, AzureCredentialProvider, MicrosoftAzure, STORE};
 use crate::client::{http_connector, HttpConnector, TokenCredentialProvider};
 use crate::config::ConfigValue;
-use crate::{ClientConfigKey, ClientOptions, Result, RetryConfig, StaticCredentialProvider};
+use crate::{
+    ClientConfigKey, ClientOptions, CopyConfig, RangeCoalescePolicy, Result, RetryConfig,
+    StaticCredentialProvider,
+};
 use percent_encoding::percent_decode_str;
 use serde::{Deserialize, Serialize};
 use std::str::FromStr;
@@ -158,6 +161,14 @@ pub struct MicrosoftAzureBuilder {
     use_azure_cli: ConfigValue<bool>,
     /// Retry config
     retry_config: RetryConfig,
+    /// THIS FIELD IS ARAS ONLY
+    ///
+    /// Configuration for copies of large objects
+    copy_config: CopyConfig,
+    /// THIS FIELD IS ARAS ONLY
+    ///
+    /// The number of blobs whose uncommitted blocks are listed concurrently
+    list_uploads_concurrency: Option<usize>,
     /// Client options
     client_options: ClientOptions,
     /// Credentials
@@ -831,6 +842,35 @@ impl MicrosoftAzureBuilder {
         self
     }
 
+    /// THIS METHOD IS ARAS ONLY
+    ///
+    /// Set the configuration for server-side copies of large objects
+    pub fn with_copy_config(mut self, copy_config: CopyConfig) -> Self {
+        self.copy_config = copy_config;
+        self
+    }
+
+    /// THIS METHOD IS ARAS ONLY
+    ///
+    /// Set the number of blobs whose uncommitted blocks are listed concurrently by
+    /// [`MultipartStore::list_multipart_uploads`], defaults to 8
+    ///
+    /// [`MultipartStore::list_multipart_uploads`]: crate::multipart::MultipartStore::list_multipart_uploads
+    pub fn with_list_uploads_concurrency(mut self, concurrency: usize) -> Self {
+        self.list_uploads_concurrency = Some(concurrency);
+        self
+    }
+
+    /// THIS METHOD IS ARAS ONLY
+    ///
+    /// Sets the [`RangeCoalescePolicy`] used by [`ObjectStore::get_ranges`]
+    ///
+    /// [`ObjectStore::get_ranges`]: crate::ObjectStore::get_ranges
+    pub fn with_range_coalesce_policy(mut self, policy: RangeCoalescePolicy) -> Self {
+        self.client_options = self.client_options.with_range_coalesce_policy(policy);
+        self
+    }
+
     /// Set the proxy_url to be used by the underlying client
     pub fn with_proxy_url(mut self, proxy_url: impl Into<String>) -> Self {
         self.client_options = self.client_options.with_proxy_url(proxy_url);
@@ -1041,6 +1081,10 @@ impl MicrosoftAzureBuilder {
             container,
             disable_tagging: self.disable_tagging.get()?,
             retry_config: self.retry_config,
+            copy_config: self.copy_config,
+            list_uploads_concurrency: self
+                .list_uploads_concurrency
+                .unwrap_or(DEFAULT_LIST_UPLOADS_CONCURRENCY),
             client_options: self.client_options,
             service: storage_url,
             credentials: auth,
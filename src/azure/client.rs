@@ -19,23 +19,27 @@ use super::credential::AzureCredential;
 use crate::azure::credential::*;
 use crate::azure::{AzureCredentialProvider, STORE};
 use crate::client::builder::HttpRequestBuilder;
-use crate::client::get::GetClient;
+use crate::client::get::{GetClient, GetClientExt};
 use crate::client::header::{get_put_result, HeaderConfig};
-use crate::client::list::ListClient;
+use crate::client::list::{ListClient, ListMultipartClient, ListVersionsClient};
 use crate::client::retry::{RetryContext, RetryExt};
 use crate::client::{GetOptionsExt, HttpClient, HttpError, HttpRequest, HttpResponse};
 use crate::list::{PaginatedListOptions, PaginatedListResult};
-use crate::multipart::PartId;
+use crate::multipart::{MultipartUploadInfo, PartId, PartInfo};
+use crate::tags::Tagging;
 use crate::util::{deserialize_rfc1123, GetRange};
+use crate::version::ObjectVersion;
 use crate::{
-    Attribute, Attributes, ClientOptions, GetOptions, ListResult, ObjectMeta, Path, PutMode,
-    PutMultipartOpts, PutOptions, PutPayload, PutResult, Result, RetryConfig, TagSet,
+    Attribute, Attributes, ClientOptions, CopyConfig, GetOptions, GetResult, ListResult,
+    MultipartId, ObjectMeta, Path, PutMode, PutMultipartOpts, PutOptions, PutPayload, PutResult,
+    Result, RetryConfig, TagSet,
 };
 use async_trait::async_trait;
 use base64::prelude::{BASE64_STANDARD, BASE64_STANDARD_NO_PAD};
 use base64::Engine;
 use bytes::{Buf, Bytes};
 use chrono::{DateTime, Utc};
+use futures::{StreamExt, TryStreamExt};
 use http::{
     header::{HeaderMap, HeaderValue, CONTENT_LENGTH, CONTENT_TYPE, IF_MATCH, IF_NONE_MATCH},
     HeaderName, Method,
@@ -43,6 +47,7 @@ use http::{
 use rand::Rng as _;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
+use std::ops::Range;
 use std::sync::Arc;
 use std::time::Duration;
 use url::Url;
@@ -58,6 +63,32 @@ static MS_CONTENT_LANGUAGE: HeaderName = HeaderName::from_static("x-ms-blob-cont
 
 static TAGS_HEADER: HeaderName = HeaderName::from_static("x-ms-tags");
 
+/// THIS STATIC IS ARAS ONLY
+static COPY_SOURCE_AUTHORIZATION: HeaderName =
+    HeaderName::from_static("x-ms-copy-source-authorization");
+/// THIS STATIC IS ARAS ONLY
+static SOURCE_RANGE: HeaderName = HeaderName::from_static("x-ms-source-range");
+
+/// THIS CONSTANT IS ARAS ONLY
+///
+/// The maximum size of a block staged with Put Block From URL
+pub(crate) const MAX_BLOCK_FROM_URL_SIZE: u64 = 4000 * 1024 * 1024;
+
+/// THIS CONSTANT IS ARAS ONLY
+///
+/// The maximum number of blocks of a block blob
+const MAX_BLOCKS: u64 = 50_000;
+
+/// THIS CONSTANT IS ARAS ONLY
+///
+/// The default number of blobs whose uncommitted blocks are listed concurrently
+pub(crate) const DEFAULT_LIST_UPLOADS_CONCURRENCY: usize = 8;
+
+/// THIS CONSTANT IS ARAS ONLY
+///
+/// How long the SAS authorizing the source of a Put Block From URL request is valid for
+const COPY_SOURCE_SAS_EXPIRY: Duration = Duration::from_secs(60 * 60);
+
 /// A specialized `Error` for object store-related errors
 #[derive(Debug, thiserror::Error)]
 pub(crate) enum Error {
@@ -144,6 +175,18 @@ pub(crate) enum Error {
 
     #[error("Generating SAS keys while skipping signatures is not supported")]
     SASwithSkipSignature,
+
+    /// THIS VARIANT IS ARAS ONLY
+    #[error("Error getting blob tags response body: {}", source)]
+    TagsResponseBody { source: HttpError },
+
+    /// THIS VARIANT IS ARAS ONLY
+    #[error("Got invalid blob tags response: {}", source)]
+    InvalidTagsResponse { source: quick_xml::de::DeError },
+
+    /// THIS VARIANT IS ARAS ONLY
+    #[error("Got invalid blob tags request: {}", source)]
+    InvalidTagsRequest { source: quick_xml::se::SeError },
 }
 
 impl From<Error> for crate::Error {
@@ -167,6 +210,10 @@ pub(crate) struct AzureConfig {
     pub container: String,
     pub credentials: AzureCredentialProvider,
     pub retry_config: RetryConfig,
+    /// THIS FIELD IS ARAS ONLY
+    pub copy_config: CopyConfig,
+    /// THIS FIELD IS ARAS ONLY
+    pub list_uploads_concurrency: usize,
     pub service: Url,
     pub is_emulator: bool,
     pub skip_signature: bool,
@@ -504,6 +551,35 @@ async fn parse_blob_batch_delete_body(
     Ok(results)
 }
 
+/// THIS CONSTANT IS ARAS ONLY
+///
+/// The maximum size of a single block appended to an append blob
+const MAX_APPEND_BLOCK_SIZE: usize = 100 * 1024 * 1024;
+
+/// THIS FUNCTION IS ARAS ONLY
+///
+/// Splits `payload` into blocks no larger than [`MAX_APPEND_BLOCK_SIZE`]
+fn append_blocks(payload: PutPayload) -> Vec<PutPayload> {
+    let mut blocks = vec![];
+    let mut current = vec![];
+    let mut current_len = 0;
+    for mut bytes in payload {
+        while !bytes.is_empty() {
+            let len = bytes.len().min(MAX_APPEND_BLOCK_SIZE - current_len);
+            current.push(bytes.split_to(len));
+            current_len += len;
+            if current_len == MAX_APPEND_BLOCK_SIZE {
+                blocks.push(std::mem::take(&mut current).into_iter().collect());
+                current_len = 0;
+            }
+        }
+    }
+    if !current.is_empty() {
+        blocks.push(current.into_iter().collect());
+    }
+    blocks
+}
+
 #[derive(Debug)]
 pub(crate) struct AzureClient {
     config: AzureConfig,
@@ -541,6 +617,8 @@ impl AzureClient {
     /// THIS METHOD IS COMMON, MODIFIED BY ARAS
     ///
     /// Make an Azure PUT request <https://docs.microsoft.com/en-us/rest/api/storageservices/put-blob>
+    ///
+    /// Appends are performed by [`Self::append_blob`], returning [`crate::Error::NotImplemented`]
     pub(crate) async fn put_blob(
         &self,
         path: &Path,
@@ -552,7 +630,6 @@ impl AzureClient {
             tags,
             attributes,
             extensions,
-            copy_and_append: _copy_and_append,
         } = opts;
 
         let builder = self
@@ -568,6 +645,7 @@ impl AzureClient {
                 let etag = v.e_tag.as_ref().ok_or(Error::MissingETag)?;
                 builder.header(&IF_MATCH, etag)
             }
+            PutMode::Append => return Err(crate::Error::NotImplemented),
         };
 
         let response = builder.header(&BLOB_TYPE, "BlockBlob").send().await?;
@@ -575,15 +653,80 @@ impl AzureClient {
             .map_err(|source| Error::Metadata { source })?)
     }
 
+    /// THIS METHOD IS ARAS ONLY
+    ///
+    /// Append `payload` to an append blob, creating the blob if it does not exist
+    /// <https://learn.microsoft.com/en-us/rest/api/storageservices/append-block>
+    pub(crate) async fn append_blob(
+        self: &Arc<Self>,
+        path: &Path,
+        payload: PutPayload,
+        opts: PutOptions,
+    ) -> Result<PutResult> {
+        let PutOptions {
+            tags,
+            attributes,
+            extensions,
+            ..
+        } = opts;
+
+        let created = self
+            .put_request(path, PutPayload::default())
+            .with_attributes(attributes)
+            .with_extensions(extensions)
+            .with_tags(tags)
+            .header(&IF_NONE_MATCH, "*")
+            .header(&BLOB_TYPE, "AppendBlob")
+            .send()
+            .await;
+
+        let mut response = match created {
+            Ok(response) => Some(response),
+            // An existing blob must be an append blob for the blocks below to succeed
+            Err(crate::Error::AlreadyExists { .. }) => None,
+            Err(e) => return Err(e),
+        };
+
+        for block in append_blocks(payload) {
+            let block_response = self
+                .put_request(path, block)
+                .query(&[("comp", "appendblock")])
+                .send()
+                .await?;
+            response = Some(block_response);
+        }
+
+        match response {
+            Some(response) => Ok(get_put_result(response.headers(), VERSION_HEADER)
+                .map_err(|source| Error::Metadata { source })?),
+            None => {
+                let options = GetOptions {
+                    head: true,
+                    ..Default::default()
+                };
+                let meta = self.get_opts(path, options).await?.meta;
+                Ok(PutResult {
+                    e_tag: meta.e_tag,
+                    version: meta.version,
+                })
+            }
+        }
+    }
+
+    /// THIS METHOD IS COMMON, MODIFIED BY ARAS
+    ///
     /// PUT a block <https://learn.microsoft.com/en-us/rest/api/storageservices/put-block>
+    ///
+    /// Block ids are random to avoid conflicts between concurrent uploads to the same blob,
+    /// with `part_idx` as a suffix so that listed blocks can be ordered, see [`block_part_idx`]
     pub(crate) async fn put_block(
         &self,
         path: &Path,
-        _part_idx: usize,
+        part_idx: usize,
         payload: PutPayload,
     ) -> Result<PartId> {
-        let part_idx = u128::from_be_bytes(rand::rng().random());
-        let content_id = format!("{part_idx:032x}");
+        let random = u128::from_be_bytes(rand::rng().random()) >> 32;
+        let content_id = format!("{random:024x}{part_idx:08x}");
         let block_id = BASE64_STANDARD.encode(&content_id);
 
         self.put_request(path, payload)
@@ -595,6 +738,56 @@ impl AzureClient {
         Ok(PartId { content_id })
     }
 
+    /// THIS METHOD IS ARAS ONLY
+    ///
+    /// PUT a block copied from `range` of the blob at `source`, or the whole blob if `None`
+    /// <https://learn.microsoft.com/en-us/rest/api/storageservices/put-block-from-url>
+    pub(crate) async fn put_block_from_url(
+        &self,
+        path: &Path,
+        source: &Path,
+        range: Option<Range<u64>>,
+    ) -> Result<PartId> {
+        let credential = self.get_credential().await?;
+        let part_idx = u128::from_be_bytes(rand::rng().random());
+        let content_id = format!("{part_idx:032x}");
+        let block_id = BASE64_STANDARD.encode(&content_id);
+
+        // The source must be authorized via a SAS or an OAuth token, shared key
+        // authorization is only accepted for the destination
+        let mut source_url = self.config.path_url(source);
+        let mut authorization = None;
+        match credential.as_deref() {
+            Some(AzureCredential::SASToken(pairs)) => {
+                source_url.query_pairs_mut().extend_pairs(pairs);
+            }
+            Some(AzureCredential::BearerToken(token)) => {
+                authorization = Some(format!("Bearer {token}"));
+            }
+            Some(AzureCredential::AccessKey(_)) => {
+                let signer = self.signer(COPY_SOURCE_SAS_EXPIRY).await?;
+                signer.sign(&Method::GET, &mut source_url)?;
+            }
+            None => {}
+        }
+
+        let mut builder = self
+            .put_request(path, PutPayload::default())
+            .query(&[("comp", "block"), ("blockid", &block_id)])
+            .header(&COPY_SOURCE, source_url.as_str())
+            .idempotent(true);
+        if let Some(authorization) = &authorization {
+            builder = builder.header(&COPY_SOURCE_AUTHORIZATION, authorization);
+        }
+        if let Some(range) = range {
+            let range = format!("bytes={}-{}", range.start, range.end - 1);
+            builder = builder.header(&SOURCE_RANGE, &range);
+        }
+        builder.send().await?;
+
+        Ok(PartId { content_id })
+    }
+
     /// THIS METHOD IS COMMON, MODIFIED BY ARAS
     ///
     /// PUT a block list <https://learn.microsoft.com/en-us/rest/api/storageservices/put-block-list>
@@ -604,11 +797,30 @@ impl AzureClient {
         parts: Vec<PartId>,
         opts: PutMultipartOpts,
     ) -> Result<PutResult> {
+        let response = self
+            .block_list_request(path, parts, opts)
+            .idempotent(true)
+            .send()
+            .await?;
+
+        Ok(get_put_result(response.headers(), VERSION_HEADER)
+            .map_err(|source| Error::Metadata { source })?)
+    }
+
+    /// THIS METHOD IS ARAS ONLY
+    ///
+    /// Build a PUT block list request <https://learn.microsoft.com/en-us/rest/api/storageservices/put-block-list>
+    fn block_list_request<'a>(
+        &'a self,
+        path: &'a Path,
+        parts: Vec<PartId>,
+        opts: PutMultipartOpts,
+    ) -> PutRequest<'a> {
         let PutMultipartOpts {
             tags,
             attributes,
             extensions,
-            copy_and_append: _copy_and_append,
+            mode: _,
         } = opts;
 
         let blocks = parts
@@ -617,18 +829,68 @@ impl AzureClient {
             .collect();
 
         let payload = BlockList { blocks }.to_xml().into();
-        let response = self
-            .put_request(path, payload)
+        self.put_request(path, payload)
             .with_attributes(attributes)
             .with_tags(tags)
             .with_extensions(extensions)
             .query(&[("comp", "blocklist")])
+    }
+
+    /// THIS METHOD IS ARAS ONLY
+    ///
+    /// Make an Azure Set Blob Tags request <https://learn.microsoft.com/en-us/rest/api/storageservices/set-blob-tags>
+    ///
+    /// Does nothing if tagging is disabled
+    pub(crate) async fn set_blob_tags(&self, path: &Path, tags: &TagSet) -> Result<()> {
+        if self.config.disable_tagging {
+            return Ok(());
+        }
+
+        let body = quick_xml::se::to_string_with_root("Tags", &Tagging::from(tags))
+            .map_err(|source| Error::InvalidTagsRequest { source })?;
+        self.put_request(path, Bytes::from(body).into())
+            .header(&CONTENT_TYPE, "application/xml")
+            .query(&[("comp", "tags")])
             .idempotent(true)
             .send()
             .await?;
+        Ok(())
+    }
 
-        Ok(get_put_result(response.headers(), VERSION_HEADER)
-            .map_err(|source| Error::Metadata { source })?)
+    /// THIS METHOD IS ARAS ONLY
+    ///
+    /// Make an Azure Get Block List request for the uncommitted blocks of the blob at `path`
+    /// <https://learn.microsoft.com/en-us/rest/api/storageservices/get-block-list>
+    pub(crate) async fn list_uncommitted_blocks(&self, path: &Path) -> Result<Vec<PartInfo>> {
+        let credential = self.get_credential().await?;
+        let url = self.config.path_url(path);
+        let sensitive = credential
+            .as_deref()
+            .map(|c| c.sensitive_request())
+            .unwrap_or_default();
+        let response = self
+            .client
+            .get(url.as_str())
+            .query(&[("comp", "blocklist"), ("blocklisttype", "uncommitted")])
+            .with_azure_authorization(&credential, &self.config.account)
+            .retryable(&self.config.retry_config)
+            .sensitive(sensitive)
+            .send()
+            .await
+            .map_err(|source| {
+                let path = path.as_ref().into();
+                Error::GetRequest { source, path }
+            })?;
+
+        let data = response
+            .into_body()
+            .bytes()
+            .await
+            .map_err(|source| Error::ListResponseBody { source })?;
+
+        let response: BlockListResponse = quick_xml::de::from_reader(data.reader())
+            .map_err(|source| Error::InvalidListResponse { source })?;
+        Ok(response.into_parts())
     }
 
     /// Make an Azure Delete request <https://docs.microsoft.com/en-us/rest/api/storageservices/delete-blob>
@@ -661,6 +923,72 @@ impl AzureClient {
         Ok(())
     }
 
+    /// THIS METHOD IS ARAS ONLY
+    ///
+    /// Make an Azure Delete request conditional on the blob's ETag <https://learn.microsoft.com/en-us/rest/api/storageservices/specifying-conditional-headers-for-blob-service-operations>
+    pub(crate) async fn delete_if_match_request(&self, path: &Path, etag: &str) -> Result<()> {
+        let credential = self.get_credential().await?;
+        let url = self.config.path_url(path);
+
+        let sensitive = credential
+            .as_deref()
+            .map(|c| c.sensitive_request())
+            .unwrap_or_default();
+        let result = self
+            .client
+            .delete(url.as_str())
+            .header(&DELETE_SNAPSHOTS, "include")
+            .header(&IF_MATCH, etag)
+            .with_azure_authorization(&credential, &self.config.account)
+            .retryable(&self.config.retry_config)
+            .sensitive(sensitive)
+            .send()
+            .await
+            .map_err(|source| {
+                let path = path.as_ref().into();
+                crate::Error::from(Error::DeleteRequest { source, path })
+            });
+
+        match result {
+            // Azure reports NotFound when the blob doesn't exist, convert for
+            // consistency with conditional puts
+            Err(crate::Error::NotFound { path, source }) => {
+                Err(crate::Error::Precondition { path, source })
+            }
+            r => r.map(|_| ()),
+        }
+    }
+
+    /// THIS METHOD IS ARAS ONLY
+    ///
+    /// Make an Azure Delete request for a specific version of a blob <https://learn.microsoft.com/en-us/rest/api/storageservices/delete-blob>
+    ///
+    /// Unlike [`Self::delete_request`] this does not include snapshots, which is
+    /// rejected by the service when targeting a version
+    pub(crate) async fn delete_version_request(&self, path: &Path, version: &str) -> Result<()> {
+        let credential = self.get_credential().await?;
+        let url = self.config.path_url(path);
+
+        let sensitive = credential
+            .as_deref()
+            .map(|c| c.sensitive_request())
+            .unwrap_or_default();
+        self.client
+            .delete(url.as_str())
+            .query(&[("versionid", version)])
+            .with_azure_authorization(&credential, &self.config.account)
+            .retryable(&self.config.retry_config)
+            .sensitive(sensitive)
+            .send()
+            .await
+            .map_err(|source| {
+                let path = path.as_ref().into();
+                Error::DeleteRequest { source, path }
+            })?;
+
+        Ok(())
+    }
+
     fn build_bulk_delete_body(
         &self,
         boundary: &str,
@@ -745,12 +1073,40 @@ impl AzureClient {
         Ok(results)
     }
 
+    /// THIS METHOD IS COMMON, MODIFIED BY ARAS
+    ///
     /// Make an Azure Copy request <https://docs.microsoft.com/en-us/rest/api/storageservices/copy-blob>
-    pub(crate) async fn copy_request(&self, from: &Path, to: &Path, overwrite: bool) -> Result<()> {
+    ///
+    /// Blobs larger than [`CopyConfig::multipart_threshold`] are copied with
+    /// [`Self::copy_blocks`] instead, if [`CopyConfig::probe_size`]. As Copy Blob accepts
+    /// blobs of any size, it is otherwise used regardless of the size of the source
+    pub(crate) async fn copy_request(
+        self: &Arc<Self>,
+        from: &Path,
+        version: Option<&str>,
+        to: &Path,
+        overwrite: bool,
+    ) -> Result<()> {
+        let copy_config = &self.config.copy_config;
+        if version.is_none() && copy_config.probe_size() {
+            let options = GetOptions {
+                head: true,
+                ..Default::default()
+            };
+            let source = self.get_opts(from, options).await?;
+            if copy_config.is_multipart(source.meta.size) {
+                return self.copy_blocks(from, to, source, overwrite).await;
+            }
+        }
+
         let credential = self.get_credential().await?;
         let url = self.config.path_url(to);
         let mut source = self.config.path_url(from);
 
+        if let Some(version) = version {
+            source.query_pairs_mut().append_pair("versionid", version);
+        }
+
         // If using SAS authorization must include the headers in the URL
         // <https://docs.microsoft.com/en-us/rest/api/storageservices/copy-blob#request-headers>
         if let Some(AzureCredential::SASToken(pairs)) = credential.as_deref() {
@@ -783,6 +1139,44 @@ impl AzureClient {
         Ok(())
     }
 
+    /// THIS METHOD IS ARAS ONLY
+    ///
+    /// Copy a blob by staging ranges of it with Put Block From URL, before committing
+    /// them with the properties and tags of the source. Unlike Copy Blob, which may
+    /// complete asynchronously, the copy is complete once this returns
+    async fn copy_blocks(
+        &self,
+        from: &Path,
+        to: &Path,
+        source: GetResult,
+        overwrite: bool,
+    ) -> Result<()> {
+        let copy_config = &self.config.copy_config;
+        let ranges =
+            copy_config.part_ranges(source.meta.size, 1, MAX_BLOCK_FROM_URL_SIZE, MAX_BLOCKS);
+        let parts = futures::stream::iter(ranges)
+            .map(|range| self.put_block_from_url(to, from, Some(range)))
+            .buffered(copy_config.max_concurrency.max(1))
+            .try_collect()
+            .await?;
+
+        let tags = match self.config.disable_tagging {
+            true => TagSet::default(),
+            false => self.get_blob_tagging(from).await?,
+        };
+        let opts = PutMultipartOpts {
+            tags,
+            attributes: source.attributes,
+            ..Default::default()
+        };
+        let mut builder = self.block_list_request(to, parts, opts);
+        if !overwrite {
+            builder = builder.header(&IF_NONE_MATCH, "*");
+        }
+        builder.idempotent(overwrite).send().await?;
+        Ok(())
+    }
+
     /// Make a Get User Delegation Key request
     /// <https://docs.microsoft.com/en-us/rest/api/storageservices/get-user-delegation-key>
     async fn get_user_delegation_key(
@@ -865,8 +1259,10 @@ impl AzureClient {
         }
     }
 
-    #[cfg(test)]
-    pub(crate) async fn get_blob_tagging(&self, path: &Path) -> Result<HttpResponse> {
+    /// THIS METHOD IS COMMON, MODIFIED BY ARAS
+    ///
+    /// Make an Azure Get Blob Tags request <https://learn.microsoft.com/en-us/rest/api/storageservices/get-blob-tags>
+    pub(crate) async fn get_blob_tagging(&self, path: &Path) -> Result<TagSet> {
         let credential = self.get_credential().await?;
         let url = self.config.path_url(path);
         let sensitive = credential
@@ -887,7 +1283,15 @@ impl AzureClient {
                 Error::GetRequest { source, path }
             })?;
 
-        Ok(response)
+        let data = response
+            .into_body()
+            .bytes()
+            .await
+            .map_err(|source| Error::TagsResponseBody { source })?;
+
+        let tags: Tagging = quick_xml::de::from_reader(data.reader())
+            .map_err(|source| Error::InvalidTagsResponse { source })?;
+        Ok(tags.into())
     }
 }
 
@@ -1043,6 +1447,170 @@ impl ListClient for Arc<AzureClient> {
     }
 }
 
+/// THIS IMPL IS ARAS ONLY
+#[async_trait]
+impl ListVersionsClient for Arc<AzureClient> {
+    /// Make an Azure List request including blob versions <https://learn.microsoft.com/en-us/rest/api/storageservices/list-blobs>
+    async fn list_versions_request(
+        &self,
+        prefix: Option<&str>,
+        page_token: Option<String>,
+    ) -> Result<(Vec<ObjectVersion>, Option<String>)> {
+        let credential = self.get_credential().await?;
+        let url = self.config.path_url(&Path::default());
+
+        let mut query = Vec::with_capacity(5);
+        query.push(("restype", "container"));
+        query.push(("comp", "list"));
+        query.push(("include", "versions"));
+
+        if let Some(prefix) = prefix {
+            query.push(("prefix", prefix))
+        }
+
+        if let Some(token) = &page_token {
+            query.push(("marker", token.as_ref()))
+        }
+
+        let sensitive = credential
+            .as_deref()
+            .map(|c| c.sensitive_request())
+            .unwrap_or_default();
+
+        let response = self
+            .client
+            .get(url.as_str())
+            .query(&query)
+            .with_azure_authorization(&credential, &self.config.account)
+            .retryable(&self.config.retry_config)
+            .sensitive(sensitive)
+            .send()
+            .await
+            .map_err(|source| Error::ListRequest { source })?
+            .into_body()
+            .bytes()
+            .await
+            .map_err(|source| Error::ListResponseBody { source })?;
+
+        let mut response: ListResultInternal = quick_xml::de::from_reader(response.reader())
+            .map_err(|source| Error::InvalidListResponse { source })?;
+
+        let token = response.next_marker.take().filter(|x| !x.is_empty());
+        let mut versions = response
+            .blobs
+            .blobs
+            .into_iter()
+            .filter(|blob| {
+                !matches!(blob.properties.resource_type.as_ref(), Some(typ) if typ == "directory")
+            })
+            .map(|blob| {
+                let version = blob.version_id.clone();
+                let is_latest = blob.is_current_version.unwrap_or_default();
+                let mut meta = ObjectMeta::try_from(blob)?;
+                meta.version = version;
+                Ok(ObjectVersion {
+                    meta,
+                    is_latest,
+                    is_delete_marker: false,
+                })
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        // Versions are listed oldest first, return the most recent version of each blob first
+        versions.sort_by(|a, b| {
+            a.meta
+                .location
+                .cmp(&b.meta.location)
+                .then(b.meta.version.cmp(&a.meta.version))
+        });
+        Ok((versions, token))
+    }
+}
+
+/// THIS IMPL IS ARAS ONLY
+#[async_trait]
+impl ListMultipartClient for Arc<AzureClient> {
+    /// Make an Azure List request including uncommitted blobs <https://learn.microsoft.com/en-us/rest/api/storageservices/list-blobs>
+    ///
+    /// Blobs with uncommitted blocks have no distinguishing properties, however, blobs that
+    /// have never been committed are empty, and so only the block lists of empty blobs are
+    /// requested. Uploads replacing a non-empty blob are therefore not listed
+    async fn list_multipart_uploads_request(
+        &self,
+        prefix: Option<&str>,
+        page_token: Option<String>,
+    ) -> Result<(Vec<MultipartUploadInfo>, Option<String>)> {
+        let credential = self.get_credential().await?;
+        let url = self.config.path_url(&Path::default());
+
+        let mut query = Vec::with_capacity(5);
+        query.push(("restype", "container"));
+        query.push(("comp", "list"));
+        query.push(("include", "uncommittedblobs"));
+
+        if let Some(prefix) = prefix {
+            query.push(("prefix", prefix))
+        }
+
+        if let Some(token) = &page_token {
+            query.push(("marker", token.as_ref()))
+        }
+
+        let sensitive = credential
+            .as_deref()
+            .map(|c| c.sensitive_request())
+            .unwrap_or_default();
+
+        let response = self
+            .client
+            .get(url.as_str())
+            .query(&query)
+            .with_azure_authorization(&credential, &self.config.account)
+            .retryable(&self.config.retry_config)
+            .sensitive(sensitive)
+            .send()
+            .await
+            .map_err(|source| Error::ListRequest { source })?
+            .into_body()
+            .bytes()
+            .await
+            .map_err(|source| Error::ListResponseBody { source })?;
+
+        let mut response: ListResultInternal = quick_xml::de::from_reader(response.reader())
+            .map_err(|source| Error::InvalidListResponse { source })?;
+
+        let token = response.next_marker.take().filter(|x| !x.is_empty());
+        let candidates = response
+            .blobs
+            .blobs
+            .into_iter()
+            .filter(|blob| {
+                blob.properties.content_length == 0
+                    && !matches!(blob.properties.resource_type.as_ref(), Some(typ) if typ == "directory")
+            })
+            .map(|blob| Ok((Path::parse(blob.name)?, blob.properties.last_modified)))
+            .collect::<Result<Vec<_>>>()?;
+
+        let uploads = futures::stream::iter(candidates)
+            .map(|(location, initiated)| async move {
+                match self.list_uncommitted_blocks(&location).await {
+                    Ok(blocks) if !blocks.is_empty() => Ok(Some(MultipartUploadInfo {
+                        location,
+                        id: MultipartId::new(),
+                        initiated: Some(initiated),
+                    })),
+                    Ok(_) | Err(crate::Error::NotFound { .. }) => Ok(None),
+                    Err(e) => Err(e),
+                }
+            })
+            .buffered(self.config.list_uploads_concurrency.max(1))
+            .try_filter_map(|upload| futures::future::ready(Ok(upload)))
+            .try_collect()
+            .await?;
+        Ok((uploads, token))
+    }
+}
+
 /// Raw / internal response from list requests
 #[derive(Debug, Clone, PartialEq, Deserialize)]
 #[serde(rename_all = "PascalCase")]
@@ -1172,6 +1740,70 @@ impl AsRef<[u8]> for BlockId {
     }
 }
 
+/// THIS FUNCTION IS ARAS ONLY
+///
+/// Returns the `part_idx` suffix of the `content_id` of a block staged by
+/// [`AzureClient::put_block`], or `None` if it was staged by another client
+fn block_part_idx(content_id: &str) -> Option<usize> {
+    if content_id.len() != 32 || !content_id.bytes().all(|b| b.is_ascii_hexdigit()) {
+        return None;
+    }
+    usize::from_str_radix(&content_id[24..], 16).ok()
+}
+
+/// THIS STRUCT IS ARAS ONLY
+///
+/// Response of <https://learn.microsoft.com/en-us/rest/api/storageservices/get-block-list>
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct BlockListResponse {
+    #[serde(default)]
+    uncommitted_blocks: Blocks,
+}
+
+/// THIS STRUCT IS ARAS ONLY
+#[derive(Debug, Default, Deserialize)]
+struct Blocks {
+    #[serde(rename = "Block", default)]
+    blocks: Vec<Block>,
+}
+
+/// THIS STRUCT IS ARAS ONLY
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct Block {
+    name: String,
+    size: u64,
+}
+
+impl BlockListResponse {
+    /// THIS METHOD IS ARAS ONLY
+    ///
+    /// Returns the uncommitted blocks as parts ordered by `part_idx`, blocks staged by other
+    /// clients are assigned the index of their position in the response, and blocks with
+    /// ids that cannot be represented by a [`PartId`] are skipped
+    fn into_parts(self) -> Vec<PartInfo> {
+        let mut parts: Vec<_> = self
+            .uncommitted_blocks
+            .blocks
+            .into_iter()
+            .enumerate()
+            .filter_map(|(position, block)| {
+                let id = BASE64_STANDARD.decode(block.name).ok()?;
+                let content_id = String::from_utf8(id).ok()?;
+                Some(PartInfo {
+                    part_idx: block_part_idx(&content_id).unwrap_or(position),
+                    part_id: PartId { content_id },
+                    size: block.size,
+                    last_modified: None,
+                })
+            })
+            .collect();
+        parts.sort_by_key(|part| part.part_idx);
+        parts
+    }
+}
+
 #[derive(Default, Debug, Clone, PartialEq, Eq)]
 pub(crate) struct BlockList {
     pub blocks: Vec<BlockId>,
@@ -1415,6 +2047,8 @@ mod tests {
             credentials: credential_provider,
             service: "http://example.com".try_into().unwrap(),
             retry_config: Default::default(),
+            copy_config: Default::default(),
+            list_uploads_concurrency: DEFAULT_LIST_UPLOADS_CONCURRENCY,
             is_emulator: false,
             skip_signature: false,
             disable_tagging: false,
@@ -1556,4 +2190,39 @@ Time:2018-06-14T16:46:54.6040685Z</Message></Error>\r
         assert_eq!("404", code);
         assert_eq!("The specified blob does not exist.", reason);
     }
+
+    /// THIS TEST IS ARAS ONLY
+    #[test]
+    fn test_uncommitted_blocks() {
+        let block = |content_id: &str, size: u64| {
+            let name = BASE64_STANDARD.encode(content_id);
+            format!("<Block><Name>{name}</Name><Size>{size}</Size></Block>")
+        };
+        let body = format!(
+            "<?xml version=\"1.0\" encoding=\"utf-8\"?>\
+            <BlockList><CommittedBlocks />\
+            <UncommittedBlocks>{}{}{}</UncommittedBlocks></BlockList>",
+            block("0123456789abcdef0123456700000001", 5),
+            block("0123456789abcdef0123456700000000", 10),
+            block("foreign", 3),
+        );
+        let response: BlockListResponse = quick_xml::de::from_str(&body).unwrap();
+        let parts: Vec<_> = response
+            .into_parts()
+            .into_iter()
+            .map(|p| (p.part_idx, p.part_id.content_id, p.size))
+            .collect();
+        assert_eq!(
+            parts,
+            vec![
+                (0, "0123456789abcdef0123456700000000".to_string(), 10),
+                (1, "0123456789abcdef0123456700000001".to_string(), 5),
+                (2, "foreign".to_string(), 3),
+            ]
+        );
+
+        let body = "<BlockList><CommittedBlocks /><UncommittedBlocks /></BlockList>";
+        let response: BlockListResponse = quick_xml::de::from_str(body).unwrap();
+        assert!(response.into_parts().is_empty());
+    }
 }
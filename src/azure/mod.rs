@@ -21,25 +21,38 @@
 //!
 //! [ObjectStore::put_multipart] will upload data in blocks and write a blob from those blocks.
 //!
-//! Unused blocks will automatically be dropped after 7 days.
+//! Unused blocks will automatically be dropped after 7 days. Blobs with unused blocks can
+//! be enumerated with [`MultipartStore::list_multipart_uploads`], but as blocks cannot be
+//! dropped before then, [`MultipartStore::abort_multipart`] does nothing, and
+//! [`abort_stale_multipart_uploads`] returns without aborting any uploads.
+//!
+//! [`abort_stale_multipart_uploads`]: crate::multipart::abort_stale_multipart_uploads
 use crate::{
-    multipart::{MultipartStore, PartId},
+    coalesce_ranges_opts,
+    multipart::{MultipartStore, MultipartUploadInfo, PartId, PartInfo},
     path::Path,
     signer::Signer,
-    GetOptions, GetResult, ListResult, MultipartId, MultipartUpload, ObjectMeta, ObjectStore,
-    PutMultipartOpts, PutOptions, PutPayload, PutResult, Result, UploadPart,
+    DeleteOptions, GetOptions, GetResult, ListResult, MultipartId, MultipartUpload, ObjectMeta,
+    ObjectStore, PutMode, PutMultipartOpts, PutOptions, PutPayload, PutResult, RangeCoalescePolicy,
+    Result, TagSet, TaggingStore, UploadPart,
 };
 use async_trait::async_trait;
+use bytes::Bytes;
 use futures::stream::{BoxStream, StreamExt, TryStreamExt};
 use reqwest::Method;
 use std::fmt::Debug;
+use std::ops::Range;
 use std::sync::Arc;
 use std::time::Duration;
 use url::Url;
 
 use crate::client::get::GetClientExt;
-use crate::client::list::{ListClient, ListClientExt};
+use crate::client::list::{
+    ListClient, ListClientExt, ListMultipartClientExt, ListVersionsClientExt,
+};
 use crate::client::CredentialProvider;
+use crate::upload::AppendUpload;
+use crate::version::{ObjectVersion, VersionedStore};
 pub use credential::{authority_hosts, AzureAccessKey, AzureAuthorizer};
 
 mod builder;
@@ -48,7 +61,7 @@ mod credential;
 
 /// [`CredentialProvider`] for [`MicrosoftAzure`]
 pub type AzureCredentialProvider = Arc<dyn CredentialProvider<Credential = AzureCredential>>;
-use crate::azure::client::AzureClient;
+use crate::azure::client::{AzureClient, MAX_BLOCK_FROM_URL_SIZE};
 use crate::client::parts::Parts;
 use crate::list::{PaginatedListOptions, PaginatedListResult, PaginatedListStore};
 pub use builder::{AzureConfigKey, MicrosoftAzureBuilder};
@@ -87,20 +100,36 @@ impl std::fmt::Display for MicrosoftAzure {
 
 #[async_trait]
 impl ObjectStore for MicrosoftAzure {
+    /// THIS METHOD IS COMMON, MODIFIED BY ARAS
     async fn put_opts(
         &self,
         location: &Path,
         payload: PutPayload,
         opts: PutOptions,
     ) -> Result<PutResult> {
-        self.client.put_blob(location, payload, opts).await
+        match opts.mode {
+            PutMode::Append => self.client.append_blob(location, payload, opts).await,
+            _ => self.client.put_blob(location, payload, opts).await,
+        }
     }
 
+    /// THIS METHOD IS COMMON, MODIFIED BY ARAS
     async fn put_multipart_opts(
         &self,
         location: &Path,
         opts: PutMultipartOpts,
     ) -> Result<Box<dyn MultipartUpload>> {
+        match opts.mode {
+            PutMode::Overwrite => {}
+            PutMode::Append => {
+                let store = Arc::new(Self {
+                    client: Arc::clone(&self.client),
+                });
+                return Ok(Box::new(AppendUpload::new(store, location.clone(), opts)));
+            }
+            PutMode::Create | PutMode::Update(_) => return Err(crate::Error::NotImplemented),
+        }
+
         Ok(Box::new(AzureMultiPartUpload {
             part_idx: 0,
             opts,
@@ -116,10 +145,41 @@ impl ObjectStore for MicrosoftAzure {
         self.client.get_opts(location, options).await
     }
 
+    /// THIS METHOD IS ARAS ONLY
+    async fn get_ranges(&self, location: &Path, ranges: &[Range<u64>]) -> Result<Vec<Bytes>> {
+        let policy = self.client.config().client_options.range_coalesce_policy();
+        self.get_ranges_opts(location, ranges, policy).await
+    }
+
+    /// THIS METHOD IS ARAS ONLY
+    async fn get_ranges_opts(
+        &self,
+        location: &Path,
+        ranges: &[Range<u64>],
+        policy: RangeCoalescePolicy,
+    ) -> Result<Vec<Bytes>> {
+        if self.client.config().client_options.multi_range_requests() {
+            return self.client.get_ranges_multi(location, ranges, policy).await;
+        }
+        coalesce_ranges_opts(ranges, |range| self.get_range(location, range), policy).await
+    }
+
     async fn delete(&self, location: &Path) -> Result<()> {
         self.client.delete_request(location, &()).await
     }
 
+    /// THIS METHOD IS ARAS ONLY
+    ///
+    /// Azure supports conditional deletes via `If-Match`, and therefore requires `if_match`,
+    /// returning [`crate::Error::NotImplemented`] if only `version` is provided
+    async fn delete_opts(&self, location: &Path, opts: DeleteOptions) -> Result<()> {
+        match (opts.if_match, opts.version) {
+            (None, None) => self.delete(location).await,
+            (Some(etag), _) => self.client.delete_if_match_request(location, &etag).await,
+            (None, Some(_)) => Err(crate::Error::NotImplemented),
+        }
+    }
+
     fn list(&self, prefix: Option<&Path>) -> BoxStream<'static, Result<ObjectMeta>> {
         self.client.list(prefix)
     }
@@ -148,11 +208,86 @@ impl ObjectStore for MicrosoftAzure {
     }
 
     async fn copy(&self, from: &Path, to: &Path) -> Result<()> {
-        self.client.copy_request(from, to, true).await
+        self.client.copy_request(from, None, to, true).await
     }
 
     async fn copy_if_not_exists(&self, from: &Path, to: &Path) -> Result<()> {
-        self.client.copy_request(from, to, false).await
+        self.client.copy_request(from, None, to, false).await
+    }
+
+    /// THIS METHOD IS ARAS ONLY
+    ///
+    /// Stages the sources as blocks with Put Block From URL, splitting sources larger
+    /// than the maximum block size into ranges, before committing them with Put Block List
+    async fn compose(&self, sources: &[Path], dest: &Path) -> Result<PutResult> {
+        let heads = futures::future::try_join_all(sources.iter().map(|s| self.head(s))).await?;
+
+        let mut blocks = vec![];
+        for (source, meta) in sources.iter().zip(heads) {
+            // Empty sources contribute no data, and zero-length blocks are rejected
+            if meta.size <= MAX_BLOCK_FROM_URL_SIZE {
+                if meta.size > 0 {
+                    blocks.push((source.clone(), None));
+                }
+                continue;
+            }
+            let mut start = 0;
+            while start < meta.size {
+                let end = meta.size.min(start + MAX_BLOCK_FROM_URL_SIZE);
+                blocks.push((source.clone(), Some(start..end)));
+                start = end;
+            }
+        }
+
+        let concurrency = self.client.config().copy_config.max_concurrency;
+        let parts = futures::stream::iter(blocks)
+            .map(|(source, range)| async move {
+                self.client.put_block_from_url(dest, &source, range).await
+            })
+            .buffered(concurrency.max(1))
+            .try_collect()
+            .await?;
+        self.client
+            .put_block_list(dest, parts, PutMultipartOpts::default())
+            .await
+    }
+}
+
+/// THIS IMPL IS ARAS ONLY
+///
+/// Requires [blob versioning] to be enabled on the storage account. Azure does not
+/// record delete markers, instead a deleted blob has no current version
+///
+/// [blob versioning]: https://learn.microsoft.com/en-us/azure/storage/blobs/versioning-overview
+#[async_trait]
+impl VersionedStore for MicrosoftAzure {
+    fn list_versions(&self, prefix: Option<&Path>) -> BoxStream<'static, Result<ObjectVersion>> {
+        self.client.list_versions(prefix)
+    }
+
+    async fn delete_version(&self, location: &Path, version: &str) -> Result<()> {
+        self.client.delete_version_request(location, version).await
+    }
+
+    async fn copy_version(&self, from: &Path, version: &str, to: &Path) -> Result<()> {
+        self.client
+            .copy_request(from, Some(version), to, true)
+            .await
+    }
+}
+
+/// THIS IMPL IS ARAS ONLY
+///
+/// If tagging is disabled with [`AzureConfigKey::DisableTagging`], tags are not
+/// updated, consistent with tags being ignored by puts
+#[async_trait]
+impl TaggingStore for MicrosoftAzure {
+    async fn get_tags(&self, location: &Path) -> Result<TagSet> {
+        self.client.get_blob_tagging(location).await
+    }
+
+    async fn put_tags(&self, location: &Path, tags: TagSet) -> Result<()> {
+        self.client.set_blob_tags(location, &tags).await
     }
 }
 
@@ -291,6 +426,29 @@ impl MultipartStore for MicrosoftAzure {
         // expire in 7 days.
         Ok(())
     }
+
+    /// THIS METHOD IS ARAS ONLY
+    fn abort_removes_parts(&self) -> bool {
+        false
+    }
+
+    /// THIS METHOD IS ARAS ONLY
+    ///
+    /// Lists blobs with uncommitted blocks that have never been committed, with an empty
+    /// [`MultipartId`] as Azure has no upload ids
+    fn list_multipart_uploads(
+        &self,
+        prefix: Option<&Path>,
+    ) -> BoxStream<'static, Result<MultipartUploadInfo>> {
+        self.client.list_multipart_uploads(prefix)
+    }
+
+    /// THIS METHOD IS ARAS ONLY
+    ///
+    /// Lists the uncommitted blocks of the blob at `path`
+    async fn list_parts(&self, path: &Path, _: &MultipartId) -> Result<Vec<PartInfo>> {
+        self.client.list_uncommitted_blocks(path).await
+    }
 }
 
 #[async_trait]
@@ -406,4 +564,74 @@ mod tests {
             azure_storage_token
         );
     }
+
+    /// THIS TEST IS ARAS ONLY
+    #[tokio::test]
+    async fn azure_test_copy() {
+        use crate::client::mock_server::MockServer;
+        use http::{Method, Response};
+
+        let server = MockServer::new().await;
+        let store = MicrosoftAzureBuilder::new()
+            .with_account("account")
+            .with_container_name("container")
+            .with_access_key("dGVzdA==")
+            .with_endpoint(server.url().to_string())
+            .with_allow_http(true)
+            .build()
+            .unwrap();
+
+        // By default blobs are copied with a single request
+        server.push_fn(|req| {
+            assert_eq!(req.method(), Method::PUT);
+            assert!(req.uri().path().ends_with("/container/to"), "{}", req.uri());
+            let source = req.headers()["x-ms-copy-source"].to_str().unwrap();
+            assert!(source.ends_with("/container/from"), "{source}");
+            Response::new(String::new())
+        });
+        store
+            .copy(&Path::from("from"), &Path::from("to"))
+            .await
+            .unwrap();
+        server.shutdown().await;
+    }
+
+    /// THIS TEST IS ARAS ONLY
+    #[tokio::test]
+    async fn azure_test_delete_opts() {
+        use crate::client::mock_server::MockServer;
+        use http::{Method, Response};
+
+        let server = MockServer::new().await;
+        let store = MicrosoftAzureBuilder::new()
+            .with_account("account")
+            .with_container_name("container")
+            .with_access_key("dGVzdA==")
+            .with_endpoint(server.url().to_string())
+            .with_allow_http(true)
+            .build()
+            .unwrap();
+        let path = Path::from("a");
+
+        // A version alone is not supported, and sends no request
+        let opts = DeleteOptions {
+            version: Some("1".to_string()),
+            ..Default::default()
+        };
+        let err = store.delete_opts(&path, opts).await.unwrap_err();
+        assert!(matches!(err, crate::Error::NotImplemented), "{err}");
+
+        server.push_fn(|req| {
+            assert_eq!(req.method(), Method::DELETE);
+            assert_eq!(req.headers()["if-match"], "\"e\"");
+            Response::new(String::new())
+        });
+        let opts = DeleteOptions {
+            if_match: Some("\"e\"".to_string()),
+            version: Some("1".to_string()),
+            ..Default::default()
+        };
+        store.delete_opts(&path, opts).await.unwrap();
+        server.shutdown().await;
+    }
 }
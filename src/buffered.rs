@@ -19,14 +19,15 @@
 
 use crate::path::Path;
 use crate::{
-    Attributes, ObjectMeta, ObjectStore, PutMultipartOpts, PutOptions, PutPayloadMut, TagSet,
-    WriteMultipart,
+    Attributes, GetOptions, ObjectMeta, ObjectStore, PutMode, PutMultipartOpts, PutOptions,
+    PutPayloadMut, TagSet, WriteMultipart,
 };
 use bytes::Bytes;
 use futures::future::{BoxFuture, FutureExt};
 use futures::ready;
-use std::cmp::Ordering;
+use std::collections::VecDeque;
 use std::io::{Error, ErrorKind, SeekFrom};
+use std::ops::Range;
 use std::pin::Pin;
 use std::sync::Arc;
 use std::task::{Context, Poll};
@@ -35,11 +36,22 @@ use tokio::io::{AsyncBufRead, AsyncRead, AsyncSeek, AsyncWrite, ReadBuf};
 /// The default buffer size used by [`BufReader`]
 pub const DEFAULT_BUFFER_SIZE: usize = 1024 * 1024;
 
+/// THIS STRUCT IS COMMON, MODIFIED BY ARAS
+///
 /// An async-buffered reader compatible with the tokio IO traits
 ///
 /// Internally this maintains a buffer of the requested size, and uses [`ObjectStore::get_range`]
 /// to populate its internal buffer once depleted. This buffer is cleared on seek.
 ///
+/// A read-ahead window can be configured with [`BufReader::with_read_ahead`], in which case
+/// up to that many subsequent blocks of `capacity` bytes are fetched concurrently with the
+/// block being read, and the same number of already consumed blocks are retained, so that
+/// short seeks in either direction are served without further requests.
+///
+/// If the [`ObjectMeta`] the reader was created from has an [`ObjectMeta::e_tag`], it is sent
+/// with every request as [`GetOptions::if_match`], so that an object overwritten whilst being
+/// read results in an error, instead of data from different versions of the object.
+///
 /// Whilst simple, this interface will typically be outperformed by the native [`ObjectStore`]
 /// methods that better map to the network APIs. This is because most object stores have
 /// very [high first-byte latencies], on the order of 100-200ms, and so avoiding unnecessary
@@ -59,26 +71,48 @@ pub struct BufReader {
     size: u64,
     /// The path to the object
     path: Path,
+    /// THIS FIELD IS ARAS ONLY
+    ///
+    /// The e-tag of the object, if any, which all requests must match
+    e_tag: Option<String>,
     /// The current position in the object
     cursor: u64,
     /// The number of bytes to read in a single request
     capacity: usize,
-    /// The buffered data if any
-    buffer: Buffer,
+    /// THIS FIELD IS ARAS ONLY
+    ///
+    /// The number of blocks to fetch ahead of, and retain behind, the cursor
+    read_ahead: usize,
+    /// THIS FIELD IS COMMON, MODIFIED BY ARAS
+    ///
+    /// The buffered blocks if any, covering a contiguous range of the object
+    blocks: VecDeque<Block>,
 }
 
 impl std::fmt::Debug for BufReader {
+    /// THIS METHOD IS COMMON, MODIFIED BY ARAS
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         f.debug_struct("BufReader")
             .field("path", &self.path)
             .field("size", &self.size)
             .field("capacity", &self.capacity)
+            .field("read_ahead", &self.read_ahead)
             .finish()
     }
 }
 
+/// THIS STRUCT IS ARAS ONLY
+///
+/// A block of the object buffered by [`BufReader`]
+struct Block {
+    /// The range of the object covered by this block
+    range: Range<u64>,
+    /// The data of this block
+    buffer: Buffer,
+}
+
+/// THIS ENUM IS COMMON, MODIFIED BY ARAS
 enum Buffer {
-    Empty,
     Pending(BoxFuture<'static, std::io::Result<Bytes>>),
     Ready(Bytes),
 }
@@ -89,51 +123,146 @@ impl BufReader {
         Self::with_capacity(store, meta, DEFAULT_BUFFER_SIZE)
     }
 
+    /// THIS METHOD IS COMMON, MODIFIED BY ARAS
+    ///
     /// Create a new [`BufReader`] from the provided [`ObjectMeta`], [`ObjectStore`], and `capacity`
     pub fn with_capacity(store: Arc<dyn ObjectStore>, meta: &ObjectMeta, capacity: usize) -> Self {
         Self {
             path: meta.location.clone(),
             size: meta.size as _,
+            e_tag: meta.e_tag.clone(),
             store,
             capacity,
+            read_ahead: 0,
             cursor: 0,
-            buffer: Buffer::Empty,
+            blocks: VecDeque::new(),
+        }
+    }
+
+    /// THIS METHOD IS ARAS ONLY
+    ///
+    /// Fetch up to `blocks` blocks of `capacity` bytes ahead of the block being read
+    /// concurrently, and retain up to `blocks` already consumed blocks for backward seeks
+    ///
+    /// Defaults to 0
+    pub fn with_read_ahead(self, blocks: usize) -> Self {
+        Self {
+            read_ahead: blocks,
+            ..self
         }
     }
 
+    /// THIS METHOD IS ARAS ONLY
+    ///
+    /// Returns a [`Block`] fetching `range` of the object
+    fn fetch(&self, range: Range<u64>) -> Block {
+        let store = Arc::clone(&self.store);
+        let path = self.path.clone();
+        let e_tag = self.e_tag.clone();
+        let r = range.clone();
+        let fut = async move {
+            let bytes = match e_tag {
+                Some(e_tag) => {
+                    let options = GetOptions {
+                        range: Some(r.into()),
+                        if_match: Some(e_tag),
+                        ..Default::default()
+                    };
+                    store.get_opts(&path, options).await?.bytes().await?
+                }
+                None => store.get_range(&path, r).await?,
+            };
+            Ok(bytes)
+        };
+        Block {
+            range,
+            buffer: Buffer::Pending(Box::pin(fut)),
+        }
+    }
+
+    /// THIS METHOD IS ARAS ONLY
+    ///
+    /// Discards consumed blocks beyond the read-ahead window
+    fn trim(&mut self) {
+        let behind = self
+            .blocks
+            .iter()
+            .take_while(|b| b.range.end <= self.cursor)
+            .count();
+        for _ in self.read_ahead..behind {
+            self.blocks.pop_front();
+        }
+    }
+
+    /// THIS METHOD IS COMMON, MODIFIED BY ARAS
     fn poll_fill_buf_impl(
         &mut self,
         cx: &mut Context<'_>,
         amnt: usize,
     ) -> Poll<std::io::Result<&[u8]>> {
-        let buf = &mut self.buffer;
-        loop {
-            match buf {
-                Buffer::Empty => {
-                    let store = Arc::clone(&self.store);
-                    let path = self.path.clone();
-                    let start = self.cursor.min(self.size) as _;
-                    let end = self.cursor.saturating_add(amnt as u64).min(self.size) as _;
+        let start = self.cursor.min(self.size);
+        if start == self.size {
+            return Poll::Ready(Ok(&[]));
+        }
 
-                    if start == end {
-                        return Poll::Ready(Ok(&[]));
-                    }
+        self.trim();
+        if self.blocks.is_empty() {
+            let end = self.cursor.saturating_add(amnt as u64).min(self.size);
+            let block = self.fetch(start..end);
+            self.blocks.push_back(block);
+        }
 
-                    *buf = Buffer::Pending(Box::pin(async move {
-                        Ok(store.get_range(&path, start..end).await?)
-                    }))
+        // Schedule the blocks of the read-ahead window
+        let current = self
+            .blocks
+            .iter()
+            .position(|b| b.range.end > self.cursor)
+            .unwrap_or(self.blocks.len());
+        while self.blocks.len() - current <= self.read_ahead {
+            let start = self.blocks.back().unwrap().range.end;
+            if start == self.size {
+                break;
+            }
+            let end = start.saturating_add(self.capacity as u64).min(self.size);
+            let block = self.fetch(start..end);
+            self.blocks.push_back(block);
+        }
+
+        // Drive all pending blocks, discarding read-ahead blocks that failed so
+        // that they will be requested again, and any error returned, once needed
+        let mut idx = current;
+        while idx < self.blocks.len() {
+            if let Buffer::Pending(fut) = &mut self.blocks[idx].buffer {
+                match fut.poll_unpin(cx) {
+                    Poll::Ready(Ok(b)) => self.blocks[idx].buffer = Buffer::Ready(b),
+                    // The failed block is requested again if read after the error
+                    Poll::Ready(Err(e)) if idx == current => {
+                        self.blocks.truncate(idx);
+                        return Poll::Ready(Err(e));
+                    }
+                    Poll::Ready(Err(_)) => {
+                        self.blocks.truncate(idx);
+                        break;
+                    }
+                    Poll::Pending => {}
                 }
-                Buffer::Pending(fut) => match ready!(fut.poll_unpin(cx)) {
-                    Ok(b) => *buf = Buffer::Ready(b),
-                    Err(e) => return Poll::Ready(Err(e)),
-                },
-                Buffer::Ready(r) => return Poll::Ready(Ok(r)),
             }
+            idx += 1;
+        }
+
+        let block = &self.blocks[current];
+        match &block.buffer {
+            Buffer::Ready(b) => {
+                let offset = (self.cursor - block.range.start) as usize;
+                Poll::Ready(Ok(&b[offset..]))
+            }
+            Buffer::Pending(_) => Poll::Pending,
         }
     }
 }
 
 impl AsyncSeek for BufReader {
+    /// THIS METHOD IS COMMON, MODIFIED BY ARAS
     fn start_seek(mut self: Pin<&mut Self>, position: SeekFrom) -> std::io::Result<()> {
         self.cursor = match position {
             SeekFrom::Start(offset) => offset,
@@ -158,7 +287,17 @@ impl AsyncSeek for BufReader {
                 })?
             }
         };
-        self.buffer = Buffer::Empty;
+
+        // Retain the buffered blocks only if they contain the new position
+        let retain = match (self.blocks.front(), self.blocks.back()) {
+            (Some(front), Some(back)) if self.read_ahead > 0 => {
+                front.range.start <= self.cursor && self.cursor < back.range.end
+            }
+            _ => false,
+        };
+        if !retain {
+            self.blocks.clear();
+        }
         Ok(())
     }
 
@@ -194,17 +333,27 @@ impl AsyncBufRead for BufReader {
         self.get_mut().poll_fill_buf_impl(cx, capacity)
     }
 
+    /// THIS METHOD IS COMMON, MODIFIED BY ARAS
     fn consume(mut self: Pin<&mut Self>, amt: usize) {
-        match &mut self.buffer {
-            Buffer::Empty => assert_eq!(amt, 0, "cannot consume from empty buffer"),
-            Buffer::Ready(b) => match b.len().cmp(&amt) {
-                Ordering::Less => panic!("{amt} exceeds buffer sized of {}", b.len()),
-                Ordering::Greater => *b = b.slice(amt..),
-                Ordering::Equal => self.buffer = Buffer::Empty,
-            },
-            Buffer::Pending(_) => panic!("cannot consume from pending buffer"),
+        let cursor = self.cursor;
+        match self.blocks.iter().find(|b| b.range.end > cursor) {
+            None => assert_eq!(amt, 0, "cannot consume from empty buffer"),
+            Some(Block {
+                buffer: Buffer::Ready(b),
+                range,
+            }) => {
+                let len = b.len() - (cursor - range.start) as usize;
+                if len < amt {
+                    panic!("{amt} exceeds buffer sized of {len}")
+                }
+            }
+            Some(Block {
+                buffer: Buffer::Pending(_),
+                ..
+            }) => panic!("cannot consume from pending buffer"),
         }
         self.cursor += amt as u64;
+        self.trim();
     }
 }
 
@@ -379,7 +528,10 @@ impl BufWriter {
                             attributes: self.attributes.take().unwrap_or_default(),
                             tags: self.tags.take().unwrap_or_default(),
                             extensions: self.extensions.take().unwrap_or_default(),
-                            copy_and_append: prepare_after_flush,
+                            mode: match prepare_after_flush {
+                                true => PutMode::Append,
+                                false => PutMode::Overwrite,
+                            },
                         };
                         let upload = self.store.put_multipart_opts(&path, opts).await?;
                         let mut chunked =
@@ -446,7 +598,10 @@ impl AsyncWrite for BufWriter {
                             attributes: self.attributes.take().unwrap_or_default(),
                             tags: self.tags.take().unwrap_or_default(),
                             extensions: self.extensions.take().unwrap_or_default(),
-                            copy_and_append: prepare_after_flush,
+                            mode: match prepare_after_flush {
+                                true => PutMode::Append,
+                                false => PutMode::Overwrite,
+                            },
                         };
                         let store = Arc::clone(&self.store);
                         self.state = BufWriterState::Prepare(Box::pin(async move {
@@ -491,9 +646,9 @@ impl AsyncWrite for BufWriter {
                         let buf = std::mem::take(b);
                         let path = std::mem::take(p);
                         let opts = PutOptions {
+                            mode: PutMode::Append,
                             attributes: self.attributes.take().unwrap_or_default(),
                             tags: self.tags.take().unwrap_or_default(),
-                            copy_and_append: true,
                             ..Default::default()
                         };
 
@@ -540,7 +695,7 @@ impl AsyncWrite for BufWriter {
                         let opts = PutMultipartOpts {
                             attributes: self.attributes.clone().unwrap_or_default(),
                             tags: self.tags.clone().unwrap_or_default(),
-                            copy_and_append: true,
+                            mode: PutMode::Append,
                             extensions: self.extensions.take().unwrap_or_default(),
                         };
                         let store = Arc::clone(&self.store);
@@ -575,10 +730,14 @@ impl AsyncWrite for BufWriter {
                 BufWriterState::Buffer(p, b) => {
                     let buf = std::mem::take(b);
                     let path = std::mem::take(p);
+                    let mode = match self.prepare_after_flush {
+                        true => PutMode::Append,
+                        false => PutMode::Overwrite,
+                    };
                     let opts = PutOptions {
+                        mode,
                         attributes: self.attributes.take().unwrap_or_default(),
                         tags: self.tags.take().unwrap_or_default(),
-                        copy_and_append: true,
                         ..Default::default()
                     };
                     let store = Arc::clone(&self.store);
@@ -628,6 +787,12 @@ mod tests {
     use tokio::io::{AsyncBufReadExt, AsyncReadExt, AsyncSeekExt, AsyncWriteExt};
 
     // THESE IMPORTS ARE ARAS ONLY
+    use crate::{
+        GetResult, ListResult, MultipartUpload, PutMultipartOpts, PutOptions, PutPayload,
+        PutResult, Result,
+    };
+    use async_trait::async_trait;
+    use futures::stream::BoxStream;
     use std::fs::File;
     use std::io::Read;
 
@@ -710,6 +875,154 @@ mod tests {
         }
     }
 
+    /// THIS TEST IS ARAS ONLY
+    #[tokio::test]
+    async fn test_buf_reader_read_ahead() {
+        let store = Arc::new(InMemory::new()) as Arc<dyn ObjectStore>;
+
+        let path = Path::from("read_ahead.txt");
+        const BYTES: usize = 4096;
+
+        let data: Bytes = b"12345678".iter().cycle().copied().take(BYTES).collect();
+        store.put(&path, data.clone().into()).await.unwrap();
+        let meta = store.head(&path).await.unwrap();
+
+        for (capacity, read_ahead) in [(200, 1), (256, 3), (1024, 8), (4096, 2)] {
+            let store = Arc::clone(&store);
+            let mut reader =
+                BufReader::with_capacity(store, &meta, capacity).with_read_ahead(read_ahead);
+
+            let mut out = Vec::with_capacity(BYTES);
+            reader.read_to_end(&mut out).await.unwrap();
+            assert_eq!(&out, &data);
+
+            let mut buf = vec![0; 76];
+            reader.seek(SeekFrom::Current(-100)).await.unwrap();
+            reader.read_exact(&mut buf).await.unwrap();
+            assert_eq!(&buf, &data[BYTES - 100..BYTES - 24]);
+
+            reader.seek(SeekFrom::Start(325)).await.unwrap();
+            reader.read_exact(&mut buf).await.unwrap();
+            assert_eq!(&buf, &data[325..401]);
+        }
+
+        let mut reader =
+            BufReader::with_capacity(Arc::clone(&store), &meta, 256).with_read_ahead(2);
+        let buf = reader.fill_buf().await.unwrap();
+        assert_eq!(buf, &data[..256]);
+        reader.consume(256);
+        let buf = reader.fill_buf().await.unwrap();
+        assert_eq!(buf, &data[256..512]);
+
+        // Overwrite the object, the already fetched blocks remain readable
+        store.put(&path, "changed".into()).await.unwrap();
+
+        reader.rewind().await.unwrap();
+        let mut buf = vec![0; 1024];
+        reader.read_exact(&mut buf).await.unwrap();
+        assert_eq!(&buf, &data[..1024]);
+
+        // But reading beyond them detects the object was modified
+        let err = reader.fill_buf().await.unwrap_err();
+        let err = err.into_inner().unwrap();
+        let err = err.downcast_ref::<crate::Error>().unwrap();
+        assert!(matches!(err, crate::Error::Precondition { .. }), "{err}");
+
+        // As does a new reader created from the stale metadata
+        let mut reader = BufReader::new(Arc::clone(&store), &meta);
+        reader.fill_buf().await.unwrap_err();
+    }
+
+    /// THIS STRUCT IS ARAS ONLY
+    ///
+    /// A store failing the next request to get an object
+    #[derive(Debug)]
+    struct FailingStore {
+        inner: InMemory,
+        fail: std::sync::atomic::AtomicBool,
+    }
+
+    impl std::fmt::Display for FailingStore {
+        fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+            write!(f, "FailingStore")
+        }
+    }
+
+    #[async_trait]
+    impl ObjectStore for FailingStore {
+        async fn put_opts(
+            &self,
+            location: &Path,
+            payload: PutPayload,
+            opts: PutOptions,
+        ) -> Result<PutResult> {
+            self.inner.put_opts(location, payload, opts).await
+        }
+
+        async fn put_multipart_opts(
+            &self,
+            location: &Path,
+            opts: PutMultipartOpts,
+        ) -> Result<Box<dyn MultipartUpload>> {
+            self.inner.put_multipart_opts(location, opts).await
+        }
+
+        async fn get_opts(&self, location: &Path, options: GetOptions) -> Result<GetResult> {
+            if self.fail.swap(false, std::sync::atomic::Ordering::SeqCst) {
+                return Err(crate::Error::Generic {
+                    store: "FailingStore",
+                    source: "injected failure".into(),
+                });
+            }
+            self.inner.get_opts(location, options).await
+        }
+
+        async fn delete(&self, location: &Path) -> Result<()> {
+            self.inner.delete(location).await
+        }
+
+        fn list(&self, prefix: Option<&Path>) -> BoxStream<'static, Result<ObjectMeta>> {
+            self.inner.list(prefix)
+        }
+
+        async fn list_with_delimiter(&self, prefix: Option<&Path>) -> Result<ListResult> {
+            self.inner.list_with_delimiter(prefix).await
+        }
+
+        async fn copy(&self, from: &Path, to: &Path) -> Result<()> {
+            self.inner.copy(from, to).await
+        }
+
+        async fn copy_if_not_exists(&self, from: &Path, to: &Path) -> Result<()> {
+            self.inner.copy_if_not_exists(from, to).await
+        }
+    }
+
+    /// THIS TEST IS ARAS ONLY
+    #[tokio::test]
+    async fn test_buf_reader_retry() {
+        let store = Arc::new(FailingStore {
+            inner: InMemory::new(),
+            fail: Default::default(),
+        });
+
+        let path = Path::from("retry.txt");
+        let data: Bytes = b"12345678".iter().cycle().copied().take(1024).collect();
+        store.put(&path, data.clone().into()).await.unwrap();
+        let meta = store.head(&path).await.unwrap();
+
+        let mut reader =
+            BufReader::with_capacity(Arc::clone(&store) as _, &meta, 256).with_read_ahead(2);
+        store.fail.store(true, std::sync::atomic::Ordering::SeqCst);
+        reader.fill_buf().await.unwrap_err();
+
+        // Seeking back after the error retries the failed request
+        reader.rewind().await.unwrap();
+        let mut out = Vec::new();
+        reader.read_to_end(&mut out).await.unwrap();
+        assert_eq!(out, data);
+    }
+
     // Note: `BufWriter::with_tags` functionality is tested in `crate::tests::tagging`
     #[tokio::test]
     async fn test_buf_writer() {
@@ -905,6 +1218,35 @@ mod tests {
         }
     }
 
+    /// THIS TEST IS ARAS ONLY
+    #[tokio::test]
+    async fn test_buf_writer_prepare_after_flush_in_memory() {
+        let store = Arc::new(InMemory::new()) as Arc<dyn ObjectStore>;
+        let location = Path::from("append.json");
+        store.put(&location, "existing\n".into()).await.unwrap();
+
+        let mut writer = BufWriter::with_capacity(Arc::clone(&store), location.clone(), 3)
+            .with_prepare_after_flush(true);
+        writer.write_all(b"1\n2\n").await.unwrap();
+        writer.flush().await.unwrap();
+        writer.write_all(b"3\n").await.unwrap();
+        writer.shutdown().await.unwrap();
+
+        let response = store.get(&location).await.unwrap();
+        assert_eq!(
+            response.bytes().await.unwrap().as_ref(),
+            b"existing\n1\n2\n3\n"
+        );
+
+        // Without prepare_after_flush the writer replaces the object
+        let mut writer = BufWriter::new(Arc::clone(&store), location.clone());
+        writer.write_all(b"4\n").await.unwrap();
+        writer.shutdown().await.unwrap();
+
+        let response = store.get(&location).await.unwrap();
+        assert_eq!(response.bytes().await.unwrap().as_ref(), b"4\n");
+    }
+
     fn read_file_contents(path: &str) -> std::io::Result<String> {
         let file = File::open(path)?;
         let mut reader = std::io::BufReader::new(file);
@@ -0,0 +1,854 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! THIS MODULE IS ARAS ONLY
+//!
+//! An object store wrapper that caches the data read from the wrapped implementation
+
+use crate::{
+    Attributes, BoxStream, DeleteOptions, Error, GetOptions, GetRange, GetResult, GetResultPayload,
+    ListResult, MultipartUpload, ObjectMeta, ObjectStore, Path, PutMultipartOpts, PutOptions,
+    PutPayload, PutResult, Result, StreamExt, UploadPart,
+};
+use async_trait::async_trait;
+use bytes::{Bytes, BytesMut};
+use futures::future::{join_all, try_join_all};
+use parking_lot::Mutex;
+use std::collections::{BTreeMap, HashMap};
+use std::ops::Range;
+use std::sync::Arc;
+
+/// The configuration for a [`CachingStore`]
+#[derive(Debug, Clone, Copy)]
+pub struct CacheConfig {
+    /// Objects of at most this many bytes are cached whole, larger objects are
+    /// cached in blocks of [`Self::block_size`] bytes
+    pub whole_object_threshold: u64,
+
+    /// The size in bytes of the aligned blocks objects larger than
+    /// [`Self::whole_object_threshold`] are cached in
+    pub block_size: u64,
+
+    /// The maximum number of bytes to cache, beyond which the least recently
+    /// used entries are evicted
+    pub capacity: u64,
+}
+
+impl Default for CacheConfig {
+    fn default() -> Self {
+        Self {
+            whole_object_threshold: 4 * 1024 * 1024,
+            block_size: 1024 * 1024,
+            capacity: 1024 * 1024 * 1024,
+        }
+    }
+}
+
+/// Store wrapper that caches the data read from an inner store in another store,
+/// typically a [`LocalFileSystem`] directory or [`InMemory`]
+///
+/// Objects of at most [`CacheConfig::whole_object_threshold`] bytes are cached whole,
+/// larger objects are cached in aligned blocks of [`CacheConfig::block_size`] bytes,
+/// with any request for a range missing a block fetching all the blocks it spans.
+/// Once the cache holds more than [`CacheConfig::capacity`] bytes, the least
+/// recently used entries are evicted. Responses larger than the capacity, or larger
+/// than the whole object threshold without containing a complete block, are returned
+/// unchanged without being buffered or cached.
+///
+/// Cached entries are validated before use with a conditional `HEAD` request using
+/// `If-None-Match` with the e-tag of the cached object, and objects without an e-tag
+/// are not cached. Entries are additionally invalidated when written, deleted, copied
+/// to or renamed through this store.
+///
+/// Requests with preconditions, for a specific version, or for only the metadata of
+/// an object are passed through to the inner store without being cached.
+///
+/// The index of cached entries is held in memory, the cache store should therefore be
+/// dedicated to a single [`CachingStore`] and any existing contents may be overwritten
+///
+/// ```
+/// # use std::sync::Arc;
+/// # use object_store::cache::{CacheConfig, CachingStore};
+/// # use object_store::local::LocalFileSystem;
+/// # use object_store::memory::InMemory;
+/// # let dir = tempfile::tempdir().unwrap();
+/// // Cache reads from an in-memory `ObjectStore` in a local directory
+/// let cache = Arc::new(LocalFileSystem::new_with_prefix(dir.path()).unwrap());
+/// let store = CachingStore::new(InMemory::new(), cache, CacheConfig::default());
+/// ```
+///
+/// [`LocalFileSystem`]: crate::local::LocalFileSystem
+/// [`InMemory`]: crate::memory::InMemory
+#[derive(Debug)]
+pub struct CachingStore<T: ObjectStore> {
+    inner: Arc<T>,
+    cache: Arc<Cache>,
+}
+
+impl<T: ObjectStore> CachingStore<T> {
+    /// Create a new [`CachingStore`] caching the data read from `inner` in `cache`
+    pub fn new(inner: T, cache: Arc<dyn ObjectStore>, config: CacheConfig) -> Self {
+        Self {
+            inner: Arc::new(inner),
+            cache: Arc::new(Cache {
+                store: cache,
+                config,
+                state: Default::default(),
+            }),
+        }
+    }
+
+    /// Returns the number of bytes currently cached
+    pub fn cached_bytes(&self) -> u64 {
+        self.cache.state.lock().size
+    }
+
+    /// Returns true if a cached entry passes revalidation against the inner store
+    async fn revalidate(&self, location: &Path, meta: &ObjectMeta) -> Result<bool> {
+        let options = GetOptions {
+            if_none_match: meta.e_tag.clone(),
+            head: true,
+            ..Default::default()
+        };
+        match self.inner.get_opts(location, options).await {
+            Ok(_) => Ok(false),
+            Err(Error::NotModified { .. }) => Ok(true),
+            Err(e) => {
+                if matches!(e, Error::NotFound { .. }) {
+                    self.cache.invalidate(location).await;
+                }
+                Err(e)
+            }
+        }
+    }
+
+    /// Fetches `options` from the inner store, caching any blocks fully contained
+    /// in the returned range
+    ///
+    /// Responses too large to be cached are returned unchanged, without buffering
+    async fn fetch(&self, location: &Path, options: GetOptions) -> Result<GetResult> {
+        let result = self.inner.get_opts(location, options).await?;
+        if result.meta.e_tag.is_none() || !self.cache.is_cacheable(&result.meta, &result.range) {
+            return Ok(result);
+        }
+
+        let meta = result.meta.clone();
+        let attributes = result.attributes.clone();
+        let range = result.range.clone();
+        let data = result.bytes().await?;
+        self.cache
+            .insert(location, &meta, &attributes, range.clone(), data.clone())
+            .await;
+        Ok(cached_result(meta, attributes, range, data))
+    }
+}
+
+impl<T: ObjectStore> std::fmt::Display for CachingStore<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "CachingStore({})", self.inner)
+    }
+}
+
+#[async_trait]
+impl<T: ObjectStore> ObjectStore for CachingStore<T> {
+    async fn put(&self, location: &Path, payload: PutPayload) -> Result<PutResult> {
+        let r = self.inner.put(location, payload).await;
+        self.cache.invalidate(location).await;
+        r
+    }
+
+    async fn put_opts(
+        &self,
+        location: &Path,
+        payload: PutPayload,
+        opts: PutOptions,
+    ) -> Result<PutResult> {
+        let r = self.inner.put_opts(location, payload, opts).await;
+        self.cache.invalidate(location).await;
+        r
+    }
+
+    async fn append(&self, location: &Path, payload: PutPayload) -> Result<PutResult> {
+        let r = self.inner.append(location, payload).await;
+        self.cache.invalidate(location).await;
+        r
+    }
+
+    async fn put_multipart(&self, location: &Path) -> Result<Box<dyn MultipartUpload>> {
+        let upload = self.inner.put_multipart(location).await?;
+        Ok(Box::new(CachingUpload {
+            upload,
+            location: location.clone(),
+            cache: Arc::clone(&self.cache),
+        }))
+    }
+
+    async fn put_multipart_opts(
+        &self,
+        location: &Path,
+        opts: PutMultipartOpts,
+    ) -> Result<Box<dyn MultipartUpload>> {
+        let upload = self.inner.put_multipart_opts(location, opts).await?;
+        Ok(Box::new(CachingUpload {
+            upload,
+            location: location.clone(),
+            cache: Arc::clone(&self.cache),
+        }))
+    }
+
+    async fn get_opts(&self, location: &Path, options: GetOptions) -> Result<GetResult> {
+        if !is_cacheable(&options) {
+            return self.inner.get_opts(location, options).await;
+        }
+
+        match self.cache.lookup(location, options.range.as_ref()) {
+            Lookup::Hit {
+                meta,
+                attributes,
+                range,
+                blocks,
+            } => {
+                if self.revalidate(location, &meta).await? {
+                    if let Ok(data) = self.cache.read(&blocks, &range).await {
+                        return Ok(cached_result(meta, attributes, range, data));
+                    }
+                }
+                self.cache.invalidate(location).await;
+            }
+            // Spans too large to be cached are fetched as requested below
+            Lookup::Partial { e_tag, range, span }
+                if span.end - span.start <= self.cache.config.capacity =>
+            {
+                // Fetch every block spanned by the request, failing if the object has
+                // changed since it was cached as `span` may then no longer be aligned
+                let fetch = GetOptions {
+                    if_match: e_tag,
+                    range: Some(GetRange::Bounded(span)),
+                    ..Default::default()
+                };
+                match self.fetch(location, fetch).await {
+                    Ok(result) => {
+                        let start = (range.start - result.range.start) as usize;
+                        let end = (range.end - result.range.start) as usize;
+                        let meta = result.meta.clone();
+                        let attributes = result.attributes.clone();
+                        let data = result.bytes().await?.slice(start..end);
+                        return Ok(cached_result(meta, attributes, range, data));
+                    }
+                    Err(Error::Precondition { .. }) => self.cache.invalidate(location).await,
+                    Err(e) => return Err(e),
+                }
+            }
+            Lookup::Partial { .. } | Lookup::Miss => {}
+        }
+        self.fetch(location, options).await
+    }
+
+    async fn head(&self, location: &Path) -> Result<ObjectMeta> {
+        self.inner.head(location).await
+    }
+
+    async fn delete(&self, location: &Path) -> Result<()> {
+        let r = self.inner.delete(location).await;
+        self.cache.invalidate(location).await;
+        r
+    }
+
+    async fn delete_opts(&self, location: &Path, opts: DeleteOptions) -> Result<()> {
+        let r = self.inner.delete_opts(location, opts).await;
+        self.cache.invalidate(location).await;
+        r
+    }
+
+    fn delete_stream<'a>(
+        &'a self,
+        locations: BoxStream<'a, Result<Path>>,
+    ) -> BoxStream<'a, Result<Path>> {
+        let cache = &self.cache;
+        self.inner
+            .delete_stream(locations)
+            .then(move |r| async move {
+                if let Ok(location) = &r {
+                    cache.invalidate(location).await;
+                }
+                r
+            })
+            .boxed()
+    }
+
+    fn list(&self, prefix: Option<&Path>) -> BoxStream<'static, Result<ObjectMeta>> {
+        self.inner.list(prefix)
+    }
+
+    fn list_with_offset(
+        &self,
+        prefix: Option<&Path>,
+        offset: &Path,
+    ) -> BoxStream<'static, Result<ObjectMeta>> {
+        self.inner.list_with_offset(prefix, offset)
+    }
+
+    async fn list_with_delimiter(&self, prefix: Option<&Path>) -> Result<ListResult> {
+        self.inner.list_with_delimiter(prefix).await
+    }
+
+    async fn copy(&self, from: &Path, to: &Path) -> Result<()> {
+        let r = self.inner.copy(from, to).await;
+        self.cache.invalidate(to).await;
+        r
+    }
+
+    async fn rename(&self, from: &Path, to: &Path) -> Result<()> {
+        let r = self.inner.rename(from, to).await;
+        self.cache.invalidate(from).await;
+        self.cache.invalidate(to).await;
+        r
+    }
+
+    async fn copy_if_not_exists(&self, from: &Path, to: &Path) -> Result<()> {
+        let r = self.inner.copy_if_not_exists(from, to).await;
+        self.cache.invalidate(to).await;
+        r
+    }
+
+    async fn rename_if_not_exists(&self, from: &Path, to: &Path) -> Result<()> {
+        let r = self.inner.rename_if_not_exists(from, to).await;
+        self.cache.invalidate(from).await;
+        self.cache.invalidate(to).await;
+        r
+    }
+
+    async fn compose(&self, sources: &[Path], dest: &Path) -> Result<PutResult> {
+        let r = self.inner.compose(sources, dest).await;
+        self.cache.invalidate(dest).await;
+        r
+    }
+}
+
+/// Returns true if the result of `options` can be served from the cache
+fn is_cacheable(options: &GetOptions) -> bool {
+    options.if_match.is_none()
+        && options.if_none_match.is_none()
+        && options.if_modified_since.is_none()
+        && options.if_unmodified_since.is_none()
+        && options.version.is_none()
+        && !options.head
+}
+
+fn cached_result(
+    meta: ObjectMeta,
+    attributes: Attributes,
+    range: Range<u64>,
+    data: Bytes,
+) -> GetResult {
+    let stream = futures::stream::once(futures::future::ready(Ok(data)));
+    GetResult {
+        payload: GetResultPayload::Stream(stream.boxed()),
+        meta,
+        range,
+        attributes,
+    }
+}
+
+/// The result of looking up a request in the cache
+enum Lookup {
+    /// Nothing is known about the object
+    Miss,
+    /// Every block spanned by `range` is cached
+    Hit {
+        meta: ObjectMeta,
+        attributes: Attributes,
+        range: Range<u64>,
+        blocks: Vec<(Range<u64>, u64)>,
+    },
+    /// The object is known, but some blocks spanned by `range` are not cached
+    Partial {
+        e_tag: Option<String>,
+        range: Range<u64>,
+        span: Range<u64>,
+    },
+}
+
+#[derive(Debug)]
+struct Cache {
+    store: Arc<dyn ObjectStore>,
+    config: CacheConfig,
+    state: Mutex<CacheState>,
+}
+
+#[derive(Debug, Default)]
+struct CacheState {
+    objects: HashMap<Path, CachedObject>,
+    /// The cached blocks keyed by the tick of their last access
+    lru: BTreeMap<u64, (Path, u64)>,
+    tick: u64,
+    next_id: u64,
+    size: u64,
+}
+
+#[derive(Debug)]
+struct CachedObject {
+    meta: ObjectMeta,
+    attributes: Attributes,
+    blocks: HashMap<u64, CachedBlock>,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct CachedBlock {
+    id: u64,
+    len: u64,
+    tick: u64,
+}
+
+impl CacheState {
+    /// Marks `block` of `location` as the most recently used
+    fn touch(&mut self, location: &Path, idx: u64) {
+        self.tick += 1;
+        let tick = self.tick;
+        if let Some(block) = self
+            .objects
+            .get_mut(location)
+            .and_then(|o| o.blocks.get_mut(&idx))
+        {
+            self.lru.remove(&block.tick);
+            block.tick = tick;
+            self.lru.insert(tick, (location.clone(), idx));
+        }
+    }
+
+    /// Removes `location` from the cache, returning the ids of its blocks
+    fn remove(&mut self, location: &Path) -> Vec<u64> {
+        let object = match self.objects.remove(location) {
+            Some(object) => object,
+            None => return vec![],
+        };
+        object
+            .blocks
+            .into_values()
+            .map(|block| {
+                self.lru.remove(&block.tick);
+                self.size -= block.len;
+                block.id
+            })
+            .collect()
+    }
+
+    /// Evicts the least recently used blocks until within `capacity`, returning their ids
+    fn evict(&mut self, capacity: u64) -> Vec<u64> {
+        let mut evicted = vec![];
+        while self.size > capacity {
+            let tick = match self.lru.keys().next() {
+                Some(tick) => *tick,
+                None => break,
+            };
+            let (location, idx) = self.lru.remove(&tick).unwrap();
+            let object = self.objects.get_mut(&location).unwrap();
+            let block = object.blocks.remove(&idx).unwrap();
+            self.size -= block.len;
+            evicted.push(block.id);
+        }
+        evicted
+    }
+}
+
+impl Cache {
+    /// Returns the size of the blocks an object of `size` bytes is cached in
+    fn block_size(&self, size: u64) -> u64 {
+        match size <= self.config.whole_object_threshold {
+            true => size.max(1),
+            false => self.config.block_size.max(1),
+        }
+    }
+
+    /// Returns true if a response containing `range` of `meta` should be buffered to
+    /// be cached, that is if it is no larger than [`CacheConfig::whole_object_threshold`]
+    /// or fully contains blocks and fits within [`CacheConfig::capacity`]
+    fn is_cacheable(&self, meta: &ObjectMeta, range: &Range<u64>) -> bool {
+        let len = range.end - range.start;
+        if len <= self.config.whole_object_threshold {
+            return true;
+        }
+        let contains_block = self
+            .block_indices(meta.size, range)
+            .map(|idx| self.block_range(meta.size, idx))
+            .any(|block| block.start >= range.start && block.end <= range.end);
+        contains_block && len <= self.config.capacity
+    }
+
+    /// Returns the indices of the blocks spanned by `range` of an object of `size` bytes
+    fn block_indices(&self, size: u64, range: &Range<u64>) -> Range<u64> {
+        let block_size = self.block_size(size);
+        let first = range.start / block_size;
+        let last = match range.end > range.start {
+            true => (range.end - 1) / block_size,
+            false => first,
+        };
+        first..last + 1
+    }
+
+    /// Returns the range of block `idx` of an object of `size` bytes
+    fn block_range(&self, size: u64, idx: u64) -> Range<u64> {
+        let block_size = self.block_size(size);
+        let start = (idx * block_size).min(size);
+        start..size.min(start + block_size)
+    }
+
+    fn lookup(&self, location: &Path, range: Option<&GetRange>) -> Lookup {
+        let mut state = self.state.lock();
+        let object = match state.objects.get(location) {
+            Some(object) => object,
+            None => return Lookup::Miss,
+        };
+        let size = object.meta.size;
+        let range = match range.map(|r| r.as_range(size)) {
+            Some(Ok(range)) => range,
+            // Let the inner store report the error, the object may have changed
+            Some(Err(_)) => return Lookup::Miss,
+            None => 0..size,
+        };
+
+        let indices = self.block_indices(size, &range);
+        let blocks: Option<Vec<_>> = indices
+            .clone()
+            .map(|idx| object.blocks.get(&idx).map(|b| (idx, b.id)))
+            .collect();
+        let meta = object.meta.clone();
+        let attributes = object.attributes.clone();
+
+        match blocks {
+            Some(blocks) => {
+                let blocks = blocks
+                    .into_iter()
+                    .map(|(idx, id)| {
+                        state.touch(location, idx);
+                        (self.block_range(size, idx), id)
+                    })
+                    .collect();
+                Lookup::Hit {
+                    meta,
+                    attributes,
+                    range,
+                    blocks,
+                }
+            }
+            None => {
+                let start = self.block_range(size, indices.start).start;
+                let end = self.block_range(size, indices.end - 1).end;
+                Lookup::Partial {
+                    e_tag: meta.e_tag,
+                    range,
+                    span: start..end,
+                }
+            }
+        }
+    }
+
+    /// Reads `range` from the cached `blocks` spanning it
+    async fn read(&self, blocks: &[(Range<u64>, u64)], range: &Range<u64>) -> Result<Bytes> {
+        let reads = blocks.iter().filter_map(|(block, id)| {
+            let start = range.start.max(block.start) - block.start;
+            let end = range.end.min(block.end) - block.start;
+            let path = block_path(*id);
+            (start < end).then_some(async move { self.store.get_range(&path, start..end).await })
+        });
+        let mut chunks = try_join_all(reads).await?;
+        match chunks.len() {
+            0 => Ok(Bytes::new()),
+            1 => Ok(chunks.pop().unwrap()),
+            _ => {
+                let mut buf = BytesMut::with_capacity((range.end - range.start) as usize);
+                chunks.iter().for_each(|c| buf.extend_from_slice(c));
+                Ok(buf.freeze())
+            }
+        }
+    }
+
+    /// Caches the blocks of `meta` fully contained in `data`, which holds `range`
+    async fn insert(
+        &self,
+        location: &Path,
+        meta: &ObjectMeta,
+        attributes: &Attributes,
+        range: Range<u64>,
+        data: Bytes,
+    ) {
+        let blocks: Vec<_> = {
+            let mut state = self.state.lock();
+            self.block_indices(meta.size, &range)
+                .map(|idx| (idx, self.block_range(meta.size, idx)))
+                .filter(|(_, block)| {
+                    block.start >= range.start
+                        && block.end <= range.end
+                        && block.end - block.start <= self.config.capacity
+                })
+                .map(|(idx, block)| {
+                    state.next_id += 1;
+                    let start = (block.start - range.start) as usize;
+                    let end = (block.end - range.start) as usize;
+                    (idx, state.next_id, data.slice(start..end))
+                })
+                .collect()
+        };
+
+        let writes = blocks.into_iter().map(|(idx, id, data)| async move {
+            let len = data.len() as u64;
+            let r = self.store.put(&block_path(id), data.into()).await;
+            (idx, id, len, r.is_ok())
+        });
+        let written = join_all(writes).await;
+
+        let new = || CachedObject {
+            meta: meta.clone(),
+            attributes: attributes.clone(),
+            blocks: HashMap::new(),
+        };
+        let stale = {
+            let mut guard = self.state.lock();
+            let state = &mut *guard;
+            let changed = state
+                .objects
+                .get(location)
+                .map_or(false, |o| o.meta.e_tag != meta.e_tag);
+            let mut stale = match changed {
+                true => state.remove(location),
+                false => vec![],
+            };
+            for (idx, id, len, ok) in written {
+                if !ok {
+                    continue;
+                }
+                state.tick += 1;
+                let tick = state.tick;
+                let object = state.objects.entry(location.clone()).or_insert_with(new);
+                let replaced = object.blocks.insert(idx, CachedBlock { id, len, tick });
+                if let Some(replaced) = replaced {
+                    state.lru.remove(&replaced.tick);
+                    state.size -= replaced.len;
+                    stale.push(replaced.id);
+                }
+                state.lru.insert(tick, (location.clone(), idx));
+                state.size += len;
+            }
+            // Record the object even if no blocks were cached, as its size is then known
+            state.objects.entry(location.clone()).or_insert_with(new);
+            stale.extend(state.evict(self.config.capacity));
+            stale
+        };
+        self.delete(stale).await
+    }
+
+    /// Removes any cached entries for `location`
+    async fn invalidate(&self, location: &Path) {
+        let ids = self.state.lock().remove(location);
+        self.delete(ids).await
+    }
+
+    async fn delete(&self, ids: Vec<u64>) {
+        join_all(ids.into_iter().map(|id| async move {
+            // Failing to delete a block only wastes space in the cache store
+            let _ = self.store.delete(&block_path(id)).await;
+        }))
+        .await;
+    }
+}
+
+/// Returns the path of the block with `id` in the cache store
+fn block_path(id: u64) -> Path {
+    Path::from(id.to_string())
+}
+
+/// A [`MultipartUpload`] that invalidates the cached entries for its location on completion
+#[derive(Debug)]
+struct CachingUpload {
+    upload: Box<dyn MultipartUpload>,
+    location: Path,
+    cache: Arc<Cache>,
+}
+
+#[async_trait]
+impl MultipartUpload for CachingUpload {
+    fn put_part(&mut self, data: PutPayload) -> UploadPart {
+        self.upload.put_part(data)
+    }
+
+    async fn complete(&mut self) -> Result<PutResult> {
+        let r = self.upload.complete().await;
+        self.cache.invalidate(&self.location).await;
+        r
+    }
+
+    async fn abort(&mut self) -> Result<()> {
+        self.upload.abort().await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::integration::*;
+    use crate::memory::InMemory;
+
+    /// THIS TEST IS ARAS ONLY
+    #[tokio::test]
+    async fn caching_test() {
+        let config = CacheConfig {
+            whole_object_threshold: 1024,
+            block_size: 64 * 1024,
+            capacity: 1024 * 1024,
+        };
+        let integration = CachingStore::new(InMemory::new(), Arc::new(InMemory::new()), config);
+
+        put_get_delete_list(&integration).await;
+        get_opts(&integration).await;
+        list_uses_directories_correctly(&integration).await;
+        list_with_delimiter(&integration).await;
+        rename_and_copy(&integration).await;
+        stream_get(&integration).await;
+    }
+
+    /// THIS TEST IS ARAS ONLY
+    #[tokio::test]
+    async fn test_cached_blocks() {
+        let inner: Arc<dyn ObjectStore> = Arc::new(InMemory::new());
+        let cache = Arc::new(InMemory::new());
+        let config = CacheConfig {
+            whole_object_threshold: 4,
+            block_size: 4,
+            capacity: 1024,
+        };
+        let store = CachingStore::new(Arc::clone(&inner), Arc::<InMemory>::clone(&cache), config);
+
+        let location = Path::from("data");
+        inner.put(&location, "0123456789".into()).await.unwrap();
+
+        // Only the blocks fully contained in the first request are cached
+        let data = store.get_range(&location, 3..6).await.unwrap();
+        assert_eq!(data.as_ref(), b"345");
+        assert_eq!(store.cached_bytes(), 0);
+
+        // Aligned requests are now made for the missing blocks
+        let data = store.get_range(&location, 3..6).await.unwrap();
+        assert_eq!(data.as_ref(), b"345");
+        assert_eq!(store.cached_bytes(), 8);
+
+        let data = store.get_range(&location, 5..10).await.unwrap();
+        assert_eq!(data.as_ref(), b"56789");
+        assert_eq!(store.cached_bytes(), 10);
+
+        // Cached blocks are served from the cache store
+        cache.put(&block_path(1), "abcd".into()).await.unwrap();
+        let data = store.get_range(&location, 2..5).await.unwrap();
+        assert_eq!(data.as_ref(), b"cd4");
+        let data = store.get(&location).await.unwrap().bytes().await.unwrap();
+        assert_eq!(data.as_ref(), b"abcd456789");
+
+        // Changes made to the inner store are detected on revalidation
+        inner.put(&location, "9876543210".into()).await.unwrap();
+        let data = store.get_range(&location, 2..5).await.unwrap();
+        assert_eq!(data.as_ref(), b"765");
+        let data = store.get(&location).await.unwrap().bytes().await.unwrap();
+        assert_eq!(data.as_ref(), b"9876543210");
+        assert_eq!(store.cached_bytes(), 10);
+
+        inner.delete(&location).await.unwrap();
+        let err = store.get(&location).await.unwrap_err();
+        assert!(matches!(err, Error::NotFound { .. }), "{err}");
+        assert_eq!(store.cached_bytes(), 0);
+        assert_eq!(cache.list(None).count().await, 0);
+    }
+
+    /// THIS TEST IS ARAS ONLY
+    #[tokio::test]
+    async fn test_eviction_and_invalidation() {
+        let cache = Arc::new(InMemory::new());
+        let config = CacheConfig {
+            whole_object_threshold: 4,
+            block_size: 4,
+            capacity: 8,
+        };
+        let store = CachingStore::new(InMemory::new(), Arc::<InMemory>::clone(&cache), config);
+
+        let (a, b, c) = (Path::from("a"), Path::from("b"), Path::from("c"));
+        for location in [&a, &b, &c] {
+            store.put(location, "abcd".into()).await.unwrap();
+        }
+
+        store.get(&a).await.unwrap();
+        store.get(&b).await.unwrap();
+        assert_eq!(store.cached_bytes(), 8);
+
+        // Reading `a` makes `b` the least recently used entry
+        store.get(&a).await.unwrap();
+        store.get(&c).await.unwrap();
+        assert_eq!(store.cached_bytes(), 8);
+        let mut cached: Vec<_> = (store.cache.state.lock().objects.iter())
+            .filter(|(_, o)| !o.blocks.is_empty())
+            .map(|(location, _)| location.clone())
+            .collect();
+        cached.sort_unstable();
+        assert_eq!(cached, vec![a.clone(), c.clone()]);
+        assert_eq!(cache.list(None).count().await, 2);
+
+        // Writes through the store invalidate entries
+        store.put(&a, "efgh".into()).await.unwrap();
+        assert_eq!(store.cached_bytes(), 4);
+        let data = store.get(&a).await.unwrap().bytes().await.unwrap();
+        assert_eq!(data.as_ref(), b"efgh");
+
+        store.copy(&b, &c).await.unwrap();
+        store.rename(&a, &b).await.unwrap();
+        assert_eq!(store.cached_bytes(), 0);
+        assert_eq!(cache.list(None).count().await, 0);
+
+        let data = store.get(&b).await.unwrap().bytes().await.unwrap();
+        assert_eq!(data.as_ref(), b"efgh");
+        store.delete(&b).await.unwrap();
+        assert_eq!(store.cached_bytes(), 0);
+    }
+
+    /// THIS TEST IS ARAS ONLY
+    #[cfg(not(target_arch = "wasm32"))]
+    #[tokio::test]
+    async fn test_uncacheable_get_streams() {
+        let root = tempfile::TempDir::new().unwrap();
+        let inner = crate::local::LocalFileSystem::new_with_prefix(root.path()).unwrap();
+        let cache = Arc::new(InMemory::new());
+        let config = CacheConfig {
+            whole_object_threshold: 4,
+            block_size: 4,
+            capacity: 8,
+        };
+        let store = CachingStore::new(inner, Arc::<InMemory>::clone(&cache), config);
+
+        let location = Path::from("data");
+        store.put(&location, "0123456789".into()).await.unwrap();
+
+        // Responses larger than the capacity are passed through unbuffered
+        let result = store.get(&location).await.unwrap();
+        assert!(matches!(result.payload, GetResultPayload::File(_, _)));
+        assert_eq!(result.bytes().await.unwrap().as_ref(), b"0123456789");
+        let data = store.get_range(&location, 1..10).await.unwrap();
+        assert_eq!(data.as_ref(), b"123456789");
+        assert_eq!(store.cached_bytes(), 0);
+        assert_eq!(cache.list(None).count().await, 0);
+
+        // Smaller responses are still cached
+        let data = store.get_range(&location, 4..8).await.unwrap();
+        assert_eq!(data.as_ref(), b"4567");
+        assert_eq!(store.cached_bytes(), 4);
+    }
+}
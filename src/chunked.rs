@@ -28,8 +28,8 @@ use futures::StreamExt;
 
 use crate::path::Path;
 use crate::{
-    GetOptions, GetResult, GetResultPayload, ListResult, MultipartUpload, ObjectMeta, ObjectStore,
-    PutMultipartOpts, PutOptions, PutResult,
+    DeleteOptions, GetOptions, GetResult, GetResultPayload, ListResult, MultipartUpload,
+    ObjectMeta, ObjectStore, PutMultipartOpts, PutOptions, PutResult,
 };
 use crate::{PutPayload, Result};
 
@@ -150,6 +150,11 @@ impl ObjectStore for ChunkedStore {
         self.inner.delete(location).await
     }
 
+    /// THIS METHOD IS ARAS ONLY
+    async fn delete_opts(&self, location: &Path, opts: DeleteOptions) -> Result<()> {
+        self.inner.delete_opts(location, opts).await
+    }
+
     fn list(&self, prefix: Option<&Path>) -> BoxStream<'static, Result<ObjectMeta>> {
         self.inner.list(prefix)
     }
@@ -173,6 +178,11 @@ impl ObjectStore for ChunkedStore {
     async fn copy_if_not_exists(&self, from: &Path, to: &Path) -> Result<()> {
         self.inner.copy_if_not_exists(from, to).await
     }
+
+    /// THIS METHOD IS ARAS ONLY
+    async fn compose(&self, sources: &[Path], dest: &Path) -> Result<PutResult> {
+        self.inner.compose(sources, dest).await
+    }
 }
 
 #[cfg(test)]
@@ -226,7 +226,8 @@ impl HttpRequestBuilder {
         self
     }
 
-    #[cfg(any(feature = "aws", feature = "gcp", feature = "azure"))]
+    /// THIS METHOD IS COMMON, MODIFIED BY ARAS
+    #[cfg(any(feature = "aws", feature = "gcp", feature = "azure", feature = "http"))]
     pub(crate) fn body(mut self, b: impl Into<HttpRequestBody>) -> Self {
         if let Ok(r) = &mut self.request {
             *r.body_mut() = b.into();
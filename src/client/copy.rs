@@ -0,0 +1,142 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! THIS MODULE IS ARAS ONLY
+//!
+//! Configuration for server-side copies of large objects
+
+#[cfg(any(feature = "aws", feature = "azure"))]
+use std::ops::Range;
+
+/// The configuration for server-side copies of large objects
+///
+/// Stores can only copy objects up to some size with a single request, e.g. 5 GiB
+/// for S3. Objects larger than [`Self::multipart_threshold`] are instead copied in
+/// ranges of [`Self::part_size`] bytes, with up to [`Self::max_concurrency`] ranges
+/// copied concurrently:
+///
+/// * S3 copies ranges with `UploadPartCopy` into a multipart upload
+/// * Azure stages ranges with `Put Block From URL` before committing the block list
+/// * GCS copies with the JSON API `rewrite`, rewriting up to `part_size` bytes per call
+///
+/// Determining the size of the source requires an additional `HEAD` request, which is
+/// only sent before copying if [`Self::multipart_threshold`] is below its default of
+/// 5 GiB, the largest object every store copies with a single request. Otherwise objects
+/// are copied with a single request, falling back to copying in ranges if the store
+/// rejects the source as too large, which Azure never does. Setting the threshold to `u64::MAX` disables copying
+/// in ranges altogether
+#[derive(Debug, Clone, Copy)]
+pub struct CopyConfig {
+    /// Objects larger than this many bytes are copied in ranges
+    pub multipart_threshold: u64,
+
+    /// The size in bytes of each range
+    ///
+    /// This is increased as necessary to keep within the maximum number of parts
+    /// supported by the store, and clamped to the range of part sizes it accepts
+    pub part_size: u64,
+
+    /// The maximum number of ranges to copy concurrently
+    pub max_concurrency: usize,
+}
+
+/// THIS CONSTANT IS ARAS ONLY
+///
+/// The default [`CopyConfig::multipart_threshold`]
+const DEFAULT_MULTIPART_THRESHOLD: u64 = 5 * 1024 * 1024 * 1024;
+
+impl Default for CopyConfig {
+    fn default() -> Self {
+        Self {
+            multipart_threshold: DEFAULT_MULTIPART_THRESHOLD,
+            part_size: 512 * 1024 * 1024,
+            max_concurrency: 8,
+        }
+    }
+}
+
+impl CopyConfig {
+    /// Returns true if an object of `size` bytes should be copied in ranges
+    #[cfg(any(feature = "aws", feature = "gcp", feature = "azure"))]
+    pub(crate) fn is_multipart(&self, size: u64) -> bool {
+        size > self.multipart_threshold
+    }
+
+    /// Returns true if the size of the source must be determined before copying it, as
+    /// [`Self::multipart_threshold`] is below the size copied with a single request
+    #[cfg(any(feature = "aws", feature = "gcp", feature = "azure"))]
+    pub(crate) fn probe_size(&self) -> bool {
+        self.multipart_threshold < DEFAULT_MULTIPART_THRESHOLD
+    }
+
+    /// Splits an object of `size` bytes into at most `max_parts` ranges, all but the
+    /// last of which are between `min_part_size` and `max_part_size` bytes
+    #[cfg(any(feature = "aws", feature = "azure"))]
+    pub(crate) fn part_ranges(
+        &self,
+        size: u64,
+        min_part_size: u64,
+        max_part_size: u64,
+        max_parts: u64,
+    ) -> Vec<Range<u64>> {
+        let part_size = self
+            .part_size
+            .max((size + max_parts - 1) / max_parts)
+            .clamp(min_part_size.max(1), max_part_size);
+
+        let mut ranges = Vec::with_capacity(((size + part_size - 1) / part_size) as usize);
+        let mut start = 0;
+        while start < size {
+            let end = size.min(start + part_size);
+            ranges.push(start..end);
+            start = end;
+        }
+        ranges
+    }
+}
+
+#[cfg(test)]
+#[cfg(any(feature = "aws", feature = "azure"))]
+mod tests {
+    use super::*;
+
+    /// THIS TEST IS ARAS ONLY
+    #[test]
+    fn test_part_ranges() {
+        let config = CopyConfig {
+            multipart_threshold: 10,
+            part_size: 4,
+            max_concurrency: 1,
+        };
+        assert!(!config.is_multipart(10));
+        assert!(config.is_multipart(11));
+
+        assert_eq!(config.part_ranges(10, 1, 100, 100), vec![0..4, 4..8, 8..10]);
+        assert_eq!(config.part_ranges(8, 1, 100, 100), vec![0..4, 4..8]);
+        assert!(config.part_ranges(0, 1, 100, 100).is_empty());
+
+        // Parts are enlarged to keep within the maximum number of parts
+        assert_eq!(config.part_ranges(10, 1, 100, 2), vec![0..5, 5..10]);
+        // And to the minimum part size
+        assert_eq!(config.part_ranges(10, 6, 100, 100), vec![0..6, 6..10]);
+        // And shrunk to the maximum part size
+        assert_eq!(
+            config.part_ranges(10, 1, 3, 100),
+            vec![0..3, 3..6, 6..9, 9..10]
+        );
+    }
+}
@@ -19,6 +19,10 @@ use crate::client::header::{get_etag, header_meta, HeaderConfig};
 use crate::client::retry::RetryContext;
 use crate::client::{HttpResponse, HttpResponseBody};
 use crate::path::Path;
+#[cfg(any(feature = "azure", feature = "http"))]
+use crate::util::plan_ranges;
+#[cfg(any(feature = "azure", feature = "http"))]
+use crate::{coalesce_ranges_opts, RangeCoalescePolicy};
 use crate::{
     Attribute, Attributes, GetOptions, GetRange, GetResult, GetResultPayload, ObjectMeta, Result,
     RetryConfig,
@@ -27,6 +31,8 @@ use async_trait::async_trait;
 use bytes::Bytes;
 use futures::stream::BoxStream;
 use futures::StreamExt;
+#[cfg(any(feature = "azure", feature = "http"))]
+use futures::TryStreamExt;
 use http::header::{
     CACHE_CONTROL, CONTENT_DISPOSITION, CONTENT_ENCODING, CONTENT_LANGUAGE, CONTENT_RANGE,
     CONTENT_TYPE,
@@ -60,6 +66,23 @@ pub(crate) trait GetClient: Send + Sync + 'static {
 #[async_trait]
 pub(crate) trait GetClientExt {
     async fn get_opts(&self, location: &Path, options: GetOptions) -> Result<GetResult>;
+
+    /// THIS METHOD IS ARAS ONLY
+    ///
+    /// Fetch `ranges` with multi-range requests, i.e. `Range: bytes=a-b,c-d`, parsing
+    /// the `multipart/byteranges` responses
+    ///
+    /// Ranges are first merged according to `policy`, and requested at most 64 at a time,
+    /// pinning the requests after the first to the e-tag it returned. Any ranges not
+    /// returned by the server, e.g. because it returned the full object or only a single
+    /// range, are instead fetched with [`coalesce_ranges_opts`]
+    #[cfg(any(feature = "azure", feature = "http"))]
+    async fn get_ranges_multi(
+        &self,
+        location: &Path,
+        ranges: &[Range<u64>],
+        policy: RangeCoalescePolicy,
+    ) -> Result<Vec<Bytes>>;
 }
 
 #[async_trait]
@@ -74,6 +97,283 @@ impl<T: GetClient> GetClientExt for Arc<T> {
 
         ctx.get_result().await
     }
+
+    /// THIS METHOD IS ARAS ONLY
+    #[cfg(any(feature = "azure", feature = "http"))]
+    async fn get_ranges_multi(
+        &self,
+        location: &Path,
+        ranges: &[Range<u64>],
+        policy: RangeCoalescePolicy,
+    ) -> Result<Vec<Bytes>> {
+        let fetch_ranges = plan_ranges(ranges, &policy);
+        let responses: Vec<_> = match fetch_ranges.len() {
+            0 | 1 => vec![],
+            _ => {
+                let mut requests = fetch_ranges.chunks(MAX_MULTI_RANGES).map(<[_]>::to_vec);
+                let first = requests.next().unwrap_or_default();
+                let first = multi_range_request(self, location, first, None).await?;
+
+                // Pin the remaining requests to the object returned by the first
+                let e_tag = first.e_tag.clone();
+                let rest: Vec<_> = futures::stream::iter(requests)
+                    .map(|ranges| multi_range_request(self, location, ranges, e_tag.clone()))
+                    .buffered(policy.parallelism.max(1))
+                    .try_collect()
+                    .await?;
+                std::iter::once(first).chain(rest).collect()
+            }
+        };
+
+        let mut e_tag = None;
+        let mut parts = vec![];
+        for response in responses {
+            e_tag = e_tag.or(response.e_tag);
+            parts.extend(response.parts);
+        }
+
+        let mut missing = vec![];
+        let mut ret: Vec<_> = ranges
+            .iter()
+            .map(|range| {
+                let part = parts.iter().find(|(r, b)| {
+                    r.start <= range.start
+                        && range.start < r.end
+                        && (range.end <= r.end || r.end == b.size)
+                });
+                match part {
+                    Some((r, b)) => {
+                        let start = (range.start - r.start) as usize;
+                        let end = ((range.end - r.start) as usize).min(b.data.len());
+                        Some(b.data.slice(start..end))
+                    }
+                    None => {
+                        missing.push(range.clone());
+                        None
+                    }
+                }
+            })
+            .collect();
+
+        if !missing.is_empty() {
+            let fetch = |range: Range<u64>| {
+                let options = GetOptions {
+                    range: Some(range.into()),
+                    if_match: e_tag.clone(),
+                    ..Default::default()
+                };
+                async move { self.get_opts(location, options).await?.bytes().await }
+            };
+            let mut fetched = coalesce_ranges_opts(&missing, fetch, policy)
+                .await?
+                .into_iter();
+            for r in ret.iter_mut().filter(|r| r.is_none()) {
+                *r = fetched.next();
+            }
+        }
+
+        Ok(ret.into_iter().map(Option::unwrap_or_default).collect())
+    }
+}
+
+/// THIS CONSTANT IS ARAS ONLY
+///
+/// The maximum number of ranges requested by a single multi-range request
+#[cfg(any(feature = "azure", feature = "http"))]
+const MAX_MULTI_RANGES: usize = 64;
+
+/// THIS STRUCT IS ARAS ONLY
+///
+/// A request extension carrying the ranges of a multi-range request, which
+/// [`GetOptionsExt::with_get_options`] sends as the `Range` header
+///
+/// [`GetOptionsExt::with_get_options`]: crate::client::GetOptionsExt::with_get_options
+#[cfg(any(feature = "azure", feature = "http"))]
+#[derive(Debug, Clone)]
+pub(crate) struct MultiRange(pub(crate) Vec<Range<u64>>);
+
+#[cfg(any(feature = "azure", feature = "http"))]
+impl MultiRange {
+    /// THIS METHOD IS ARAS ONLY
+    ///
+    /// Returns the value of the `Range` header, e.g. `bytes=0-9,20-29`
+    pub(crate) fn header_value(&self) -> String {
+        let ranges = self.0.iter().map(|r| format!("{}-{}", r.start, r.end - 1));
+        format!("bytes={}", ranges.collect::<Vec<_>>().join(","))
+    }
+}
+
+/// THIS STRUCT IS ARAS ONLY
+///
+/// A part of a response to a multi-range request
+#[cfg(any(feature = "azure", feature = "http"))]
+#[derive(Debug)]
+struct RangePart {
+    /// The data of this part
+    data: Bytes,
+    /// The total size of the object
+    size: u64,
+}
+
+/// THIS STRUCT IS ARAS ONLY
+///
+/// The response to a multi-range request
+#[cfg(any(feature = "azure", feature = "http"))]
+struct MultiRangeResponse {
+    /// The e-tag of the object, if any
+    e_tag: Option<String>,
+    /// The ranges returned, empty if the server did not return a partial response
+    parts: Vec<(Range<u64>, RangePart)>,
+}
+
+/// THIS FUNCTION IS ARAS ONLY
+///
+/// Performs a single multi-range request for `ranges`, conditional on `e_tag` if any
+#[cfg(any(feature = "azure", feature = "http"))]
+async fn multi_range_request<T: GetClient>(
+    client: &Arc<T>,
+    location: &Path,
+    ranges: Vec<Range<u64>>,
+    e_tag: Option<String>,
+) -> Result<MultiRangeResponse> {
+    let err = |e: GetResultError| crate::Error::Generic {
+        store: T::STORE,
+        source: Box::new(e),
+    };
+
+    let mut extensions = ::http::Extensions::new();
+    extensions.insert(MultiRange(ranges));
+    let options = GetOptions {
+        if_match: e_tag,
+        extensions,
+        ..Default::default()
+    };
+
+    let mut retry_ctx = RetryContext::new(client.retry_config());
+    let response = client
+        .get_request(&mut retry_ctx, location, options)
+        .await?;
+    let (parts, body) = response.into_parts();
+
+    // The server returned the full object, which is dropped without reading the body
+    if parts.status != StatusCode::PARTIAL_CONTENT {
+        return Ok(MultiRangeResponse {
+            e_tag: None,
+            parts: vec![],
+        });
+    }
+
+    let e_tag = get_etag(&parts.headers).ok();
+    let boundary = match parts.headers.get(CONTENT_TYPE).map(|v| v.to_str()) {
+        Some(Ok(v)) => multipart_boundary(v),
+        Some(Err(source)) => return Err(err(GetResultError::InvalidContentType { source })),
+        None => None,
+    };
+
+    let content_range = match &boundary {
+        Some(_) => None,
+        None => Some(parse_range(&parts.headers).map_err(err)?),
+    };
+
+    let data = body.bytes().await.map_err(|source| crate::Error::Generic {
+        store: T::STORE,
+        source: Box::new(source),
+    })?;
+
+    let parts = match (boundary, content_range) {
+        (Some(boundary), _) => parse_byteranges(&data, &boundary).map_err(err)?,
+        (None, Some(range)) => vec![(
+            range.range,
+            RangePart {
+                data,
+                size: range.size,
+            },
+        )],
+        (None, None) => unreachable!(),
+    };
+    Ok(MultiRangeResponse { e_tag, parts })
+}
+
+/// THIS FUNCTION IS ARAS ONLY
+///
+/// Returns the boundary of a `multipart/byteranges` content type, if any
+#[cfg(any(feature = "azure", feature = "http"))]
+fn multipart_boundary(content_type: &str) -> Option<String> {
+    let mut params = content_type.split(';');
+    let mime = params.next()?.trim();
+    if !mime.eq_ignore_ascii_case("multipart/byteranges") {
+        return None;
+    }
+    params.find_map(|p| {
+        let (key, value) = p.split_once('=')?;
+        key.trim()
+            .eq_ignore_ascii_case("boundary")
+            .then(|| value.trim().trim_matches('"').to_string())
+    })
+}
+
+/// THIS FUNCTION IS ARAS ONLY
+///
+/// Parses the body of a `multipart/byteranges` response
+///
+/// <https://www.rfc-editor.org/rfc/rfc9110.html#name-media-type-multipart-byterange>
+#[cfg(any(feature = "azure", feature = "http"))]
+fn parse_byteranges(
+    body: &Bytes,
+    boundary: &str,
+) -> Result<Vec<(Range<u64>, RangePart)>, GetResultError> {
+    let invalid = |reason| GetResultError::InvalidByteranges { reason };
+    let delimiter = format!("--{boundary}");
+    let find = |from: usize, needle: &[u8]| {
+        body[from..]
+            .windows(needle.len())
+            .position(|w| w == needle)
+            .map(|p| p + from)
+    };
+
+    let mut parts = vec![];
+    let mut offset = find(0, delimiter.as_bytes()).ok_or_else(|| invalid("missing boundary"))?;
+    loop {
+        offset += delimiter.len();
+        if body[offset..].starts_with(b"--") {
+            return Ok(parts);
+        }
+
+        // Skip the remainder of the boundary line, and parse the headers
+        offset = find(offset, b"\r\n").ok_or_else(|| invalid("truncated part"))? + 2;
+        let headers_end = find(offset, b"\r\n\r\n").ok_or_else(|| invalid("truncated part"))?;
+        let headers = std::str::from_utf8(&body[offset..headers_end])
+            .map_err(|_| invalid("headers contained non UTF-8 characters"))?;
+        let content_range = headers
+            .split("\r\n")
+            .find_map(|line| {
+                let (name, value) = line.split_once(':')?;
+                name.trim()
+                    .eq_ignore_ascii_case(CONTENT_RANGE.as_str())
+                    .then_some(value)
+            })
+            .ok_or_else(|| invalid("part without Content-Range"))?;
+        let range = ContentRange::from_str(content_range).ok_or_else(|| {
+            let value = content_range.trim().into();
+            GetResultError::ParseContentRange { value }
+        })?;
+
+        // The length of the data is determined by the range, as it may contain the boundary
+        let start = headers_end + 4;
+        let end = start + (range.range.end - range.range.start) as usize;
+        if end > body.len() {
+            return Err(invalid("truncated part"));
+        }
+        parts.push((
+            range.range,
+            RangePart {
+                data: body.slice(start..end),
+                size: range.size,
+            },
+        ));
+
+        offset = find(end, delimiter.as_bytes()).ok_or_else(|| invalid("missing boundary"))?;
+    }
 }
 
 struct ContentRange {
@@ -154,6 +454,11 @@ enum GetResultError {
         expected: Range<u64>,
         actual: Range<u64>,
     },
+
+    /// THIS VARIANT IS ARAS ONLY
+    #[cfg(any(feature = "azure", feature = "http"))]
+    #[error("Invalid multipart/byteranges response: {reason}")]
+    InvalidByteranges { reason: &'static str },
 }
 
 /// Retry context for a streaming get request
@@ -480,6 +785,55 @@ mod tests {
             Some(&"bar".into())
         );
     }
+
+    /// THIS TEST IS ARAS ONLY
+    #[test]
+    #[cfg(any(feature = "azure", feature = "http"))]
+    fn test_parse_byteranges() {
+        assert_eq!(
+            multipart_boundary("multipart/byteranges; boundary=3d6b6a416f9b5"),
+            Some("3d6b6a416f9b5".to_string())
+        );
+        assert_eq!(
+            multipart_boundary("Multipart/Byteranges;charset=utf-8;Boundary=\"a b\""),
+            Some("a b".to_string())
+        );
+        assert_eq!(multipart_boundary("text/plain; boundary=abc"), None);
+        assert_eq!(multipart_boundary("multipart/byteranges"), None);
+
+        // The data of a part may contain the boundary
+        let body = Bytes::from_static(
+            b"preamble\r\n--abc\r\nContent-Type: text/plain\r\nContent-Range: bytes 0-7/20\r\n\r\n\
+              --abc--\r\n--abc\r\ncontent-range:bytes 15-19/20\r\n\r\nhello\r\n--abc--\r\n",
+        );
+        let parts = parse_byteranges(&body, "abc").unwrap();
+        assert_eq!(parts.len(), 2);
+        assert_eq!(parts[0].0, 0..8);
+        assert_eq!(parts[0].1.data, "--abc--\r");
+        assert_eq!(parts[1].0, 15..20);
+        assert_eq!(parts[1].1.data, "hello");
+        assert_eq!(parts[1].1.size, 20);
+
+        let body = Bytes::from_static(b"--abc\r\nContent-Type: text/plain\r\n\r\nhello\r\n--abc--");
+        let err = parse_byteranges(&body, "abc").unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Invalid multipart/byteranges response: part without Content-Range"
+        );
+
+        let body = Bytes::from_static(b"--abc\r\nContent-Range: bytes 0-9/20\r\n\r\nhello");
+        let err = parse_byteranges(&body, "abc").unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Invalid multipart/byteranges response: truncated part"
+        );
+
+        let err = parse_byteranges(&Bytes::from_static(b"hello"), "abc").unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Invalid multipart/byteranges response: missing boundary"
+        );
+    }
 }
 #[cfg(all(test, feature = "http", not(target_arch = "wasm32")))]
 mod http_tests {
@@ -488,9 +842,13 @@ mod http_tests {
     use crate::http::HttpBuilder;
     use crate::path::Path;
     use crate::{ClientOptions, ObjectStore, RetryConfig};
+
+    // THESE IMPORTS ARE ARAS ONLY
+    use crate::RangeCoalescePolicy;
     use bytes::Bytes;
     use futures::FutureExt;
     use http::header::{CONTENT_LENGTH, CONTENT_RANGE, ETAG, RANGE};
+    use http::header::{CONTENT_TYPE, IF_MATCH};
     use http::{Response, StatusCode};
     use hyper::body::Frame;
     use std::pin::Pin;
@@ -695,4 +1053,142 @@ mod http_tests {
             "Generic HTTP error: HTTP error: request or response body error"
         );
     }
+
+    /// THIS TEST IS ARAS ONLY
+    #[tokio::test]
+    async fn test_multi_range() {
+        let mock = MockServer::new().await;
+        let options = ClientOptions::new()
+            .with_allow_http(true)
+            .with_multi_range_requests(true);
+        let store = HttpBuilder::new()
+            .with_client_options(options)
+            .with_url(mock.url())
+            .build()
+            .unwrap();
+
+        let path = Path::from("test");
+        let ranges = [0..2, 1..4, 10..15];
+        let policy = RangeCoalescePolicy {
+            max_gap: 2,
+            parallelism: 1,
+            ..Default::default()
+        };
+
+        // Server returns the requested ranges
+        mock.push_fn(|req| {
+            assert_eq!(
+                req.headers().get(RANGE).unwrap().to_str().unwrap(),
+                "bytes=0-3,10-14"
+            );
+
+            Response::builder()
+                .status(StatusCode::PARTIAL_CONTENT)
+                .header(CONTENT_TYPE, "multipart/byteranges; boundary=abc")
+                .header(ETAG, "123")
+                .body(
+                    "--abc\r\nContent-Type: text/plain\r\nContent-Range: bytes 0-3/20\r\n\r\n\
+                     0123\r\n--abc\r\nContent-Range: bytes 10-14/20\r\n\r\nabcde\r\n--abc--\r\n"
+                        .to_string(),
+                )
+                .unwrap()
+        });
+
+        let ret = store.get_ranges_opts(&path, &ranges, policy).await.unwrap();
+        assert_eq!(ret, vec!["01", "123", "abcde"]);
+
+        // Server returns a single range, the remainder is fetched separately
+        mock.push(
+            Response::builder()
+                .status(StatusCode::PARTIAL_CONTENT)
+                .header(CONTENT_RANGE, "bytes 0-3/20")
+                .header(ETAG, "123")
+                .body("0123".to_string())
+                .unwrap(),
+        );
+        mock.push_fn(|req| {
+            assert_eq!(
+                req.headers().get(RANGE).unwrap().to_str().unwrap(),
+                "bytes=10-14"
+            );
+            assert_eq!(req.headers().get(IF_MATCH).unwrap(), "123");
+
+            Response::builder()
+                .status(StatusCode::PARTIAL_CONTENT)
+                .header(CONTENT_RANGE, "bytes 10-14/20")
+                .header(ETAG, "123")
+                .body("abcde".to_string())
+                .unwrap()
+        });
+
+        let ret = store.get_ranges_opts(&path, &ranges, policy).await.unwrap();
+        assert_eq!(ret, vec!["01", "123", "abcde"]);
+
+        // Server returns the full object, all ranges are fetched separately
+        mock.push(
+            Response::builder()
+                .header(ETAG, "123")
+                .body("0123456789abcdefghij".to_string())
+                .unwrap(),
+        );
+        for (range, body) in [("bytes=0-3", "0123"), ("bytes=10-14", "abcde")] {
+            mock.push_fn(move |req| {
+                assert_eq!(req.headers().get(RANGE).unwrap().to_str().unwrap(), range);
+
+                Response::builder()
+                    .status(StatusCode::PARTIAL_CONTENT)
+                    .header(CONTENT_RANGE, range.replace('=', " ") + "/20")
+                    .body(body.to_string())
+                    .unwrap()
+            });
+        }
+
+        let ret = store.get_ranges_opts(&path, &ranges, policy).await.unwrap();
+        assert_eq!(ret, vec!["01", "123", "abcde"]);
+
+        // Requests after the first 64 ranges are pinned to the e-tag of the first
+        let data: String = (0..200)
+            .map(|i| char::from(b'a' + (i % 26) as u8))
+            .collect();
+        let ranges: Vec<_> = (0..65).map(|i| i * 3..i * 3 + 1).collect();
+        for if_match in [None, Some("123")] {
+            let data = data.clone();
+            mock.push_fn(move |req| {
+                assert_eq!(
+                    req.headers().get(IF_MATCH).map(|v| v.to_str().unwrap()),
+                    if_match
+                );
+                let header = req.headers().get(RANGE).unwrap().to_str().unwrap();
+                let parts: String = header
+                    .strip_prefix("bytes=")
+                    .unwrap()
+                    .split(',')
+                    .map(|range| {
+                        let (start, end) = range.split_once('-').unwrap();
+                        let (start, end) = (start.parse().unwrap(), end.parse::<usize>().unwrap());
+                        let body = &data[start..end + 1];
+                        format!("--abc\r\nContent-Range: bytes {range}/200\r\n\r\n{body}\r\n")
+                    })
+                    .collect();
+                Response::builder()
+                    .status(StatusCode::PARTIAL_CONTENT)
+                    .header(CONTENT_TYPE, "multipart/byteranges; boundary=abc")
+                    .header(ETAG, "123")
+                    .body(format!("{parts}--abc--\r\n"))
+                    .unwrap()
+            });
+        }
+
+        let policy = RangeCoalescePolicy {
+            max_gap: 0,
+            parallelism: 1,
+            ..Default::default()
+        };
+        let ret = store.get_ranges_opts(&path, &ranges, policy).await.unwrap();
+        let expected: Vec<_> = ranges
+            .iter()
+            .map(|r| &data[r.start as usize..][..1])
+            .collect();
+        assert_eq!(ret, expected);
+    }
 }
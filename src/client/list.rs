@@ -17,7 +17,9 @@
 
 use crate::client::pagination::stream_paginated;
 use crate::list::{PaginatedListOptions, PaginatedListResult};
+use crate::multipart::MultipartUploadInfo;
 use crate::path::{Path, DELIMITER};
+use crate::version::ObjectVersion;
 use crate::Result;
 use crate::{ListResult, ObjectMeta};
 use async_trait::async_trait;
@@ -127,3 +129,91 @@ impl<T: ListClient + Clone> ListClientExt for T {
         })
     }
 }
+
+/// THIS TRAIT IS ARAS ONLY
+///
+/// A client that can perform paginated object version list requests
+#[async_trait]
+pub(crate) trait ListVersionsClient: Send + Sync + 'static {
+    /// Returns a page of object versions and the token of the next page, if any
+    async fn list_versions_request(
+        &self,
+        prefix: Option<&str>,
+        page_token: Option<String>,
+    ) -> Result<(Vec<ObjectVersion>, Option<String>)>;
+}
+
+/// THIS TRAIT IS ARAS ONLY
+///
+/// Extension trait for [`ListVersionsClient`] that streams all pages
+pub(crate) trait ListVersionsClientExt {
+    fn list_versions(&self, prefix: Option<&Path>) -> BoxStream<'static, Result<ObjectVersion>>;
+}
+
+impl<T: ListVersionsClient + Clone> ListVersionsClientExt for T {
+    fn list_versions(&self, prefix: Option<&Path>) -> BoxStream<'static, Result<ObjectVersion>> {
+        let prefix = prefix
+            .filter(|x| !x.as_ref().is_empty())
+            .map(|p| format!("{}{}", p.as_ref(), DELIMITER));
+        stream_paginated(
+            self.clone(),
+            prefix,
+            move |client, prefix, page_token| async move {
+                let (versions, token) = client
+                    .list_versions_request(prefix.as_deref(), page_token)
+                    .await?;
+                Ok((versions, prefix, token))
+            },
+        )
+        .map_ok(|r| futures::stream::iter(r.into_iter().map(Ok)))
+        .try_flatten()
+        .boxed()
+    }
+}
+
+/// THIS TRAIT IS ARAS ONLY
+///
+/// A client that can perform paginated multipart upload list requests
+#[async_trait]
+pub(crate) trait ListMultipartClient: Send + Sync + 'static {
+    /// Returns a page of incomplete multipart uploads and the token of the next page, if any
+    async fn list_multipart_uploads_request(
+        &self,
+        prefix: Option<&str>,
+        page_token: Option<String>,
+    ) -> Result<(Vec<MultipartUploadInfo>, Option<String>)>;
+}
+
+/// THIS TRAIT IS ARAS ONLY
+///
+/// Extension trait for [`ListMultipartClient`] that streams all pages
+pub(crate) trait ListMultipartClientExt {
+    fn list_multipart_uploads(
+        &self,
+        prefix: Option<&Path>,
+    ) -> BoxStream<'static, Result<MultipartUploadInfo>>;
+}
+
+impl<T: ListMultipartClient + Clone> ListMultipartClientExt for T {
+    fn list_multipart_uploads(
+        &self,
+        prefix: Option<&Path>,
+    ) -> BoxStream<'static, Result<MultipartUploadInfo>> {
+        let prefix = prefix
+            .filter(|x| !x.as_ref().is_empty())
+            .map(|p| format!("{}{}", p.as_ref(), DELIMITER));
+        stream_paginated(
+            self.clone(),
+            prefix,
+            move |client, prefix, page_token| async move {
+                let (uploads, token) = client
+                    .list_multipart_uploads_request(prefix.as_deref(), page_token)
+                    .await?;
+                Ok((uploads, prefix, token))
+            },
+        )
+        .map_ok(|r| futures::stream::iter(r.into_iter().map(Ok)))
+        .try_flatten()
+        .boxed()
+    }
+}
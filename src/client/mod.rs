@@ -30,6 +30,8 @@ pub(crate) mod mock_server;
 
 pub(crate) mod retry;
 
+pub(crate) mod copy;
+
 #[cfg(any(feature = "aws", feature = "gcp", feature = "azure"))]
 pub(crate) mod pagination;
 
@@ -49,7 +51,7 @@ pub(crate) mod s3;
 pub(crate) mod builder;
 mod http;
 
-#[cfg(any(feature = "aws", feature = "gcp", feature = "azure"))]
+#[cfg(any(feature = "aws", feature = "gcp", feature = "azure", feature = "http"))]
 pub(crate) mod parts;
 pub use http::*;
 
@@ -64,9 +66,11 @@ use std::time::Duration;
 #[cfg(not(target_arch = "wasm32"))]
 use reqwest::{NoProxy, Proxy};
 
+#[cfg(any(feature = "azure", feature = "http"))]
+use crate::client::get::MultiRange;
 use crate::config::{fmt_duration, ConfigValue};
 use crate::path::Path;
-use crate::{GetOptions, Result};
+use crate::{GetOptions, RangeCoalescePolicy, Result};
 
 fn map_client_error(e: reqwest::Error) -> super::Error {
     super::Error::Generic {
@@ -266,6 +270,16 @@ pub struct ClientOptions {
     http1_only: ConfigValue<bool>,
     http2_only: ConfigValue<bool>,
     randomize_addresses: ConfigValue<bool>,
+    /// THIS FIELD IS ARAS ONLY
+    ///
+    /// The policy for coalescing the ranges requested by [`ObjectStore::get_ranges`]
+    ///
+    /// [`ObjectStore::get_ranges`]: crate::ObjectStore::get_ranges
+    range_coalesce_policy: RangeCoalescePolicy,
+    /// THIS FIELD IS ARAS ONLY
+    ///
+    /// If true, stores that support it fetch multiple ranges with a single request
+    multi_range_requests: bool,
 }
 
 impl Default for ClientOptions {
@@ -303,6 +317,8 @@ impl Default for ClientOptions {
             http1_only: true.into(),
             http2_only: Default::default(),
             randomize_addresses: true.into(),
+            range_coalesce_policy: Default::default(),
+            multi_range_requests: false,
         }
     }
 }
@@ -589,6 +605,53 @@ impl ClientOptions {
         self
     }
 
+    /// THIS METHOD IS ARAS ONLY
+    ///
+    /// Sets the [`RangeCoalescePolicy`] used by [`ObjectStore::get_ranges`]
+    ///
+    /// Defaults to [`RangeCoalescePolicy::default`]
+    ///
+    /// [`ObjectStore::get_ranges`]: crate::ObjectStore::get_ranges
+    pub fn with_range_coalesce_policy(mut self, policy: RangeCoalescePolicy) -> Self {
+        self.range_coalesce_policy = policy;
+        self
+    }
+
+    /// THIS METHOD IS ARAS ONLY
+    ///
+    /// Returns the [`RangeCoalescePolicy`] used by [`ObjectStore::get_ranges`]
+    ///
+    /// [`ObjectStore::get_ranges`]: crate::ObjectStore::get_ranges
+    pub fn range_coalesce_policy(&self) -> RangeCoalescePolicy {
+        self.range_coalesce_policy
+    }
+
+    /// THIS METHOD IS ARAS ONLY
+    ///
+    /// If true, [`ObjectStore::get_ranges`] fetches multiple ranges with a single
+    /// request, i.e. `Range: bytes=a-b,c-d`, parsing the `multipart/byteranges` response
+    ///
+    /// Ranges the server does not return, e.g. because it does not support multiple ranges
+    /// and returns the full object or a single range instead, are fetched with separate
+    /// requests. The response to the first request is discarded without reading its body.
+    ///
+    /// Currently supported by [`HttpStore`] and [`MicrosoftAzure`]. Defaults to false
+    ///
+    /// [`ObjectStore::get_ranges`]: crate::ObjectStore::get_ranges
+    /// [`HttpStore`]: crate::http::HttpStore
+    /// [`MicrosoftAzure`]: crate::azure::MicrosoftAzure
+    pub fn with_multi_range_requests(mut self, enabled: bool) -> Self {
+        self.multi_range_requests = enabled;
+        self
+    }
+
+    /// THIS METHOD IS ARAS ONLY
+    ///
+    /// Returns true if multiple ranges should be fetched with a single request
+    pub fn multi_range_requests(&self) -> bool {
+        self.multi_range_requests
+    }
+
     /// Get the mime type for the file in `path` to be uploaded
     ///
     /// Gets the file extension from `path`, and returns the
@@ -755,6 +818,15 @@ impl GetOptionsExt for HttpRequestBuilder {
             self = self.header(RANGE, range.to_string());
         }
 
+        #[cfg(any(feature = "azure", feature = "http"))]
+        let extensions = {
+            let mut extensions = extensions;
+            if let Some(ranges) = extensions.remove::<MultiRange>() {
+                self = self.header(RANGE, ranges.header_value());
+            }
+            extensions
+        };
+
         if let Some(tag) = if_match {
             self = self.header(IF_MATCH, tag);
         }
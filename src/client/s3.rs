@@ -16,8 +16,10 @@
 
 //! The list and multipart API used by both GCS and S3
 
-use crate::multipart::PartId;
+use crate::multipart::{MultipartUploadInfo, PartId, PartInfo};
 use crate::path::Path;
+#[cfg(feature = "aws")]
+use crate::version::ObjectVersion;
 use crate::{ListResult, ObjectMeta, Result};
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
@@ -86,6 +88,77 @@ impl TryFrom<ListContents> for ObjectMeta {
     }
 }
 
+/// THIS STRUCT IS ARAS ONLY
+///
+/// Response of <https://docs.aws.amazon.com/AmazonS3/latest/API/API_ListObjectVersions.html>
+#[cfg(feature = "aws")]
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+pub(crate) struct ListVersionsResponse {
+    #[serde(default)]
+    pub version: Vec<ListVersion>,
+    #[serde(default)]
+    pub delete_marker: Vec<ListVersion>,
+    #[serde(default)]
+    pub next_key_marker: Option<String>,
+    #[serde(default)]
+    pub next_version_id_marker: Option<String>,
+}
+
+/// THIS STRUCT IS ARAS ONLY
+///
+/// An object version or delete marker in a [`ListVersionsResponse`]
+#[cfg(feature = "aws")]
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+pub(crate) struct ListVersion {
+    pub key: String,
+    pub version_id: String,
+    pub is_latest: bool,
+    pub last_modified: DateTime<Utc>,
+    #[serde(rename = "ETag")]
+    pub e_tag: Option<String>,
+    #[serde(default)]
+    pub size: u64,
+}
+
+/// THIS IMPL IS ARAS ONLY
+#[cfg(feature = "aws")]
+impl TryFrom<ListVersionsResponse> for Vec<ObjectVersion> {
+    type Error = crate::Error;
+
+    fn try_from(value: ListVersionsResponse) -> Result<Self> {
+        let convert = |v: ListVersion, is_delete_marker: bool| {
+            Ok(ObjectVersion {
+                meta: ObjectMeta {
+                    location: Path::parse(v.key)?,
+                    last_modified: v.last_modified,
+                    size: v.size,
+                    e_tag: v.e_tag,
+                    version: Some(v.version_id),
+                },
+                is_latest: v.is_latest,
+                is_delete_marker,
+            })
+        };
+
+        let versions = value.version.into_iter().map(|v| convert(v, false));
+        let markers = value.delete_marker.into_iter().map(|v| convert(v, true));
+        let mut out = versions.chain(markers).collect::<Result<Self>>()?;
+
+        // Versions and delete markers are returned as separate lists, restore the
+        // ordering of the response with the most recent version of each key first
+        out.sort_by(|a, b| {
+            a.meta
+                .location
+                .cmp(&b.meta.location)
+                .then(b.is_latest.cmp(&a.is_latest))
+                .then(b.meta.last_modified.cmp(&a.meta.last_modified))
+        });
+        Ok(out)
+    }
+}
+
 #[derive(Debug, Deserialize)]
 #[serde(rename_all = "PascalCase")]
 pub(crate) struct InitiateMultipartUploadResult {
@@ -155,3 +228,118 @@ pub(crate) struct CompleteMultipartUploadResult {
     #[serde(rename = "ETag")]
     pub e_tag: String,
 }
+
+/// THIS STRUCT IS ARAS ONLY
+///
+/// Response of <https://docs.aws.amazon.com/AmazonS3/latest/API/API_ListMultipartUploads.html>
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+pub(crate) struct ListMultipartUploadsResponse {
+    #[serde(default)]
+    pub upload: Vec<ListMultipartUpload>,
+    #[serde(default)]
+    pub is_truncated: bool,
+    #[serde(default)]
+    pub next_key_marker: Option<String>,
+    #[serde(default)]
+    pub next_upload_id_marker: Option<String>,
+}
+
+/// THIS STRUCT IS ARAS ONLY
+///
+/// An upload in a [`ListMultipartUploadsResponse`]
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+pub(crate) struct ListMultipartUpload {
+    pub key: String,
+    pub upload_id: String,
+    pub initiated: DateTime<Utc>,
+}
+
+impl ListMultipartUploadsResponse {
+    /// THIS METHOD IS ARAS ONLY
+    ///
+    /// Returns the token of the next page, encoding both the upload id marker and the
+    /// key marker, or `None` if this is the last page
+    pub(crate) fn page_token(&mut self) -> Option<String> {
+        if !self.is_truncated {
+            return None;
+        }
+        let key = self.next_key_marker.take()?;
+        let upload_id = self.next_upload_id_marker.take().unwrap_or_default();
+        Some(format!("{upload_id}\n{key}"))
+    }
+}
+
+/// THIS IMPL IS ARAS ONLY
+impl TryFrom<ListMultipartUploadsResponse> for Vec<MultipartUploadInfo> {
+    type Error = crate::Error;
+
+    fn try_from(value: ListMultipartUploadsResponse) -> Result<Self> {
+        value
+            .upload
+            .into_iter()
+            .map(|upload| {
+                Ok(MultipartUploadInfo {
+                    location: Path::parse(upload.key)?,
+                    id: upload.upload_id,
+                    initiated: Some(upload.initiated),
+                })
+            })
+            .collect()
+    }
+}
+
+/// THIS STRUCT IS ARAS ONLY
+///
+/// Response of <https://docs.aws.amazon.com/AmazonS3/latest/API/API_ListParts.html>
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+pub(crate) struct ListPartsResponse {
+    #[serde(default)]
+    pub part: Vec<ListPart>,
+    #[serde(default)]
+    pub is_truncated: bool,
+    #[serde(default)]
+    pub next_part_number_marker: Option<String>,
+}
+
+/// THIS STRUCT IS ARAS ONLY
+///
+/// A part in a [`ListPartsResponse`]
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+pub(crate) struct ListPart {
+    pub part_number: usize,
+    #[serde(rename = "ETag")]
+    pub e_tag: String,
+    pub size: u64,
+    pub last_modified: DateTime<Utc>,
+    #[serde(rename = "ChecksumSHA256")]
+    pub checksum_sha256: Option<String>,
+}
+
+/// THIS IMPL IS ARAS ONLY
+///
+/// Parts uploaded with a checksum have a [`PartId`] encoding [`PartMetadata`], as
+/// returned by `put_part`
+impl From<ListPart> for PartInfo {
+    fn from(value: ListPart) -> Self {
+        let content_id = match value.checksum_sha256 {
+            Some(checksum_sha256) => {
+                let meta = PartMetadata {
+                    e_tag: value.e_tag,
+                    checksum_sha256: Some(checksum_sha256),
+                };
+                quick_xml::se::to_string(&meta).unwrap()
+            }
+            None => value.e_tag,
+        };
+        Self {
+            part_idx: value.part_number.saturating_sub(1),
+            part_id: PartId { content_id },
+            size: value.size,
+            last_modified: Some(value.last_modified),
+        }
+    }
+}
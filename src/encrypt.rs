@@ -0,0 +1,791 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! THIS MODULE IS ARAS ONLY
+//!
+//! An object store wrapper encrypting objects client-side with AES-256-GCM
+
+use crate::util::InvalidGetRange;
+use crate::{
+    Attribute, Attributes, BoxStream, DeleteOptions, GetOptions, GetRange, GetResult,
+    GetResultPayload, ListResult, MultipartUpload, ObjectMeta, ObjectStore, Path, PutMode,
+    PutMultipartOpts, PutOptions, PutPayload, PutResult, Result, StreamExt, UploadPart,
+};
+use async_trait::async_trait;
+use base64::prelude::BASE64_STANDARD;
+use base64::Engine;
+use bytes::{Bytes, BytesMut};
+use futures::TryStreamExt;
+use ring::aead::{Aad, LessSafeKey, Nonce, UnboundKey, AES_256_GCM, NONCE_LEN};
+use ring::rand::{SecureRandom, SystemRandom};
+use std::fmt::{Debug, Formatter};
+use std::sync::Arc;
+
+/// The length of the authentication tag appended to each segment
+const TAG_LEN: usize = 16;
+
+/// The metadata key of the wrapped data key of an object
+const DATA_KEY: &str = "encryption-data-key";
+
+/// The metadata key of the segment size of an object
+const SEGMENT_SIZE: &str = "encryption-segment-size";
+
+/// The default size in bytes of the plaintext of each segment
+const DEFAULT_SEGMENT_SIZE: usize = 64 * 1024;
+
+const STORE: &str = "EncryptedStore";
+
+/// A specialized `Error` for encryption-related errors
+#[derive(Debug, thiserror::Error)]
+enum Error {
+    #[error("Object at {path} has no data key")]
+    MissingDataKey { path: String },
+
+    #[error("Object at {path} has segment size {actual}, expected {expected}")]
+    SegmentSize {
+        path: String,
+        actual: String,
+        expected: usize,
+    },
+
+    #[error("Failed to decrypt segment {segment} of object at {path}")]
+    Decrypt { path: String, segment: u64 },
+
+    #[error("Invalid data key: {reason}")]
+    InvalidDataKey { reason: &'static str },
+
+    #[error("Failed to generate data key")]
+    GenerateDataKey,
+
+    #[error("Invalid range: {source}")]
+    Range { source: InvalidGetRange },
+}
+
+impl From<Error> for crate::Error {
+    fn from(source: Error) -> Self {
+        Self::Generic {
+            store: STORE,
+            source: Box::new(source),
+        }
+    }
+}
+
+/// A per-object key used to encrypt a single object, see [`KeyProvider`]
+#[derive(Clone)]
+pub struct DataKey {
+    /// The 256-bit AES key
+    pub key: [u8; 32],
+    /// The encrypted form of [`Self::key`] stored in the metadata of the object
+    pub wrapped: String,
+}
+
+impl Debug for DataKey {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("DataKey")
+            .field("key", &"******")
+            .field("wrapped", &self.wrapped)
+            .finish()
+    }
+}
+
+/// Provides the data keys used by an [`EncryptedStore`]
+///
+/// Each object is encrypted with its own [`DataKey`], the wrapped form of which is
+/// stored in [`Attribute::Metadata`] of the object. This allows implementations to
+/// wrap data keys with a key management service, or see [`StaticKeyProvider`] for
+/// wrapping them with a fixed key.
+///
+/// Data keys are not bound to the location of an object, allowing encrypted objects
+/// to be copied and renamed
+#[async_trait]
+pub trait KeyProvider: Debug + Send + Sync + 'static {
+    /// Generate a new data key for an object to be written to `location`
+    async fn generate_data_key(&self, location: &Path) -> Result<DataKey>;
+
+    /// Unwrap a data key previously returned by [`Self::generate_data_key`]
+    async fn decrypt_data_key(&self, wrapped: &str) -> Result<[u8; 32]>;
+}
+
+/// A [`KeyProvider`] wrapping randomly generated data keys with a fixed 256-bit key
+pub struct StaticKeyProvider {
+    key: LessSafeKey,
+    rng: SystemRandom,
+}
+
+impl StaticKeyProvider {
+    /// Create a new [`StaticKeyProvider`] wrapping data keys with `key`
+    pub fn new(key: [u8; 32]) -> Self {
+        Self {
+            key: cipher(&key),
+            rng: SystemRandom::new(),
+        }
+    }
+}
+
+impl Debug for StaticKeyProvider {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("StaticKeyProvider").finish_non_exhaustive()
+    }
+}
+
+#[async_trait]
+impl KeyProvider for StaticKeyProvider {
+    async fn generate_data_key(&self, _location: &Path) -> Result<DataKey> {
+        let mut key = [0; 32];
+        let mut nonce = [0; NONCE_LEN];
+        self.rng
+            .fill(&mut key)
+            .and_then(|_| self.rng.fill(&mut nonce))
+            .map_err(|_| Error::GenerateDataKey)?;
+
+        let mut wrapped = key.to_vec();
+        self.key
+            .seal_in_place_append_tag(
+                Nonce::assume_unique_for_key(nonce),
+                Aad::empty(),
+                &mut wrapped,
+            )
+            .map_err(|_| Error::GenerateDataKey)?;
+
+        let mut out = nonce.to_vec();
+        out.extend_from_slice(&wrapped);
+        Ok(DataKey {
+            key,
+            wrapped: BASE64_STANDARD.encode(out),
+        })
+    }
+
+    async fn decrypt_data_key(&self, wrapped: &str) -> Result<[u8; 32]> {
+        let mut wrapped = BASE64_STANDARD
+            .decode(wrapped)
+            .map_err(|_| Error::InvalidDataKey { reason: "base64" })?;
+        if wrapped.len() < NONCE_LEN {
+            return Err(Error::InvalidDataKey { reason: "length" }.into());
+        }
+        let mut nonce = [0; NONCE_LEN];
+        nonce.copy_from_slice(&wrapped[..NONCE_LEN]);
+
+        let key = self
+            .key
+            .open_in_place(
+                Nonce::assume_unique_for_key(nonce),
+                Aad::empty(),
+                &mut wrapped[NONCE_LEN..],
+            )
+            .map_err(|_| Error::InvalidDataKey {
+                reason: "authentication",
+            })?;
+        <[u8; 32]>::try_from(&*key).map_err(|_| {
+            Error::InvalidDataKey {
+                reason: "key length",
+            }
+            .into()
+        })
+    }
+}
+
+/// Store wrapper that encrypts objects with AES-256-GCM before writing them to the
+/// inner store, and decrypts them when read
+///
+/// Objects are encrypted in authenticated segments of a fixed plaintext size, such
+/// that range requests only fetch and decrypt the segments covering the range. Each
+/// object is encrypted with its own data key from a [`KeyProvider`], stored wrapped
+/// in [`Attribute::Metadata`] alongside the segment size, and so the inner store
+/// must support metadata [`Attributes`].
+///
+/// The sizes reported by [`ObjectStore::head`] and [`ObjectStore::list`] are those of
+/// the plaintext, derived from the encrypted size using the configured segment size.
+/// The segment size must therefore not be changed for a store with existing objects.
+///
+/// Appending to objects, whether with [`PutMode::Append`] or
+/// [`PutMultipartOpts::mode`], and [`ObjectStore::compose`] are not
+/// supported and return [`Error::NotImplemented`](crate::Error::NotImplemented)
+///
+/// ```
+/// # use std::sync::Arc;
+/// # use object_store::encrypt::{EncryptedStore, StaticKeyProvider};
+/// # use object_store::memory::InMemory;
+/// let keys = Arc::new(StaticKeyProvider::new([42; 32]));
+/// let store = EncryptedStore::new(InMemory::new(), keys);
+/// ```
+#[derive(Debug)]
+pub struct EncryptedStore<T: ObjectStore> {
+    inner: Arc<T>,
+    keys: Arc<dyn KeyProvider>,
+    segments: Segments,
+}
+
+impl<T: ObjectStore> EncryptedStore<T> {
+    /// Create a new [`EncryptedStore`] encrypting the objects of `inner` with data
+    /// keys from `keys`
+    pub fn new(inner: T, keys: Arc<dyn KeyProvider>) -> Self {
+        Self {
+            inner: Arc::new(inner),
+            keys,
+            segments: Segments {
+                size: DEFAULT_SEGMENT_SIZE,
+            },
+        }
+    }
+
+    /// Set the size in bytes of the plaintext of each segment, defaults to 64 KiB
+    pub fn with_segment_size(self, size: usize) -> Self {
+        Self {
+            segments: Segments { size: size.max(1) },
+            ..self
+        }
+    }
+
+    /// Returns `attributes` with the metadata of `key` added
+    fn encryption_attributes(&self, mut attributes: Attributes, key: &DataKey) -> Attributes {
+        let wrapped = key.wrapped.clone().into();
+        attributes.insert(Attribute::Metadata(DATA_KEY.into()), wrapped);
+        let segment_size = self.segments.size.to_string().into();
+        attributes.insert(Attribute::Metadata(SEGMENT_SIZE.into()), segment_size);
+        attributes
+    }
+
+    /// Returns the data key of the object at `location` with `attributes`, removing
+    /// the encryption metadata from `attributes`
+    async fn data_key(&self, location: &Path, attributes: &mut Attributes) -> Result<LessSafeKey> {
+        let path = location.to_string();
+        match attributes.remove(&Attribute::Metadata(SEGMENT_SIZE.into())) {
+            Some(size) if size.as_ref() == self.segments.size.to_string() => {}
+            actual => {
+                return Err(Error::SegmentSize {
+                    path,
+                    actual: actual.as_deref().unwrap_or_default().to_string(),
+                    expected: self.segments.size,
+                }
+                .into())
+            }
+        }
+        let wrapped = attributes
+            .remove(&Attribute::Metadata(DATA_KEY.into()))
+            .ok_or(Error::MissingDataKey { path })?;
+        let key = self.keys.decrypt_data_key(&wrapped).await?;
+        Ok(cipher(&key))
+    }
+
+    fn plaintext_meta(&self, meta: ObjectMeta) -> ObjectMeta {
+        ObjectMeta {
+            size: self.segments.plaintext_len(meta.size),
+            ..meta
+        }
+    }
+}
+
+impl<T: ObjectStore> std::fmt::Display for EncryptedStore<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "EncryptedStore({})", self.inner)
+    }
+}
+
+#[async_trait]
+impl<T: ObjectStore> ObjectStore for EncryptedStore<T> {
+    async fn put_opts(
+        &self,
+        location: &Path,
+        payload: PutPayload,
+        opts: PutOptions,
+    ) -> Result<PutResult> {
+        if matches!(opts.mode, PutMode::Append) {
+            return Err(crate::Error::NotImplemented);
+        }
+        let key = self.keys.generate_data_key(location).await?;
+        let mut encryptor = Encryptor::new(&key.key, self.segments);
+        payload.iter().for_each(|chunk| encryptor.push(chunk));
+
+        let opts = PutOptions {
+            attributes: self.encryption_attributes(opts.attributes, &key),
+            ..opts
+        };
+        let payload = encryptor.finish();
+        self.inner.put_opts(location, payload.into(), opts).await
+    }
+
+    async fn put_multipart_opts(
+        &self,
+        location: &Path,
+        opts: PutMultipartOpts,
+    ) -> Result<Box<dyn MultipartUpload>> {
+        if matches!(opts.mode, PutMode::Append) {
+            return Err(crate::Error::NotImplemented);
+        }
+        let key = self.keys.generate_data_key(location).await?;
+        let opts = PutMultipartOpts {
+            attributes: self.encryption_attributes(opts.attributes, &key),
+            ..opts
+        };
+        let upload = self.inner.put_multipart_opts(location, opts).await?;
+        Ok(Box::new(EncryptedUpload {
+            upload,
+            encryptor: Encryptor::new(&key.key, self.segments),
+        }))
+    }
+
+    async fn get_opts(&self, location: &Path, mut options: GetOptions) -> Result<GetResult> {
+        if options.head {
+            let mut result = self.inner.get_opts(location, options).await?;
+            self.data_key(location, &mut result.attributes).await?;
+            result.meta = self.plaintext_meta(result.meta);
+            result.range = 0..result.meta.size;
+            return Ok(result);
+        }
+
+        // The plaintext size is required to resolve a suffix
+        let requested = match options.range.take() {
+            Some(GetRange::Suffix(n)) => {
+                let head = GetOptions {
+                    head: true,
+                    ..options.clone()
+                };
+                let meta = self.inner.get_opts(location, head).await?.meta;
+                let size = self.segments.plaintext_len(meta.size);
+                options.if_match = meta.e_tag.or(options.if_match);
+                (size > 0).then_some(GetRange::Bounded(size.saturating_sub(n)..size))
+            }
+            range => range,
+        };
+
+        if let Some(requested) = &requested {
+            requested
+                .is_valid()
+                .map_err(|source| Error::Range { source })?;
+        }
+        let segment_len = self.segments.encrypted_segment_len();
+        let first = match &requested {
+            Some(GetRange::Bounded(r)) => {
+                let last = (r.end - 1) / self.segments.size as u64;
+                let first = r.start / self.segments.size as u64;
+                options.range = Some(GetRange::Bounded(
+                    first * segment_len..(last + 1) * segment_len,
+                ));
+                first
+            }
+            Some(GetRange::Offset(o)) => {
+                let first = o / self.segments.size as u64;
+                options.range = Some(GetRange::Offset(first * segment_len));
+                first
+            }
+            _ => 0,
+        };
+
+        let mut result = self.inner.get_opts(location, options).await?;
+        let key = self.data_key(location, &mut result.attributes).await?;
+        let meta = self.plaintext_meta(result.meta.clone());
+        let range = match &requested {
+            Some(r) => r
+                .as_range(meta.size)
+                .map_err(|source| Error::Range { source })?,
+            None => 0..meta.size,
+        };
+
+        let decryptor = Decryptor {
+            key,
+            segments: self.segments,
+            path: location.to_string(),
+            next: first,
+            last: self.segments.count(result.meta.size) - 1,
+            skip: range.start - first * self.segments.size as u64,
+            take: range.end - range.start,
+            buf: BytesMut::new(),
+        };
+        let attributes = std::mem::take(&mut result.attributes);
+        let payload = decryptor.decrypt(result.into_stream());
+        Ok(GetResult {
+            payload: GetResultPayload::Stream(payload),
+            meta,
+            range,
+            attributes,
+        })
+    }
+
+    async fn delete(&self, location: &Path) -> Result<()> {
+        self.inner.delete(location).await
+    }
+
+    async fn delete_opts(&self, location: &Path, opts: DeleteOptions) -> Result<()> {
+        self.inner.delete_opts(location, opts).await
+    }
+
+    fn delete_stream<'a>(
+        &'a self,
+        locations: BoxStream<'a, Result<Path>>,
+    ) -> BoxStream<'a, Result<Path>> {
+        self.inner.delete_stream(locations)
+    }
+
+    fn list(&self, prefix: Option<&Path>) -> BoxStream<'static, Result<ObjectMeta>> {
+        let segments = self.segments;
+        self.inner
+            .list(prefix)
+            .map_ok(move |meta| ObjectMeta {
+                size: segments.plaintext_len(meta.size),
+                ..meta
+            })
+            .boxed()
+    }
+
+    fn list_with_offset(
+        &self,
+        prefix: Option<&Path>,
+        offset: &Path,
+    ) -> BoxStream<'static, Result<ObjectMeta>> {
+        let segments = self.segments;
+        self.inner
+            .list_with_offset(prefix, offset)
+            .map_ok(move |meta| ObjectMeta {
+                size: segments.plaintext_len(meta.size),
+                ..meta
+            })
+            .boxed()
+    }
+
+    async fn list_with_delimiter(&self, prefix: Option<&Path>) -> Result<ListResult> {
+        let mut result = self.inner.list_with_delimiter(prefix).await?;
+        result.objects = result
+            .objects
+            .into_iter()
+            .map(|meta| self.plaintext_meta(meta))
+            .collect();
+        Ok(result)
+    }
+
+    async fn copy(&self, from: &Path, to: &Path) -> Result<()> {
+        self.inner.copy(from, to).await
+    }
+
+    async fn rename(&self, from: &Path, to: &Path) -> Result<()> {
+        self.inner.rename(from, to).await
+    }
+
+    async fn copy_if_not_exists(&self, from: &Path, to: &Path) -> Result<()> {
+        self.inner.copy_if_not_exists(from, to).await
+    }
+
+    async fn rename_if_not_exists(&self, from: &Path, to: &Path) -> Result<()> {
+        self.inner.rename_if_not_exists(from, to).await
+    }
+}
+
+fn cipher(key: &[u8; 32]) -> LessSafeKey {
+    LessSafeKey::new(UnboundKey::new(&AES_256_GCM, key).unwrap())
+}
+
+/// Returns the nonce of segment `idx`, which is unique as each object has its own key
+fn nonce(idx: u64) -> Nonce {
+    let mut nonce = [0; NONCE_LEN];
+    nonce[NONCE_LEN - 8..].copy_from_slice(&idx.to_be_bytes());
+    Nonce::assume_unique_for_key(nonce)
+}
+
+/// Returns the additional authenticated data of a segment, marking the final segment
+/// of an object to detect truncation
+fn aad(last: bool) -> Aad<[u8; 1]> {
+    Aad::from([last as u8])
+}
+
+/// The layout of the segments of encrypted objects
+///
+/// Each segment holds up to `size` bytes of plaintext followed by an authentication
+/// tag, with every object having at least one, possibly empty, segment
+#[derive(Debug, Clone, Copy)]
+struct Segments {
+    size: usize,
+}
+
+impl Segments {
+    fn encrypted_segment_len(&self) -> u64 {
+        (self.size + TAG_LEN) as u64
+    }
+
+    /// Returns the number of segments of an object of `encrypted_len` bytes
+    fn count(&self, encrypted_len: u64) -> u64 {
+        let segment_len = self.encrypted_segment_len();
+        ((encrypted_len + segment_len - 1) / segment_len).max(1)
+    }
+
+    /// Returns the plaintext size of an object of `encrypted_len` bytes
+    fn plaintext_len(&self, encrypted_len: u64) -> u64 {
+        encrypted_len.saturating_sub(self.count(encrypted_len) * TAG_LEN as u64)
+    }
+}
+
+/// Encrypts a stream of plaintext into segments
+struct Encryptor {
+    key: LessSafeKey,
+    segments: Segments,
+    /// The plaintext of the current segment
+    buf: BytesMut,
+    /// The encrypted segments not yet returned
+    out: Vec<u8>,
+    next: u64,
+}
+
+impl Encryptor {
+    fn new(key: &[u8; 32], segments: Segments) -> Self {
+        Self {
+            key: cipher(key),
+            segments,
+            buf: BytesMut::new(),
+            out: vec![],
+            next: 0,
+        }
+    }
+
+    /// Encrypts `data`, holding back the current segment as it may be the last
+    fn push(&mut self, mut data: &[u8]) {
+        while !data.is_empty() {
+            if self.buf.len() == self.segments.size {
+                self.seal(false);
+            }
+            let n = data.len().min(self.segments.size - self.buf.len());
+            self.buf.extend_from_slice(&data[..n]);
+            data = &data[n..];
+        }
+    }
+
+    /// Returns the encrypted segments so far
+    fn take(&mut self) -> Vec<u8> {
+        std::mem::take(&mut self.out)
+    }
+
+    /// Encrypts the last segment, returning the remaining encrypted segments
+    fn finish(&mut self) -> Vec<u8> {
+        self.seal(true);
+        self.take()
+    }
+
+    fn seal(&mut self, last: bool) {
+        let start = self.out.len();
+        self.out.extend_from_slice(&self.buf);
+        self.buf.clear();
+        let tag = self
+            .key
+            .seal_in_place_separate_tag(nonce(self.next), aad(last), &mut self.out[start..])
+            .unwrap();
+        self.out.extend_from_slice(tag.as_ref());
+        self.next += 1;
+    }
+}
+
+/// Decrypts a stream of segments, yielding `take` bytes of plaintext after skipping
+/// the first `skip`
+struct Decryptor {
+    key: LessSafeKey,
+    segments: Segments,
+    path: String,
+    /// The index of the next segment
+    next: u64,
+    /// The index of the last segment of the object
+    last: u64,
+    skip: u64,
+    take: u64,
+    buf: BytesMut,
+}
+
+impl Decryptor {
+    fn decrypt(
+        self,
+        stream: BoxStream<'static, Result<Bytes>>,
+    ) -> BoxStream<'static, Result<Bytes>> {
+        futures::stream::try_unfold(
+            (stream, self, false),
+            |(mut stream, mut decryptor, done)| async move {
+                if done {
+                    return Ok(None);
+                }
+                while let Some(chunk) = stream.try_next().await? {
+                    decryptor.buf.extend_from_slice(&chunk);
+                    let out = decryptor.drain(false)?;
+                    if !out.is_empty() {
+                        return Ok(Some((out, (stream, decryptor, false))));
+                    }
+                }
+                let out = decryptor.drain(true)?;
+                Ok((!out.is_empty()).then_some((out, (stream, decryptor, true))))
+            },
+        )
+        .boxed()
+    }
+
+    /// Decrypts the buffered segments, including any partial segment if `eof`
+    fn drain(&mut self, eof: bool) -> Result<Bytes> {
+        let segment_len = self.segments.encrypted_segment_len() as usize;
+        let mut out = BytesMut::new();
+        while self.take > 0 && (self.buf.len() >= segment_len || (eof && !self.buf.is_empty())) {
+            let len = self.buf.len().min(segment_len);
+            let mut segment = self.buf.split_to(len);
+            let idx = self.next;
+            let plaintext = self
+                .key
+                .open_in_place(nonce(idx), aad(idx == self.last), &mut segment)
+                .map_err(|_| Error::Decrypt {
+                    path: self.path.clone(),
+                    segment: idx,
+                })?;
+            self.next += 1;
+
+            let skip = self.skip.min(plaintext.len() as u64) as usize;
+            self.skip -= skip as u64;
+            let plaintext = &plaintext[skip..];
+            let take = self.take.min(plaintext.len() as u64) as usize;
+            self.take -= take as u64;
+            out.extend_from_slice(&plaintext[..take]);
+        }
+        Ok(out.freeze())
+    }
+}
+
+/// A [`MultipartUpload`] encrypting each part before uploading it
+struct EncryptedUpload {
+    upload: Box<dyn MultipartUpload>,
+    encryptor: Encryptor,
+}
+
+impl Debug for EncryptedUpload {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("EncryptedUpload")
+            .field("upload", &self.upload)
+            .finish_non_exhaustive()
+    }
+}
+
+#[async_trait]
+impl MultipartUpload for EncryptedUpload {
+    fn put_part(&mut self, data: PutPayload) -> UploadPart {
+        // Each part is uploaded once at least as much ciphertext as the size of the
+        // plaintext part is available, keeping parts above any minimum part size
+        let len = data.content_length();
+        data.iter().for_each(|chunk| self.encryptor.push(chunk));
+        let out = self.encryptor.out.len();
+        match out > 0 && out >= len {
+            true => self.upload.put_part(self.encryptor.take().into()),
+            false => Box::pin(futures::future::ready(Ok(()))),
+        }
+    }
+
+    async fn complete(&mut self) -> Result<PutResult> {
+        let last = self.encryptor.finish();
+        self.upload.put_part(last.into()).await?;
+        self.upload.complete().await
+    }
+
+    async fn abort(&mut self) -> Result<()> {
+        self.upload.abort().await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::integration::*;
+    use crate::memory::InMemory;
+    use crate::WriteMultipart;
+    use std::ops::Range;
+
+    fn range(r: Range<u64>) -> Range<usize> {
+        r.start as usize..r.end as usize
+    }
+
+    /// THIS TEST IS ARAS ONLY
+    #[tokio::test]
+    async fn encrypted_test() {
+        let keys = Arc::new(StaticKeyProvider::new([1; 32]));
+        let integration = EncryptedStore::new(InMemory::new(), keys).with_segment_size(5);
+
+        put_get_delete_list(&integration).await;
+        get_opts(&integration).await;
+        list_uses_directories_correctly(&integration).await;
+        list_with_delimiter(&integration).await;
+        rename_and_copy(&integration).await;
+        put_get_attributes(&integration).await;
+    }
+
+    /// THIS TEST IS ARAS ONLY
+    #[tokio::test]
+    async fn test_segments() {
+        let inner: Arc<dyn ObjectStore> = Arc::new(InMemory::new());
+        let keys = Arc::new(StaticKeyProvider::new([2; 32]));
+        let store = EncryptedStore::new(Arc::clone(&inner), keys).with_segment_size(4);
+
+        let location = Path::from("data");
+        let data = Bytes::from_static(b"0123456789");
+        store.put(&location, data.clone().into()).await.unwrap();
+
+        // Three segments each with a tag
+        let encrypted = inner.get(&location).await.unwrap();
+        let attributes = encrypted.attributes.clone();
+        let encrypted = encrypted.bytes().await.unwrap();
+        assert_eq!(encrypted.len(), 10 + 3 * TAG_LEN);
+        assert!(!encrypted.windows(4).any(|w| w == b"0123"));
+        assert_eq!(store.head(&location).await.unwrap().size, 10);
+        let listed: Vec<_> = store.list(None).try_collect().await.unwrap();
+        assert_eq!(listed[0].size, 10);
+
+        for r in [0..10, 0..1, 3..5, 4..8, 5..10, 9..10, 2..100] {
+            let bytes = store.get_range(&location, r.clone()).await.unwrap();
+            let expected = range(r.start..r.end.min(10));
+            assert_eq!(bytes, data.slice(expected), "{r:?}");
+        }
+        let ranges = store.get_ranges(&location, &[1..2, 6..9]).await.unwrap();
+        assert_eq!(ranges, vec![data.slice(1..2), data.slice(6..9)]);
+
+        // Empty objects have a single empty segment
+        store.put(&location, PutPayload::default()).await.unwrap();
+        let bytes = store.get(&location).await.unwrap().bytes().await.unwrap();
+        assert!(bytes.is_empty());
+        assert_eq!(store.head(&location).await.unwrap().size, 0);
+
+        let mut upload =
+            WriteMultipart::new_with_chunk_size(store.put_multipart(&location).await.unwrap(), 3);
+        upload.write(b"0123456789");
+        upload.finish().await.unwrap();
+        let bytes = store.get(&location).await.unwrap().bytes().await.unwrap();
+        assert_eq!(bytes, data);
+        let bytes = store.get_range(&location, 3..9).await.unwrap();
+        assert_eq!(bytes, data.slice(3..9));
+
+        // Tampered or truncated segments fail to decrypt
+        let truncated = encrypted.slice(..4 + TAG_LEN);
+        let opts = PutOptions::from(attributes.clone());
+        inner
+            .put_opts(&location, truncated.into(), opts)
+            .await
+            .unwrap();
+        let result = store.get(&location).await.unwrap();
+        let err = result.bytes().await.unwrap_err();
+        assert!(err.to_string().contains("decrypt segment 0"), "{err}");
+
+        let mut tampered = encrypted.to_vec();
+        tampered[4 + TAG_LEN + 1] ^= 1;
+        let opts = PutOptions::from(attributes);
+        inner
+            .put_opts(&location, tampered.into(), opts)
+            .await
+            .unwrap();
+        store.get_range(&location, 0..4).await.unwrap();
+        let err = store.get_range(&location, 4..5).await.unwrap_err();
+        assert!(err.to_string().contains("decrypt segment 1"), "{err}");
+
+        let other = EncryptedStore::new(inner, Arc::new(StaticKeyProvider::new([3; 32])));
+        other.put(&location, data.into()).await.unwrap();
+        let err = store.get(&location).await.unwrap_err();
+        assert!(err.to_string().contains("segment size"), "{err}");
+    }
+}
@@ -26,7 +26,10 @@ use crate::gcp::{
     credential, GcpCredential, GcpCredentialProvider, GcpSigningCredential,
     GcpSigningCredentialProvider, GoogleCloudStorage, STORE,
 };
-use crate::{ClientConfigKey, ClientOptions, Result, RetryConfig, StaticCredentialProvider};
+use crate::{
+    ClientConfigKey, ClientOptions, CopyConfig, RangeCoalescePolicy, Result, RetryConfig,
+    StaticCredentialProvider,
+};
 use serde::{Deserialize, Serialize};
 use std::str::FromStr;
 use std::sync::Arc;
@@ -106,6 +109,10 @@ pub struct GoogleCloudStorageBuilder {
     application_credentials_path: Option<String>,
     /// Retry config
     retry_config: RetryConfig,
+    /// THIS FIELD IS ARAS ONLY
+    ///
+    /// Configuration for copies of large objects
+    copy_config: CopyConfig,
     /// Client options
     client_options: ClientOptions,
     /// Credentials
@@ -213,6 +220,7 @@ impl Default for GoogleCloudStorageBuilder {
             service_account_key: None,
             application_credentials_path: None,
             retry_config: Default::default(),
+            copy_config: Default::default(),
             client_options: ClientOptions::new().with_allow_http(true),
             url: None,
             credentials: None,
@@ -420,6 +428,24 @@ impl GoogleCloudStorageBuilder {
         self
     }
 
+    /// THIS METHOD IS ARAS ONLY
+    ///
+    /// Set the configuration for server-side copies of large objects
+    pub fn with_copy_config(mut self, copy_config: CopyConfig) -> Self {
+        self.copy_config = copy_config;
+        self
+    }
+
+    /// THIS METHOD IS ARAS ONLY
+    ///
+    /// Sets the [`RangeCoalescePolicy`] used by [`ObjectStore::get_ranges`]
+    ///
+    /// [`ObjectStore::get_ranges`]: crate::ObjectStore::get_ranges
+    pub fn with_range_coalesce_policy(mut self, policy: RangeCoalescePolicy) -> Self {
+        self.client_options = self.client_options.with_range_coalesce_policy(policy);
+        self
+    }
+
     /// Set the proxy_url to be used by the underlying client
     pub fn with_proxy_url(mut self, proxy_url: impl Into<String>) -> Self {
         self.client_options = self.client_options.with_proxy_url(proxy_url);
@@ -571,6 +597,7 @@ impl GoogleCloudStorageBuilder {
             signing_credentials,
             bucket_name,
             retry_config: self.retry_config,
+            copy_config: self.copy_config,
             client_options: self.client_options,
             skip_signature: self.skip_signature.get()?,
         };
@@ -17,35 +17,40 @@
 
 use crate::client::builder::HttpRequestBuilder;
 use crate::client::get::GetClient;
+use crate::client::get::GetClientExt;
 use crate::client::header::{get_put_result, get_version, HeaderConfig};
-use crate::client::list::ListClient;
+use crate::client::list::{ListClient, ListMultipartClient, ListVersionsClient};
 use crate::client::retry::{RetryContext, RetryExt};
 use crate::client::s3::{
     CompleteMultipartUpload, CompleteMultipartUploadResult, InitiateMultipartUploadResult,
-    ListResponse,
+    ListMultipartUploadsResponse, ListPartsResponse, ListResponse,
 };
 use crate::client::{GetOptionsExt, HttpClient, HttpError, HttpResponse};
 use crate::gcp::credential::CredentialExt;
 use crate::gcp::{GcpCredential, GcpCredentialProvider, GcpSigningCredentialProvider, STORE};
 use crate::list::{PaginatedListOptions, PaginatedListResult};
-use crate::multipart::PartId;
+use crate::multipart::{MultipartUploadInfo, PartId, PartInfo};
 use crate::path::Path;
 use crate::util::hex_encode;
+use crate::version::ObjectVersion;
 use crate::{
-    Attribute, Attributes, ClientOptions, GetOptions, MultipartId, PutMode, PutMultipartOpts,
-    PutOptions, PutPayload, PutResult, Result, RetryConfig,
+    Attribute, Attributes, ClientOptions, CopyConfig, GetOptions, MultipartId, ObjectMeta, PutMode,
+    PutMultipartOpts, PutOptions, PutPayload, PutResult, Result, RetryConfig, TagSet,
 };
 use async_trait::async_trait;
 use base64::prelude::BASE64_STANDARD;
 use base64::Engine;
 use bytes::Buf;
+use chrono::{DateTime, Utc};
 use http::header::{
     CACHE_CONTROL, CONTENT_DISPOSITION, CONTENT_ENCODING, CONTENT_LANGUAGE, CONTENT_LENGTH,
     CONTENT_TYPE,
 };
 use http::{HeaderName, Method, StatusCode};
 use percent_encoding::{percent_encode, utf8_percent_encode, NON_ALPHANUMERIC};
+use rand::Rng as _;
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 use std::sync::Arc;
 
 const VERSION_HEADER: &str = "x-goog-generation";
@@ -53,6 +58,38 @@ const DEFAULT_CONTENT_TYPE: &str = "application/octet-stream";
 const USER_DEFINED_METADATA_HEADER_PREFIX: &str = "x-goog-meta-";
 
 static VERSION_MATCH: HeaderName = HeaderName::from_static("x-goog-if-generation-match");
+/// THIS CONSTANT IS ARAS ONLY
+///
+/// GCS has no native object tags, instead tags are stored as custom metadata with keys
+/// prefixed by this string, which cannot collide with [`Attribute::Metadata`] set via
+/// `x-goog-meta-*` headers as `:` is not permitted in header names
+pub(crate) const TAG_METADATA_PREFIX: &str = "tag:";
+
+/// THIS CONSTANT IS ARAS ONLY
+///
+/// The maximum number of components of a single compose request
+const MAX_COMPOSE_SOURCES: usize = 32;
+
+/// THIS STATIC IS ARAS ONLY
+static COPY_SOURCE_GENERATION: HeaderName =
+    HeaderName::from_static("x-goog-copy-source-generation");
+
+/// THIS CONSTANT IS ARAS ONLY
+///
+/// Part of the message of the error returned by a copy that cannot complete within a
+/// single request, e.g. `Copy spanning locations and/or storage classes could not
+/// complete within 30 seconds. Please use the Rewrite method`
+const REWRITE_REQUIRED: &str = "Rewrite method";
+
+/// THIS FUNCTION IS ARAS ONLY
+///
+/// Returns true if `e` is the rejection of a copy that must instead be rewritten
+fn is_rewrite_required(e: &crate::client::retry::RetryError) -> bool {
+    e.status() == Some(StatusCode::BAD_REQUEST)
+        && e.body()
+            .map(|b| b.contains(REWRITE_REQUIRED))
+            .unwrap_or(false)
+}
 
 #[derive(Debug, thiserror::Error)]
 enum Error {
@@ -67,6 +104,18 @@ enum Error {
     #[error("Got invalid list response: {}", source)]
     InvalidListResponse { source: quick_xml::de::DeError },
 
+    /// THIS VARIANT IS ARAS ONLY
+    #[error("Got invalid list versions response: {}", source)]
+    InvalidListVersionsResponse { source: HttpError },
+
+    /// THIS VARIANT IS ARAS ONLY
+    #[error("Got invalid object metadata response: {}", source)]
+    InvalidObjectMetadataResponse { source: HttpError },
+
+    /// THIS VARIANT IS ARAS ONLY
+    #[error("Got invalid rewrite response: {}", source)]
+    InvalidRewriteResponse { source: HttpError },
+
     #[error("Error performing get request {}: {}", path, source)]
     GetRequest {
         source: crate::client::retry::RetryError,
@@ -145,6 +194,9 @@ pub(crate) struct GoogleCloudStorageConfig {
 
     pub retry_config: RetryConfig,
 
+    /// THIS FIELD IS ARAS ONLY
+    pub copy_config: CopyConfig,
+
     pub client_options: ClientOptions,
 
     pub skip_signature: bool,
@@ -253,6 +305,76 @@ impl Request<'_> {
     }
 }
 
+/// THIS STRUCT IS ARAS ONLY
+///
+/// Compose Request Body <https://cloud.google.com/storage/docs/xml-api/put-object-compose>
+#[derive(Debug, Serialize)]
+#[serde(rename = "ComposeRequest")]
+struct ComposeRequest {
+    #[serde(rename = "Component")]
+    component: Vec<ComposeComponent>,
+}
+
+/// THIS STRUCT IS ARAS ONLY
+#[derive(Debug, Serialize)]
+struct ComposeComponent {
+    #[serde(rename = "Name")]
+    name: String,
+}
+
+/// THIS STRUCT IS ARAS ONLY
+///
+/// JSON API list response <https://cloud.google.com/storage/docs/json_api/v1/objects/list>
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ListVersionsResponse {
+    #[serde(default)]
+    items: Vec<ListVersionsItem>,
+    next_page_token: Option<String>,
+}
+
+/// THIS STRUCT IS ARAS ONLY
+///
+/// An object generation in a [`ListVersionsResponse`]
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ListVersionsItem {
+    name: String,
+    generation: String,
+    size: String,
+    updated: DateTime<Utc>,
+    etag: Option<String>,
+    time_deleted: Option<DateTime<Utc>>,
+}
+
+/// THIS STRUCT IS ARAS ONLY
+///
+/// JSON API object resource, restricted to its custom metadata <https://cloud.google.com/storage/docs/json_api/v1/objects#resource>
+#[derive(Debug, Deserialize)]
+struct ObjectMetadataResponse {
+    #[serde(default)]
+    metadata: BTreeMap<String, String>,
+    metageneration: String,
+}
+
+/// THIS STRUCT IS ARAS ONLY
+///
+/// JSON API rewrite response <https://cloud.google.com/storage/docs/json_api/v1/objects/rewrite>
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct RewriteResponse {
+    done: bool,
+    rewrite_token: Option<String>,
+}
+
+/// THIS STRUCT IS ARAS ONLY
+///
+/// JSON API object patch request body, where `None` removes a metadata entry <https://cloud.google.com/storage/docs/json_api/v1/objects/patch>
+#[derive(Debug, Serialize)]
+struct ObjectMetadataPatch {
+    metadata: BTreeMap<String, Option<String>>,
+}
+
 /// Sign Blob Request Body
 #[derive(Debug, Serialize)]
 struct SignBlobBody {
@@ -361,6 +483,17 @@ impl GoogleCloudStorageClient {
         )
     }
 
+    /// THIS METHOD IS ARAS ONLY
+    ///
+    /// Returns the JSON API URL of the object at `path`
+    fn object_json_url(&self, path: &Path) -> String {
+        let encoded = utf8_percent_encode(path.as_ref(), NON_ALPHANUMERIC);
+        format!(
+            "{}/storage/v1/b/{}/o/{}",
+            self.config.base_url, self.bucket_name_encoded, encoded
+        )
+    }
+
     /// Perform a put request <https://cloud.google.com/storage/docs/xml-api/put-object-upload>
     ///
     /// Returns the new ETag
@@ -377,6 +510,9 @@ impl GoogleCloudStorageClient {
     }
 
     /// THIS METHOD IS COMMON, MODIFIED BY ARAS
+    ///
+    /// Any tags are written once the object is created with [`Self::put_written_tags`].
+    /// Appends are performed by [`Self::append`], returning [`crate::Error::NotImplemented`]
     pub(crate) async fn put(
         &self,
         path: &Path,
@@ -385,11 +521,9 @@ impl GoogleCloudStorageClient {
     ) -> Result<PutResult> {
         let PutOptions {
             mode,
-            // not supported by GCP
-            tags: _,
+            tags,
             attributes,
             extensions,
-            copy_and_append: _copy_and_append,
         } = opts;
 
         let builder = self
@@ -405,14 +539,196 @@ impl GoogleCloudStorageClient {
                 let etag = v.version.as_ref().ok_or(Error::MissingVersion)?;
                 builder.header(&VERSION_MATCH, etag)
             }
+            PutMode::Append => return Err(crate::Error::NotImplemented),
         };
 
-        match (mode, builder.do_put().await) {
+        let result = match (mode, builder.do_put().await) {
             (PutMode::Create, Err(crate::Error::Precondition { path, source })) => {
                 Err(crate::Error::AlreadyExists { path, source })
             }
             (_, r) => r,
+        }?;
+
+        self.put_written_tags(path, &tags).await?;
+        Ok(result)
+    }
+
+    /// THIS METHOD IS ARAS ONLY
+    ///
+    /// Append `payload` to the object at `path` by uploading it to a temporary object
+    /// and composing it onto the end of the existing object
+    ///
+    /// Any tags are written once the object is appended to with [`Self::put_written_tags`]
+    pub(crate) async fn append(
+        self: &Arc<Self>,
+        path: &Path,
+        payload: PutPayload,
+        opts: PutOptions,
+    ) -> Result<PutResult> {
+        let PutOptions {
+            tags,
+            attributes,
+            extensions,
+            ..
+        } = opts;
+        let result = self
+            .append_object(path, payload, attributes, extensions)
+            .await?;
+        self.put_written_tags(path, &tags).await?;
+        Ok(result)
+    }
+
+    /// THIS METHOD IS ARAS ONLY
+    ///
+    /// Append `payload` to the object at `path` with `attributes`, see [`Self::append`]
+    async fn append_object(
+        self: &Arc<Self>,
+        path: &Path,
+        payload: PutPayload,
+        attributes: Attributes,
+        extensions: ::http::Extensions,
+    ) -> Result<PutResult> {
+        let options = GetOptions {
+            head: true,
+            ..Default::default()
+        };
+        let existing = match self.get_opts(path, options).await {
+            Ok(existing) => existing,
+            Err(crate::Error::NotFound { .. }) => {
+                return self
+                    .request(Method::PUT, path)
+                    .with_payload(payload)
+                    .with_attributes(attributes)
+                    .with_extensions(extensions)
+                    .header(&VERSION_MATCH, "0")
+                    .do_put()
+                    .await;
+            }
+            Err(e) => return Err(e),
+        };
+        let generation = existing.meta.version.ok_or(Error::MissingVersion)?;
+
+        let suffix = u128::from_be_bytes(rand::rng().random());
+        let temporary = Path::from(format!("{path}.append-{suffix:032x}"));
+        self.request(Method::PUT, &temporary)
+            .with_payload(payload)
+            .idempotent(true)
+            .do_put()
+            .await?;
+
+        let attributes = match attributes.is_empty() {
+            true => existing.attributes,
+            false => attributes,
+        };
+        let sources = [path.clone(), temporary.clone()];
+        let result = self
+            .compose_request(
+                &sources,
+                path,
+                Some(generation.as_str()),
+                attributes,
+                extensions,
+            )
+            .await;
+
+        let _ = self.delete_request(&temporary).await; // Attempt to cleanup
+        result
+    }
+
+    /// THIS METHOD IS ARAS ONLY
+    ///
+    /// Perform a compose request <https://cloud.google.com/storage/docs/xml-api/put-object-compose>
+    pub(crate) async fn compose_request(
+        &self,
+        sources: &[Path],
+        to: &Path,
+        if_generation_match: Option<&str>,
+        attributes: Attributes,
+        extensions: ::http::Extensions,
+    ) -> Result<PutResult> {
+        let body = ComposeRequest {
+            component: sources
+                .iter()
+                .map(|source| ComposeComponent {
+                    name: source.to_string(),
+                })
+                .collect(),
+        };
+        let data = quick_xml::se::to_string(&body)
+            .map_err(|source| Error::InvalidPutRequest { source })?;
+
+        let mut builder = self
+            .request(Method::PUT, to)
+            .query(&[("compose", "")])
+            .with_payload(data.into())
+            .with_attributes(attributes)
+            .with_extensions(extensions);
+
+        if let Some(generation) = if_generation_match {
+            builder = builder.header(&VERSION_MATCH, generation);
         }
+        builder.do_put().await
+    }
+
+    /// THIS METHOD IS ARAS ONLY
+    ///
+    /// Composes any number of `sources` into `to`, composing them into a temporary
+    /// object [`MAX_COMPOSE_SOURCES`] at a time if there are too many for a single request
+    pub(crate) async fn compose(&self, sources: &[Path], to: &Path) -> Result<PutResult> {
+        if sources.is_empty() {
+            return self
+                .request(Method::PUT, to)
+                .with_payload(PutPayload::default())
+                .do_put()
+                .await;
+        }
+        if sources.len() <= MAX_COMPOSE_SOURCES {
+            return self
+                .compose_request(sources, to, None, Attributes::new(), Default::default())
+                .await;
+        }
+
+        let suffix = u128::from_be_bytes(rand::rng().random());
+        let temporary = Path::from(format!("{to}.compose-{suffix:032x}"));
+        let result = async {
+            let (first, mut remaining) = sources.split_at(MAX_COMPOSE_SOURCES);
+            self.compose_request(
+                first,
+                &temporary,
+                None,
+                Attributes::new(),
+                Default::default(),
+            )
+            .await?;
+            loop {
+                let (next, rest) = remaining.split_at(remaining.len().min(MAX_COMPOSE_SOURCES - 1));
+                remaining = rest;
+
+                let mut components = Vec::with_capacity(next.len() + 1);
+                components.push(temporary.clone());
+                components.extend_from_slice(next);
+                let dest = match remaining.is_empty() {
+                    true => to,
+                    false => &temporary,
+                };
+                let result = self
+                    .compose_request(
+                        &components,
+                        dest,
+                        None,
+                        Attributes::new(),
+                        Default::default(),
+                    )
+                    .await?;
+                if remaining.is_empty() {
+                    return Ok(result);
+                }
+            }
+        }
+        .await;
+
+        let _ = self.delete_request(&temporary).await; // Attempt to cleanup
+        result
     }
 
     /// Perform a put part request <https://cloud.google.com/storage/docs/xml-api/put-object-multipart>
@@ -451,11 +767,11 @@ impl GoogleCloudStorageClient {
         opts: PutMultipartOpts,
     ) -> Result<MultipartId> {
         let PutMultipartOpts {
-            // not supported by GCP
+            // written once the upload is completed, see GCSMultipartUpload
             tags: _,
             attributes,
             extensions,
-            copy_and_append: _copy_and_append,
+            mode: _,
         } = opts;
 
         let response = self
@@ -505,6 +821,46 @@ impl GoogleCloudStorageClient {
         Ok(())
     }
 
+    /// THIS METHOD IS ARAS ONLY
+    ///
+    /// List the parts of a multipart upload, fetching all pages <https://cloud.google.com/storage/docs/xml-api/get-object-multipart>
+    pub(crate) async fn list_parts(
+        &self,
+        path: &Path,
+        multipart_id: &MultipartId,
+    ) -> Result<Vec<PartInfo>> {
+        let mut parts = Vec::new();
+        let mut marker: Option<String> = None;
+        loop {
+            let mut query = vec![("uploadId", multipart_id.as_str())];
+            if let Some(marker) = &marker {
+                query.push(("part-number-marker", marker.as_str()));
+            }
+
+            let response = self
+                .request(Method::GET, path)
+                .query(&query)
+                .idempotent(true)
+                .send()
+                .await?
+                .into_body()
+                .bytes()
+                .await
+                .map_err(|source| Error::ListResponseBody { source })?;
+
+            let response: ListPartsResponse = quick_xml::de::from_reader(response.reader())
+                .map_err(|source| Error::InvalidListResponse { source })?;
+
+            parts.extend(response.part.into_iter().map(PartInfo::from));
+            match response.next_part_number_marker {
+                Some(next) if response.is_truncated => marker = Some(next),
+                _ => break,
+            }
+        }
+        parts.sort_by_key(|part| part.part_idx);
+        Ok(parts)
+    }
+
     pub(crate) async fn multipart_complete(
         &self,
         path: &Path,
@@ -569,29 +925,198 @@ impl GoogleCloudStorageClient {
         Ok(())
     }
 
+    /// THIS METHOD IS ARAS ONLY
+    ///
+    /// Perform a delete request conditional on the object's generation <https://cloud.google.com/storage/docs/xml-api/reference-headers#xgoogifgenerationmatch>
+    pub(crate) async fn delete_if_generation_match_request(
+        &self,
+        path: &Path,
+        generation: &str,
+    ) -> Result<()> {
+        let result = self
+            .request(Method::DELETE, path)
+            .header(&VERSION_MATCH, generation)
+            .send()
+            .await;
+
+        match result {
+            // GCS reports NotFound when the object doesn't exist, convert for
+            // consistency with conditional puts
+            Err(crate::Error::NotFound { path, source }) => {
+                Err(crate::Error::Precondition { path, source })
+            }
+            r => r.map(|_| ()),
+        }
+    }
+
+    /// THIS METHOD IS ARAS ONLY
+    ///
+    /// Perform a delete request for a specific generation <https://cloud.google.com/storage/docs/xml-api/delete-object>
+    pub(crate) async fn delete_version_request(&self, path: &Path, version: &str) -> Result<()> {
+        self.request(Method::DELETE, path)
+            .query(&[("generation", version)])
+            .send()
+            .await?;
+        Ok(())
+    }
+
+    /// THIS METHOD IS ARAS ONLY
+    ///
+    /// Perform a JSON API get request for the custom metadata of an object <https://cloud.google.com/storage/docs/json_api/v1/objects/get>
+    async fn get_object_metadata(&self, path: &Path) -> Result<ObjectMetadataResponse> {
+        let credential = self.get_credential().await?;
+        let response = self
+            .client
+            .request(Method::GET, self.object_json_url(path))
+            .query(&[("fields", "metadata,metageneration")])
+            .with_bearer_auth(credential.as_deref())
+            .send_retry(&self.config.retry_config)
+            .await
+            .map_err(|source| Error::GetRequest {
+                source,
+                path: path.to_string(),
+            })?
+            .into_body()
+            .json::<ObjectMetadataResponse>()
+            .await
+            .map_err(|source| Error::InvalidObjectMetadataResponse { source })?;
+        Ok(response)
+    }
+
+    /// THIS METHOD IS ARAS ONLY
+    ///
+    /// Read the tags of an object from its custom metadata, see [`TAG_METADATA_PREFIX`]
+    pub(crate) async fn get_object_tags(&self, path: &Path) -> Result<TagSet> {
+        let response = self.get_object_metadata(path).await?;
+        Ok(response
+            .metadata
+            .iter()
+            .filter_map(|(k, v)| Some((k.strip_prefix(TAG_METADATA_PREFIX)?, v)))
+            .collect())
+    }
+
+    /// THIS METHOD IS ARAS ONLY
+    ///
+    /// Replace the tags of an object with a metadata patch request, see [`TAG_METADATA_PREFIX`] <https://cloud.google.com/storage/docs/json_api/v1/objects/patch>
+    ///
+    /// Existing tags are removed by the same request, which is conditional on the object's
+    /// metageneration and retried up to [`RetryConfig::max_retries`] times if the metadata
+    /// is modified concurrently
+    pub(crate) async fn put_object_tags(&self, path: &Path, tags: &TagSet) -> Result<()> {
+        let mut retries = 0;
+        loop {
+            let existing = self.get_object_metadata(path).await?;
+            let mut metadata: BTreeMap<_, _> = existing
+                .metadata
+                .into_keys()
+                .filter(|k| k.starts_with(TAG_METADATA_PREFIX))
+                .map(|k| (k, None))
+                .collect();
+            for (k, v) in tags.iter() {
+                let key = format!("{TAG_METADATA_PREFIX}{k}");
+                metadata.insert(key, Some(v.into_owned()));
+            }
+            if metadata.is_empty() {
+                return Ok(());
+            }
+
+            let credential = self.get_credential().await?;
+            let result = self
+                .client
+                .request(Method::PATCH, self.object_json_url(path))
+                .query(&[
+                    ("fields", "metageneration"),
+                    ("ifMetagenerationMatch", existing.metageneration.as_str()),
+                ])
+                .with_bearer_auth(credential.as_deref())
+                .json(ObjectMetadataPatch { metadata })
+                .retryable(&self.config.retry_config)
+                .idempotent(true)
+                .send()
+                .await
+                .map_err(|source| Error::Request {
+                    source,
+                    path: path.to_string(),
+                });
+
+            match result.map_err(crate::Error::from) {
+                Ok(_) => return Ok(()),
+                Err(crate::Error::Precondition { .. })
+                    if retries < self.config.retry_config.max_retries =>
+                {
+                    retries += 1
+                }
+                Err(e) => return Err(e),
+            }
+        }
+    }
+
+    /// THIS METHOD IS ARAS ONLY
+    ///
+    /// Write the tags of an object that was just written with [`Self::put_object_tags`]
+    ///
+    /// This is a separate, non-atomic step, and so a failure is reported as
+    /// [`crate::Error::TagsNotWritten`] as the object itself has been written
+    pub(crate) async fn put_written_tags(&self, path: &Path, tags: &TagSet) -> Result<()> {
+        if tags.is_empty() {
+            return Ok(());
+        }
+        self.put_object_tags(path, tags)
+            .await
+            .map_err(|e| crate::Error::TagsNotWritten {
+                path: path.to_string(),
+                source: Box::new(e),
+            })
+    }
+
+    /// THIS METHOD IS COMMON, MODIFIED BY ARAS
+    ///
     /// Perform a copy request <https://cloud.google.com/storage/docs/xml-api/put-object-copy>
+    ///
+    /// Objects larger than [`CopyConfig::multipart_threshold`] are copied with
+    /// [`Self::rewrite_request`] instead. Their size is only determined up front if
+    /// [`CopyConfig::probe_size`], otherwise the rewrite is used once the copy is rejected
+    /// for having to be rewritten
     pub(crate) async fn copy_request(
-        &self,
+        self: &Arc<Self>,
         from: &Path,
+        version: Option<&str>,
         to: &Path,
         if_not_exists: bool,
     ) -> Result<()> {
+        let copy_config = &self.config.copy_config;
+        let ranged = version.is_none() && copy_config.multipart_threshold != u64::MAX;
+        if ranged && copy_config.probe_size() {
+            let options = GetOptions {
+                head: true,
+                ..Default::default()
+            };
+            let size = self.get_opts(from, options).await?.meta.size;
+            if copy_config.is_multipart(size) {
+                return self.rewrite_request(from, to, if_not_exists).await;
+            }
+        }
+
         let credential = self.get_credential().await?;
         let url = self.object_url(to);
 
-        let from = utf8_percent_encode(from.as_ref(), NON_ALPHANUMERIC);
-        let source = format!("{}/{}", self.bucket_name_encoded, from);
+        let encoded = utf8_percent_encode(from.as_ref(), NON_ALPHANUMERIC);
+        let source = format!("{}/{}", self.bucket_name_encoded, encoded);
 
         let mut builder = self
             .client
             .request(Method::PUT, url)
             .header("x-goog-copy-source", source);
 
+        if let Some(version) = version {
+            builder = builder.header(&COPY_SOURCE_GENERATION, version);
+        }
+
         if if_not_exists {
             builder = builder.header(&VERSION_MATCH, 0);
         }
 
-        builder
+        let result = builder
             .with_bearer_auth(credential.as_deref())
             // Needed if reqwest is compiled with native-tls instead of rustls-tls
             // See https://github.com/apache/arrow-rs/pull/3921
@@ -599,16 +1124,80 @@ impl GoogleCloudStorageClient {
             .retryable(&self.config.retry_config)
             .idempotent(!if_not_exists)
             .send()
-            .await
-            .map_err(|err| match err.status() {
+            .await;
+
+        match result {
+            Ok(_) => Ok(()),
+            Err(err) if ranged && is_rewrite_required(&err) => {
+                self.rewrite_request(from, to, if_not_exists).await
+            }
+            Err(err) => Err(match err.status() {
                 Some(StatusCode::PRECONDITION_FAILED) => crate::Error::AlreadyExists {
                     source: Box::new(err),
                     path: to.to_string(),
                 },
-                _ => err.error(STORE, from.to_string()),
-            })?;
+                _ => err.error(STORE, encoded.to_string()),
+            }),
+        }
+    }
+}
 
-        Ok(())
+impl GoogleCloudStorageClient {
+    /// THIS METHOD IS ARAS ONLY
+    ///
+    /// Copy an object with a series of rewrite requests, each copying up to
+    /// [`CopyConfig::part_size`] bytes <https://cloud.google.com/storage/docs/json_api/v1/objects/rewrite>
+    async fn rewrite_request(&self, from: &Path, to: &Path, if_not_exists: bool) -> Result<()> {
+        const MIB: u64 = 1024 * 1024;
+        let credential = self.get_credential().await?;
+        let url = format!(
+            "{}/rewriteTo/b/{}/o/{}",
+            self.object_json_url(from),
+            self.bucket_name_encoded,
+            utf8_percent_encode(to.as_ref(), NON_ALPHANUMERIC)
+        );
+
+        // The number of bytes rewritten per call must be a multiple of 1 MiB
+        let part_size = self.config.copy_config.part_size;
+        let max_bytes = (part_size.max(1) + MIB - 1) / MIB * MIB;
+
+        let mut rewrite_token = None;
+        loop {
+            let mut query = vec![("maxBytesRewrittenPerCall", max_bytes.to_string())];
+            if let Some(token) = rewrite_token.take() {
+                query.push(("rewriteToken", token));
+            }
+            if if_not_exists {
+                query.push(("ifGenerationMatch", "0".to_string()));
+            }
+
+            let response = self
+                .client
+                .request(Method::POST, &url)
+                .query(&query)
+                .with_bearer_auth(credential.as_deref())
+                .header(CONTENT_LENGTH, 0)
+                .retryable(&self.config.retry_config)
+                .idempotent(!if_not_exists)
+                .send()
+                .await
+                .map_err(|err| match err.status() {
+                    Some(StatusCode::PRECONDITION_FAILED) => crate::Error::AlreadyExists {
+                        source: Box::new(err),
+                        path: to.to_string(),
+                    },
+                    _ => err.error(STORE, from.to_string()),
+                })?
+                .into_body()
+                .json::<RewriteResponse>()
+                .await
+                .map_err(|source| Error::InvalidRewriteResponse { source })?;
+
+            match (response.done, response.rewrite_token) {
+                (false, Some(token)) => rewrite_token = Some(token),
+                _ => return Ok(()),
+            }
+        }
     }
 }
 
@@ -662,6 +1251,131 @@ impl GetClient for GoogleCloudStorageClient {
     }
 }
 
+/// THIS IMPL IS ARAS ONLY
+#[async_trait]
+impl ListMultipartClient for Arc<GoogleCloudStorageClient> {
+    /// List incomplete multipart uploads <https://cloud.google.com/storage/docs/xml-api/get-bucket-uploads>
+    ///
+    /// The page token encodes both the upload id marker and the key marker
+    async fn list_multipart_uploads_request(
+        &self,
+        prefix: Option<&str>,
+        page_token: Option<String>,
+    ) -> Result<(Vec<MultipartUploadInfo>, Option<String>)> {
+        let credential = self.get_credential().await?;
+        let url = format!("{}/{}", self.config.base_url, self.bucket_name_encoded);
+
+        let mut query = Vec::with_capacity(4);
+        query.push(("uploads", ""));
+
+        if let Some(prefix) = prefix {
+            query.push(("prefix", prefix))
+        }
+
+        if let Some((upload_id_marker, key_marker)) =
+            page_token.as_deref().and_then(|x| x.split_once('\n'))
+        {
+            query.push(("key-marker", key_marker));
+            if !upload_id_marker.is_empty() {
+                query.push(("upload-id-marker", upload_id_marker));
+            }
+        }
+
+        let response = self
+            .client
+            .request(Method::GET, url)
+            .query(&query)
+            .with_bearer_auth(credential.as_deref())
+            .send_retry(&self.config.retry_config)
+            .await
+            .map_err(|source| Error::ListRequest { source })?
+            .into_body()
+            .bytes()
+            .await
+            .map_err(|source| Error::ListResponseBody { source })?;
+
+        let mut response: ListMultipartUploadsResponse =
+            quick_xml::de::from_reader(response.reader())
+                .map_err(|source| Error::InvalidListResponse { source })?;
+
+        let token = response.page_token();
+        Ok((response.try_into()?, token))
+    }
+}
+
+/// THIS IMPL IS ARAS ONLY
+#[async_trait]
+impl ListVersionsClient for Arc<GoogleCloudStorageClient> {
+    /// Perform a list request including noncurrent generations <https://cloud.google.com/storage/docs/json_api/v1/objects/list>
+    ///
+    /// The XML API does not report whether a generation is live, so this uses the JSON API
+    async fn list_versions_request(
+        &self,
+        prefix: Option<&str>,
+        page_token: Option<String>,
+    ) -> Result<(Vec<ObjectVersion>, Option<String>)> {
+        let credential = self.get_credential().await?;
+        let url = format!(
+            "{}/storage/v1/b/{}/o",
+            self.config.base_url, self.bucket_name_encoded
+        );
+
+        let mut query = Vec::with_capacity(3);
+        query.push(("versions", "true"));
+        if let Some(prefix) = prefix {
+            query.push(("prefix", prefix))
+        }
+
+        if let Some(page_token) = &page_token {
+            query.push(("pageToken", page_token))
+        }
+
+        let response = self
+            .client
+            .request(Method::GET, url)
+            .query(&query)
+            .with_bearer_auth(credential.as_deref())
+            .send_retry(&self.config.retry_config)
+            .await
+            .map_err(|source| Error::ListRequest { source })?
+            .into_body()
+            .json::<ListVersionsResponse>()
+            .await
+            .map_err(|source| Error::InvalidListVersionsResponse { source })?;
+
+        let mut versions = response
+            .items
+            .into_iter()
+            .map(|item| {
+                Ok(ObjectVersion {
+                    meta: ObjectMeta {
+                        location: Path::parse(item.name)?,
+                        last_modified: item.updated,
+                        size: item.size.parse().unwrap_or_default(),
+                        e_tag: item.etag,
+                        version: Some(item.generation),
+                    },
+                    is_latest: item.time_deleted.is_none(),
+                    is_delete_marker: false,
+                })
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        // Generations are listed oldest first, return the most recent generation first
+        versions.sort_by(|a, b| {
+            let generation = |v: &ObjectVersion| {
+                let version = v.meta.version.as_deref().unwrap_or_default();
+                version.parse::<u64>().unwrap_or_default()
+            };
+            a.meta
+                .location
+                .cmp(&b.meta.location)
+                .then(generation(b).cmp(&generation(a)))
+        });
+        Ok((versions, response.next_page_token))
+    }
+}
+
 #[async_trait]
 impl ListClient for Arc<GoogleCloudStorageClient> {
     /// Perform a list request <https://cloud.google.com/storage/docs/xml-api/get-bucket-list>
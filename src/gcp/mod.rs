@@ -24,7 +24,11 @@
 //! [`MultipartUpload::complete`] nor [`MultipartUpload::abort`] is invoked, you may
 //! have parts uploaded to GCS but not used, that you will be charged for. It is recommended
 //! you configure a [lifecycle rule] to abort incomplete multipart uploads after a certain
-//! period of time to avoid being charged for storing partial uploads.
+//! period of time to avoid being charged for storing partial uploads. Alternatively,
+//! incomplete uploads can be enumerated with [`MultipartStore::list_multipart_uploads`]
+//! and those older than some threshold aborted with [`abort_stale_multipart_uploads`].
+//!
+//! [`abort_stale_multipart_uploads`]: crate::multipart::abort_stale_multipart_uploads
 //!
 //! ## Using HTTP/2
 //!
@@ -34,6 +38,8 @@
 //! enabled by setting [crate::ClientConfigKey::Http1Only] to false.
 //!
 //! [lifecycle rule]: https://cloud.google.com/storage/docs/lifecycle#abort-mpu
+use bytes::Bytes;
+use std::ops::Range;
 use std::sync::Arc;
 use std::time::Duration;
 
@@ -41,9 +47,9 @@ use crate::client::CredentialProvider;
 use crate::gcp::credential::GCSAuthorizer;
 use crate::signer::Signer;
 use crate::{
-    multipart::PartId, path::Path, GetOptions, GetResult, ListResult, MultipartId, MultipartUpload,
-    ObjectMeta, ObjectStore, PutMultipartOpts, PutOptions, PutPayload, PutResult, Result,
-    UploadPart,
+    multipart::PartId, path::Path, DeleteOptions, GetOptions, GetResult, ListResult, MultipartId,
+    MultipartUpload, ObjectMeta, ObjectStore, PutMode, PutMultipartOpts, PutOptions, PutPayload,
+    PutResult, Result, TagSet, TaggingStore, UploadPart,
 };
 use async_trait::async_trait;
 use client::GoogleCloudStorageClient;
@@ -52,10 +58,14 @@ use http::Method;
 use url::Url;
 
 use crate::client::get::GetClientExt;
-use crate::client::list::{ListClient, ListClientExt};
+use crate::client::list::{
+    ListClient, ListClientExt, ListMultipartClientExt, ListVersionsClientExt,
+};
 use crate::client::parts::Parts;
 use crate::list::{PaginatedListOptions, PaginatedListResult, PaginatedListStore};
-use crate::multipart::MultipartStore;
+use crate::multipart::{MultipartStore, MultipartUploadInfo, PartInfo};
+use crate::upload::AppendUpload;
+use crate::version::{ObjectVersion, VersionedStore};
 pub use builder::{GoogleCloudStorageBuilder, GoogleConfigKey};
 pub use credential::{GcpCredential, GcpSigningCredential, ServiceAccountKey};
 
@@ -112,6 +122,10 @@ struct UploadState {
     path: Path,
     multipart_id: MultipartId,
     parts: Parts,
+    /// THIS FIELD IS ARAS ONLY
+    ///
+    /// The tags written once the upload is completed, as GCS has no native object tags
+    tags: TagSet,
 }
 
 #[async_trait]
@@ -133,10 +147,17 @@ impl MultipartUpload for GCSMultipartUpload {
     async fn complete(&mut self) -> Result<PutResult> {
         let parts = self.state.parts.finish(self.part_idx)?;
 
-        self.state
+        let result = self
+            .state
             .client
             .multipart_complete(&self.state.path, &self.state.multipart_id, parts)
-            .await
+            .await?;
+
+        self.state
+            .client
+            .put_written_tags(&self.state.path, &self.state.tags)
+            .await?;
+        Ok(result)
     }
 
     async fn abort(&mut self) -> Result<()> {
@@ -149,20 +170,35 @@ impl MultipartUpload for GCSMultipartUpload {
 
 #[async_trait]
 impl ObjectStore for GoogleCloudStorage {
+    /// THIS METHOD IS COMMON, MODIFIED BY ARAS
     async fn put_opts(
         &self,
         location: &Path,
         payload: PutPayload,
         opts: PutOptions,
     ) -> Result<PutResult> {
-        self.client.put(location, payload, opts).await
+        match opts.mode {
+            PutMode::Append => self.client.append(location, payload, opts).await,
+            _ => self.client.put(location, payload, opts).await,
+        }
     }
 
+    /// THIS METHOD IS COMMON, MODIFIED BY ARAS
     async fn put_multipart_opts(
         &self,
         location: &Path,
         opts: PutMultipartOpts,
     ) -> Result<Box<dyn MultipartUpload>> {
+        match opts.mode {
+            PutMode::Overwrite => {}
+            PutMode::Append => {
+                let store = Arc::new(self.clone());
+                return Ok(Box::new(AppendUpload::new(store, location.clone(), opts)));
+            }
+            PutMode::Create | PutMode::Update(_) => return Err(crate::Error::NotImplemented),
+        }
+
+        let tags = opts.tags.clone();
         let upload_id = self.client.multipart_initiate(location, opts).await?;
 
         Ok(Box::new(GCSMultipartUpload {
@@ -172,6 +208,7 @@ impl ObjectStore for GoogleCloudStorage {
                 path: location.clone(),
                 multipart_id: upload_id.clone(),
                 parts: Default::default(),
+                tags,
             }),
         }))
     }
@@ -180,10 +217,33 @@ impl ObjectStore for GoogleCloudStorage {
         self.client.get_opts(location, options).await
     }
 
+    /// THIS METHOD IS ARAS ONLY
+    async fn get_ranges(&self, location: &Path, ranges: &[Range<u64>]) -> Result<Vec<Bytes>> {
+        let policy = self.client.config().client_options.range_coalesce_policy();
+        self.get_ranges_opts(location, ranges, policy).await
+    }
+
     async fn delete(&self, location: &Path) -> Result<()> {
         self.client.delete_request(location).await
     }
 
+    /// THIS METHOD IS ARAS ONLY
+    ///
+    /// GCS supports conditional deletes via generation preconditions, and therefore
+    /// requires `version`, returning [`crate::Error::NotImplemented`] if only `if_match`
+    /// is provided
+    async fn delete_opts(&self, location: &Path, opts: DeleteOptions) -> Result<()> {
+        match (opts.if_match, opts.version) {
+            (None, None) => self.delete(location).await,
+            (_, Some(generation)) => {
+                self.client
+                    .delete_if_generation_match_request(location, &generation)
+                    .await
+            }
+            (Some(_), None) => Err(crate::Error::NotImplemented),
+        }
+    }
+
     fn list(&self, prefix: Option<&Path>) -> BoxStream<'static, Result<ObjectMeta>> {
         self.client.list(prefix)
     }
@@ -201,11 +261,59 @@ impl ObjectStore for GoogleCloudStorage {
     }
 
     async fn copy(&self, from: &Path, to: &Path) -> Result<()> {
-        self.client.copy_request(from, to, false).await
+        self.client.copy_request(from, None, to, false).await
     }
 
     async fn copy_if_not_exists(&self, from: &Path, to: &Path) -> Result<()> {
-        self.client.copy_request(from, to, true).await
+        self.client.copy_request(from, None, to, true).await
+    }
+
+    /// THIS METHOD IS ARAS ONLY
+    async fn compose(&self, sources: &[Path], dest: &Path) -> Result<PutResult> {
+        self.client.compose(sources, dest).await
+    }
+}
+
+/// THIS IMPL IS ARAS ONLY
+///
+/// Requires [object versioning] to be enabled on the bucket, with version ids being
+/// object generations. GCS does not record delete markers, instead a deleted object
+/// has no live generation
+///
+/// [object versioning]: https://cloud.google.com/storage/docs/object-versioning
+#[async_trait]
+impl VersionedStore for GoogleCloudStorage {
+    fn list_versions(&self, prefix: Option<&Path>) -> BoxStream<'static, Result<ObjectVersion>> {
+        self.client.list_versions(prefix)
+    }
+
+    async fn delete_version(&self, location: &Path, version: &str) -> Result<()> {
+        self.client.delete_version_request(location, version).await
+    }
+
+    async fn copy_version(&self, from: &Path, version: &str, to: &Path) -> Result<()> {
+        self.client
+            .copy_request(from, Some(version), to, false)
+            .await
+    }
+}
+
+/// THIS IMPL IS ARAS ONLY
+///
+/// GCS has no native object tags, instead tags are stored as custom metadata
+///
+/// As they cannot be sent with the upload, the tags of a put, append or multipart upload
+/// are written by a separate request once the object is written. This is not atomic,
+/// and if that request fails [`crate::Error::TagsNotWritten`] is returned, with the
+/// object left in place without its tags
+#[async_trait]
+impl TaggingStore for GoogleCloudStorage {
+    async fn get_tags(&self, location: &Path) -> Result<TagSet> {
+        self.client.get_object_tags(location).await
+    }
+
+    async fn put_tags(&self, location: &Path, tags: TagSet) -> Result<()> {
+        self.client.put_object_tags(location, &tags).await
     }
 }
 
@@ -239,6 +347,19 @@ impl MultipartStore for GoogleCloudStorage {
     async fn abort_multipart(&self, path: &Path, id: &MultipartId) -> Result<()> {
         self.client.multipart_cleanup(path, id).await
     }
+
+    /// THIS METHOD IS ARAS ONLY
+    fn list_multipart_uploads(
+        &self,
+        prefix: Option<&Path>,
+    ) -> BoxStream<'static, Result<MultipartUploadInfo>> {
+        self.client.list_multipart_uploads(prefix)
+    }
+
+    /// THIS METHOD IS ARAS ONLY
+    async fn list_parts(&self, path: &Path, id: &MultipartId) -> Result<Vec<PartInfo>> {
+        self.client.list_parts(path, id).await
+    }
 }
 
 #[async_trait]
@@ -432,4 +553,268 @@ mod test {
             err
         )
     }
+
+    /// THIS TEST IS ARAS ONLY
+    #[tokio::test]
+    async fn gcs_test_put_tags() {
+        use crate::client::mock_server::MockServer;
+        use crate::{ClientOptions, RetryConfig};
+        use http::header::ETAG;
+        use http::{Method, Response, StatusCode};
+        use http_body_util::BodyExt;
+
+        let server = MockServer::new().await;
+        let key = serde_json::json!({
+            "gcs_base_url": server.url(),
+            "disable_oauth": true,
+            "client_email": "",
+            "private_key": "",
+            "private_key_id": "",
+        });
+        let store = GoogleCloudStorageBuilder::new()
+            .with_bucket_name("bucket")
+            .with_service_account_key(key.to_string())
+            .with_client_options(ClientOptions::new().with_allow_http(true))
+            .build()
+            .unwrap();
+
+        server.push_fn(|req| {
+            assert_eq!(req.method(), Method::PUT);
+            assert_eq!(req.uri().path(), "/bucket/data");
+            Response::builder()
+                .header(ETAG, "\"1\"")
+                .body(String::new())
+                .unwrap()
+        });
+        server.push_fn(|req| {
+            assert_eq!(req.method(), Method::GET);
+            assert_eq!(req.uri().path(), "/storage/v1/b/bucket/o/data");
+            let body = r#"{"metadata":{"tag:old":"x","other":"y"},"metageneration":"3"}"#;
+            Response::new(body.to_string())
+        });
+        server.push_async_fn(|req| async move {
+            assert_eq!(req.method(), Method::PATCH);
+            let query = req.uri().query().unwrap();
+            assert!(query.contains("ifMetagenerationMatch=3"), "{query}");
+            let body = req.into_body().collect().await.unwrap().to_bytes();
+            let body: serde_json::Value = serde_json::from_slice(&body).unwrap();
+            let expected = serde_json::json!({"metadata": {"tag:old": null, "tag:k": "v"}});
+            assert_eq!(body, expected);
+            Response::new(r#"{"metageneration":"4"}"#.to_string())
+        });
+
+        let opts = PutOptions {
+            tags: TagSet::from_iter([("k", "v")]),
+            ..Default::default()
+        };
+        let location = Path::from("data");
+        store
+            .put_opts(&location, "data".into(), opts)
+            .await
+            .unwrap();
+
+        // Appends write the tags too
+        server.push_fn(|req| {
+            assert_eq!(req.method(), Method::HEAD);
+            Response::builder()
+                .status(StatusCode::NOT_FOUND)
+                .body(String::new())
+                .unwrap()
+        });
+        server.push_fn(|req| {
+            assert_eq!(req.method(), Method::PUT);
+            assert_eq!(req.headers()["x-goog-if-generation-match"], "0");
+            Response::builder()
+                .header(ETAG, "\"1\"")
+                .body(String::new())
+                .unwrap()
+        });
+        server.push_fn(|req| {
+            assert_eq!(req.method(), Method::GET);
+            Response::new(r#"{"metadata":{},"metageneration":"1"}"#.to_string())
+        });
+        server.push_async_fn(|req| async move {
+            assert_eq!(req.method(), Method::PATCH);
+            let body = req.into_body().collect().await.unwrap().to_bytes();
+            let body: serde_json::Value = serde_json::from_slice(&body).unwrap();
+            assert_eq!(body, serde_json::json!({"metadata": {"tag:k": "v"}}));
+            Response::new(r#"{"metageneration":"2"}"#.to_string())
+        });
+        let opts = PutOptions {
+            mode: PutMode::Append,
+            tags: TagSet::from_iter([("k", "v")]),
+            ..Default::default()
+        };
+        let appended = Path::from("appended");
+        store
+            .put_opts(&appended, "data".into(), opts)
+            .await
+            .unwrap();
+
+        // The client only performs appends with append
+        let opts = PutOptions {
+            mode: PutMode::Append,
+            ..Default::default()
+        };
+        let err = store
+            .client
+            .put(&location, "data".into(), opts)
+            .await
+            .unwrap_err();
+        assert!(matches!(err, crate::Error::NotImplemented), "{err}");
+
+        // Concurrent modifications are retried up to the maximum number of retries
+        let store = GoogleCloudStorageBuilder::new()
+            .with_bucket_name("bucket")
+            .with_service_account_key(key.to_string())
+            .with_client_options(ClientOptions::new().with_allow_http(true))
+            .with_retry(RetryConfig {
+                max_retries: 1,
+                ..Default::default()
+            })
+            .build()
+            .unwrap();
+        for _ in 0..2 {
+            server.push_fn(|req| {
+                assert_eq!(req.method(), Method::GET);
+                let body = r#"{"metadata":{},"metageneration":"3"}"#;
+                Response::new(body.to_string())
+            });
+            server.push_fn(|req| {
+                assert_eq!(req.method(), Method::PATCH);
+                Response::builder()
+                    .status(StatusCode::PRECONDITION_FAILED)
+                    .body(String::new())
+                    .unwrap()
+            });
+        }
+        let tags = TagSet::from_iter([("k", "v")]);
+        let err = store.put_tags(&location, tags).await.unwrap_err();
+        assert!(matches!(err, crate::Error::Precondition { .. }), "{err}");
+
+        // A failure to write the tags once the object is written is reported separately
+        server.push_fn(|req| {
+            assert_eq!(req.method(), Method::PUT);
+            Response::builder()
+                .header(ETAG, "\"1\"")
+                .body(String::new())
+                .unwrap()
+        });
+        server.push_fn(|req| {
+            assert_eq!(req.method(), Method::GET);
+            Response::builder()
+                .status(StatusCode::FORBIDDEN)
+                .body(String::new())
+                .unwrap()
+        });
+        let opts = PutOptions {
+            tags: TagSet::from_iter([("k", "v")]),
+            ..Default::default()
+        };
+        let err = store
+            .put_opts(&location, "data".into(), opts)
+            .await
+            .unwrap_err();
+        assert!(matches!(err, crate::Error::TagsNotWritten { .. }), "{err}");
+
+        server.shutdown().await;
+    }
+
+    /// THIS TEST IS ARAS ONLY
+    #[tokio::test]
+    async fn gcs_test_copy() {
+        use crate::client::mock_server::MockServer;
+        use crate::ClientOptions;
+        use http::{Method, Response, StatusCode};
+
+        let server = MockServer::new().await;
+        let key = serde_json::json!({
+            "gcs_base_url": server.url(),
+            "disable_oauth": true,
+            "client_email": "",
+            "private_key": "",
+            "private_key_id": "",
+        });
+        let store = GoogleCloudStorageBuilder::new()
+            .with_bucket_name("bucket")
+            .with_service_account_key(key.to_string())
+            .with_client_options(ClientOptions::new().with_allow_http(true))
+            .build()
+            .unwrap();
+        let (from, to) = (Path::from("from"), Path::from("to"));
+
+        // By default objects are copied with a single request
+        server.push_fn(|req| {
+            assert_eq!(req.method(), Method::PUT);
+            assert_eq!(req.uri().path(), "/bucket/to");
+            assert_eq!(req.headers()["x-goog-copy-source"], "bucket/from");
+            Response::new(String::new())
+        });
+        store.copy(&from, &to).await.unwrap();
+
+        // Falling back to a rewrite if the copy cannot complete in a single request
+        server.push_fn(|_| {
+            let body = "<Error><Code>InvalidArgument</Code><Message>Copy spanning locations \
+                and/or storage classes could not complete within 30 seconds. Please use the \
+                Rewrite method</Message></Error>";
+            Response::builder()
+                .status(StatusCode::BAD_REQUEST)
+                .body(body.to_string())
+                .unwrap()
+        });
+        server.push_fn(|req| {
+            assert_eq!(req.method(), Method::POST);
+            let path = "/storage/v1/b/bucket/o/from/rewriteTo/b/bucket/o/to";
+            assert_eq!(req.uri().path(), path);
+            Response::new(r#"{"done":true}"#.to_string())
+        });
+        store.copy(&from, &to).await.unwrap();
+
+        server.shutdown().await;
+    }
+
+    /// THIS TEST IS ARAS ONLY
+    #[tokio::test]
+    async fn gcs_test_delete_opts() {
+        use crate::client::mock_server::MockServer;
+        use crate::ClientOptions;
+        use http::{Method, Response};
+
+        let server = MockServer::new().await;
+        let key = serde_json::json!({
+            "gcs_base_url": server.url(),
+            "disable_oauth": true,
+            "client_email": "",
+            "private_key": "",
+            "private_key_id": "",
+        });
+        let store = GoogleCloudStorageBuilder::new()
+            .with_bucket_name("bucket")
+            .with_service_account_key(key.to_string())
+            .with_client_options(ClientOptions::new().with_allow_http(true))
+            .build()
+            .unwrap();
+        let path = Path::from("a");
+
+        // An e-tag alone is not supported, and sends no request
+        let opts = DeleteOptions {
+            if_match: Some("\"e\"".to_string()),
+            ..Default::default()
+        };
+        let err = store.delete_opts(&path, opts).await.unwrap_err();
+        assert!(matches!(err, crate::Error::NotImplemented), "{err}");
+
+        server.push_fn(|req| {
+            assert_eq!(req.method(), Method::DELETE);
+            assert_eq!(req.headers()["x-goog-if-generation-match"], "1");
+            Response::new(String::new())
+        });
+        let opts = DeleteOptions {
+            if_match: Some("\"e\"".to_string()),
+            version: Some("1".to_string()),
+            ..Default::default()
+        };
+        store.delete_opts(&path, opts).await.unwrap();
+        server.shutdown().await;
+    }
 }
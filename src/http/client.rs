@@ -15,24 +15,34 @@
 // specific language governing permissions and limitations
 // under the License.
 
-use super::STORE;
+use super::{HttpMultipartMode, STORE};
 use crate::client::get::GetClient;
+use crate::client::get::GetClientExt;
 use crate::client::header::HeaderConfig;
 use crate::client::retry::{self, RetryConfig, RetryContext, RetryExt};
-use crate::client::{GetOptionsExt, HttpClient, HttpError, HttpResponse};
+use crate::client::{GetOptionsExt, HttpClient, HttpError, HttpResponse, HttpResponseBody};
 use crate::path::{Path, DELIMITER};
 use crate::util::deserialize_rfc1123;
 use crate::{Attribute, Attributes, ClientOptions, GetOptions, ObjectMeta, PutPayload, Result};
 use async_trait::async_trait;
-use bytes::Buf;
 use chrono::{DateTime, Utc};
+use futures::stream::{BoxStream, SelectAll};
+use futures::{ready, Stream, StreamExt, TryStreamExt};
 use http::header::{
     CACHE_CONTROL, CONTENT_DISPOSITION, CONTENT_ENCODING, CONTENT_LANGUAGE, CONTENT_LENGTH,
-    CONTENT_TYPE,
+    CONTENT_RANGE, CONTENT_TYPE, IF_MATCH, IF_NONE_MATCH,
 };
 use percent_encoding::percent_decode_str;
+use quick_xml::errors::IllFormedError;
+use quick_xml::events::Event;
+use rand::Rng as _;
 use reqwest::{Method, StatusCode};
 use serde::Deserialize;
+use std::collections::VecDeque;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::task::{Context, Poll};
+use tracing::warn;
 use url::Url;
 
 #[derive(Debug, thiserror::Error)]
@@ -75,6 +85,23 @@ enum Error {
         path: String,
         source: crate::path::Error,
     },
+
+    /// THIS VARIANT IS ARAS ONLY
+    #[error("LOCK response for \"{}\" did not contain a Lock-Token", href)]
+    MissingLockToken { href: String },
+
+    /// THIS VARIANT IS ARAS ONLY
+    #[error(
+        "Staged upload \"{}\" is {} bytes, expected {}, the server may not support Content-Range",
+        href,
+        actual,
+        expected
+    )]
+    StagedSizeMismatch {
+        href: String,
+        actual: u64,
+        expected: u64,
+    },
 }
 
 impl From<Error> for crate::Error {
@@ -89,6 +116,81 @@ impl From<Error> for crate::Error {
     }
 }
 
+/// THIS CONSTANT IS ARAS ONLY
+///
+/// Separates the destination of a multipart upload in [`HttpMultipartMode::ContentRange`]
+/// from the id of the upload, in the name of the resource the parts are staged to
+const STAGED_INFIX: &str = ".upload-";
+
+/// THIS CONSTANT IS ARAS ONLY
+///
+/// The timeout requested for the WebDAV locks acquired by conditional puts
+const LOCK_TIMEOUT: &str = "Second-60";
+
+/// THIS CONSTANT IS ARAS ONLY
+///
+/// The body of a LOCK request for an exclusive write lock
+const LOCK_BODY: &str = "<?xml version=\"1.0\" encoding=\"utf-8\"?>\
+<D:lockinfo xmlns:D=\"DAV:\">\
+<D:lockscope><D:exclusive/></D:lockscope>\
+<D:locktype><D:write/></D:locktype>\
+</D:lockinfo>";
+
+/// THIS ENUM IS ARAS ONLY
+///
+/// The precondition of a PUT request
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum PutPrecondition<'a> {
+    /// Unconditionally write the object
+    None,
+    /// The object must not already exist
+    NotExists,
+    /// The object must have the provided e-tag
+    Matches(&'a str),
+}
+
+impl PutPrecondition<'_> {
+    /// THIS METHOD IS ARAS ONLY
+    ///
+    /// Returns the error for a request that failed as this precondition did not hold
+    fn error(&self, source: retry::RetryError, path: &Path) -> crate::Error {
+        let path = path.to_string();
+        match self {
+            Self::NotExists => crate::Error::AlreadyExists {
+                path,
+                source: Box::new(source),
+            },
+            _ => crate::Error::Precondition {
+                path,
+                source: Box::new(source),
+            },
+        }
+    }
+}
+
+/// THIS ENUM IS ARAS ONLY
+///
+/// Where the parts of a multipart upload are staged before being assembled
+#[derive(Debug)]
+pub(crate) enum Staging {
+    /// A temporary resource written with `Content-Range` PUTs, moved into place on completion
+    Resource(Path),
+    /// A chunking v2 upload collection, assembled by a MOVE of its `.file`
+    Collection(String),
+}
+
+/// THIS FUNCTION IS ARAS ONLY
+///
+/// Returns `e_tag` as a quoted entity-tag, as required by the WebDAV `If` header
+fn entity_tag(e_tag: &str) -> String {
+    match e_tag.starts_with('"') || e_tag.starts_with("W/") {
+        true => e_tag.to_string(),
+        false => format!("\"{e_tag}\""),
+    }
+}
+
+/// THIS STRUCT IS COMMON, MODIFIED BY ARAS
+///
 /// Internal client for HttpStore
 #[derive(Debug)]
 pub(crate) struct Client {
@@ -96,23 +198,47 @@ pub(crate) struct Client {
     client: HttpClient,
     retry_config: RetryConfig,
     client_options: ClientOptions,
+    /// THIS FIELD IS ARAS ONLY
+    ///
+    /// If true, conditional puts are performed whilst holding a WebDAV write lock
+    locking: bool,
+    /// THIS FIELD IS ARAS ONLY
+    ///
+    /// How multipart uploads are performed
+    multipart_mode: HttpMultipartMode,
+    /// THIS FIELD IS ARAS ONLY
+    ///
+    /// The maximum number of concurrent PROPFIND requests when walking directories
+    list_concurrency: usize,
 }
 
 impl Client {
+    /// THIS METHOD IS COMMON, MODIFIED BY ARAS
     pub(crate) fn new(
         url: Url,
         client: HttpClient,
         client_options: ClientOptions,
         retry_config: RetryConfig,
+        locking: bool,
+        multipart_mode: HttpMultipartMode,
+        list_concurrency: usize,
     ) -> Self {
         Self {
             url,
             retry_config,
             client_options,
             client,
+            locking,
+            multipart_mode,
+            list_concurrency: list_concurrency.max(1),
         }
     }
 
+    /// THIS METHOD IS ARAS ONLY
+    pub(crate) fn client_options(&self) -> &ClientOptions {
+        &self.client_options
+    }
+
     pub(crate) fn base_url(&self) -> &Url {
         &self.url
     }
@@ -172,11 +298,49 @@ impl Client {
         Ok(())
     }
 
+    /// THIS METHOD IS ARAS ONLY
+    ///
+    /// Write `payload` to `location` if `precondition` holds
+    ///
+    /// If locking is enabled, conditional writes are performed whilst holding an
+    /// exclusive write lock on `location`
     pub(crate) async fn put(
         &self,
         location: &Path,
         payload: PutPayload,
         attributes: Attributes,
+        precondition: PutPrecondition<'_>,
+    ) -> Result<HttpResponse> {
+        if !self.locking || precondition == PutPrecondition::None {
+            return self
+                .put_request(location, payload, &attributes, precondition, None)
+                .await;
+        }
+
+        let token = self.lock(location, precondition).await?;
+        let result = self
+            .put_request(location, payload, &attributes, precondition, Some(&token))
+            .await;
+
+        let release = match (&result, precondition) {
+            // Remove the empty resource created by locking an unmapped URL
+            (Err(_), PutPrecondition::NotExists) => self.delete_locked(location, &token).await,
+            _ => self.unlock(location, &token).await,
+        };
+        if let Err(e) = release {
+            warn!("Failed to release lock {token} on {location}: {e}");
+        }
+        result
+    }
+
+    /// THIS METHOD IS COMMON, MODIFIED BY ARAS
+    async fn put_request(
+        &self,
+        location: &Path,
+        payload: PutPayload,
+        attributes: &Attributes,
+        precondition: PutPrecondition<'_>,
+        lock_token: Option<&str>,
     ) -> Result<HttpResponse> {
         let mut retry = false;
         loop {
@@ -184,7 +348,7 @@ impl Client {
             let mut builder = self.client.put(url);
 
             let mut has_content_type = false;
-            for (k, v) in &attributes {
+            for (k, v) in attributes {
                 builder = match k {
                     Attribute::CacheControl => builder.header(CACHE_CONTROL, v.as_ref()),
                     Attribute::ContentDisposition => {
@@ -207,10 +371,26 @@ impl Client {
                 }
             }
 
+            builder = match (precondition, lock_token) {
+                (PutPrecondition::None, _) => builder,
+                (PutPrecondition::NotExists, None) => builder.header(IF_NONE_MATCH, "*"),
+                // The lock was acquired on the unmapped URL, creating an empty resource
+                (PutPrecondition::NotExists, Some(token)) => {
+                    builder.header("If", format!("({token})"))
+                }
+                (PutPrecondition::Matches(e_tag), token) => {
+                    let condition = match token {
+                        Some(token) => format!("({token} [{}])", entity_tag(e_tag)),
+                        None => format!("([{}])", entity_tag(e_tag)),
+                    };
+                    builder.header(IF_MATCH, e_tag).header("If", condition)
+                }
+            };
+
             let resp = builder
                 .header(CONTENT_LENGTH, payload.content_length())
                 .retryable(&self.retry_config)
-                .idempotent(true)
+                .idempotent(precondition == PutPrecondition::None)
                 .payload(Some(payload.clone()))
                 .send()
                 .await;
@@ -218,6 +398,11 @@ impl Client {
             match resp {
                 Ok(response) => return Ok(response),
                 Err(source) => match source.status() {
+                    Some(StatusCode::PRECONDITION_FAILED | StatusCode::LOCKED)
+                        if precondition != PutPrecondition::None =>
+                    {
+                        return Err(precondition.error(source, location))
+                    }
                     // Some implementations return 404 instead of 409
                     Some(StatusCode::CONFLICT | StatusCode::NOT_FOUND) if !retry => {
                         retry = true;
@@ -235,11 +420,292 @@ impl Client {
         }
     }
 
-    pub(crate) async fn list(&self, location: Option<&Path>, depth: &str) -> Result<MultiStatus> {
+    /// THIS METHOD IS ARAS ONLY
+    ///
+    /// Acquire an exclusive write lock on `location` if `precondition` holds,
+    /// returning the lock token
+    async fn lock(&self, location: &Path, precondition: PutPrecondition<'_>) -> Result<String> {
+        let mut retry = false;
+        loop {
+            let method = Method::from_bytes(b"LOCK").unwrap();
+            let mut builder = self
+                .client
+                .request(method, self.path_url(location))
+                .header("Depth", "0")
+                .header("Timeout", LOCK_TIMEOUT)
+                .header(CONTENT_TYPE, "application/xml; charset=utf-8")
+                .body(LOCK_BODY.to_string());
+
+            builder = match precondition {
+                PutPrecondition::None => builder,
+                PutPrecondition::NotExists => builder.header(IF_NONE_MATCH, "*"),
+                PutPrecondition::Matches(e_tag) => builder.header(IF_MATCH, e_tag),
+            };
+
+            let response = match builder.send_retry(&self.retry_config).await {
+                Ok(response) => response,
+                Err(source) => match source.status() {
+                    Some(StatusCode::PRECONDITION_FAILED | StatusCode::LOCKED)
+                        if precondition != PutPrecondition::None =>
+                    {
+                        return Err(precondition.error(source, location))
+                    }
+                    // Some implementations return 404 instead of 409
+                    Some(StatusCode::CONFLICT | StatusCode::NOT_FOUND)
+                        if !retry && precondition == PutPrecondition::NotExists =>
+                    {
+                        retry = true;
+                        self.create_parent_directories(location).await?;
+                        continue;
+                    }
+                    _ => {
+                        return Err(Error::Request {
+                            source,
+                            path: location.to_string(),
+                        }
+                        .into())
+                    }
+                },
+            };
+
+            let token = response
+                .headers()
+                .get("Lock-Token")
+                .and_then(|v| v.to_str().ok())
+                .map(|v| v.trim())
+                .ok_or_else(|| Error::MissingLockToken {
+                    href: location.to_string(),
+                })?;
+            let token = match token.starts_with('<') {
+                true => token.to_string(),
+                false => format!("<{token}>"),
+            };
+
+            // Servers ignoring If-None-Match lock existing resources with 200 OK,
+            // rather than creating a new resource with 201 Created
+            if precondition == PutPrecondition::NotExists
+                && response.status() != StatusCode::CREATED
+            {
+                self.unlock(location, &token).await?;
+                return Err(crate::Error::AlreadyExists {
+                    path: location.to_string(),
+                    source: "LOCK did not create a new resource".into(),
+                });
+            }
+            return Ok(token);
+        }
+    }
+
+    /// THIS METHOD IS ARAS ONLY
+    ///
+    /// Release the lock with `token` on `location`
+    async fn unlock(&self, location: &Path, token: &str) -> Result<()> {
+        let method = Method::from_bytes(b"UNLOCK").unwrap();
+        self.client
+            .request(method, self.path_url(location))
+            .header("Lock-Token", token)
+            .send_retry(&self.retry_config)
+            .await
+            .map_err(|source| Error::Request {
+                source,
+                path: location.to_string(),
+            })?;
+        Ok(())
+    }
+
+    /// THIS METHOD IS ARAS ONLY
+    ///
+    /// Delete `location`, which is locked with `token`, releasing the lock
+    async fn delete_locked(&self, location: &Path, token: &str) -> Result<()> {
+        self.client
+            .delete(self.path_url(location))
+            .header("If", format!("({token})"))
+            .send_retry(&self.retry_config)
+            .await
+            .map_err(|source| Error::Request {
+                source,
+                path: location.to_string(),
+            })?;
+        Ok(())
+    }
+
+    /// THIS METHOD IS ARAS ONLY
+    ///
+    /// Create the staging area for a multipart upload to `location`
+    pub(crate) async fn multipart_create(
+        &self,
+        location: &Path,
+        attributes: Attributes,
+    ) -> Result<Staging> {
+        let id = u128::from_be_bytes(rand::rng().random());
+        match &self.multipart_mode {
+            HttpMultipartMode::ContentRange => {
+                // Staged next to the destination, so that it can be moved into place
+                let staged = Path::from(format!("{location}{STAGED_INFIX}{id:032x}"));
+                let payload = PutPayload::new();
+                let precondition = PutPrecondition::None;
+                self.put_request(&staged, payload, &attributes, precondition, None)
+                    .await?;
+                Ok(Staging::Resource(staged))
+            }
+            HttpMultipartMode::ChunkingV2 { uploads_url } => {
+                let collection = format!("{}/{id:032x}", uploads_url.trim_end_matches('/'));
+                let method = Method::from_bytes(b"MKCOL").unwrap();
+                self.client
+                    .request(method, collection.as_str())
+                    .header("Destination", self.path_url(location))
+                    .send_retry(&self.retry_config)
+                    .await
+                    .map_err(|source| Error::Request {
+                        source,
+                        path: location.to_string(),
+                    })?;
+                Ok(Staging::Collection(collection))
+            }
+        }
+    }
+
+    /// THIS METHOD IS ARAS ONLY
+    ///
+    /// Upload the part with index `part_idx`, starting at `offset`, of a multipart upload
+    pub(crate) async fn multipart_put_part(
+        &self,
+        location: &Path,
+        staging: &Staging,
+        part_idx: usize,
+        offset: u64,
+        payload: PutPayload,
+    ) -> Result<()> {
+        let length = payload.content_length() as u64;
+        let builder = match staging {
+            // Content-Range cannot express an empty range
+            Staging::Resource(_) if length == 0 => return Ok(()),
+            Staging::Resource(staged) => self.client.put(self.path_url(staged)).header(
+                CONTENT_RANGE,
+                format!("bytes {}-{}/*", offset, offset + length - 1),
+            ),
+            Staging::Collection(collection) => self
+                .client
+                .put(format!("{collection}/{:05}", part_idx + 1))
+                .header("Destination", self.path_url(location)),
+        };
+
+        builder
+            .header(CONTENT_LENGTH, length)
+            .retryable(&self.retry_config)
+            .idempotent(true)
+            .payload(Some(payload))
+            .send()
+            .await
+            .map_err(|source| Error::Request {
+                source,
+                path: location.to_string(),
+            })?;
+        Ok(())
+    }
+
+    /// THIS METHOD IS ARAS ONLY
+    ///
+    /// Assemble the staged parts, totalling `size` bytes, of a multipart upload to `location`
+    pub(crate) async fn multipart_complete(
+        self: &Arc<Self>,
+        location: &Path,
+        staging: &Staging,
+        size: u64,
+    ) -> Result<HttpResponse> {
+        let source = match staging {
+            Staging::Resource(staged) => {
+                // Servers ignoring Content-Range would silently corrupt the upload
+                let options = GetOptions {
+                    head: true,
+                    ..Default::default()
+                };
+                let actual = self.get_opts(staged, options).await?.meta.size;
+                if actual != size {
+                    return Err(Error::StagedSizeMismatch {
+                        href: staged.to_string(),
+                        actual,
+                        expected: size,
+                    }
+                    .into());
+                }
+                self.path_url(staged)
+            }
+            Staging::Collection(collection) => format!("{collection}/.file"),
+        };
+
+        let mut retry = false;
+        loop {
+            let method = Method::from_bytes(b"MOVE").unwrap();
+            let result = self
+                .client
+                .request(method, source.as_str())
+                .header("Destination", self.path_url(location))
+                .header("Overwrite", "T")
+                .header("OC-Total-Length", size)
+                .send_retry(&self.retry_config)
+                .await;
+
+            return match result {
+                Ok(response) => Ok(response),
+                Err(source) => match source.status() {
+                    // Some implementations return 404 instead of 409
+                    Some(StatusCode::CONFLICT | StatusCode::NOT_FOUND) if !retry => {
+                        retry = true;
+                        self.create_parent_directories(location).await?;
+                        continue;
+                    }
+                    _ => Err(Error::Request {
+                        source,
+                        path: location.to_string(),
+                    }
+                    .into()),
+                },
+            };
+        }
+    }
+
+    /// THIS METHOD IS ARAS ONLY
+    ///
+    /// Remove the staged parts of a multipart upload
+    pub(crate) async fn multipart_abort(&self, staging: &Staging) -> Result<()> {
+        let (url, path) = match staging {
+            Staging::Resource(staged) => (self.path_url(staged), staged.to_string()),
+            Staging::Collection(collection) => (collection.clone(), collection.clone()),
+        };
+        match self.client.delete(url).send_retry(&self.retry_config).await {
+            Ok(_) => Ok(()),
+            Err(e) if matches!(e.status(), Some(StatusCode::NOT_FOUND)) => Ok(()),
+            Err(source) => Err(Error::Request { source, path }.into()),
+        }
+    }
+
+    /// THIS METHOD IS COMMON, MODIFIED BY ARAS
+    ///
+    /// Perform a PROPFIND request with `depth`, returning the responses as they are received
+    pub(crate) async fn list(
+        &self,
+        location: Option<&Path>,
+        depth: &str,
+    ) -> Result<BoxStream<'static, Result<MultiStatusResponse>>> {
         let url = location
             .map(|path| self.path_url(path))
             .unwrap_or_else(|| self.url.to_string());
+        let path = location.map(|x| x.as_ref()).unwrap_or("");
+        self.propfind(url, depth, path).await
+    }
 
+    /// THIS METHOD IS ARAS ONLY
+    ///
+    /// Perform a PROPFIND request of `url`, the location of which is `path`
+    ///
+    /// Resources staged by multipart uploads in progress are omitted from listings
+    async fn propfind(
+        &self,
+        url: String,
+        depth: &str,
+        path: &str,
+    ) -> Result<BoxStream<'static, Result<MultiStatusResponse>>> {
         let method = Method::from_bytes(b"PROPFIND").unwrap();
         let result = self
             .client
@@ -250,40 +716,59 @@ impl Client {
             .send()
             .await;
 
-        let response = match result {
-            Ok(result) => result
-                .into_body()
-                .bytes()
-                .await
-                .map_err(|source| Error::Reqwest { source })?,
-            Err(e) if matches!(e.status(), Some(StatusCode::NOT_FOUND)) => {
-                return match depth {
-                    "0" => {
-                        let path = location.map(|x| x.as_ref()).unwrap_or("");
-                        Err(crate::Error::NotFound {
-                            path: path.to_string(),
-                            source: Box::new(e),
-                        })
-                    }
-                    _ => {
-                        // If prefix not found, return empty result set
-                        Ok(Default::default())
-                    }
-                };
+        match result {
+            Ok(response) if depth == "0" => Ok(multi_status_stream(response.into_body())),
+            Ok(response) => Ok(multi_status_stream(response.into_body())
+                .try_filter(|r| futures::future::ready(!r.is_staged()))
+                .boxed()),
+            Err(e) if matches!(e.status(), Some(StatusCode::NOT_FOUND)) => match depth {
+                "0" => Err(crate::Error::NotFound {
+                    path: path.to_string(),
+                    source: Box::new(e),
+                }),
+                // If prefix not found, return empty result set
+                _ => Ok(futures::stream::empty().boxed()),
+            },
+            Err(source) => Err(Error::Request {
+                source,
+                path: path.to_string(),
             }
-            Err(source) => {
-                return Err(Error::Request {
-                    source,
-                    path: location.map(|x| x.to_string()).unwrap_or_default(),
+            .into()),
+        }
+    }
+
+    /// THIS METHOD IS ARAS ONLY
+    ///
+    /// List all resources below `location`, including collections
+    ///
+    /// This performs a single PROPFIND request with `Depth: infinity`, falling back to
+    /// walking the collections with `Depth: 1` requests, up to `list_concurrency` at a
+    /// time, if the server forbids infinite depth
+    ///
+    /// The walk skips the collections containing only resources sorting before `start`,
+    /// for which resources may or may not be returned
+    pub(crate) fn list_recursive(
+        self: &Arc<Self>,
+        location: Option<&Path>,
+        start: Option<String>,
+    ) -> BoxStream<'static, Result<MultiStatusResponse>> {
+        let client = Arc::clone(self);
+        let location = location.cloned();
+        futures::stream::once(async move {
+            match client.list(location.as_ref(), "infinity").await {
+                // Servers commonly forbid infinite depth, e.g. Apache's `DavDepthInfinity Off`
+                Err(crate::Error::PermissionDenied { .. }) => {
+                    let mut url = client.url.clone();
+                    if let Some(path) = &location {
+                        url.path_segments_mut().unwrap().extend(path.parts());
+                    }
+                    Ok(Walk::new(client, url, start).boxed())
                 }
-                .into())
+                result => result,
             }
-        };
-
-        let status = quick_xml::de::from_reader(response.reader())
-            .map_err(|source| Error::InvalidPropFind { source })?;
-
-        Ok(status)
+        })
+        .try_flatten()
+        .boxed()
     }
 
     pub(crate) async fn delete(&self, path: &Path) -> Result<()> {
@@ -405,10 +890,178 @@ impl GetClient for Client {
     }
 }
 
-/// The response returned by a PROPFIND request, i.e. list
-#[derive(Deserialize, Default)]
-pub(crate) struct MultiStatus {
-    pub response: Vec<MultiStatusResponse>,
+/// THIS FUNCTION IS ARAS ONLY
+///
+/// Decodes the `response` elements of the `multistatus` returned by a PROPFIND request,
+/// i.e. list, as the body is received
+fn multi_status_stream(body: HttpResponseBody) -> BoxStream<'static, Result<MultiStatusResponse>> {
+    let decoder = MultiStatusDecoder::default();
+    futures::stream::try_unfold(
+        (body.bytes_stream(), decoder),
+        |(mut body, mut decoder)| async move {
+            while !decoder.finished {
+                let chunk = body
+                    .next()
+                    .await
+                    .transpose()
+                    .map_err(|source| Error::Reqwest { source })?;
+
+                match chunk {
+                    Some(chunk) => decoder.buffer.extend_from_slice(&chunk),
+                    None => decoder.finished = true,
+                }
+
+                let responses = decoder.decode()?;
+                if !responses.is_empty() {
+                    let responses = futures::stream::iter(responses.into_iter().map(Ok));
+                    return Ok::<_, crate::Error>(Some((responses, (body, decoder))));
+                }
+            }
+            Ok(None)
+        },
+    )
+    .try_flatten()
+    .boxed()
+}
+
+/// THIS STRUCT IS ARAS ONLY
+///
+/// Incrementally decodes the `response` elements of a `multistatus` document
+#[derive(Debug, Default)]
+struct MultiStatusDecoder {
+    /// The data received following the last decoded `response` element
+    buffer: Vec<u8>,
+    /// If the end of the document has been received
+    finished: bool,
+}
+
+impl MultiStatusDecoder {
+    /// THIS METHOD IS ARAS ONLY
+    ///
+    /// Decode the complete `response` elements in the buffer
+    fn decode(&mut self) -> Result<Vec<MultiStatusResponse>, Error> {
+        let mut reader = quick_xml::Reader::from_reader(self.buffer.as_slice());
+
+        let mut responses = vec![];
+        let mut consumed = 0;
+        let mut start = None;
+        loop {
+            let position = reader.buffer_position() as usize;
+            match reader.read_event() {
+                Ok(Event::Start(e)) if e.local_name().as_ref() == b"response" => {
+                    start = Some(position)
+                }
+                Ok(Event::End(e)) if e.local_name().as_ref() == b"response" => {
+                    if let Some(start) = start.take() {
+                        let end = reader.buffer_position() as usize;
+                        let element = &self.buffer[start..end];
+                        let response = quick_xml::de::from_reader(element)
+                            .map_err(|source| Error::InvalidPropFind { source })?;
+                        responses.push(response);
+                        consumed = end;
+                    }
+                }
+                Ok(Event::Eof) if self.finished && start.is_some() => {
+                    let source = quick_xml::DeError::UnexpectedEof;
+                    return Err(Error::InvalidPropFind { source });
+                }
+                Ok(Event::Eof) => break,
+                Ok(_) => {}
+                // The buffer does not contain the start of the enclosing elements
+                Err(quick_xml::Error::IllFormed(IllFormedError::UnmatchedEndTag(_))) => {}
+                // The buffer may end part way through an element
+                Err(_) if !self.finished => break,
+                Err(e) => return Err(Error::InvalidPropFind { source: e.into() }),
+            }
+        }
+
+        self.buffer.drain(..consumed);
+        Ok(responses)
+    }
+}
+
+/// THIS STRUCT IS ARAS ONLY
+///
+/// Lists the resources below a collection by walking its collections with `Depth: 1`
+/// PROPFIND requests
+struct Walk {
+    client: Arc<Client>,
+    /// The collections yet to be listed
+    pending: VecDeque<Url>,
+    /// The listings in progress, along with the collection being listed
+    active: SelectAll<BoxStream<'static, Result<(Url, MultiStatusResponse)>>>,
+    /// The key all resources of interest sort after, e.g. that of a page token
+    start: Option<String>,
+}
+
+impl Walk {
+    /// THIS METHOD IS ARAS ONLY
+    fn new(client: Arc<Client>, url: Url, start: Option<String>) -> Self {
+        Self {
+            client,
+            pending: VecDeque::from([url]),
+            active: SelectAll::new(),
+            start,
+        }
+    }
+
+    /// THIS METHOD IS ARAS ONLY
+    ///
+    /// Returns true if the resources below the collection `response` all sort before `start`
+    fn is_before_start(&self, response: &MultiStatusResponse) -> Result<bool> {
+        let start = match &self.start {
+            Some(start) => start,
+            None => return Ok(false),
+        };
+        let prefix = format!("{}{DELIMITER}", response.path(self.client.base_url())?);
+        Ok(prefix.as_str() < start.as_str() && !start.starts_with(&prefix))
+    }
+}
+
+impl Stream for Walk {
+    type Item = Result<MultiStatusResponse>;
+
+    /// THIS METHOD IS ARAS ONLY
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        loop {
+            while self.active.len() < self.client.list_concurrency {
+                let url = match self.pending.pop_front() {
+                    Some(url) => url,
+                    None => break,
+                };
+                let client = Arc::clone(&self.client);
+                let listing = futures::stream::once(async move {
+                    let path = url.path().to_string();
+                    let responses = client.propfind(url.to_string(), "1", &path).await?;
+                    Ok::<_, crate::Error>(responses.map_ok(move |r| (url.clone(), r)))
+                })
+                .try_flatten()
+                .boxed();
+                self.active.push(listing);
+            }
+
+            match ready!(self.active.poll_next_unpin(cx)) {
+                Some(Ok((collection, response))) => {
+                    if response.is_dir() {
+                        let url = response.url(self.client.base_url())?;
+                        // The response for the listed collection itself
+                        if url.path().trim_end_matches('/')
+                            == collection.path().trim_end_matches('/')
+                        {
+                            continue;
+                        }
+                        if !self.is_before_start(&response)? {
+                            self.pending.push_back(url);
+                        }
+                    }
+                    return Poll::Ready(Some(Ok(response)));
+                }
+                Some(Err(e)) => return Poll::Ready(Some(Err(e))),
+                None if self.pending.is_empty() => return Poll::Ready(None),
+                None => {}
+            }
+        }
+    }
 }
 
 #[derive(Deserialize)]
@@ -431,15 +1084,24 @@ impl MultiStatusResponse {
         }
     }
 
-    /// Returns the resolved path of this element relative to `base_url`
-    pub(crate) fn path(&self, base_url: &Url) -> Result<Path> {
-        let url = Url::options()
+    /// THIS METHOD IS ARAS ONLY
+    ///
+    /// Returns the URL of this element resolved against `base_url`
+    pub(crate) fn url(&self, base_url: &Url) -> Result<Url> {
+        Ok(Url::options()
             .base_url(Some(base_url))
             .parse(&self.href)
             .map_err(|source| Error::InvalidHref {
                 href: self.href.clone(),
                 source,
-            })?;
+            })?)
+    }
+
+    /// THIS METHOD IS COMMON, MODIFIED BY ARAS
+    ///
+    /// Returns the resolved path of this element relative to `base_url`
+    pub(crate) fn path(&self, base_url: &Url) -> Result<Path> {
+        let url = self.url(base_url)?;
 
         // Reverse any percent encoding
         let path = percent_decode_str(url.path())
@@ -483,6 +1145,21 @@ impl MultiStatusResponse {
     pub(crate) fn is_dir(&self) -> bool {
         self.prop_stat.prop.resource_type.collection.is_some()
     }
+
+    /// THIS METHOD IS ARAS ONLY
+    ///
+    /// Returns true if this is the resource a multipart upload is staged to, see
+    /// [`Client::multipart_create`]
+    fn is_staged(&self) -> bool {
+        if self.is_dir() {
+            return false;
+        }
+        let name = self.href.rsplit('/').next().unwrap_or_default();
+        match name.rsplit_once(STAGED_INFIX) {
+            Some((_, id)) => id.len() == 32 && id.bytes().all(|b| b.is_ascii_hexdigit()),
+            None => false,
+        }
+    }
 }
 
 #[derive(Deserialize)]
@@ -510,3 +1187,53 @@ pub(crate) struct Prop {
 pub(crate) struct ResourceType {
     collection: Option<()>,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// THIS TEST IS ARAS ONLY
+    #[test]
+    fn test_multi_status_decoder() {
+        let response = |href: &str| {
+            format!(
+                "<D:response><D:href>{href}</D:href><D:propstat><D:prop>\
+                <D:getlastmodified>Mon, 01 Jan 2024 00:00:00 GMT</D:getlastmodified>\
+                <D:getcontentlength>3</D:getcontentlength><D:resourcetype/>\
+                </D:prop><D:status>HTTP/1.1 200 OK</D:status></D:propstat></D:response>"
+            )
+        };
+        let document = format!(
+            "<?xml version=\"1.0\" encoding=\"utf-8\"?>\n<D:multistatus xmlns:D=\"DAV:\">{}{}</D:multistatus>",
+            response("/a"),
+            response("/b")
+        );
+
+        // Responses are decoded once complete, regardless of how the body is split
+        let base_url = Url::parse("http://localhost").unwrap();
+        let mut decoder = MultiStatusDecoder::default();
+        let mut paths = vec![];
+        for chunk in document.as_bytes().chunks(7) {
+            decoder.buffer.extend_from_slice(chunk);
+            for response in decoder.decode().unwrap() {
+                paths.push(response.object_meta(&base_url).unwrap().location);
+            }
+            // Decoded responses are removed from the buffer
+            if !paths.is_empty() {
+                assert!(decoder.buffer.len() < response("/a").len() + 7);
+            }
+        }
+        decoder.finished = true;
+        assert!(decoder.decode().unwrap().is_empty());
+        assert_eq!(paths, vec![Path::from("a"), Path::from("b")]);
+
+        // A truncated response is an error once the body is finished
+        let mut decoder = MultiStatusDecoder::default();
+        let truncated = &document[..document.len() - 40];
+        decoder.buffer.extend_from_slice(truncated.as_bytes());
+        assert_eq!(decoder.decode().unwrap().len(), 1);
+        decoder.finished = true;
+        let err = decoder.decode().err().unwrap();
+        assert!(matches!(err, Error::InvalidPropFind { .. }), "{err}");
+    }
+}
@@ -26,33 +26,55 @@
 //! require server-side configuration. A list of HTTP servers with support
 //! can be found [here](https://wiki.archlinux.org/title/WebDAV#Server)
 //!
-//! Multipart uploads are not currently supported
+//! Multipart uploads stage the parts on the server before assembling them,
+//! see [`HttpMultipartMode`]
+//!
+//! Conditional puts are performed with the `If-None-Match` and `If-Match` headers,
+//! and the WebDAV `If` header, optionally whilst holding a WebDAV write lock,
+//! see [`HttpBuilder::with_webdav_locking`]
+//!
+//! Recursive listing uses a single `Depth: infinity` PROPFIND, falling back to walking
+//! the directories if the server forbids it, see [`HttpBuilder::with_list_concurrency`].
+//! The responses are decoded as they are received, rather than buffered in memory
 //!
 //! [rfc2518]: https://datatracker.ietf.org/doc/html/rfc2518
 //! [WebDAV]: https://en.wikipedia.org/wiki/WebDAV
 
+use bytes::Bytes;
+use std::ops::Range;
 use std::sync::Arc;
 
 use async_trait::async_trait;
 use futures::stream::BoxStream;
 use futures::{StreamExt, TryStreamExt};
-use itertools::Itertools;
 use url::Url;
 
 use crate::client::get::GetClientExt;
 use crate::client::header::get_etag;
+use crate::client::parts::Parts;
 use crate::client::{http_connector, HttpConnector};
-use crate::http::client::Client;
+use crate::http::client::{Client, PutPrecondition, Staging};
+use crate::list::{
+    is_delimited, page_start, paginate, prefix_directory, ListEntry, PaginatedListOptions,
+    PaginatedListResult, PaginatedListStore,
+};
+use crate::multipart::PartId;
 use crate::path::Path;
 use crate::{
-    ClientConfigKey, ClientOptions, GetOptions, GetResult, ListResult, MultipartUpload, ObjectMeta,
-    ObjectStore, PutMode, PutMultipartOpts, PutOptions, PutPayload, PutResult, Result, RetryConfig,
+    coalesce_ranges_opts, ClientConfigKey, ClientOptions, GetOptions, GetResult, ListResult,
+    MultipartUpload, ObjectMeta, ObjectStore, PutMode, PutMultipartOpts, PutOptions, PutPayload,
+    PutResult, RangeCoalescePolicy, Result, RetryConfig, UploadPart,
 };
 
 mod client;
 
 const STORE: &str = "HTTP";
 
+/// THIS CONSTANT IS ARAS ONLY
+///
+/// The default maximum number of concurrent PROPFIND requests when walking directories
+const DEFAULT_LIST_CONCURRENCY: usize = 8;
+
 #[derive(Debug, thiserror::Error)]
 enum Error {
     #[error("Must specify a URL")]
@@ -79,6 +101,94 @@ impl From<Error> for crate::Error {
     }
 }
 
+/// THIS ENUM IS ARAS ONLY
+///
+/// How [`HttpStore`] performs multipart uploads
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub enum HttpMultipartMode {
+    /// Stage the parts into a temporary resource next to the destination, written with
+    /// `Content-Range` PUTs, which is moved into place with `MOVE` on completion
+    ///
+    /// The staged resource is named `<destination>.upload-<id>`, and omitted from listings
+    ///
+    /// Requires a server that supports partial PUTs, such as Apache `mod_dav`. The size of
+    /// the staged resource is checked before completion, so servers ignoring `Content-Range`
+    /// result in an error rather than a corrupt object
+    #[default]
+    ContentRange,
+    /// Nextcloud / ownCloud [chunking v2], where parts are uploaded to a collection
+    /// created under `uploads_url`, e.g. `https://example.com/remote.php/dav/uploads/<user>`,
+    /// and assembled with a `MOVE` of its `.file`
+    ///
+    /// [chunking v2]: https://docs.nextcloud.com/server/latest/developer_manual/client_apis/WebDAV/chunking.html
+    ChunkingV2 {
+        /// The URL of the user's uploads collection
+        uploads_url: String,
+    },
+}
+
+/// THIS STRUCT IS ARAS ONLY
+///
+/// A multipart upload to a [`HttpStore`]
+#[derive(Debug)]
+struct HttpMultipartUpload {
+    state: Arc<UploadState>,
+    part_idx: usize,
+    /// The offset of the next part
+    offset: u64,
+}
+
+/// THIS STRUCT IS ARAS ONLY
+#[derive(Debug)]
+struct UploadState {
+    client: Arc<Client>,
+    path: Path,
+    staging: Staging,
+    parts: Parts,
+}
+
+#[async_trait]
+impl MultipartUpload for HttpMultipartUpload {
+    /// THIS METHOD IS ARAS ONLY
+    fn put_part(&mut self, payload: PutPayload) -> UploadPart {
+        let idx = self.part_idx;
+        let offset = self.offset;
+        self.part_idx += 1;
+        self.offset += payload.content_length() as u64;
+        let state = Arc::clone(&self.state);
+        Box::pin(async move {
+            state
+                .client
+                .multipart_put_part(&state.path, &state.staging, idx, offset, payload)
+                .await?;
+            let content_id = idx.to_string();
+            state.parts.put(idx, PartId { content_id });
+            Ok(())
+        })
+    }
+
+    /// THIS METHOD IS ARAS ONLY
+    async fn complete(&mut self) -> Result<PutResult> {
+        self.state.parts.finish(self.part_idx)?;
+
+        let response = self
+            .state
+            .client
+            .multipart_complete(&self.state.path, &self.state.staging, self.offset)
+            .await?;
+
+        Ok(PutResult {
+            e_tag: get_etag(response.headers()).ok(),
+            version: None,
+        })
+    }
+
+    /// THIS METHOD IS ARAS ONLY
+    async fn abort(&mut self) -> Result<()> {
+        self.state.client.multipart_abort(&self.state.staging).await
+    }
+}
+
 /// An [`ObjectStore`] implementation for generic HTTP servers
 ///
 /// See [`crate::http`] for more information
@@ -95,18 +205,36 @@ impl std::fmt::Display for HttpStore {
 
 #[async_trait]
 impl ObjectStore for HttpStore {
+    /// THIS METHOD IS COMMON, MODIFIED BY ARAS
     async fn put_opts(
         &self,
         location: &Path,
         payload: PutPayload,
         opts: PutOptions,
     ) -> Result<PutResult> {
-        if opts.mode != PutMode::Overwrite {
-            // TODO: Add support for If header - https://datatracker.ietf.org/doc/html/rfc2518#section-9.4
-            return Err(crate::Error::NotImplemented);
-        }
+        let precondition = match &opts.mode {
+            PutMode::Overwrite => PutPrecondition::None,
+            PutMode::Create => PutPrecondition::NotExists,
+            PutMode::Update(v) => match &v.e_tag {
+                Some(e_tag) => PutPrecondition::Matches(e_tag),
+                None => {
+                    return Err(crate::Error::Generic {
+                        store: STORE,
+                        source: "ETag required for conditional put".into(),
+                    })
+                }
+            },
+            PutMode::Append => {
+                return Err(crate::Error::NotSupported {
+                    source: "HTTP does not support appending to objects".into(),
+                })
+            }
+        };
 
-        let response = self.client.put(location, payload, opts.attributes).await?;
+        let response = self
+            .client
+            .put(location, payload, opts.attributes, precondition)
+            .await?;
         let e_tag = match get_etag(response.headers()) {
             Ok(e_tag) => Some(e_tag),
             Err(crate::client::header::Error::MissingEtag) => None,
@@ -119,53 +247,92 @@ impl ObjectStore for HttpStore {
         })
     }
 
+    /// THIS METHOD IS COMMON, MODIFIED BY ARAS
     async fn put_multipart_opts(
         &self,
-        _location: &Path,
-        _opts: PutMultipartOpts,
+        location: &Path,
+        opts: PutMultipartOpts,
     ) -> Result<Box<dyn MultipartUpload>> {
-        Err(crate::Error::NotImplemented)
+        match opts.mode {
+            PutMode::Overwrite => {}
+            PutMode::Append => {
+                return Err(crate::Error::NotSupported {
+                    source: "HTTP does not support appending to objects".into(),
+                })
+            }
+            PutMode::Create | PutMode::Update(_) => return Err(crate::Error::NotImplemented),
+        }
+
+        let staging = self
+            .client
+            .multipart_create(location, opts.attributes)
+            .await?;
+
+        Ok(Box::new(HttpMultipartUpload {
+            part_idx: 0,
+            offset: 0,
+            state: Arc::new(UploadState {
+                client: Arc::clone(&self.client),
+                path: location.clone(),
+                staging,
+                parts: Default::default(),
+            }),
+        }))
     }
 
     async fn get_opts(&self, location: &Path, options: GetOptions) -> Result<GetResult> {
         self.client.get_opts(location, options).await
     }
 
+    /// THIS METHOD IS ARAS ONLY
+    async fn get_ranges(&self, location: &Path, ranges: &[Range<u64>]) -> Result<Vec<Bytes>> {
+        let policy = self.client.client_options().range_coalesce_policy();
+        self.get_ranges_opts(location, ranges, policy).await
+    }
+
+    /// THIS METHOD IS ARAS ONLY
+    async fn get_ranges_opts(
+        &self,
+        location: &Path,
+        ranges: &[Range<u64>],
+        policy: RangeCoalescePolicy,
+    ) -> Result<Vec<Bytes>> {
+        if self.client.client_options().multi_range_requests() {
+            return self.client.get_ranges_multi(location, ranges, policy).await;
+        }
+        coalesce_ranges_opts(ranges, |range| self.get_range(location, range), policy).await
+    }
+
     async fn delete(&self, location: &Path) -> Result<()> {
         self.client.delete(location).await
     }
 
+    /// THIS METHOD IS COMMON, MODIFIED BY ARAS
     fn list(&self, prefix: Option<&Path>) -> BoxStream<'static, Result<ObjectMeta>> {
         let prefix_len = prefix.map(|p| p.as_ref().len()).unwrap_or_default();
-        let prefix = prefix.cloned();
-        let client = Arc::clone(&self.client);
-        futures::stream::once(async move {
-            let status = client.list(prefix.as_ref(), "infinity").await?;
-
-            let iter = status
-                .response
-                .into_iter()
-                .filter(|r| !r.is_dir())
-                .map(move |response| {
-                    response.check_ok()?;
-                    response.object_meta(client.base_url())
-                })
-                // Filter out exact prefix matches
-                .filter_ok(move |r| r.location.as_ref().len() > prefix_len);
-
-            Ok::<_, crate::Error>(futures::stream::iter(iter))
-        })
-        .try_flatten()
-        .boxed()
+        let base_url = self.client.base_url().clone();
+        self.client
+            .list_recursive(prefix, None)
+            .try_filter(|r| futures::future::ready(!r.is_dir()))
+            .and_then(move |response| {
+                let meta = response
+                    .check_ok()
+                    .and_then(|_| response.object_meta(&base_url));
+                futures::future::ready(meta)
+            })
+            // Filter out exact prefix matches
+            .try_filter(move |r| futures::future::ready(r.location.as_ref().len() > prefix_len))
+            .boxed()
     }
 
+    /// THIS METHOD IS COMMON, MODIFIED BY ARAS
     async fn list_with_delimiter(&self, prefix: Option<&Path>) -> Result<ListResult> {
-        let status = self.client.list(prefix, "1").await?;
+        let mut responses = self.client.list(prefix, "1").await?;
         let prefix_len = prefix.map(|p| p.as_ref().len()).unwrap_or(0);
 
-        let mut objects: Vec<ObjectMeta> = Vec::with_capacity(status.response.len());
-        let mut common_prefixes = Vec::with_capacity(status.response.len());
-        for response in status.response {
+        let mut objects: Vec<ObjectMeta> = vec![];
+        let mut common_prefixes = vec![];
+        while let Some(response) = responses.try_next().await? {
             response.check_ok()?;
             match response.is_dir() {
                 false => {
@@ -200,6 +367,54 @@ impl ObjectStore for HttpStore {
     }
 }
 
+/// THIS IMPL IS ARAS ONLY
+///
+/// WebDAV has no native pagination, each page instead lists all the resources below the
+/// longest directory of the prefix, retaining only those of the page, see
+/// [`HttpBuilder::with_list_concurrency`]. When walking the collections, those sorting
+/// entirely before the page are not listed. Only a `/` delimiter is supported
+#[async_trait]
+impl PaginatedListStore for HttpStore {
+    async fn list_paginated(
+        &self,
+        prefix: Option<&str>,
+        opts: PaginatedListOptions,
+    ) -> Result<PaginatedListResult> {
+        let prefix = prefix.unwrap_or_default().to_string();
+        let directory = prefix_directory(&prefix)?;
+        let directory_len = directory.as_ref().len();
+        let base_url = self.client.base_url().clone();
+
+        let delimited = is_delimited(STORE, &opts)?;
+        let responses = match delimited {
+            true => self.client.list(Some(&directory), "1").await?,
+            false => {
+                let start = page_start(STORE, &opts)?;
+                self.client.list_recursive(Some(&directory), start)
+            }
+        };
+
+        let entries = responses
+            .try_filter_map(move |response| {
+                let entry = response.check_ok().and_then(|_| {
+                    Ok(match response.is_dir() {
+                        true if !delimited => None,
+                        true => {
+                            let path = response.path(&base_url)?;
+                            // Exclude the listed directory
+                            (path.as_ref().len() > directory_len).then_some(ListEntry::Prefix(path))
+                        }
+                        false => Some(ListEntry::Object(response.object_meta(&base_url)?)),
+                    })
+                });
+                futures::future::ready(entry)
+            })
+            .try_filter(move |entry| futures::future::ready(entry.key().starts_with(&prefix)));
+
+        paginate(STORE, entries, &opts).await
+    }
+}
+
 /// Configure a connection to a generic HTTP server
 #[derive(Debug, Default, Clone)]
 pub struct HttpBuilder {
@@ -207,6 +422,12 @@ pub struct HttpBuilder {
     client_options: ClientOptions,
     retry_config: RetryConfig,
     http_connector: Option<Arc<dyn HttpConnector>>,
+    /// THIS FIELD IS ARAS ONLY
+    webdav_locking: bool,
+    /// THIS FIELD IS ARAS ONLY
+    multipart_mode: HttpMultipartMode,
+    /// THIS FIELD IS ARAS ONLY
+    list_concurrency: Option<usize>,
 }
 
 impl HttpBuilder {
@@ -239,6 +460,50 @@ impl HttpBuilder {
         self
     }
 
+    /// THIS METHOD IS ARAS ONLY
+    ///
+    /// Sets the [`RangeCoalescePolicy`] used by [`ObjectStore::get_ranges`]
+    ///
+    /// [`ObjectStore::get_ranges`]: crate::ObjectStore::get_ranges
+    pub fn with_range_coalesce_policy(mut self, policy: RangeCoalescePolicy) -> Self {
+        self.client_options = self.client_options.with_range_coalesce_policy(policy);
+        self
+    }
+
+    /// THIS METHOD IS ARAS ONLY
+    ///
+    /// If true, conditional puts, i.e. [`PutMode::Create`] and [`PutMode::Update`], acquire
+    /// an exclusive WebDAV write lock on the object with `LOCK`, perform the `PUT` with the
+    /// lock token in the `If` header, and then release the lock with `UNLOCK`
+    ///
+    /// This guards against concurrent writers for servers that do not evaluate the
+    /// `If-None-Match` and `If-Match` headers atomically with the write. Defaults to false
+    pub fn with_webdav_locking(mut self, webdav_locking: bool) -> Self {
+        self.webdav_locking = webdav_locking;
+        self
+    }
+
+    /// THIS METHOD IS ARAS ONLY
+    ///
+    /// Sets how multipart uploads are performed
+    ///
+    /// Defaults to [`HttpMultipartMode::ContentRange`]
+    pub fn with_multipart_mode(mut self, multipart_mode: HttpMultipartMode) -> Self {
+        self.multipart_mode = multipart_mode;
+        self
+    }
+
+    /// THIS METHOD IS ARAS ONLY
+    ///
+    /// Sets the maximum number of concurrent PROPFIND requests made when listing
+    /// recursively from a server that does not support `Depth: infinity`
+    ///
+    /// Defaults to 8
+    pub fn with_list_concurrency(mut self, list_concurrency: usize) -> Self {
+        self.list_concurrency = Some(list_concurrency);
+        self
+    }
+
     /// The [`HttpConnector`] to use
     ///
     /// On non-WASM32 platforms uses [`reqwest`] by default, on WASM32 platforms must be provided
@@ -260,6 +525,9 @@ impl HttpBuilder {
                 client,
                 self.client_options,
                 self.retry_config,
+                self.webdav_locking,
+                self.multipart_mode,
+                self.list_concurrency.unwrap_or(DEFAULT_LIST_CONCURRENCY),
             )),
         })
     }
@@ -288,5 +556,525 @@ mod tests {
         list_with_delimiter(&integration).await;
         rename_and_copy(&integration).await;
         copy_if_not_exists(&integration).await;
+        list_paginated(&integration, &integration).await;
+    }
+
+    /// THIS TEST IS ARAS ONLY
+    #[tokio::test]
+    #[cfg(not(target_arch = "wasm32"))]
+    async fn test_conditional_put() {
+        use crate::client::mock_server::MockServer;
+        use crate::UpdateVersion;
+        use http::header::{ETAG, IF_MATCH, IF_NONE_MATCH};
+        use http::{Method, Response, StatusCode};
+
+        let server = MockServer::new().await;
+        let store = HttpBuilder::new()
+            .with_url(server.url())
+            .with_client_options(ClientOptions::new().with_allow_http(true))
+            .build()
+            .unwrap();
+        let path = Path::from("file");
+
+        server.push_fn(|req| {
+            assert_eq!(req.method(), Method::PUT);
+            assert_eq!(req.headers()[IF_NONE_MATCH], "*");
+            Response::builder()
+                .status(StatusCode::CREATED)
+                .header(ETAG, "\"1\"")
+                .body(String::new())
+                .unwrap()
+        });
+        let result = store
+            .put_opts(&path, "data".into(), PutMode::Create.into())
+            .await
+            .unwrap();
+        assert_eq!(result.e_tag.as_deref(), Some("\"1\""));
+
+        server.push(
+            Response::builder()
+                .status(StatusCode::PRECONDITION_FAILED)
+                .body(String::new())
+                .unwrap(),
+        );
+        let err = store
+            .put_opts(&path, "data".into(), PutMode::Create.into())
+            .await
+            .unwrap_err();
+        assert!(matches!(err, crate::Error::AlreadyExists { .. }), "{err}");
+
+        let update = |e_tag: Option<&str>| {
+            PutMode::Update(UpdateVersion {
+                e_tag: e_tag.map(ToString::to_string),
+                version: None,
+            })
+        };
+
+        server.push_fn(|req| {
+            assert_eq!(req.headers()[IF_MATCH], "\"1\"");
+            assert_eq!(req.headers()["If"], "([\"1\"])");
+            Response::builder()
+                .status(StatusCode::NO_CONTENT)
+                .header(ETAG, "\"2\"")
+                .body(String::new())
+                .unwrap()
+        });
+        let result = store
+            .put_opts(&path, "data".into(), update(Some("\"1\"")).into())
+            .await
+            .unwrap();
+        assert_eq!(result.e_tag.as_deref(), Some("\"2\""));
+
+        server.push(
+            Response::builder()
+                .status(StatusCode::PRECONDITION_FAILED)
+                .body(String::new())
+                .unwrap(),
+        );
+        let err = store
+            .put_opts(&path, "data".into(), update(Some("\"1\"")).into())
+            .await
+            .unwrap_err();
+        assert!(matches!(err, crate::Error::Precondition { .. }), "{err}");
+
+        let err = store
+            .put_opts(&path, "data".into(), update(None).into())
+            .await
+            .unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Generic HTTP error: ETag required for conditional put"
+        );
+    }
+
+    /// THIS TEST IS ARAS ONLY
+    #[tokio::test]
+    #[cfg(not(target_arch = "wasm32"))]
+    async fn test_conditional_put_locking() {
+        use crate::client::mock_server::MockServer;
+        use crate::UpdateVersion;
+        use http::header::{ETAG, IF_MATCH, IF_NONE_MATCH};
+        use http::{Method, Response, StatusCode};
+
+        const TOKEN: &str = "<opaquelocktoken:e71d4fae-5dec-22d6-fea5-00a0c91e6be4>";
+
+        let server = MockServer::new().await;
+        let store = HttpBuilder::new()
+            .with_url(server.url())
+            .with_client_options(ClientOptions::new().with_allow_http(true))
+            .with_webdav_locking(true)
+            .build()
+            .unwrap();
+        let path = Path::from("file");
+
+        let lock = |status: StatusCode| {
+            move |req: http::Request<_>| {
+                assert_eq!(req.method().as_str(), "LOCK");
+                assert_eq!(req.headers()["Depth"], "0");
+                assert_eq!(req.headers()[IF_NONE_MATCH], "*");
+                Response::builder()
+                    .status(status)
+                    .header("Lock-Token", TOKEN)
+                    .body(String::new())
+                    .unwrap()
+            }
+        };
+        let unlock = |req: http::Request<_>| {
+            assert_eq!(req.method().as_str(), "UNLOCK");
+            assert_eq!(req.headers()["Lock-Token"], TOKEN);
+            Response::builder()
+                .status(StatusCode::NO_CONTENT)
+                .body(String::new())
+                .unwrap()
+        };
+
+        // Create acquires a lock on the unmapped URL
+        server.push_fn(lock(StatusCode::CREATED));
+        server.push_fn(|req| {
+            assert_eq!(req.method(), Method::PUT);
+            assert_eq!(req.headers()["If"], format!("({TOKEN})"));
+            assert!(req.headers().get(IF_NONE_MATCH).is_none());
+            Response::builder()
+                .status(StatusCode::CREATED)
+                .header(ETAG, "\"1\"")
+                .body(String::new())
+                .unwrap()
+        });
+        server.push_fn(unlock);
+        let result = store
+            .put_opts(&path, "data".into(), PutMode::Create.into())
+            .await
+            .unwrap();
+        assert_eq!(result.e_tag.as_deref(), Some("\"1\""));
+
+        // Servers ignoring If-None-Match lock the existing resource
+        server.push_fn(lock(StatusCode::OK));
+        server.push_fn(unlock);
+        let err = store
+            .put_opts(&path, "data".into(), PutMode::Create.into())
+            .await
+            .unwrap_err();
+        assert!(matches!(err, crate::Error::AlreadyExists { .. }), "{err}");
+
+        // The empty resource created by the lock is removed if the put fails
+        server.push_fn(lock(StatusCode::CREATED));
+        server.push(
+            Response::builder()
+                .status(StatusCode::FORBIDDEN)
+                .body(String::new())
+                .unwrap(),
+        );
+        server.push_fn(|req| {
+            assert_eq!(req.method(), Method::DELETE);
+            assert_eq!(req.headers()["If"], format!("({TOKEN})"));
+            Response::builder()
+                .status(StatusCode::NO_CONTENT)
+                .body(String::new())
+                .unwrap()
+        });
+        let err = store
+            .put_opts(&path, "data".into(), PutMode::Create.into())
+            .await
+            .unwrap_err();
+        assert!(
+            matches!(err, crate::Error::PermissionDenied { .. }),
+            "{err}"
+        );
+
+        // Update locks the resource with the expected e-tag
+        server.push_fn(|req| {
+            assert_eq!(req.method().as_str(), "LOCK");
+            assert_eq!(req.headers()[IF_MATCH], "\"1\"");
+            Response::builder()
+                .header("Lock-Token", TOKEN)
+                .body(String::new())
+                .unwrap()
+        });
+        server.push_fn(|req| {
+            assert_eq!(req.method(), Method::PUT);
+            assert_eq!(req.headers()[IF_MATCH], "\"1\"");
+            assert_eq!(req.headers()["If"], format!("({TOKEN} [\"1\"])"));
+            Response::builder()
+                .status(StatusCode::NO_CONTENT)
+                .header(ETAG, "\"2\"")
+                .body(String::new())
+                .unwrap()
+        });
+        server.push_fn(unlock);
+        let mode = PutMode::Update(UpdateVersion {
+            e_tag: Some("\"1\"".to_string()),
+            version: None,
+        });
+        let result = store
+            .put_opts(&path, "data".into(), mode.clone().into())
+            .await
+            .unwrap();
+        assert_eq!(result.e_tag.as_deref(), Some("\"2\""));
+
+        server.push(
+            Response::builder()
+                .status(StatusCode::LOCKED)
+                .body(String::new())
+                .unwrap(),
+        );
+        let err = store
+            .put_opts(&path, "data".into(), mode.into())
+            .await
+            .unwrap_err();
+        assert!(matches!(err, crate::Error::Precondition { .. }), "{err}");
+    }
+
+    /// THIS TEST IS ARAS ONLY
+    #[tokio::test]
+    #[cfg(not(target_arch = "wasm32"))]
+    async fn test_multipart_content_range() {
+        use crate::client::mock_server::MockServer;
+        use http::header::{CONTENT_LENGTH, CONTENT_RANGE, ETAG};
+        use http::{Method, Response, StatusCode};
+
+        let server = MockServer::new().await;
+        let store = HttpBuilder::new()
+            .with_url(server.url())
+            .with_client_options(ClientOptions::new().with_allow_http(true))
+            .build()
+            .unwrap();
+        let path = Path::from("dir/file");
+        let ok = |status: StatusCode| Response::builder().status(status).body(String::new());
+
+        let staged = Arc::new(std::sync::Mutex::new(String::new()));
+        let s = Arc::clone(&staged);
+        server.push_fn(move |req| {
+            assert_eq!(req.method(), Method::PUT);
+            assert!(req.uri().path().starts_with("/dir/file.upload-"));
+            *s.lock().unwrap() = req.uri().path().to_string();
+            ok(StatusCode::CREATED).unwrap()
+        });
+        let mut upload = store.put_multipart(&path).await.unwrap();
+
+        for range in ["bytes 0-3/*", "bytes 4-5/*"] {
+            let s = Arc::clone(&staged);
+            server.push_fn(move |req| {
+                assert_eq!(req.method(), Method::PUT);
+                assert_eq!(req.uri().path(), s.lock().unwrap().as_str());
+                assert_eq!(req.headers()[CONTENT_RANGE], range);
+                ok(StatusCode::NO_CONTENT).unwrap()
+            });
+        }
+        upload.put_part("abcd".into()).await.unwrap();
+        upload.put_part("ef".into()).await.unwrap();
+        // Empty parts are not uploaded
+        upload.put_part(PutPayload::new()).await.unwrap();
+
+        let s = Arc::clone(&staged);
+        server.push_fn(move |req| {
+            assert_eq!(req.method(), Method::HEAD);
+            assert_eq!(req.uri().path(), s.lock().unwrap().as_str());
+            Response::builder()
+                .header(CONTENT_LENGTH, 6)
+                .body(String::new())
+                .unwrap()
+        });
+        server.push_fn(|req| {
+            assert_eq!(req.method().as_str(), "MOVE");
+            let destination = req.headers()["Destination"].to_str().unwrap();
+            assert!(destination.ends_with("/dir/file"), "{destination}");
+            assert_eq!(req.headers()["Overwrite"], "T");
+            Response::builder()
+                .status(StatusCode::CREATED)
+                .header(ETAG, "\"1\"")
+                .body(String::new())
+                .unwrap()
+        });
+        let result = upload.complete().await.unwrap();
+        assert_eq!(result.e_tag.as_deref(), Some("\"1\""));
+
+        // Servers ignoring Content-Range are detected before completion
+        server.push(ok(StatusCode::CREATED).unwrap());
+        let mut upload = store.put_multipart(&path).await.unwrap();
+        server.push(ok(StatusCode::NO_CONTENT).unwrap());
+        server.push(ok(StatusCode::NO_CONTENT).unwrap());
+        upload.put_part("abcd".into()).await.unwrap();
+        upload.put_part("ef".into()).await.unwrap();
+        server.push(
+            Response::builder()
+                .header(CONTENT_LENGTH, 2)
+                .body(String::new())
+                .unwrap(),
+        );
+        let err = upload.complete().await.unwrap_err().to_string();
+        assert!(err.contains("is 2 bytes, expected 6"), "{err}");
+
+        server.push_fn(move |req| {
+            assert_eq!(req.method(), Method::DELETE);
+            ok(StatusCode::NOT_FOUND).unwrap()
+        });
+        upload.abort().await.unwrap();
+    }
+
+    /// THIS TEST IS ARAS ONLY
+    #[tokio::test]
+    #[cfg(not(target_arch = "wasm32"))]
+    async fn test_multipart_chunking_v2() {
+        use crate::client::mock_server::MockServer;
+        use http::{Method, Response, StatusCode};
+
+        let server = MockServer::new().await;
+        let uploads_url = format!("{}/uploads/user", server.url());
+        let store = HttpBuilder::new()
+            .with_url(format!("{}/files/user", server.url()))
+            .with_client_options(ClientOptions::new().with_allow_http(true))
+            .with_multipart_mode(HttpMultipartMode::ChunkingV2 { uploads_url })
+            .build()
+            .unwrap();
+        let path = Path::from("file");
+        let ok = |status: StatusCode| Response::builder().status(status).body(String::new());
+
+        let collection = Arc::new(std::sync::Mutex::new(String::new()));
+        let c = Arc::clone(&collection);
+        server.push_fn(move |req| {
+            assert_eq!(req.method().as_str(), "MKCOL");
+            assert!(req.uri().path().starts_with("/uploads/user/"));
+            let destination = req.headers()["Destination"].to_str().unwrap();
+            assert!(destination.ends_with("/files/user/file"), "{destination}");
+            *c.lock().unwrap() = req.uri().path().to_string();
+            ok(StatusCode::CREATED).unwrap()
+        });
+        let mut upload = store.put_multipart(&path).await.unwrap();
+
+        for chunk in ["00001", "00002"] {
+            let c = Arc::clone(&collection);
+            server.push_fn(move |req| {
+                assert_eq!(req.method(), Method::PUT);
+                let expected = format!("{}/{chunk}", c.lock().unwrap());
+                assert_eq!(req.uri().path(), expected);
+                ok(StatusCode::CREATED).unwrap()
+            });
+        }
+        upload.put_part("abcd".into()).await.unwrap();
+        upload.put_part("ef".into()).await.unwrap();
+
+        let c = Arc::clone(&collection);
+        server.push_fn(move |req| {
+            assert_eq!(req.method().as_str(), "MOVE");
+            let expected = format!("{}/.file", c.lock().unwrap());
+            assert_eq!(req.uri().path(), expected);
+            assert_eq!(req.headers()["OC-Total-Length"], "6");
+            ok(StatusCode::CREATED).unwrap()
+        });
+        upload.complete().await.unwrap();
+
+        // Appending is not supported
+        let opts = PutMultipartOpts {
+            mode: PutMode::Append,
+            ..Default::default()
+        };
+        let err = store.put_multipart_opts(&path, opts).await.unwrap_err();
+        assert!(matches!(err, crate::Error::NotSupported { .. }), "{err}");
+    }
+
+    /// THIS TEST IS ARAS ONLY
+    #[tokio::test]
+    #[cfg(not(target_arch = "wasm32"))]
+    async fn test_list_paginated() {
+        use crate::client::mock_server::MockServer;
+        use http::{Response, StatusCode};
+
+        fn multi_status(entries: &[&str]) -> String {
+            let responses: String = entries
+                .iter()
+                .map(|href| {
+                    let resource_type = match href.ends_with('/') {
+                        true => "<D:resourcetype><D:collection/></D:resourcetype>",
+                        false => "<D:getcontentlength>3</D:getcontentlength><D:resourcetype/>",
+                    };
+                    format!(
+                        "<D:response><D:href>{href}</D:href><D:propstat><D:prop>\
+                        <D:getlastmodified>Mon, 01 Jan 2024 00:00:00 GMT</D:getlastmodified>\
+                        {resource_type}</D:prop><D:status>HTTP/1.1 200 OK</D:status>\
+                        </D:propstat></D:response>"
+                    )
+                })
+                .collect();
+            format!("<D:multistatus xmlns:D=\"DAV:\">{responses}</D:multistatus>")
+        }
+
+        let propfind = |path: &'static str, depth: &'static str, entries: &'static [&str]| {
+            move |req: http::Request<_>| {
+                assert_eq!(req.method().as_str(), "PROPFIND");
+                assert_eq!(req.uri().path(), path);
+                assert_eq!(req.headers()["Depth"], depth);
+                Response::builder()
+                    .status(StatusCode::MULTI_STATUS)
+                    .body(multi_status(entries))
+                    .unwrap()
+            }
+        };
+
+        let server = MockServer::new().await;
+        let store = HttpBuilder::new()
+            .with_url(server.url())
+            .with_client_options(ClientOptions::new().with_allow_http(true))
+            .with_list_concurrency(2)
+            .build()
+            .unwrap();
+
+        // Servers forbidding infinite depth are walked
+        server.push(
+            Response::builder()
+                .status(StatusCode::FORBIDDEN)
+                .body(String::new())
+                .unwrap(),
+        );
+        // Along with resources staged by multipart uploads, which are not listed
+        let entries = &[
+            "/",
+            "/a",
+            "/a.upload-0123456789abcdef0123456789abcdef",
+            "/dir/",
+        ];
+        server.push_fn(propfind("/", "1", entries));
+        server.push_fn(propfind("/dir/", "1", &["/dir/", "/dir/b"]));
+        let mut paths: Vec<_> = store
+            .list(None)
+            .map_ok(|m| m.location)
+            .try_collect()
+            .await
+            .unwrap();
+        paths.sort();
+        assert_eq!(paths, vec![Path::from("a"), Path::from("dir/b")]);
+
+        // Walks skip the collections before the start of the page
+        server.push(
+            Response::builder()
+                .status(StatusCode::FORBIDDEN)
+                .body(String::new())
+                .unwrap(),
+        );
+        server.push_fn(propfind("/", "1", &["/", "/a/", "/b/", "/c"]));
+        server.push_fn(propfind("/b/", "1", &["/b/", "/b/x", "/b/y"]));
+        let opts = PaginatedListOptions {
+            offset: Some("b/x".into()),
+            ..Default::default()
+        };
+        let page = store.list_paginated(None, opts).await.unwrap();
+        let paths: Vec<_> = page
+            .result
+            .objects
+            .iter()
+            .map(|m| m.location.as_ref())
+            .collect();
+        assert_eq!(paths, vec!["b/y", "c"]);
+        assert_eq!(page.page_token, None);
+
+        // Pages are ordered regardless of the order of the responses
+        let entries = &[
+            "/dir/",
+            "/dir/d",
+            "/dir/b",
+            "/dir/c",
+            "/dir/sub/",
+            "/dir/sub/e",
+        ];
+        server.push_fn(propfind("/dir", "infinity", entries));
+        let opts = PaginatedListOptions {
+            max_keys: Some(2),
+            ..Default::default()
+        };
+        let page = store.list_paginated(Some("dir/"), opts).await.unwrap();
+        let paths: Vec<_> = page
+            .result
+            .objects
+            .iter()
+            .map(|m| m.location.as_ref())
+            .collect();
+        assert_eq!(paths, vec!["dir/b", "dir/c"]);
+        assert!(page.page_token.is_some());
+
+        server.push_fn(propfind("/dir", "infinity", entries));
+        let opts = PaginatedListOptions {
+            max_keys: Some(2),
+            page_token: page.page_token,
+            ..Default::default()
+        };
+        let page = store.list_paginated(Some("dir/"), opts).await.unwrap();
+        let paths: Vec<_> = page
+            .result
+            .objects
+            .iter()
+            .map(|m| m.location.as_ref())
+            .collect();
+        assert_eq!(paths, vec!["dir/d", "dir/sub/e"]);
+        assert_eq!(page.page_token, None);
+
+        // Directories are returned as common prefixes with a delimiter
+        server.push_fn(propfind("/", "1", &["/", "/a", "/dir/", "/other"]));
+        let opts = PaginatedListOptions {
+            delimiter: Some("/".into()),
+            ..Default::default()
+        };
+        let page = store.list_paginated(Some("d"), opts).await.unwrap();
+        assert!(page.result.objects.is_empty());
+        assert_eq!(page.result.common_prefixes, vec![Path::from("dir")]);
+        assert_eq!(page.page_token, None);
     }
 }
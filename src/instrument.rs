@@ -0,0 +1,813 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! THIS MODULE IS ARAS ONLY
+//!
+//! An object store wrapper that records metrics and emits [`tracing`] spans for the
+//! operations performed on the wrapped implementation
+
+use crate::{
+    BoxStream, DeleteOptions, Error, GetOptions, GetResult, GetResultPayload, ListResult,
+    MultipartUpload, ObjectMeta, ObjectStore, Path, PutMultipartOpts, PutOptions, PutPayload,
+    PutResult, RangeCoalescePolicy, Result, StreamExt, UploadPart,
+};
+use async_trait::async_trait;
+use bytes::Bytes;
+use futures::{Future, Stream};
+use parking_lot::Mutex;
+use std::collections::HashMap;
+use std::fmt::{Debug, Display, Formatter};
+use std::ops::Range;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::task::{Context, Poll};
+use std::time::{Duration, Instant};
+use tracing::{field, Instrument, Span};
+
+/// An operation performed on an [`ObjectStore`] or [`MultipartUpload`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[non_exhaustive]
+pub enum Operation {
+    /// [`ObjectStore::put`] and [`ObjectStore::put_opts`]
+    Put,
+    /// [`ObjectStore::append`]
+    Append,
+    /// [`ObjectStore::put_multipart`] and [`ObjectStore::put_multipart_opts`]
+    PutMultipart,
+    /// [`MultipartUpload::put_part`]
+    PutPart,
+    /// [`MultipartUpload::complete`]
+    CompleteMultipart,
+    /// [`MultipartUpload::abort`]
+    AbortMultipart,
+    /// [`ObjectStore::get`] and [`ObjectStore::get_opts`]
+    Get,
+    /// [`ObjectStore::get_range`]
+    GetRange,
+    /// [`ObjectStore::get_ranges`] and [`ObjectStore::get_ranges_opts`]
+    GetRanges,
+    /// [`ObjectStore::head`], and [`ObjectStore::get_opts`] with [`GetOptions::head`]
+    Head,
+    /// [`ObjectStore::delete`], [`ObjectStore::delete_opts`], and each location
+    /// deleted by [`ObjectStore::delete_stream`]
+    Delete,
+    /// [`ObjectStore::list`] and [`ObjectStore::list_with_offset`]
+    List,
+    /// [`ObjectStore::list_with_delimiter`]
+    ListWithDelimiter,
+    /// [`ObjectStore::copy`]
+    Copy,
+    /// [`ObjectStore::copy_if_not_exists`]
+    CopyIfNotExists,
+    /// [`ObjectStore::rename`]
+    Rename,
+    /// [`ObjectStore::rename_if_not_exists`]
+    RenameIfNotExists,
+    /// [`ObjectStore::compose`]
+    Compose,
+}
+
+impl Operation {
+    /// Returns the name of this operation
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Put => "put",
+            Self::Append => "append",
+            Self::PutMultipart => "put_multipart",
+            Self::PutPart => "put_part",
+            Self::CompleteMultipart => "complete_multipart",
+            Self::AbortMultipart => "abort_multipart",
+            Self::Get => "get",
+            Self::GetRange => "get_range",
+            Self::GetRanges => "get_ranges",
+            Self::Head => "head",
+            Self::Delete => "delete",
+            Self::List => "list",
+            Self::ListWithDelimiter => "list_with_delimiter",
+            Self::Copy => "copy",
+            Self::CopyIfNotExists => "copy_if_not_exists",
+            Self::Rename => "rename",
+            Self::RenameIfNotExists => "rename_if_not_exists",
+            Self::Compose => "compose",
+        }
+    }
+}
+
+impl Display for Operation {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// The variant of an [`Error`], without its context
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[non_exhaustive]
+pub enum ErrorKind {
+    /// [`Error::Generic`]
+    Generic,
+    /// [`Error::NotFound`]
+    NotFound,
+    /// [`Error::InvalidPath`]
+    InvalidPath,
+    /// [`Error::JoinError`]
+    JoinError,
+    /// [`Error::NotSupported`]
+    NotSupported,
+    /// [`Error::AlreadyExists`]
+    AlreadyExists,
+    /// [`Error::Precondition`]
+    Precondition,
+    /// [`Error::NotModified`]
+    NotModified,
+    /// [`Error::NotImplemented`]
+    NotImplemented,
+    /// [`Error::PermissionDenied`]
+    PermissionDenied,
+    /// [`Error::Unauthenticated`]
+    Unauthenticated,
+    /// [`Error::TagsNotWritten`]
+    TagsNotWritten,
+    /// [`Error::UnknownConfigurationKey`]
+    UnknownConfigurationKey,
+}
+
+impl From<&Error> for ErrorKind {
+    fn from(e: &Error) -> Self {
+        match e {
+            Error::Generic { .. } => Self::Generic,
+            Error::NotFound { .. } => Self::NotFound,
+            Error::InvalidPath { .. } => Self::InvalidPath,
+            Error::JoinError { .. } => Self::JoinError,
+            Error::NotSupported { .. } => Self::NotSupported,
+            Error::AlreadyExists { .. } => Self::AlreadyExists,
+            Error::Precondition { .. } => Self::Precondition,
+            Error::NotModified { .. } => Self::NotModified,
+            Error::NotImplemented => Self::NotImplemented,
+            Error::PermissionDenied { .. } => Self::PermissionDenied,
+            Error::Unauthenticated { .. } => Self::Unauthenticated,
+            Error::TagsNotWritten { .. } => Self::TagsNotWritten,
+            Error::UnknownConfigurationKey { .. } => Self::UnknownConfigurationKey,
+        }
+    }
+}
+
+/// The metrics of a single [`Operation`] recorded by an [`InstrumentedStore`]
+#[derive(Debug, Clone)]
+pub struct OperationMetrics {
+    /// The name of the store the operation was performed on
+    pub store: Arc<str>,
+    /// The operation performed
+    pub operation: Operation,
+    /// The time taken by the operation
+    ///
+    /// For operations returning a stream, such as [`ObjectStore::list`] or
+    /// [`ObjectStore::get`], this includes the time taken to consume the stream
+    pub duration: Duration,
+    /// The number of bytes uploaded or downloaded by the operation
+    pub bytes: u64,
+    /// The kind of error the operation failed with, if any
+    pub error: Option<ErrorKind>,
+}
+
+/// A sink for the [`OperationMetrics`] recorded by an [`InstrumentedStore`]
+///
+/// This can be implemented to forward metrics to the metrics system of an
+/// application, or see [`StoreMetrics`] for a sink aggregating them in memory
+pub trait MetricsSink: Debug + Send + Sync + 'static {
+    /// Records the metrics of a completed operation
+    fn record(&self, metrics: &OperationMetrics);
+}
+
+/// The aggregated metrics of an [`Operation`], see [`StoreMetrics`]
+#[derive(Debug, Clone, Default)]
+pub struct OperationStats {
+    /// The number of times the operation was performed
+    pub count: u64,
+    /// The total time taken by the operation
+    pub duration: Duration,
+    /// The maximum time taken by a single operation
+    pub max_duration: Duration,
+    /// The total number of bytes transferred by the operation
+    pub bytes: u64,
+    /// The number of times the operation failed, by the kind of error
+    pub errors: HashMap<ErrorKind, u64>,
+}
+
+/// A [`MetricsSink`] aggregating metrics in memory by [`Operation`]
+#[derive(Debug, Default)]
+pub struct StoreMetrics {
+    stats: Mutex<HashMap<Operation, OperationStats>>,
+}
+
+impl StoreMetrics {
+    /// Create a new, empty [`StoreMetrics`]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the aggregated metrics of `operation`
+    pub fn get(&self, operation: Operation) -> OperationStats {
+        self.stats
+            .lock()
+            .get(&operation)
+            .cloned()
+            .unwrap_or_default()
+    }
+
+    /// Returns the aggregated metrics of every operation recorded
+    pub fn snapshot(&self) -> HashMap<Operation, OperationStats> {
+        self.stats.lock().clone()
+    }
+}
+
+impl MetricsSink for StoreMetrics {
+    fn record(&self, metrics: &OperationMetrics) {
+        let mut stats = self.stats.lock();
+        let stats = stats.entry(metrics.operation).or_default();
+        stats.count += 1;
+        stats.duration += metrics.duration;
+        stats.max_duration = stats.max_duration.max(metrics.duration);
+        stats.bytes += metrics.bytes;
+        if let Some(kind) = metrics.error {
+            *stats.errors.entry(kind).or_default() += 1;
+        }
+    }
+}
+
+/// Store wrapper that records the [`OperationMetrics`] of every operation performed
+/// through it in a [`MetricsSink`], and performs each operation within a [`tracing`]
+/// span named `object_store` carrying the store name, operation, path and range
+///
+/// The bytes transferred by streamed [`GetResult`] payloads and [`MultipartUpload`]
+/// parts are included, with the metrics of an operation returning a stream recorded
+/// once the stream is exhausted or dropped
+///
+/// ```
+/// # use std::sync::Arc;
+/// # use object_store::instrument::{InstrumentedStore, Operation, StoreMetrics};
+/// # use object_store::memory::InMemory;
+/// # use object_store::{path::Path, ObjectStore};
+/// # async fn example() -> object_store::Result<()> {
+/// let metrics = Arc::new(StoreMetrics::new());
+/// let store = InstrumentedStore::new(InMemory::new(), metrics.clone()).with_name("memory");
+///
+/// store.put(&Path::from("data"), "hello".into()).await?;
+/// assert_eq!(metrics.get(Operation::Put).bytes, 5);
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug)]
+pub struct InstrumentedStore<T: ObjectStore> {
+    inner: Arc<T>,
+    instrumentation: Arc<Instrumentation>,
+}
+
+impl<T: ObjectStore> InstrumentedStore<T> {
+    /// Create a new [`InstrumentedStore`] recording the metrics of `inner` in `sink`
+    ///
+    /// The store is named by the [`Display`] implementation of `inner`
+    pub fn new(inner: T, sink: Arc<dyn MetricsSink>) -> Self {
+        let name = inner.to_string().into();
+        Self {
+            inner: Arc::new(inner),
+            instrumentation: Arc::new(Instrumentation { name, sink }),
+        }
+    }
+
+    /// Set the name of the store reported in metrics and spans
+    pub fn with_name(self, name: impl Into<String>) -> Self {
+        Self {
+            inner: self.inner,
+            instrumentation: Arc::new(Instrumentation {
+                name: name.into().into(),
+                sink: Arc::clone(&self.instrumentation.sink),
+            }),
+        }
+    }
+
+    fn span(&self, operation: Operation, location: &Path) -> Span {
+        self.instrumentation.span(operation, Some(location))
+    }
+
+    fn instrument_upload(
+        &self,
+        location: &Path,
+        upload: Box<dyn MultipartUpload>,
+    ) -> Box<dyn MultipartUpload> {
+        Box::new(InstrumentedUpload {
+            upload,
+            location: location.clone(),
+            instrumentation: Arc::clone(&self.instrumentation),
+        })
+    }
+}
+
+impl<T: ObjectStore> Display for InstrumentedStore<T> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "InstrumentedStore({})", self.inner)
+    }
+}
+
+#[async_trait]
+impl<T: ObjectStore> ObjectStore for InstrumentedStore<T> {
+    async fn put(&self, location: &Path, payload: PutPayload) -> Result<PutResult> {
+        let len = payload.content_length() as u64;
+        let span = self.span(Operation::Put, location);
+        let fut = self.inner.put(location, payload);
+        self.instrumentation
+            .perform(Operation::Put, span, |_| len, fut)
+            .await
+    }
+
+    async fn put_opts(
+        &self,
+        location: &Path,
+        payload: PutPayload,
+        opts: PutOptions,
+    ) -> Result<PutResult> {
+        let len = payload.content_length() as u64;
+        let span = self.span(Operation::Put, location);
+        let fut = self.inner.put_opts(location, payload, opts);
+        self.instrumentation
+            .perform(Operation::Put, span, |_| len, fut)
+            .await
+    }
+
+    async fn append(&self, location: &Path, payload: PutPayload) -> Result<PutResult> {
+        let len = payload.content_length() as u64;
+        let span = self.span(Operation::Append, location);
+        let fut = self.inner.append(location, payload);
+        self.instrumentation
+            .perform(Operation::Append, span, |_| len, fut)
+            .await
+    }
+
+    async fn put_multipart(&self, location: &Path) -> Result<Box<dyn MultipartUpload>> {
+        let span = self.span(Operation::PutMultipart, location);
+        let fut = self.inner.put_multipart(location);
+        let upload = self
+            .instrumentation
+            .perform(Operation::PutMultipart, span, |_| 0, fut)
+            .await?;
+        Ok(self.instrument_upload(location, upload))
+    }
+
+    async fn put_multipart_opts(
+        &self,
+        location: &Path,
+        opts: PutMultipartOpts,
+    ) -> Result<Box<dyn MultipartUpload>> {
+        let span = self.span(Operation::PutMultipart, location);
+        let fut = self.inner.put_multipart_opts(location, opts);
+        let upload = self
+            .instrumentation
+            .perform(Operation::PutMultipart, span, |_| 0, fut)
+            .await?;
+        Ok(self.instrument_upload(location, upload))
+    }
+
+    async fn get_opts(&self, location: &Path, options: GetOptions) -> Result<GetResult> {
+        let operation = match options.head {
+            true => Operation::Head,
+            false => Operation::Get,
+        };
+        let span = self.span(operation, location);
+        if let Some(range) = &options.range {
+            span.record("range", field::debug(range));
+        }
+
+        let mut recorder = self.instrumentation.recorder(operation);
+        let mut result = match self
+            .inner
+            .get_opts(location, options)
+            .instrument(span.clone())
+            .await
+        {
+            Ok(result) => result,
+            Err(e) => {
+                recorder.set_error(&e);
+                return Err(e);
+            }
+        };
+
+        match result.payload {
+            #[cfg(all(feature = "fs", not(target_arch = "wasm32")))]
+            GetResultPayload::File(file, path) => {
+                recorder.bytes = result.range.end - result.range.start;
+                result.payload = GetResultPayload::File(file, path);
+            }
+            GetResultPayload::Stream(s) => {
+                let s = InstrumentedStream::new(s, recorder, span, |b: &Bytes| b.len() as u64);
+                result.payload = GetResultPayload::Stream(s.boxed());
+            }
+        }
+        Ok(result)
+    }
+
+    async fn get_range(&self, location: &Path, range: Range<u64>) -> Result<Bytes> {
+        let span = self.span(Operation::GetRange, location);
+        span.record("range", field::debug(&range));
+        let fut = self.inner.get_range(location, range);
+        let bytes = |b: &Bytes| b.len() as u64;
+        self.instrumentation
+            .perform(Operation::GetRange, span, bytes, fut)
+            .await
+    }
+
+    async fn get_ranges(&self, location: &Path, ranges: &[Range<u64>]) -> Result<Vec<Bytes>> {
+        let span = self.span(Operation::GetRanges, location);
+        span.record("range", field::debug(ranges));
+        let fut = self.inner.get_ranges(location, ranges);
+        let bytes = |b: &Vec<Bytes>| b.iter().map(|b| b.len() as u64).sum();
+        self.instrumentation
+            .perform(Operation::GetRanges, span, bytes, fut)
+            .await
+    }
+
+    async fn get_ranges_opts(
+        &self,
+        location: &Path,
+        ranges: &[Range<u64>],
+        policy: RangeCoalescePolicy,
+    ) -> Result<Vec<Bytes>> {
+        let span = self.span(Operation::GetRanges, location);
+        span.record("range", field::debug(ranges));
+        let fut = self.inner.get_ranges_opts(location, ranges, policy);
+        let bytes = |b: &Vec<Bytes>| b.iter().map(|b| b.len() as u64).sum();
+        self.instrumentation
+            .perform(Operation::GetRanges, span, bytes, fut)
+            .await
+    }
+
+    async fn head(&self, location: &Path) -> Result<ObjectMeta> {
+        let span = self.span(Operation::Head, location);
+        let fut = self.inner.head(location);
+        self.instrumentation
+            .perform(Operation::Head, span, |_| 0, fut)
+            .await
+    }
+
+    async fn delete(&self, location: &Path) -> Result<()> {
+        let span = self.span(Operation::Delete, location);
+        let fut = self.inner.delete(location);
+        self.instrumentation
+            .perform(Operation::Delete, span, |_| 0, fut)
+            .await
+    }
+
+    async fn delete_opts(&self, location: &Path, opts: DeleteOptions) -> Result<()> {
+        let span = self.span(Operation::Delete, location);
+        let fut = self.inner.delete_opts(location, opts);
+        self.instrumentation
+            .perform(Operation::Delete, span, |_| 0, fut)
+            .await
+    }
+
+    fn delete_stream<'a>(
+        &'a self,
+        locations: BoxStream<'a, Result<Path>>,
+    ) -> BoxStream<'a, Result<Path>> {
+        // The duration of each deletion is the time taken to yield its result
+        let instrumentation = &self.instrumentation;
+        let mut start = Instant::now();
+        self.inner
+            .delete_stream(locations)
+            .map(move |r| {
+                let mut recorder = instrumentation.recorder(Operation::Delete);
+                recorder.start = start;
+                if let Err(e) = &r {
+                    recorder.set_error(e);
+                }
+                start = Instant::now();
+                r
+            })
+            .boxed()
+    }
+
+    fn list(&self, prefix: Option<&Path>) -> BoxStream<'static, Result<ObjectMeta>> {
+        let span = self.instrumentation.span(Operation::List, prefix);
+        let recorder = self.instrumentation.recorder(Operation::List);
+        let s = span.in_scope(|| self.inner.list(prefix));
+        InstrumentedStream::new(s, recorder, span, |_| 0).boxed()
+    }
+
+    fn list_with_offset(
+        &self,
+        prefix: Option<&Path>,
+        offset: &Path,
+    ) -> BoxStream<'static, Result<ObjectMeta>> {
+        let span = self.instrumentation.span(Operation::List, prefix);
+        let recorder = self.instrumentation.recorder(Operation::List);
+        let s = span.in_scope(|| self.inner.list_with_offset(prefix, offset));
+        InstrumentedStream::new(s, recorder, span, |_| 0).boxed()
+    }
+
+    async fn list_with_delimiter(&self, prefix: Option<&Path>) -> Result<ListResult> {
+        let span = self
+            .instrumentation
+            .span(Operation::ListWithDelimiter, prefix);
+        let fut = self.inner.list_with_delimiter(prefix);
+        self.instrumentation
+            .perform(Operation::ListWithDelimiter, span, |_| 0, fut)
+            .await
+    }
+
+    async fn copy(&self, from: &Path, to: &Path) -> Result<()> {
+        let span = self.span(Operation::Copy, from);
+        let fut = self.inner.copy(from, to);
+        self.instrumentation
+            .perform(Operation::Copy, span, |_| 0, fut)
+            .await
+    }
+
+    async fn rename(&self, from: &Path, to: &Path) -> Result<()> {
+        let span = self.span(Operation::Rename, from);
+        let fut = self.inner.rename(from, to);
+        self.instrumentation
+            .perform(Operation::Rename, span, |_| 0, fut)
+            .await
+    }
+
+    async fn copy_if_not_exists(&self, from: &Path, to: &Path) -> Result<()> {
+        let span = self.span(Operation::CopyIfNotExists, from);
+        let fut = self.inner.copy_if_not_exists(from, to);
+        self.instrumentation
+            .perform(Operation::CopyIfNotExists, span, |_| 0, fut)
+            .await
+    }
+
+    async fn rename_if_not_exists(&self, from: &Path, to: &Path) -> Result<()> {
+        let span = self.span(Operation::RenameIfNotExists, from);
+        let fut = self.inner.rename_if_not_exists(from, to);
+        self.instrumentation
+            .perform(Operation::RenameIfNotExists, span, |_| 0, fut)
+            .await
+    }
+
+    async fn compose(&self, sources: &[Path], dest: &Path) -> Result<PutResult> {
+        let span = self.span(Operation::Compose, dest);
+        let fut = self.inner.compose(sources, dest);
+        self.instrumentation
+            .perform(Operation::Compose, span, |_| 0, fut)
+            .await
+    }
+}
+
+/// The state shared by an [`InstrumentedStore`] and the uploads it creates
+#[derive(Debug)]
+struct Instrumentation {
+    name: Arc<str>,
+    sink: Arc<dyn MetricsSink>,
+}
+
+impl Instrumentation {
+    fn span(&self, operation: Operation, location: Option<&Path>) -> Span {
+        let span = tracing::info_span!(
+            "object_store",
+            store = %self.name,
+            operation = operation.as_str(),
+            path = field::Empty,
+            range = field::Empty,
+        );
+        if let Some(location) = location {
+            span.record("path", field::display(location));
+        }
+        span
+    }
+
+    /// Performs `fut` within `span`, recording the bytes returned by `bytes`
+    async fn perform<R>(
+        &self,
+        operation: Operation,
+        span: Span,
+        bytes: impl FnOnce(&R) -> u64,
+        fut: impl Future<Output = Result<R>>,
+    ) -> Result<R> {
+        let mut recorder = self.recorder(operation);
+        let r = fut.instrument(span).await;
+        match &r {
+            Ok(v) => recorder.bytes += bytes(v),
+            Err(e) => recorder.set_error(e),
+        }
+        r
+    }
+
+    fn recorder(&self, operation: Operation) -> Recorder {
+        Recorder {
+            sink: Arc::clone(&self.sink),
+            store: Arc::clone(&self.name),
+            operation,
+            start: Instant::now(),
+            bytes: 0,
+            error: None,
+        }
+    }
+}
+
+/// Records the metrics of an operation in a [`MetricsSink`] when dropped
+#[derive(Debug)]
+struct Recorder {
+    sink: Arc<dyn MetricsSink>,
+    store: Arc<str>,
+    operation: Operation,
+    start: Instant,
+    bytes: u64,
+    error: Option<ErrorKind>,
+}
+
+impl Recorder {
+    fn set_error(&mut self, e: &Error) {
+        self.error = Some(e.into());
+    }
+}
+
+impl Drop for Recorder {
+    fn drop(&mut self) {
+        self.sink.record(&OperationMetrics {
+            store: Arc::clone(&self.store),
+            operation: self.operation,
+            duration: self.start.elapsed(),
+            bytes: self.bytes,
+            error: self.error,
+        })
+    }
+}
+
+/// A [`Stream`] recording the bytes returned by `measure` for each item yielded
+struct InstrumentedStream<S, T> {
+    stream: S,
+    recorder: Recorder,
+    span: Span,
+    measure: fn(&T) -> u64,
+}
+
+impl<S, T> InstrumentedStream<S, T> {
+    fn new(stream: S, recorder: Recorder, span: Span, measure: fn(&T) -> u64) -> Self {
+        Self {
+            stream,
+            recorder,
+            span,
+            measure,
+        }
+    }
+}
+
+impl<S, T> Stream for InstrumentedStream<S, T>
+where
+    S: Stream<Item = Result<T>> + Unpin,
+{
+    type Item = Result<T>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let this = self.get_mut();
+        let _guard = this.span.enter();
+        let r = Pin::new(&mut this.stream).poll_next(cx);
+        match &r {
+            Poll::Ready(Some(Ok(v))) => this.recorder.bytes += (this.measure)(v),
+            Poll::Ready(Some(Err(e))) => this.recorder.set_error(e),
+            _ => {}
+        }
+        r
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.stream.size_hint()
+    }
+}
+
+/// A [`MultipartUpload`] wrapper recording the metrics of each part uploaded
+#[derive(Debug)]
+struct InstrumentedUpload {
+    upload: Box<dyn MultipartUpload>,
+    location: Path,
+    instrumentation: Arc<Instrumentation>,
+}
+
+#[async_trait]
+impl MultipartUpload for InstrumentedUpload {
+    fn put_part(&mut self, data: PutPayload) -> UploadPart {
+        let span = self
+            .instrumentation
+            .span(Operation::PutPart, Some(&self.location));
+        let mut recorder = self.instrumentation.recorder(Operation::PutPart);
+        recorder.bytes = data.content_length() as u64;
+        let fut = self.upload.put_part(data);
+        Box::pin(
+            async move {
+                let r = fut.await;
+                if let Err(e) = &r {
+                    recorder.set_error(e);
+                }
+                r
+            }
+            .instrument(span),
+        )
+    }
+
+    async fn complete(&mut self) -> Result<PutResult> {
+        let operation = Operation::CompleteMultipart;
+        let span = self.instrumentation.span(operation, Some(&self.location));
+        let fut = self.upload.complete();
+        self.instrumentation
+            .perform(operation, span, |_| 0, fut)
+            .await
+    }
+
+    async fn abort(&mut self) -> Result<()> {
+        let operation = Operation::AbortMultipart;
+        let span = self.instrumentation.span(operation, Some(&self.location));
+        let fut = self.upload.abort();
+        self.instrumentation
+            .perform(operation, span, |_| 0, fut)
+            .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::integration::*;
+    use crate::memory::InMemory;
+    use crate::WriteMultipart;
+
+    /// THIS TEST IS ARAS ONLY
+    #[tokio::test]
+    async fn instrumented_test() {
+        let metrics = Arc::new(StoreMetrics::new());
+        let integration = InstrumentedStore::new(InMemory::new(), metrics);
+
+        put_get_delete_list(&integration).await;
+        get_opts(&integration).await;
+        list_uses_directories_correctly(&integration).await;
+        list_with_delimiter(&integration).await;
+        rename_and_copy(&integration).await;
+        stream_get(&integration).await;
+    }
+
+    /// THIS TEST IS ARAS ONLY
+    #[tokio::test]
+    async fn test_metrics() {
+        let metrics = Arc::new(StoreMetrics::new());
+        let sink = Arc::<StoreMetrics>::clone(&metrics);
+        let store = InstrumentedStore::new(InMemory::new(), sink).with_name("memory");
+
+        let location = Path::from("data");
+        store.put(&location, "0123456789".into()).await.unwrap();
+        let put = metrics.get(Operation::Put);
+        assert_eq!((put.count, put.bytes), (1, 10));
+
+        // Streamed payloads are recorded once consumed
+        let result = store.get(&location).await.unwrap();
+        assert_eq!(metrics.get(Operation::Get).count, 0);
+        result.bytes().await.unwrap();
+        let get = metrics.get(Operation::Get);
+        assert_eq!((get.count, get.bytes), (1, 10));
+
+        store.get_range(&location, 2..5).await.unwrap();
+        store.get_ranges(&location, &[0..1, 4..8]).await.unwrap();
+        assert_eq!(metrics.get(Operation::GetRange).bytes, 3);
+        assert_eq!(metrics.get(Operation::GetRanges).bytes, 5);
+
+        let mut upload = WriteMultipart::new(store.put_multipart(&location).await.unwrap());
+        upload.put("abcdef".into());
+        upload.finish().await.unwrap();
+        let parts = metrics.get(Operation::PutPart);
+        assert_eq!((parts.count, parts.bytes), (1, 6));
+        assert_eq!(metrics.get(Operation::CompleteMultipart).count, 1);
+
+        // Errors are recorded by kind
+        let missing = Path::from("missing");
+        store.head(&missing).await.unwrap_err();
+        store.get(&missing).await.unwrap_err();
+        let head = metrics.get(Operation::Head);
+        assert_eq!(head.errors.get(&ErrorKind::NotFound), Some(&1));
+        let get = metrics.get(Operation::Get);
+        assert_eq!(
+            (get.count, get.errors.get(&ErrorKind::NotFound)),
+            (2, Some(&1))
+        );
+
+        let listed = store.list(None).count().await;
+        assert_eq!(listed, 1);
+        assert_eq!(metrics.get(Operation::List).count, 1);
+
+        let locations = futures::stream::iter([Ok(location), Ok(missing)]).boxed();
+        let deleted = store.delete_stream(locations).count().await;
+        assert_eq!(deleted, 2);
+        assert_eq!(metrics.get(Operation::Delete).count, 2);
+    }
+}
@@ -25,17 +25,20 @@
 //! They are intended solely for testing purposes.
 
 use crate::list::{PaginatedListOptions, PaginatedListStore};
-use crate::multipart::MultipartStore;
+use crate::multipart::{abort_stale_multipart_uploads, MultipartStore};
 use crate::path::Path;
+use crate::version::VersionedStore;
 use crate::{
-    Attribute, Attributes, DynObjectStore, Error, GetOptions, GetRange, MultipartUpload,
-    ObjectStore, PutMode, PutPayload, UpdateVersion, WriteMultipart,
+    Attribute, Attributes, DeleteOptions, DynObjectStore, Error, GetOptions, GetRange,
+    MultipartUpload, ObjectStore, PutMode, PutMultipartOpts, PutPayload, TagSet, TaggingStore,
+    UpdateVersion, WriteMultipart,
 };
 use bytes::Bytes;
 use futures::stream::FuturesUnordered;
 use futures::{StreamExt, TryStreamExt};
 use rand::{rng, Rng};
 use std::collections::HashSet;
+use std::time::Duration;
 
 pub(crate) async fn flatten_list_stream(
     storage: &DynObjectStore,
@@ -1095,6 +1098,50 @@ pub async fn multipart(storage: &dyn ObjectStore, multipart: &dyn MultipartStore
     assert_eq!(meta.size, 0);
 }
 
+/// THIS FUNCTION IS ARAS ONLY
+///
+/// Tests listing the incomplete uploads of a [`MultipartStore`] and their parts
+pub async fn list_multipart_uploads(multipart: &dyn MultipartStore) {
+    let prefix = Path::from("test_list_multipart");
+    let path = prefix.child("upload");
+    let chunk_size = 5 * 1024 * 1024;
+
+    let id = multipart.create_multipart(&path).await.unwrap();
+    let mut put = Vec::new();
+    for (idx, chunk) in get_chunks(chunk_size, 2).into_iter().enumerate() {
+        let part = multipart.put_part(&path, &id, idx, chunk.into()).await;
+        put.push(part.unwrap());
+    }
+
+    let listed = |multipart: &dyn MultipartStore| {
+        let uploads = multipart.list_multipart_uploads(Some(&prefix));
+        uploads.try_collect::<Vec<_>>()
+    };
+    let uploads = listed(multipart).await.unwrap();
+    let upload = uploads.iter().find(|u| u.location == path && u.id == id);
+    assert!(upload.unwrap().initiated.is_some());
+
+    let parts = multipart.list_parts(&path, &id).await.unwrap();
+    assert_eq!(parts.len(), 2);
+    for (idx, (part, put)) in parts.iter().zip(&put).enumerate() {
+        assert_eq!(part.part_idx, idx);
+        assert_eq!(part.size, chunk_size as u64);
+        assert_eq!(part.part_id.content_id, put.content_id);
+    }
+
+    // Recently initiated uploads are not stale
+    let day = Duration::from_secs(24 * 60 * 60);
+    abort_stale_multipart_uploads(multipart, Some(&prefix), day)
+        .await
+        .unwrap();
+    let uploads = listed(multipart).await.unwrap();
+    assert!(uploads.iter().any(|u| u.location == path && u.id == id));
+
+    multipart.abort_multipart(&path, &id).await.unwrap();
+    let uploads = listed(multipart).await.unwrap();
+    assert!(!uploads.iter().any(|u| u.location == path && u.id == id));
+}
+
 async fn delete_fixtures(storage: &DynObjectStore) {
     let paths = storage.list(None).map_ok(|meta| meta.location).boxed();
     storage
@@ -1225,6 +1272,270 @@ pub async fn multipart_out_of_order(storage: &dyn ObjectStore) {
     assert_eq!(bytes, full);
 }
 
+/// THIS FUNCTION IS ARAS ONLY
+///
+/// Tests [`VersionedStore`] for stores that record delete markers
+pub async fn versions(storage: &dyn VersionedStore) {
+    let prefix = Path::from("versions");
+    let path = prefix.child("object");
+
+    let existing: Vec<_> = storage
+        .list_versions(Some(&prefix))
+        .try_collect()
+        .await
+        .unwrap();
+    for v in existing {
+        let version = v.meta.version.unwrap();
+        storage
+            .delete_version(&v.meta.location, &version)
+            .await
+            .unwrap();
+    }
+
+    let v1 = storage
+        .put(&path, "v1".into())
+        .await
+        .unwrap()
+        .version
+        .unwrap();
+    let v2 = storage
+        .put(&path, "v2".into())
+        .await
+        .unwrap()
+        .version
+        .unwrap();
+    storage.delete(&path).await.unwrap();
+
+    let err = storage.head(&path).await.unwrap_err();
+    assert!(matches!(err, Error::NotFound { .. }), "{err}");
+
+    let listed: Vec<_> = storage
+        .list_versions(Some(&prefix))
+        .try_collect()
+        .await
+        .unwrap();
+    assert_eq!(listed.len(), 3);
+    assert!(listed[0].is_latest && listed[0].is_delete_marker);
+    assert!(!listed[1].is_latest && !listed[1].is_delete_marker);
+    assert_eq!(listed[1].meta.version.as_ref(), Some(&v2));
+    assert_eq!(listed[2].meta.version.as_ref(), Some(&v1));
+    assert_eq!(listed[2].meta.size, 2);
+
+    let meta = storage.head_version(&path, &v1).await.unwrap();
+    assert_eq!(meta.version.as_ref(), Some(&v1));
+    assert_eq!(meta.size, 2);
+
+    let options = GetOptions {
+        version: Some(v1.clone()),
+        ..Default::default()
+    };
+    let b = storage
+        .get_opts(&path, options)
+        .await
+        .unwrap()
+        .bytes()
+        .await
+        .unwrap();
+    assert_eq!(b.as_ref(), b"v1");
+
+    // Restore the first version
+    storage.copy_version(&path, &v1, &path).await.unwrap();
+    let b = storage.get(&path).await.unwrap().bytes().await.unwrap();
+    assert_eq!(b.as_ref(), b"v1");
+
+    // Deleting the current version and the delete marker makes v2 current
+    let listed: Vec<_> = storage
+        .list_versions(Some(&prefix))
+        .try_collect()
+        .await
+        .unwrap();
+    assert_eq!(listed.len(), 4);
+    for v in &listed[..2] {
+        let version = v.meta.version.as_ref().unwrap();
+        storage.delete_version(&path, version).await.unwrap();
+    }
+    let b = storage.get(&path).await.unwrap().bytes().await.unwrap();
+    assert_eq!(b.as_ref(), b"v2");
+
+    for version in [v1, v2] {
+        storage.delete_version(&path, &version).await.unwrap();
+    }
+    let listed: Vec<_> = storage
+        .list_versions(Some(&prefix))
+        .try_collect()
+        .await
+        .unwrap();
+    assert!(listed.is_empty());
+    let err = storage.head(&path).await.unwrap_err();
+    assert!(matches!(err, Error::NotFound { .. }), "{err}");
+}
+
+/// THIS FUNCTION IS ARAS ONLY
+///
+/// Tests [`ObjectStore::append`] and appending multipart uploads
+pub async fn append(storage: &dyn ObjectStore) {
+    let path = Path::from("append");
+    let _ = storage.delete(&path).await;
+
+    storage.append(&path, "foo".into()).await.unwrap();
+    storage.append(&path, "bar".into()).await.unwrap();
+    let b = storage.get(&path).await.unwrap().bytes().await.unwrap();
+    assert_eq!(b.as_ref(), b"foobar");
+
+    let opts = PutMultipartOpts {
+        mode: PutMode::Append,
+        ..Default::default()
+    };
+    let mut upload = storage.put_multipart_opts(&path, opts).await.unwrap();
+    upload.put_part("baz".into()).await.unwrap();
+    upload.put_part("qux".into()).await.unwrap();
+    upload.complete().await.unwrap();
+    let b = storage.get(&path).await.unwrap().bytes().await.unwrap();
+    assert_eq!(b.as_ref(), b"foobarbazqux");
+
+    // A regular put replaces the appended object
+    storage.put(&path, "new".into()).await.unwrap();
+    let b = storage.get(&path).await.unwrap().bytes().await.unwrap();
+    assert_eq!(b.as_ref(), b"new");
+
+    storage.delete(&path).await.unwrap();
+}
+
+/// THIS FUNCTION IS ARAS ONLY
+///
+/// Tests conditional deletes with [`ObjectStore::delete_opts`]
+pub async fn delete_opts(storage: &dyn ObjectStore) {
+    let path = Path::from("conditional_delete");
+    let _ = storage.delete(&path).await;
+
+    let v1 = storage.put(&path, "a".into()).await.unwrap();
+    let v2 = storage.put(&path, "bb".into()).await.unwrap();
+
+    let stale = DeleteOptions {
+        if_match: v1.e_tag,
+        version: v1.version,
+        ..Default::default()
+    };
+    let err = storage.delete_opts(&path, stale.clone()).await.unwrap_err();
+    assert!(matches!(err, Error::Precondition { .. }), "{err}");
+
+    let current = UpdateVersion::from(v2);
+    storage.delete_opts(&path, current.into()).await.unwrap();
+    let err = storage.head(&path).await.unwrap_err();
+    assert!(matches!(err, Error::NotFound { .. }), "{err}");
+
+    // A conditional delete of a missing object fails
+    let err = storage.delete_opts(&path, stale).await.unwrap_err();
+    assert!(matches!(err, Error::Precondition { .. }), "{err}");
+}
+
+/// THIS FUNCTION IS ARAS ONLY
+///
+/// Tests concatenating objects with [`ObjectStore::compose`]
+pub async fn compose(storage: &dyn ObjectStore) {
+    let a = Path::from("compose/a");
+    let b = Path::from("compose/b");
+    let c = Path::from("compose/c");
+    let dest = Path::from("compose/dest");
+
+    let large = get_chunk(6 * 1024 * 1024);
+    storage.put(&a, "foo".into()).await.unwrap();
+    storage.put(&b, PutPayload::default()).await.unwrap();
+    storage.put(&c, large.clone().into()).await.unwrap();
+
+    let sources = [a.clone(), b.clone(), c.clone(), a.clone()];
+    storage.compose(&sources, &dest).await.unwrap();
+    let data = storage.get(&dest).await.unwrap().bytes().await.unwrap();
+    let expected = [b"foo".as_slice(), &large, b"foo"].concat();
+    assert_eq!(data.len(), expected.len());
+    assert!(data == expected);
+
+    // The destination may also be a source
+    storage
+        .compose(&[dest.clone(), a.clone()], &dest)
+        .await
+        .unwrap();
+    let data = storage.get(&dest).await.unwrap().bytes().await.unwrap();
+    assert_eq!(data.len(), expected.len() + 3);
+    assert_eq!(&data[expected.len()..], b"foo");
+
+    // A missing source fails, leaving the destination unchanged
+    let missing = Path::from("compose/missing");
+    let err = storage
+        .compose(&[a.clone(), missing], &dest)
+        .await
+        .unwrap_err();
+    assert!(matches!(err, Error::NotFound { .. }), "{err}");
+    let meta = storage.head(&dest).await.unwrap();
+    assert_eq!(meta.size, expected.len() as u64 + 3);
+
+    // Composing no sources creates an empty object
+    storage.compose(&[], &dest).await.unwrap();
+    let data = storage.get(&dest).await.unwrap().bytes().await.unwrap();
+    assert!(data.is_empty());
+
+    for path in [a, b, c, dest] {
+        storage.delete(&path).await.unwrap();
+    }
+}
+
+/// THIS FUNCTION IS ARAS ONLY
+///
+/// Tests reading and updating tags with [`TaggingStore`]
+pub async fn tags(storage: &dyn TaggingStore) {
+    let tag_set = TagSet::from_iter([("foo.com=bar/s", "bananas"), ("key", "value with a space")]);
+
+    let path = Path::from("tags");
+    storage
+        .put_opts(&path, "test".into(), tag_set.clone().into())
+        .await
+        .unwrap();
+    let tags = storage.get_tags(&path).await.unwrap();
+    assert_eq!(tags.len(), 2);
+    assert_eq!(tags.get("foo.com=bar/s").unwrap(), "bananas");
+    assert_eq!(tags.get("key").unwrap(), "value with a space");
+
+    let multi_path = Path::from("tags_multi");
+    let mut upload = storage
+        .put_multipart_opts(&multi_path, tag_set.clone().into())
+        .await
+        .unwrap();
+    upload.put_part("foo".into()).await.unwrap();
+    upload.complete().await.unwrap();
+    let tags = storage.get_tags(&multi_path).await.unwrap();
+    assert_eq!(tags.get("key").unwrap(), "value with a space");
+
+    // Overwriting without tags clears them
+    storage.put(&path, "test".into()).await.unwrap();
+    assert!(storage.get_tags(&path).await.unwrap().is_empty());
+
+    // Tags can be updated without rewriting the object
+    let updated = TagSet::from_iter([("retention", "short")]);
+    storage.put_tags(&path, updated.clone()).await.unwrap();
+    assert_eq!(storage.get_tags(&path).await.unwrap(), updated);
+    let b = storage.get(&path).await.unwrap().bytes().await.unwrap();
+    assert_eq!(b.as_ref(), b"test");
+
+    // Putting tags replaces rather than merges
+    storage
+        .put_tags(&multi_path, updated.clone())
+        .await
+        .unwrap();
+    assert_eq!(storage.get_tags(&multi_path).await.unwrap(), updated);
+
+    storage.delete_tags(&path).await.unwrap();
+    assert!(storage.get_tags(&path).await.unwrap().is_empty());
+
+    let missing = Path::from("missing");
+    let err = storage.get_tags(&missing).await.unwrap_err();
+    assert!(matches!(err, Error::NotFound { .. }), "{err}");
+    let err = storage.put_tags(&missing, updated).await.unwrap_err();
+    assert!(matches!(err, Error::NotFound { .. }), "{err}");
+
+    storage.delete(&path).await.unwrap();
+    storage.delete(&multi_path).await.unwrap();
+}
+
 /// Tests [`PaginatedListStore`]
 pub async fn list_paginated(storage: &dyn ObjectStore, list: &dyn PaginatedListStore) {
     delete_fixtures(storage).await;
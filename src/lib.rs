@@ -119,6 +119,11 @@
 //!
 //! * Rate Throttling: [`ThrottleConfig`](throttle::ThrottleConfig)
 //! * Concurrent Request Limit: [`LimitStore`](limit::LimitStore)
+//! * Read-through Caching: [`CachingStore`](cache::CachingStore)
+//! * Metrics and Tracing: [`InstrumentedStore`](instrument::InstrumentedStore)
+//! * Client-side Encryption: [`EncryptedStore`](encrypt::EncryptedStore)
+//! * Transparent Compression: [`CompressedStore`](compress::CompressedStore)
+//! * Path-based Routing: [`MountStore`](mount::MountStore)
 //!
 //! # Configuration System
 //!
@@ -511,31 +516,40 @@ pub mod aws;
 #[cfg(feature = "azure")]
 pub mod azure;
 pub mod buffered;
+pub mod cache;
 #[cfg(not(target_arch = "wasm32"))]
 pub mod chunked;
+#[cfg(feature = "compression")]
+pub mod compress;
 pub mod delimited;
+#[cfg(feature = "cloud")]
+pub mod encrypt;
 #[cfg(feature = "gcp")]
 pub mod gcp;
 #[cfg(feature = "http")]
 pub mod http;
+pub mod instrument;
 pub mod limit;
 #[cfg(all(feature = "fs", not(target_arch = "wasm32")))]
 pub mod local;
 pub mod memory;
+pub mod mount;
 pub mod path;
 pub mod prefix;
 pub mod registry;
 #[cfg(feature = "cloud")]
 pub mod signer;
 pub mod throttle;
+pub mod transfer;
+pub mod version;
 
 #[cfg(feature = "cloud")]
 pub mod client;
 
 #[cfg(feature = "cloud")]
 pub use client::{
-    backoff::BackoffConfig, retry::RetryConfig, ClientConfigKey, ClientOptions, CredentialProvider,
-    StaticCredentialProvider,
+    backoff::BackoffConfig, copy::CopyConfig, retry::RetryConfig, ClientConfigKey, ClientOptions,
+    CredentialProvider, StaticCredentialProvider,
 };
 
 #[cfg(all(feature = "cloud", not(target_arch = "wasm32")))]
@@ -546,7 +560,7 @@ mod config;
 
 mod tags;
 
-pub use tags::TagSet;
+pub use tags::{TagSet, TaggingStore};
 
 pub mod list;
 pub mod multipart;
@@ -565,7 +579,10 @@ pub use attributes::*;
 pub use parse::{parse_url, parse_url_opts, ObjectStoreScheme};
 pub use payload::*;
 pub use upload::*;
-pub use util::{coalesce_ranges, collect_bytes, GetRange, OBJECT_STORE_COALESCE_DEFAULT};
+pub use util::{
+    coalesce_ranges, coalesce_ranges_opts, collect_bytes, GetRange, RangeCoalescePolicy,
+    OBJECT_STORE_COALESCE_DEFAULT,
+};
 
 use crate::path::Path;
 #[cfg(all(feature = "fs", not(target_arch = "wasm32")))]
@@ -607,6 +624,17 @@ pub trait ObjectStore: std::fmt::Display + Send + Sync + Debug + 'static {
         opts: PutOptions,
     ) -> Result<PutResult>;
 
+    /// THIS METHOD IS ARAS ONLY
+    ///
+    /// Append the provided `payload` to the object at `location`, creating the
+    /// object if it does not already exist
+    ///
+    /// See [`PutMode::Append`] for the guarantees provided by each store
+    async fn append(&self, location: &Path, payload: PutPayload) -> Result<PutResult> {
+        self.put_opts(location, payload, PutMode::Append.into())
+            .await
+    }
+
     /// Perform a multipart upload
     ///
     /// Client should prefer [`ObjectStore::put`] for small payloads, as streaming uploads
@@ -661,6 +689,21 @@ pub trait ObjectStore: std::fmt::Display + Send + Sync + Debug + 'static {
         .await
     }
 
+    /// THIS METHOD IS ARAS ONLY
+    ///
+    /// Return the bytes that are stored at the specified location
+    /// in the given byte ranges, requested according to `policy`
+    ///
+    /// See [`coalesce_ranges_opts`] for how `policy` is applied
+    async fn get_ranges_opts(
+        &self,
+        location: &Path,
+        ranges: &[Range<u64>],
+        policy: RangeCoalescePolicy,
+    ) -> Result<Vec<Bytes>> {
+        coalesce_ranges_opts(ranges, |range| self.get_range(location, range), policy).await
+    }
+
     /// Return the metadata for the specified location
     async fn head(&self, location: &Path) -> Result<ObjectMeta> {
         let options = GetOptions {
@@ -673,6 +716,20 @@ pub trait ObjectStore: std::fmt::Display + Send + Sync + Debug + 'static {
     /// Delete the object at the specified location.
     async fn delete(&self, location: &Path) -> Result<()>;
 
+    /// THIS METHOD IS ARAS ONLY
+    ///
+    /// Delete the object at the specified location with the given options
+    ///
+    /// If the preconditions in `opts` are not satisfied, including if the object does
+    /// not exist, returns [`Error::Precondition`]. The default implementation returns
+    /// [`Error::NotImplemented`] if any preconditions are specified
+    async fn delete_opts(&self, location: &Path, opts: DeleteOptions) -> Result<()> {
+        if opts.if_match.is_some() || opts.version.is_some() {
+            return Err(Error::NotImplemented);
+        }
+        self.delete(location).await
+    }
+
     /// Delete all the objects at the specified locations
     ///
     /// When supported, this method will use bulk operations that delete more
@@ -797,6 +854,21 @@ pub trait ObjectStore: std::fmt::Display + Send + Sync + Debug + 'static {
         self.copy_if_not_exists(from, to).await?;
         self.delete(from).await
     }
+
+    /// THIS METHOD IS ARAS ONLY
+    ///
+    /// Create an object at `dest` whose contents are the concatenation of the
+    /// objects at `sources`, in order.
+    ///
+    /// If there exists an object at the destination, it will be overwritten. `dest`
+    /// may also be one of the `sources`, allowing an object to be appended to.
+    ///
+    /// Where supported, the concatenation is performed server-side without downloading
+    /// the source objects. The default implementation returns [`Error::NotImplemented`]
+    async fn compose(&self, sources: &[Path], dest: &Path) -> Result<PutResult> {
+        let _ = (sources, dest);
+        Err(Error::NotImplemented)
+    }
 }
 
 macro_rules! as_ref_impl {
@@ -816,6 +888,10 @@ macro_rules! as_ref_impl {
                 self.as_ref().put_opts(location, payload, opts).await
             }
 
+            async fn append(&self, location: &Path, payload: PutPayload) -> Result<PutResult> {
+                self.as_ref().append(location, payload).await
+            }
+
             async fn put_multipart(&self, location: &Path) -> Result<Box<dyn MultipartUpload>> {
                 self.as_ref().put_multipart(location).await
             }
@@ -848,6 +924,18 @@ macro_rules! as_ref_impl {
                 self.as_ref().get_ranges(location, ranges).await
             }
 
+            /// THIS METHOD IS ARAS ONLY
+            async fn get_ranges_opts(
+                &self,
+                location: &Path,
+                ranges: &[Range<u64>],
+                policy: RangeCoalescePolicy,
+            ) -> Result<Vec<Bytes>> {
+                self.as_ref()
+                    .get_ranges_opts(location, ranges, policy)
+                    .await
+            }
+
             async fn head(&self, location: &Path) -> Result<ObjectMeta> {
                 self.as_ref().head(location).await
             }
@@ -856,6 +944,10 @@ macro_rules! as_ref_impl {
                 self.as_ref().delete(location).await
             }
 
+            async fn delete_opts(&self, location: &Path, opts: DeleteOptions) -> Result<()> {
+                self.as_ref().delete_opts(location, opts).await
+            }
+
             fn delete_stream<'a>(
                 &'a self,
                 locations: BoxStream<'a, Result<Path>>,
@@ -894,6 +986,10 @@ macro_rules! as_ref_impl {
             async fn rename_if_not_exists(&self, from: &Path, to: &Path) -> Result<()> {
                 self.as_ref().rename_if_not_exists(from, to).await
             }
+
+            async fn compose(&self, sources: &[Path], dest: &Path) -> Result<PutResult> {
+                self.as_ref().compose(sources, dest).await
+            }
         }
     };
 }
@@ -919,9 +1015,14 @@ pub struct ObjectMeta {
     pub location: Path,
     /// The last modified time
     pub last_modified: DateTime<Utc>,
+    /// THIS FIELD IS COMMON, MODIFIED BY ARAS
+    ///
     /// The size in bytes of the object.
     ///
     /// Note this is not `usize` as `object_store` supports 32-bit architectures such as WASM
+    ///
+    /// Stores transforming the data they store, such as `CompressedStore`, may report the
+    /// size of the transformed data when listing, and of the original data otherwise
     pub size: u64,
     /// The unique identifier for the object
     ///
@@ -1140,12 +1241,39 @@ pub enum PutMode {
     /// Perform an atomic write operation if the current version of the object matches the
     /// provided [`UpdateVersion`], returning [`Error::Precondition`] otherwise
     Update(UpdateVersion),
+    /// THIS VARIANT IS ARAS ONLY
+    ///
+    /// Append the payload to the object at the provided path, creating it if no
+    /// object exists yet
+    ///
+    /// This maps onto the native append primitive of each store:
+    ///
+    /// * [`LocalFileSystem`](local::LocalFileSystem) stages a copy of the existing file
+    ///   and atomically renames it into place
+    /// * `InMemory` appends in place
+    /// * Azure uses [Append Blobs], and so cannot append to an existing block blob
+    /// * S3 Express One Zone uses [write offsets], other S3 buckets return [`Error::NotSupported`]
+    /// * GCS uploads the payload to a temporary object and [composes] it onto the existing one
+    ///
+    /// Stores that have no equivalent return [`Error::NotSupported`]
+    ///
+    /// [Append Blobs]: https://learn.microsoft.com/en-us/rest/api/storageservices/append-block
+    /// [write offsets]: https://docs.aws.amazon.com/AmazonS3/latest/userguide/directory-buckets-objects-append.html
+    /// [composes]: https://cloud.google.com/storage/docs/composing-objects
+    Append,
 }
 
+/// THIS STRUCT IS COMMON, MODIFIED BY ARAS
+///
 /// Uniquely identifies a version of an object to update
 ///
 /// Stores will use differing combinations of `e_tag` and `version` to provide conditional
-/// updates, and it is therefore recommended applications preserve both
+/// updates, and it is therefore recommended applications preserve both:
+///
+/// * S3, Azure, HTTP, `InMemory` and [`LocalFileSystem`](local::LocalFileSystem) require `e_tag`
+/// * GCS requires `version`
+///
+/// Conditional deletes, where supported, use the same fields, see [`DeleteOptions`]
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct UpdateVersion {
     /// The unique identifier for the newly created object
@@ -1165,8 +1293,6 @@ impl From<PutResult> for UpdateVersion {
     }
 }
 
-/// THIS STRUCT IS COMMON, MODIFIED BY ARAS
-///
 /// Options for a put request
 #[derive(Debug, Clone, Default)]
 pub struct PutOptions {
@@ -1187,33 +1313,23 @@ pub struct PutOptions {
     ///
     /// They are also eclused from [`PartialEq`] and [`Eq`].
     pub extensions: ::http::Extensions,
-    /// THIS FIELD IS ARAS ONLY
-    ///
-    /// Copy file and append to it during checkpointing
-    pub copy_and_append: bool,
 }
 
 impl PartialEq<Self> for PutOptions {
-    /// THIS METHOD IS COMMON, MODIFIED BY ARAS
     fn eq(&self, other: &Self) -> bool {
         let Self {
             mode,
             tags,
             attributes,
             extensions: _,
-            copy_and_append,
         } = self;
         let Self {
             mode: other_mode,
             tags: other_tags,
             attributes: other_attributes,
             extensions: _,
-            copy_and_append: other_copy_and_append,
         } = other;
-        (mode == other_mode)
-            && (tags == other_tags)
-            && (attributes == other_attributes)
-            && (copy_and_append == other_copy_and_append)
+        (mode == other_mode) && (tags == other_tags) && (attributes == other_attributes)
     }
 }
 
@@ -1268,8 +1384,11 @@ pub struct PutMultipartOpts {
     pub extensions: ::http::Extensions,
     /// THIS FIELD IS ARAS ONLY
     ///
-    /// Copy file and append to it during checkpointing
-    pub copy_and_append: bool,
+    /// Configure the [`PutMode`] for this operation
+    ///
+    /// Only [`PutMode::Overwrite`] and [`PutMode::Append`] are supported, other modes
+    /// return [`Error::NotImplemented`]
+    pub mode: PutMode,
 }
 
 impl PartialEq<Self> for PutMultipartOpts {
@@ -1279,17 +1398,15 @@ impl PartialEq<Self> for PutMultipartOpts {
             tags,
             attributes,
             extensions: _,
-            copy_and_append,
+            mode,
         } = self;
         let Self {
             tags: other_tags,
             attributes: other_attributes,
             extensions: _,
-            copy_and_append: other_copy_and_append,
+            mode: other_mode,
         } = other;
-        (tags == other_tags)
-            && (attributes == other_attributes)
-            && (copy_and_append == other_copy_and_append)
+        (tags == other_tags) && (attributes == other_attributes) && (mode == other_mode)
     }
 }
 
@@ -1313,6 +1430,47 @@ impl From<Attributes> for PutMultipartOpts {
     }
 }
 
+/// THIS STRUCT IS ARAS ONLY
+///
+/// Options for [`ObjectStore::delete_opts`]
+///
+/// Like [`UpdateVersion`], stores use differing combinations of `if_match` and `version`
+/// to provide conditional deletes, and it is therefore recommended applications
+/// provide both. Stores return [`Error::NotImplemented`] if the provided combination
+/// is not supported:
+///
+/// * S3 and Azure require `if_match`, and ignore `version`
+/// * GCS requires `version`, and ignores `if_match`
+/// * [`LocalFileSystem`](local::LocalFileSystem) requires `if_match`, and does not support `version`
+/// * `InMemory` checks both against the object's e-tag
+/// * Other stores do not support conditional deletes
+#[derive(Debug, Clone, Default)]
+pub struct DeleteOptions {
+    /// Delete the object only if its [`ObjectMeta::e_tag`] matches, otherwise
+    /// returning [`Error::Precondition`]
+    ///
+    /// <https://datatracker.ietf.org/doc/html/rfc9110#name-if-match>
+    pub if_match: Option<String>,
+    /// Delete the object only if its [`ObjectMeta::version`] matches, otherwise
+    /// returning [`Error::Precondition`]
+    pub version: Option<String>,
+    /// Implementation-specific extensions. Intended for use by [`ObjectStore`] implementations
+    /// that need to pass context-specific information (like tracing spans) via trait methods.
+    ///
+    /// These extensions are ignored entirely by backends offered through this crate.
+    pub extensions: ::http::Extensions,
+}
+
+impl From<UpdateVersion> for DeleteOptions {
+    fn from(value: UpdateVersion) -> Self {
+        Self {
+            if_match: value.e_tag,
+            version: value.version,
+            ..Default::default()
+        }
+    }
+}
+
 /// Result for a put request
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct PutResult {
@@ -1430,6 +1588,22 @@ pub enum Error {
         source: Box<dyn std::error::Error + Send + Sync + 'static>,
     },
 
+    /// THIS VARIANT IS ARAS ONLY
+    ///
+    /// Error when an object was written, but writing its tags with the separate request
+    /// that follows failed, as on stores without native object tags
+    #[error(
+        "Object at location {} was written, but writing its tags failed: {}",
+        path,
+        source
+    )]
+    TagsNotWritten {
+        /// The path to the file
+        path: String,
+        /// The wrapped error
+        source: Box<dyn std::error::Error + Send + Sync + 'static>,
+    },
+
     /// Error when a configuration key is invalid for the store used
     #[error("Configuration key: '{}' is not valid for store '{}'.", key, store)]
     UnknownConfigurationKey {
@@ -1499,48 +1673,21 @@ mod tests {
         assert_eq!(data, loaded);
     }
 
+    /// THIS FUNCTION IS COMMON, MODIFIED BY ARAS
     #[cfg(any(feature = "aws", feature = "azure"))]
     pub(crate) async fn tagging<F, Fut>(storage: Arc<dyn ObjectStore>, validate: bool, get_tags: F)
     where
         F: Fn(Path) -> Fut + Send + Sync,
-        Fut: std::future::Future<Output = Result<client::HttpResponse>> + Send,
+        Fut: std::future::Future<Output = Result<TagSet>> + Send,
     {
-        use bytes::Buf;
-        use serde::Deserialize;
-
-        #[derive(Deserialize)]
-        struct Tagging {
-            #[serde(rename = "TagSet")]
-            list: TagList,
-        }
-
-        #[derive(Debug, Deserialize)]
-        struct TagList {
-            #[serde(rename = "Tag")]
-            tags: Vec<Tag>,
-        }
-
-        #[derive(Debug, Deserialize, Eq, PartialEq)]
-        #[serde(rename_all = "PascalCase")]
-        struct Tag {
-            key: String,
-            value: String,
-        }
-
         let tags = vec![
-            Tag {
-                key: "foo.com=bar/s".to_string(),
-                value: "bananas/foo.com-_".to_string(),
-            },
-            Tag {
-                key: "namespace/key.foo".to_string(),
-                value: "value with a space".to_string(),
-            },
+            ("foo.com=bar/s".to_string(), "bananas/foo.com-_".to_string()),
+            (
+                "namespace/key.foo".to_string(),
+                "value with a space".to_string(),
+            ),
         ];
-        let mut tag_set = TagSet::default();
-        for t in &tags {
-            tag_set.push(&t.key, &t.value)
-        }
+        let tag_set: TagSet = tags.iter().cloned().collect();
 
         let path = Path::from("tag_test");
         storage
@@ -1569,11 +1716,12 @@ mod tests {
 
         for path in [path, multi_path, buf_path] {
             let resp = get_tags(path.clone()).await.unwrap();
-            let body = resp.into_body().bytes().await.unwrap();
-
-            let mut resp: Tagging = quick_xml::de::from_reader(body.reader()).unwrap();
-            resp.list.tags.sort_by(|a, b| a.key.cmp(&b.key));
-            assert_eq!(resp.list.tags, tags);
+            let mut resp: Vec<_> = resp
+                .iter()
+                .map(|(k, v)| (k.into_owned(), v.into_owned()))
+                .collect();
+            resp.sort();
+            assert_eq!(resp, tags);
         }
     }
 
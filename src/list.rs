@@ -18,9 +18,15 @@
 //! Paginated Listing
 
 use super::Result;
-use crate::ListResult;
+use crate::path::{Path, DELIMITER};
+use crate::{ListResult, ObjectMeta};
 use async_trait::async_trait;
 use std::borrow::Cow;
+#[cfg(feature = "http")]
+use {
+    futures::{Stream, TryStreamExt},
+    std::collections::BTreeMap,
+};
 
 /// Options for a paginated list request
 #[derive(Debug, Default, Clone)]
@@ -83,3 +89,259 @@ pub trait PaginatedListStore: Send + Sync + 'static {
         opts: PaginatedListOptions,
     ) -> Result<PaginatedListResult>;
 }
+
+/// THIS ENUM IS ARAS ONLY
+///
+/// An entry of a listing of a store without native pagination
+#[derive(Debug)]
+pub(crate) enum ListEntry {
+    /// An object
+    Object(ObjectMeta),
+    /// A common prefix, i.e. a directory
+    Prefix(Path),
+}
+
+impl ListEntry {
+    /// THIS METHOD IS ARAS ONLY
+    ///
+    /// The key this entry is ordered by, common prefixes including the trailing delimiter
+    pub(crate) fn key(&self) -> String {
+        match self {
+            Self::Object(meta) => meta.location.to_string(),
+            Self::Prefix(path) => format!("{path}{DELIMITER}"),
+        }
+    }
+}
+
+/// THIS STRUCT IS ARAS ONLY
+///
+/// A page of a listing of a store without native pagination
+struct Page {
+    result: ListResult,
+    /// The key of the last entry of the page
+    last: Option<String>,
+}
+
+impl Page {
+    /// THIS METHOD IS ARAS ONLY
+    fn new() -> Self {
+        Self {
+            result: ListResult {
+                common_prefixes: vec![],
+                objects: vec![],
+            },
+            last: None,
+        }
+    }
+
+    /// THIS METHOD IS ARAS ONLY
+    fn push(&mut self, key: String, entry: ListEntry) {
+        match entry {
+            ListEntry::Object(meta) => self.result.objects.push(meta),
+            ListEntry::Prefix(path) => self.result.common_prefixes.push(path),
+        }
+        self.last = Some(key);
+    }
+
+    /// THIS METHOD IS ARAS ONLY
+    ///
+    /// Returns the result, with a page token if `truncated`
+    fn finish(self, truncated: bool) -> PaginatedListResult {
+        let page_token = match truncated {
+            true => self.last.map(|key| encode_page_token(&key)),
+            false => None,
+        };
+        PaginatedListResult {
+            result: self.result,
+            page_token,
+        }
+    }
+}
+
+/// THIS FUNCTION IS ARAS ONLY
+///
+/// Encodes the key of the last entry of a page as an opaque page token
+fn encode_page_token(key: &str) -> String {
+    key.bytes().map(|b| format!("{b:02x}")).collect()
+}
+
+/// THIS FUNCTION IS ARAS ONLY
+fn decode_page_token(store: &'static str, token: &str) -> Result<String> {
+    let invalid = || crate::Error::Generic {
+        store,
+        source: format!("Invalid page token {token:?}").into(),
+    };
+    let bytes = (0..token.len())
+        .step_by(2)
+        .map(|i| {
+            let digits = token.get(i..i + 2).ok_or_else(invalid)?;
+            u8::from_str_radix(digits, 16).map_err(|_| invalid())
+        })
+        .collect::<Result<Vec<_>>>()?;
+    String::from_utf8(bytes).map_err(|_| invalid())
+}
+
+/// THIS FUNCTION IS ARAS ONLY
+///
+/// Returns the key that the entries of the page requested by `opts` must sort after,
+/// i.e. the greater of the [`PaginatedListOptions::offset`] and the page token
+pub(crate) fn page_start(
+    store: &'static str,
+    opts: &PaginatedListOptions,
+) -> Result<Option<String>> {
+    let token = match &opts.page_token {
+        Some(token) => Some(decode_page_token(store, token)?),
+        None => None,
+    };
+    Ok(match (opts.offset.clone(), token) {
+        (Some(offset), Some(token)) => Some(offset.max(token)),
+        (offset, token) => offset.or(token),
+    })
+}
+
+/// THIS CONSTANT IS ARAS ONLY
+///
+/// The number of entries of a page if [`PaginatedListOptions::max_keys`] is not set, for
+/// stores without native pagination, matching the default of S3
+const DEFAULT_MAX_KEYS: usize = 1000;
+
+/// THIS FUNCTION IS ARAS ONLY
+///
+/// Returns the longest directory of `prefix`, which contains all matching entries
+pub(crate) fn prefix_directory(prefix: &str) -> Result<Path> {
+    match prefix.rfind(DELIMITER) {
+        Some(idx) => Ok(Path::parse(&prefix[..idx])?),
+        None => Ok(Path::default()),
+    }
+}
+
+/// THIS FUNCTION IS ARAS ONLY
+///
+/// Returns true if `opts` requests a listing delimited by `/`, the only delimiter supported
+/// by stores without native pagination
+pub(crate) fn is_delimited(store: &'static str, opts: &PaginatedListOptions) -> Result<bool> {
+    match opts.delimiter.as_deref() {
+        None => Ok(false),
+        Some(DELIMITER) => Ok(true),
+        Some(delimiter) => Err(crate::Error::NotSupported {
+            source: format!("{store} does not support listing with delimiter {delimiter:?}").into(),
+        }),
+    }
+}
+
+/// THIS FUNCTION IS ARAS ONLY
+///
+/// Paginates `entries`, which must be returned in order of [`ListEntry::key`], for stores
+/// without native pagination, stopping once the page is full
+///
+/// Entries not matching `prefix` are skipped, as are those not after [`page_start`]. Pages
+/// hold at most [`PaginatedListOptions::max_keys`] entries, defaulting to 1000
+pub(crate) fn paginate_sorted<I>(
+    store: &'static str,
+    entries: I,
+    prefix: &str,
+    opts: &PaginatedListOptions,
+) -> Result<PaginatedListResult>
+where
+    I: IntoIterator<Item = Result<ListEntry>>,
+{
+    let start = page_start(store, opts)?;
+    let max_keys = opts.max_keys.unwrap_or(DEFAULT_MAX_KEYS).max(1);
+
+    let mut page = Page::new();
+    let mut len = 0;
+    for entry in entries {
+        let entry = entry?;
+        let key = entry.key();
+        if !key.starts_with(prefix) || start.as_ref().map(|s| key <= *s).unwrap_or(false) {
+            continue;
+        }
+        if len == max_keys {
+            return Ok(page.finish(true));
+        }
+        page.push(key, entry);
+        len += 1;
+    }
+    Ok(page.finish(false))
+}
+
+/// THIS FUNCTION IS ARAS ONLY
+///
+/// Paginates `entries`, which may be returned in any order, for stores without native
+/// pagination
+///
+/// The page contains the [`PaginatedListOptions::max_keys`], defaulting to 1000,
+/// lexicographically smallest entries after [`page_start`]. Only the entries of one page
+/// are buffered, however, every page requires listing all of `entries`
+#[cfg(feature = "http")]
+pub(crate) async fn paginate<S>(
+    store: &'static str,
+    entries: S,
+    opts: &PaginatedListOptions,
+) -> Result<PaginatedListResult>
+where
+    S: Stream<Item = Result<ListEntry>>,
+{
+    let start = page_start(store, opts)?;
+    // Retain one more entry than requested to determine if the result is truncated
+    let capacity = opts.max_keys.unwrap_or(DEFAULT_MAX_KEYS).max(1) + 1;
+
+    let mut entries_by_key = BTreeMap::new();
+    futures::pin_mut!(entries);
+    while let Some(entry) = entries.try_next().await? {
+        let key = entry.key();
+        if start.as_ref().map(|s| key <= *s).unwrap_or(false) {
+            continue;
+        }
+        entries_by_key.insert(key, entry);
+        if entries_by_key.len() > capacity {
+            let last = entries_by_key.keys().next_back().cloned().unwrap();
+            entries_by_key.remove(&last);
+        }
+    }
+
+    let truncated = entries_by_key.len() == capacity;
+    if truncated {
+        let last = entries_by_key.keys().next_back().cloned().unwrap();
+        entries_by_key.remove(&last);
+    }
+
+    let mut page = Page::new();
+    for (key, entry) in entries_by_key {
+        page.push(key, entry);
+    }
+    Ok(page.finish(truncated))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// THIS TEST IS ARAS ONLY
+    #[test]
+    fn test_page_token() {
+        let token = encode_page_token("a/b\u{e9}");
+        assert_eq!(decode_page_token("test", &token).unwrap(), "a/b\u{e9}");
+        assert!(decode_page_token("test", "a").is_err());
+        assert!(decode_page_token("test", "zz").is_err());
+        assert!(decode_page_token("test", "ff").is_err());
+
+        let opts = PaginatedListOptions {
+            offset: Some("b".to_string()),
+            page_token: Some(encode_page_token("a")),
+            ..Default::default()
+        };
+        assert_eq!(page_start("test", &opts).unwrap().as_deref(), Some("b"));
+    }
+
+    /// THIS TEST IS ARAS ONLY
+    #[test]
+    fn test_default_max_keys() {
+        let entries =
+            (0..DEFAULT_MAX_KEYS + 1).map(|i| Ok(ListEntry::Prefix(Path::from(format!("{i:04}")))));
+        let opts = PaginatedListOptions::default();
+        let page = paginate_sorted("test", entries, "", &opts).unwrap();
+        assert_eq!(page.result.common_prefixes.len(), DEFAULT_MAX_KEYS);
+        assert!(page.page_token.is_some());
+    }
+}
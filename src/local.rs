@@ -21,7 +21,7 @@ use std::io::{ErrorKind, Read, Seek, SeekFrom, Write};
 use std::ops::Range;
 use std::sync::Arc;
 use std::time::SystemTime;
-use std::{collections::BTreeSet, io};
+use std::{borrow::Cow, collections::BTreeSet, io};
 use std::{collections::VecDeque, path::PathBuf};
 
 use async_trait::async_trait;
@@ -30,15 +30,23 @@ use chrono::{DateTime, Utc};
 use futures::{stream::BoxStream, StreamExt};
 use futures::{FutureExt, TryStreamExt};
 use parking_lot::Mutex;
+use percent_encoding::{percent_decode_str, utf8_percent_encode, AsciiSet, CONTROLS};
 use url::Url;
 use walkdir::{DirEntry, WalkDir};
 
+use crate::multipart::{MultipartStore, MultipartUploadInfo, PartId, PartInfo};
 use crate::{
+    coalesce_ranges_opts,
+    list::{
+        is_delimited, page_start, paginate_sorted, prefix_directory, ListEntry,
+        PaginatedListOptions, PaginatedListResult, PaginatedListStore,
+    },
     maybe_spawn_blocking,
-    path::{absolute_path_to_url, Path},
+    path::{absolute_path_to_url, Path, PathPart, DELIMITER},
     util::InvalidGetRange,
-    Attributes, GetOptions, GetResult, GetResultPayload, ListResult, MultipartUpload, ObjectMeta,
-    ObjectStore, PutMode, PutMultipartOpts, PutOptions, PutPayload, PutResult, Result, UploadPart,
+    Attribute, Attributes, DeleteOptions, GetOptions, GetResult, GetResultPayload, ListResult,
+    MultipartId, MultipartUpload, ObjectMeta, ObjectStore, PutMode, PutMultipartOpts, PutOptions,
+    PutPayload, PutResult, RangeCoalescePolicy, Result, UploadPart,
 };
 
 /// A specialized `Error` for filesystem object store-related errors
@@ -106,11 +114,42 @@ pub(crate) enum Error {
     #[error("Unable to canonicalize filesystem root: {}", path.display())]
     UnableToCanonicalize { path: PathBuf, source: io::Error },
 
-    #[error("Filenames containing trailing '/#\\d+/' are not supported: {}", path)]
+    #[error(
+        "Filenames containing trailing '/#\\d+/', or '#attributes' with attribute sidecars, are not supported: {}",
+        path
+    )]
     InvalidPath { path: String },
 
     #[error("Upload aborted")]
     Aborted,
+
+    /// THIS VARIANT IS ARAS ONLY
+    #[error("Unable to lock directory {}: {}", path.display(), source)]
+    UnableToLockDirectory { path: PathBuf, source: io::Error },
+
+    /// THIS VARIANT IS ARAS ONLY
+    #[error("Precondition failed for {}: {}", path, message)]
+    Precondition { path: String, message: String },
+
+    /// THIS VARIANT IS ARAS ONLY
+    #[error("ETag required for conditional update")]
+    MissingETag,
+
+    /// THIS VARIANT IS ARAS ONLY
+    #[error("Unable to read attributes of {}: {}", path.display(), source)]
+    UnableToReadAttributes { path: PathBuf, source: io::Error },
+
+    /// THIS VARIANT IS ARAS ONLY
+    #[error("Unable to write attributes of {}: {}", path.display(), source)]
+    UnableToWriteAttributes { path: PathBuf, source: io::Error },
+
+    /// THIS VARIANT IS ARAS ONLY
+    #[error("Invalid multipart upload id \"{}\"", id)]
+    InvalidMultipartId { id: String },
+
+    /// THIS VARIANT IS ARAS ONLY
+    #[error("Part {} of {} is missing or does not match its PartId", part_idx, path.display())]
+    InvalidPart { path: PathBuf, part_idx: usize },
 }
 
 impl From<Error> for super::Error {
@@ -124,6 +163,10 @@ impl From<Error> for super::Error {
                 path,
                 source: source.into(),
             },
+            Error::Precondition { path, message } => Self::Precondition {
+                path,
+                source: message.into(),
+            },
             _ => Self::Generic {
                 store: "LocalFileSystem",
                 source: Box::new(source),
@@ -165,7 +208,10 @@ impl From<Error> for super::Error {
 ///
 /// Finally, filenames matching the regex `/.*#\d+/`, e.g. `foo.parquet#123`, are not supported
 /// by [`LocalFileSystem`] as they are used to provide atomic writes. Such files will be ignored
-/// for listing operations, and attempting to address such a file will error.
+/// for listing operations, and attempting to address such a file will error. Those left behind
+/// by writes that never completed can be removed with [`abort_stale_multipart_uploads`].
+///
+/// [`abort_stale_multipart_uploads`]: crate::multipart::abort_stale_multipart_uploads
 ///
 /// # Tokio Compatibility
 ///
@@ -197,11 +243,21 @@ pub struct LocalFileSystem {
     config: Arc<Config>,
     // if you want to delete empty directories when deleting files
     automatic_cleanup: bool,
+    /// THIS FIELD IS ARAS ONLY
+    ///
+    /// The policy for coalescing the ranges requested by [`ObjectStore::get_ranges`],
+    /// if `None` each range is read separately
+    range_coalesce_policy: Option<RangeCoalescePolicy>,
 }
 
 #[derive(Debug)]
 struct Config {
     root: Url,
+    /// THIS FIELD IS ARAS ONLY
+    ///
+    /// Whether attributes are stored in sidecar files, see
+    /// [`LocalFileSystem::with_attribute_sidecars`]
+    attribute_sidecars: bool,
 }
 
 impl std::fmt::Display for LocalFileSystem {
@@ -222,8 +278,10 @@ impl LocalFileSystem {
         Self {
             config: Arc::new(Config {
                 root: Url::parse("file:///").unwrap(),
+                attribute_sidecars: false,
             }),
             automatic_cleanup: false,
+            range_coalesce_policy: None,
         }
     }
 
@@ -240,14 +298,16 @@ impl LocalFileSystem {
         Ok(Self {
             config: Arc::new(Config {
                 root: absolute_path_to_url(path)?,
+                attribute_sidecars: false,
             }),
             automatic_cleanup: false,
+            range_coalesce_policy: None,
         })
     }
 
     /// Return an absolute filesystem path of the given file location
     pub fn path_to_filesystem(&self, location: &Path) -> Result<PathBuf> {
-        if !is_valid_file_path(location) {
+        if !is_valid_file_path(location, self.config.attribute_sidecars) {
             let path = location.as_ref().into();
             let error = Error::InvalidPath { path };
             return Err(error.into());
@@ -276,6 +336,34 @@ impl LocalFileSystem {
         self.automatic_cleanup = automatic_cleanup;
         self
     }
+
+    /// THIS METHOD IS ARAS ONLY
+    ///
+    /// Sets the [`RangeCoalescePolicy`] used by [`ObjectStore::get_ranges`]
+    ///
+    /// By default each range is read separately, without coalescing
+    pub fn with_range_coalesce_policy(mut self, policy: RangeCoalescePolicy) -> Self {
+        self.range_coalesce_policy = Some(policy);
+        self
+    }
+
+    /// THIS METHOD IS ARAS ONLY
+    ///
+    /// Store [`Attributes`] in a sidecar file `{path}#attributes` next to each file where
+    /// extended attributes are not supported, as on platforms other than Linux
+    ///
+    /// Files with names ending in `#attributes` are then reserved, returning an invalid
+    /// path error, and deleting a file also removes its sidecar file, if any
+    ///
+    /// Disabled by default, in which case attributes are only stored as extended attributes,
+    /// and otherwise silently discarded
+    pub fn with_attribute_sidecars(mut self, attribute_sidecars: bool) -> Self {
+        self.config = Arc::new(Config {
+            root: self.config.root.clone(),
+            attribute_sidecars,
+        });
+        self
+    }
 }
 
 impl Config {
@@ -302,14 +390,20 @@ impl Config {
     }
 }
 
-fn is_valid_file_path(path: &Path) -> bool {
+/// THIS FUNCTION IS COMMON, MODIFIED BY ARAS
+fn is_valid_file_path(path: &Path, attribute_sidecars: bool) -> bool {
     match path.filename() {
+        // Reserved for attribute sidecar files
+        Some(p)
+            if attribute_sidecars
+                && matches!(p.strip_suffix(ATTRIBUTES_SUFFIX), Some(x) if x.ends_with('#')) =>
+        {
+            false
+        }
         Some(p) => match p.split_once('#') {
-            Some((_, suffix)) if !suffix.is_empty() => {
-                // Valid if contains non-digits
-                !suffix.as_bytes().iter().all(|x| x.is_ascii_digit())
-            }
-            _ => true,
+            // Valid if contains non-digits
+            Some((_, suffix)) => !is_upload_id(suffix),
+            None => true,
         },
         None => false,
     }
@@ -318,71 +412,94 @@ fn is_valid_file_path(path: &Path) -> bool {
 #[async_trait]
 impl ObjectStore for LocalFileSystem {
     /// THIS METHOD IS COMMON, MODIFIED BY ARAS
+    ///
+    /// [`PutMode::Update`] compares the e-tag of the existing file while holding an
+    /// advisory lock on its parent directory, and is only supported on unix platforms
+    ///
+    /// [`Attributes`] are stored as extended attributes where supported, and otherwise in
+    /// a sidecar file if [`LocalFileSystem::with_attribute_sidecars`] is enabled.
+    /// [`PutMode::Append`] preserves the existing attributes if none are given
+    ///
+    /// With [`PutMode::Create`] a sidecar file is only moved into place once the file is
+    /// created, and so a concurrent reader may briefly observe the file without attributes
     async fn put_opts(
         &self,
         location: &Path,
         payload: PutPayload,
         opts: PutOptions,
     ) -> Result<PutResult> {
-        if matches!(opts.mode, PutMode::Update(_)) {
-            return Err(crate::Error::NotImplemented);
-        }
-
-        if !opts.attributes.is_empty() {
-            return Err(crate::Error::NotImplemented);
-        }
-
         let path = self.path_to_filesystem(location)?;
+        let sidecars = self.config.attribute_sidecars;
         maybe_spawn_blocking(move || {
-            let (mut file, staging_path) = if opts.copy_and_append {
+            let (mut file, staging_path, attributes) = if matches!(opts.mode, PutMode::Append) {
+                let attributes = match opts.attributes.is_empty() {
+                    true => read_path_attributes(&path, sidecars)?,
+                    false => opts.attributes,
+                };
                 let (file, staging_path, _offset) = new_staged_upload_copy_and_append(&path)?;
-                (file, staging_path)
+                (file, staging_path, attributes)
             } else {
-                new_staged_upload(&path)?
+                let (file, staging_path) = new_staged_upload(&path)?;
+                (file, staging_path, opts.attributes)
             };
-            let mut e_tag = None;
-
-            let err = match payload.iter().try_for_each(|x| file.write_all(x)) {
-                Ok(_) => {
+            let r = payload
+                .iter()
+                .try_for_each(|x| file.write_all(x))
+                .map_err(|source| Error::UnableToCopyDataToFile { source })
+                .and_then(|_| {
+                    let sidecar = stage_attributes(&file, &staging_path, &attributes, sidecars)?;
                     let metadata = file.metadata().map_err(|e| Error::Metadata {
                         source: e.into(),
                         path: path.to_string_lossy().to_string(),
                     })?;
-                    e_tag = Some(get_etag(&metadata));
                     match opts.mode {
-                        PutMode::Overwrite => {
+                        PutMode::Overwrite | PutMode::Append => {
                             // For some fuse types of file systems, the file must be closed first
                             // to trigger the upload operation, and then renamed, such as Blobfuse
                             std::mem::drop(file);
-                            match std::fs::rename(&staging_path, &path) {
-                                Ok(_) => None,
-                                Err(source) => Some(Error::UnableToRenameFile { source }),
+                            if sidecar {
+                                rename_sidecar(&staging_path, &path)?;
                             }
+                            std::fs::rename(&staging_path, &path)
+                                .map_err(|source| Error::UnableToRenameFile { source })?;
                         }
-                        PutMode::Create => match std::fs::hard_link(&staging_path, &path) {
-                            Ok(_) => {
-                                let _ = std::fs::remove_file(&staging_path); // Attempt to cleanup
-                                None
+                        PutMode::Create => {
+                            // The sidecar can only be moved once the file is known not to
+                            // exist, as it would otherwise replace that of the existing file
+                            std::fs::hard_link(&staging_path, &path).map_err(
+                                |source| match source.kind() {
+                                    ErrorKind::AlreadyExists => Error::AlreadyExists {
+                                        path: path.to_str().unwrap().to_string(),
+                                        source,
+                                    },
+                                    _ => Error::UnableToRenameFile { source },
+                                },
+                            )?;
+                            let _ = std::fs::remove_file(&staging_path); // Attempt to cleanup
+                            if sidecar {
+                                rename_sidecar(&staging_path, &path)?;
                             }
-                            Err(source) => match source.kind() {
-                                ErrorKind::AlreadyExists => Some(Error::AlreadyExists {
-                                    path: path.to_str().unwrap().to_string(),
-                                    source,
-                                }),
-                                _ => Some(Error::UnableToRenameFile { source }),
-                            },
-                        },
-                        PutMode::Update(_) => unreachable!(),
+                        }
+                        PutMode::Update(v) => {
+                            std::mem::drop(file);
+                            update_file(&staging_path, &path, v.e_tag.as_deref(), sidecar)?;
+                        }
+                    }
+                    Ok(get_etag(&metadata))
+                });
+
+            let e_tag = match r {
+                Ok(e_tag) => Some(e_tag),
+                Err(err) => {
+                    // Attempt to cleanup
+                    let _ = std::fs::remove_file(&staging_path);
+                    if sidecars {
+                        let _ = std::fs::remove_file(sidecar_path(&staging_path));
                     }
+                    return Err(err.into());
                 }
-                Err(source) => Some(Error::UnableToCopyDataToFile { source }),
             };
 
-            if let Some(err) = err {
-                let _ = std::fs::remove_file(&staging_path); // Attempt to cleanup
-                return Err(err.into());
-            }
-
             Ok(PutResult {
                 e_tag,
                 version: None,
@@ -397,27 +514,39 @@ impl ObjectStore for LocalFileSystem {
         location: &Path,
         opts: PutMultipartOpts,
     ) -> Result<Box<dyn MultipartUpload>> {
-        if !opts.attributes.is_empty() {
-            return Err(crate::Error::NotImplemented);
-        }
-
+        let append = match opts.mode {
+            PutMode::Overwrite => false,
+            PutMode::Append => true,
+            PutMode::Create | PutMode::Update(_) => return Err(crate::Error::NotImplemented),
+        };
         let dest = self.path_to_filesystem(location)?;
-        let (file, src, offset) = if opts.copy_and_append {
-            new_staged_upload_copy_and_append(&dest)?
-        } else {
-            let (file, src) = new_staged_upload(&dest)?;
-            (file, src, 0)
+        let sidecars = self.config.attribute_sidecars;
+        let attributes = match append && opts.attributes.is_empty() {
+            true => read_path_attributes(&dest, sidecars)?,
+            false => opts.attributes,
+        };
+        let (file, src, offset) = match append {
+            true => new_staged_upload_copy_and_append(&dest)?,
+            false => {
+                let (file, src) = new_staged_upload(&dest)?;
+                (file, src, 0)
+            }
         };
-        Ok(Box::new(LocalUpload::new(src, dest, file, offset)))
+        Ok(Box::new(LocalUpload::new(
+            src, dest, file, offset, attributes, sidecars,
+        )))
     }
 
+    /// THIS METHOD IS COMMON, MODIFIED BY ARAS
     async fn get_opts(&self, location: &Path, options: GetOptions) -> Result<GetResult> {
         let location = location.clone();
         let path = self.path_to_filesystem(&location)?;
+        let sidecars = self.config.attribute_sidecars;
         maybe_spawn_blocking(move || {
             let (file, metadata) = open_file(&path)?;
             let meta = convert_metadata(metadata, location);
             options.check_preconditions(&meta)?;
+            let attributes = read_attributes(&file, &path, sidecars)?;
 
             let range = match options.range {
                 Some(r) => r
@@ -428,7 +557,7 @@ impl ObjectStore for LocalFileSystem {
 
             Ok(GetResult {
                 payload: GetResultPayload::File(file, path),
-                attributes: Attributes::default(),
+                attributes,
                 range,
                 meta,
             })
@@ -445,7 +574,12 @@ impl ObjectStore for LocalFileSystem {
         .await
     }
 
+    /// THIS METHOD IS COMMON, MODIFIED BY ARAS
     async fn get_ranges(&self, location: &Path, ranges: &[Range<u64>]) -> Result<Vec<Bytes>> {
+        if let Some(policy) = self.range_coalesce_policy {
+            return self.get_ranges_opts(location, ranges, policy).await;
+        }
+
         let path = self.path_to_filesystem(location)?;
         let ranges = ranges.to_vec();
         maybe_spawn_blocking(move || {
@@ -459,6 +593,25 @@ impl ObjectStore for LocalFileSystem {
         .await
     }
 
+    /// THIS METHOD IS ARAS ONLY
+    async fn get_ranges_opts(
+        &self,
+        location: &Path,
+        ranges: &[Range<u64>],
+        policy: RangeCoalescePolicy,
+    ) -> Result<Vec<Bytes>> {
+        let path = self.path_to_filesystem(location)?;
+        let fetch = |range| {
+            let path = path.clone();
+            maybe_spawn_blocking(move || {
+                let (mut file, _) = open_file(&path)?;
+                read_range(&mut file, &path, range)
+            })
+        };
+        coalesce_ranges_opts(ranges, fetch, policy).await
+    }
+
+    /// THIS METHOD IS COMMON, MODIFIED BY ARAS
     async fn delete(&self, location: &Path) -> Result<()> {
         let config = Arc::clone(&self.config);
         let path = self.path_to_filesystem(location)?;
@@ -469,6 +622,8 @@ impl ObjectStore for LocalFileSystem {
                     ErrorKind::NotFound => Error::NotFound { path, source: e }.into(),
                     _ => Error::UnableToDeleteFile { path, source: e }.into(),
                 })
+            } else if let Err(e) = remove_sidecar(&path, config.attribute_sidecars) {
+                Err(e.into())
             } else if automactic_cleanup {
                 let root = &config.root;
                 let root = root
@@ -494,6 +649,32 @@ impl ObjectStore for LocalFileSystem {
         .await
     }
 
+    /// THIS METHOD IS ARAS ONLY
+    ///
+    /// Files are not versioned, and so only `if_match` preconditions are supported
+    async fn delete_opts(&self, location: &Path, opts: DeleteOptions) -> Result<()> {
+        if opts.version.is_some() {
+            return Err(crate::Error::NotImplemented);
+        }
+        let expected = match opts.if_match {
+            Some(expected) => expected,
+            None => return self.delete(location).await,
+        };
+
+        let path = self.path_to_filesystem(location)?;
+        let sidecars = self.config.attribute_sidecars;
+        maybe_spawn_blocking(move || {
+            let _lock = lock_parent(&path)?;
+            check_etag(&path, &expected)?;
+            std::fs::remove_file(&path).map_err(|source| Error::UnableToDeleteFile {
+                path: path.clone(),
+                source,
+            })?;
+            Ok(remove_sidecar(&path, sidecars)?)
+        })
+        .await
+    }
+
     fn list(&self, prefix: Option<&Path>) -> BoxStream<'static, Result<ObjectMeta>> {
         self.list_with_maybe_offset(prefix, None)
     }
@@ -521,11 +702,16 @@ impl ObjectStore for LocalFileSystem {
             let mut common_prefixes = BTreeSet::new();
             let mut objects = Vec::new();
 
-            for entry_res in walkdir.into_iter().map(convert_walkdir_result) {
+            let entries = walkdir
+                .into_iter()
+                .filter_entry(|entry| !is_upload_dir(entry));
+            for entry_res in entries.map(convert_walkdir_result) {
                 if let Some(entry) = entry_res? {
                     let is_directory = entry.file_type().is_dir();
                     let entry_location = config.filesystem_to_path(entry.path())?;
-                    if !is_directory && !is_valid_file_path(&entry_location) {
+                    if !is_directory
+                        && !is_valid_file_path(&entry_location, config.attribute_sidecars)
+                    {
                         continue;
                     }
 
@@ -557,9 +743,11 @@ impl ObjectStore for LocalFileSystem {
         .await
     }
 
+    /// THIS METHOD IS COMMON, MODIFIED BY ARAS
     async fn copy(&self, from: &Path, to: &Path) -> Result<()> {
         let from = self.path_to_filesystem(from)?;
         let to = self.path_to_filesystem(to)?;
+        let sidecars = self.config.attribute_sidecars;
         let mut id = 0;
         // In order to make this atomic we:
         //
@@ -571,10 +759,14 @@ impl ObjectStore for LocalFileSystem {
             let staged = staged_upload_path(&to, &id.to_string());
             match std::fs::hard_link(&from, &staged) {
                 Ok(_) => {
-                    return std::fs::rename(&staged, &to).map_err(|source| {
-                        let _ = std::fs::remove_file(&staged); // Attempt to clean up
-                        Error::UnableToCopyFile { from, to, source }.into()
-                    });
+                    return match std::fs::rename(&staged, &to) {
+                        Ok(_) if sidecars => Ok(copy_sidecar(&from, &to)?),
+                        Ok(_) => Ok(()),
+                        Err(source) => {
+                            let _ = std::fs::remove_file(&staged); // Attempt to clean up
+                            Err(Error::UnableToCopyFile { from, to, source }.into())
+                        }
+                    };
                 }
                 Err(source) => match source.kind() {
                     ErrorKind::AlreadyExists => id += 1,
@@ -589,11 +781,14 @@ impl ObjectStore for LocalFileSystem {
         .await
     }
 
+    /// THIS METHOD IS COMMON, MODIFIED BY ARAS
     async fn rename(&self, from: &Path, to: &Path) -> Result<()> {
         let from = self.path_to_filesystem(from)?;
         let to = self.path_to_filesystem(to)?;
+        let sidecars = self.config.attribute_sidecars;
         maybe_spawn_blocking(move || loop {
             match std::fs::rename(&from, &to) {
+                Ok(_) if sidecars => return Ok(rename_sidecar(&from, &to)?),
                 Ok(_) => return Ok(()),
                 Err(source) => match source.kind() {
                     ErrorKind::NotFound => match from.exists() {
@@ -607,12 +802,15 @@ impl ObjectStore for LocalFileSystem {
         .await
     }
 
+    /// THIS METHOD IS COMMON, MODIFIED BY ARAS
     async fn copy_if_not_exists(&self, from: &Path, to: &Path) -> Result<()> {
         let from = self.path_to_filesystem(from)?;
         let to = self.path_to_filesystem(to)?;
 
+        let sidecars = self.config.attribute_sidecars;
         maybe_spawn_blocking(move || loop {
             match std::fs::hard_link(&from, &to) {
+                Ok(_) if sidecars => return Ok(copy_sidecar(&from, &to)?),
                 Ok(_) => return Ok(()),
                 Err(source) => match source.kind() {
                     ErrorKind::AlreadyExists => {
@@ -632,6 +830,367 @@ impl ObjectStore for LocalFileSystem {
         })
         .await
     }
+
+    /// THIS METHOD IS ARAS ONLY
+    async fn compose(&self, sources: &[Path], dest: &Path) -> Result<PutResult> {
+        let sources = sources
+            .iter()
+            .map(|p| self.path_to_filesystem(p))
+            .collect::<Result<Vec<_>>>()?;
+        let path = self.path_to_filesystem(dest)?;
+        let sidecars = self.config.attribute_sidecars;
+        maybe_spawn_blocking(move || compose_file(&path, &sources, sidecars)).await
+    }
+}
+
+/// THIS IMPL IS ARAS ONLY
+///
+/// The parts of an upload to `{path}` are written to the staging directory `{path}#{id}`,
+/// which is hidden from listings, and so uploads can be shared between processes using
+/// the same filesystem. Completing the upload concatenates the parts into a staged file,
+/// which is renamed into place
+///
+/// The incomplete uploads listed by [`MultipartStore::list_multipart_uploads`] additionally
+/// include the `{path}#{id}` files staged by writes that never completed, for example due to
+/// a crash, which have no parts but can be removed with [`MultipartStore::abort_multipart`].
+/// The `initiated` time of an upload is the time it was last modified, so that uploads
+/// still being written are not considered stale
+#[async_trait]
+impl MultipartStore for LocalFileSystem {
+    async fn create_multipart(&self, path: &Path) -> Result<MultipartId> {
+        let dest = self.path_to_filesystem(path)?;
+        maybe_spawn_blocking(move || new_upload_dir(&dest)).await
+    }
+
+    async fn put_part(
+        &self,
+        path: &Path,
+        id: &MultipartId,
+        part_idx: usize,
+        data: PutPayload,
+    ) -> Result<PartId> {
+        let dest = self.path_to_filesystem(path)?;
+        let id = id.clone();
+        maybe_spawn_blocking(move || {
+            let dir = upload_dir(&dest, &id)?;
+
+            // Write to a staged file, so that a part is never partially visible
+            let part = dir.join(part_idx.to_string());
+            let (mut file, staging_path) = new_staged_upload(&part)?;
+            let r = data
+                .iter()
+                .try_for_each(|x| file.write_all(x))
+                .map_err(|source| Error::UnableToCopyDataToFile { source })
+                .and_then(|_| {
+                    std::fs::rename(&staging_path, &part)
+                        .map_err(|source| Error::UnableToRenameFile { source })
+                })
+                .and_then(|_| {
+                    file.metadata().map_err(|e| Error::Metadata {
+                        source: e.into(),
+                        path: part.to_string_lossy().to_string(),
+                    })
+                });
+
+            match r {
+                Ok(metadata) => Ok(PartId {
+                    content_id: get_etag(&metadata),
+                }),
+                Err(e) => {
+                    let _ = std::fs::remove_file(&staging_path); // Attempt to cleanup
+                    Err(e.into())
+                }
+            }
+        })
+        .await
+    }
+
+    async fn complete_multipart(
+        &self,
+        path: &Path,
+        id: &MultipartId,
+        parts: Vec<PartId>,
+    ) -> Result<PutResult> {
+        let dest = self.path_to_filesystem(path)?;
+        let id = id.clone();
+        let sidecars = self.config.attribute_sidecars;
+        maybe_spawn_blocking(move || {
+            let dir = upload_dir(&dest, &id)?;
+
+            // Parts overwritten since being uploaded no longer match their PartId
+            let sources = parts
+                .iter()
+                .enumerate()
+                .map(|(part_idx, part)| {
+                    let path = dir.join(part_idx.to_string());
+                    match std::fs::metadata(&path) {
+                        Ok(metadata) if get_etag(&metadata) == part.content_id => Ok(path),
+                        Ok(_) => Err(Error::InvalidPart {
+                            path: dir.clone(),
+                            part_idx,
+                        }),
+                        Err(e) if e.kind() == ErrorKind::NotFound => Err(Error::InvalidPart {
+                            path: dir.clone(),
+                            part_idx,
+                        }),
+                        Err(e) => Err(Error::Metadata {
+                            source: e.into(),
+                            path: path.to_string_lossy().to_string(),
+                        }),
+                    }
+                })
+                .collect::<Result<Vec<_>, _>>()?;
+
+            let result = compose_file(&dest, &sources, sidecars)?;
+            let _ = std::fs::remove_dir_all(&dir); // Attempt to cleanup
+            Ok(result)
+        })
+        .await
+    }
+
+    async fn abort_multipart(&self, path: &Path, id: &MultipartId) -> Result<()> {
+        let dest = self.path_to_filesystem(path)?;
+        let id = id.clone();
+        maybe_spawn_blocking(move || {
+            let path = staged_upload_path(&dest, &id);
+            let r = match upload_dir(&dest, &id) {
+                Ok(dir) => std::fs::remove_dir_all(dir),
+                Err(Error::NotFound { .. }) if path.is_file() => std::fs::remove_file(&path),
+                Err(e) => return Err(e.into()),
+            };
+            match r {
+                Ok(_) => Ok(()),
+                Err(source) if source.kind() == ErrorKind::NotFound => {
+                    Err(Error::NotFound { path, source }.into())
+                }
+                Err(source) => Err(Error::UnableToDeleteFile { path, source }.into()),
+            }
+        })
+        .await
+    }
+
+    fn list_multipart_uploads(
+        &self,
+        prefix: Option<&Path>,
+    ) -> BoxStream<'static, Result<MultipartUploadInfo>> {
+        let config = Arc::clone(&self.config);
+        let root = match prefix {
+            Some(prefix) => match config.prefix_to_filesystem(prefix) {
+                Ok(path) => path,
+                Err(e) => return futures::future::ready(Err(e)).into_stream().boxed(),
+            },
+            None => config.root.to_file_path().unwrap(),
+        };
+
+        futures::stream::once(maybe_spawn_blocking(move || {
+            list_staged_uploads(&config, root)
+        }))
+        .map_ok(|uploads| futures::stream::iter(uploads.into_iter().map(Ok)))
+        .try_flatten()
+        .boxed()
+    }
+
+    async fn list_parts(&self, path: &Path, id: &MultipartId) -> Result<Vec<PartInfo>> {
+        let dest = self.path_to_filesystem(path)?;
+        let id = id.clone();
+        maybe_spawn_blocking(move || {
+            let dir = match upload_dir(&dest, &id) {
+                Ok(dir) => dir,
+                Err(Error::NotFound { .. }) if staged_upload_path(&dest, &id).is_file() => {
+                    return Ok(vec![])
+                }
+                Err(e) => return Err(e.into()),
+            };
+
+            let mut parts = Vec::new();
+            for result in WalkDir::new(&dir).min_depth(1).max_depth(1) {
+                let entry = match convert_walkdir_result(result)? {
+                    Some(entry) => entry,
+                    None => continue,
+                };
+                // Skip the marker and parts still being written
+                let part_idx = match entry.file_name().to_str().map(str::parse::<usize>) {
+                    Some(Ok(part_idx)) => part_idx,
+                    _ => continue,
+                };
+                let metadata = match entry.metadata() {
+                    Ok(metadata) => metadata,
+                    Err(e) if e.io_error().map(|e| e.kind()) == Some(ErrorKind::NotFound) => {
+                        continue
+                    }
+                    Err(e) => {
+                        return Err(Error::Metadata {
+                            source: e.into(),
+                            path: entry.path().to_string_lossy().to_string(),
+                        }
+                        .into())
+                    }
+                };
+                parts.push(PartInfo {
+                    part_idx,
+                    part_id: PartId {
+                        content_id: get_etag(&metadata),
+                    },
+                    size: metadata.len(),
+                    last_modified: Some(last_modified(&metadata)),
+                });
+            }
+            parts.sort_by_key(|part| part.part_idx);
+            Ok(parts)
+        })
+        .await
+    }
+}
+
+/// THIS IMPL IS ARAS ONLY
+///
+/// Pages are read with a walk of the directories sorted by name, skipping the directories
+/// before the start of the page, with page tokens encoding the key of the last entry.
+/// Only a `/` delimiter is supported
+#[async_trait]
+impl PaginatedListStore for LocalFileSystem {
+    async fn list_paginated(
+        &self,
+        prefix: Option<&str>,
+        opts: PaginatedListOptions,
+    ) -> Result<PaginatedListResult> {
+        let config = Arc::clone(&self.config);
+        let prefix = prefix.unwrap_or_default().to_string();
+        let delimited = is_delimited("LocalFileSystem", &opts)?;
+        let start = page_start("LocalFileSystem", &opts)?;
+        let root = config.prefix_to_filesystem(&prefix_directory(&prefix)?)?;
+
+        maybe_spawn_blocking(move || {
+            let walkdir = WalkDir::new(root)
+                // Don't include the root directory itself
+                .min_depth(1)
+                .max_depth(if delimited { 1 } else { usize::MAX })
+                .follow_links(true)
+                // Walk in the order of the keys, where directories include the delimiter
+                .sort_by_key(|entry| {
+                    let name = entry.file_name().to_string_lossy();
+                    let mut key = PathPart::from(name.as_ref()).as_ref().to_string();
+                    if entry.file_type().is_dir() {
+                        key.push_str(DELIMITER);
+                    }
+                    key
+                });
+
+            let entries = walkdir
+                .into_iter()
+                .filter_entry(|entry| {
+                    if is_upload_dir(entry) {
+                        return false;
+                    }
+                    // Skip entries before the start of the page, or not matching the prefix,
+                    // before accessing their metadata, which matters for NFS mounts
+                    let mut key = match config.filesystem_to_path(entry.path()) {
+                        Ok(path) => String::from(path),
+                        Err(_) => return true,
+                    };
+                    let is_dir = entry.file_type().is_dir();
+                    if is_dir {
+                        key.push_str(DELIMITER);
+                    }
+                    let matches = key.starts_with(&prefix) || (is_dir && prefix.starts_with(&key));
+                    let after = match &start {
+                        Some(start) => key > *start || (is_dir && start.starts_with(&key)),
+                        None => true,
+                    };
+                    matches && after
+                })
+                .filter_map(|result| {
+                    let entry = match convert_walkdir_result(result).transpose()? {
+                        Ok(entry) => entry,
+                        Err(e) => return Some(Err(e)),
+                    };
+                    let location = match config.filesystem_to_path(entry.path()) {
+                        Ok(location) => location,
+                        Err(e) => return Some(Err(e)),
+                    };
+                    match entry.path().is_file() {
+                        true if is_valid_file_path(&location, config.attribute_sidecars) => {
+                            convert_entry(entry, location)
+                                .map(|meta| meta.map(ListEntry::Object))
+                                .transpose()
+                        }
+                        true => None,
+                        false if delimited => Some(Ok(ListEntry::Prefix(location))),
+                        false => None,
+                    }
+                });
+
+            paginate_sorted("LocalFileSystem", entries, &prefix, &opts)
+        })
+        .await
+    }
+}
+
+/// THIS FUNCTION IS ARAS ONLY
+///
+/// Atomically replaces `path` with the concatenation of `sources`, without attributes
+fn compose_file(
+    path: &std::path::Path,
+    sources: &[PathBuf],
+    attribute_sidecars: bool,
+) -> Result<PutResult> {
+    let (mut file, staging_path) = new_staged_upload(path)?;
+    let r = compose_files(&mut file, sources).and_then(|_| {
+        let metadata = file.metadata().map_err(|e| Error::Metadata {
+            source: e.into(),
+            path: path.to_string_lossy().to_string(),
+        })?;
+        std::mem::drop(file);
+        std::fs::rename(&staging_path, path)
+            .map_err(|source| Error::UnableToRenameFile { source })?;
+        Ok(get_etag(&metadata))
+    });
+
+    let e_tag = match r {
+        Ok(e_tag) => e_tag,
+        Err(e) => {
+            let _ = std::fs::remove_file(&staging_path); // Attempt to cleanup
+            return Err(e.into());
+        }
+    };
+
+    // The composed object does not carry the attributes of any source
+    remove_sidecar(path, attribute_sidecars)?;
+    Ok(PutResult {
+        e_tag: Some(e_tag),
+        version: None,
+    })
+}
+
+/// THIS FUNCTION IS ARAS ONLY
+///
+/// Appends the contents of each of `sources`, in order, to `file`
+fn compose_files(file: &mut File, sources: &[PathBuf]) -> Result<(), Error> {
+    for source in sources {
+        let mut src = match File::open(source) {
+            Ok(f) if !f.metadata().map(|m| m.is_dir()).unwrap_or(false) => f,
+            Ok(_) => {
+                return Err(Error::NotFound {
+                    path: source.clone(),
+                    source: io::Error::new(ErrorKind::NotFound, "is directory"),
+                })
+            }
+            Err(e) if e.kind() == ErrorKind::NotFound => {
+                return Err(Error::NotFound {
+                    path: source.clone(),
+                    source: e,
+                })
+            }
+            Err(source_err) => {
+                return Err(Error::UnableToOpenFile {
+                    path: source.clone(),
+                    source: source_err,
+                })
+            }
+        };
+        io::copy(&mut src, file).map_err(|source| Error::UnableToCopyDataToFile { source })?;
+    }
+    Ok(())
 }
 
 impl LocalFileSystem {
@@ -657,35 +1216,40 @@ impl LocalFileSystem {
 
         let maybe_offset = maybe_offset.cloned();
 
-        let s = walkdir.into_iter().flat_map(move |result_dir_entry| {
-            // Apply offset filter before proceeding, to reduce statx file system calls
-            // This matters for NFS mounts
-            if let (Some(offset), Ok(entry)) = (maybe_offset.as_ref(), result_dir_entry.as_ref()) {
-                let location = config.filesystem_to_path(entry.path());
-                match location {
-                    Ok(path) if path <= *offset => return None,
-                    Err(e) => return Some(Err(e)),
-                    _ => {}
+        let s = walkdir
+            .into_iter()
+            .filter_entry(|entry| !is_upload_dir(entry))
+            .flat_map(move |result_dir_entry| {
+                // Apply offset filter before proceeding, to reduce statx file system calls
+                // This matters for NFS mounts
+                if let (Some(offset), Ok(entry)) =
+                    (maybe_offset.as_ref(), result_dir_entry.as_ref())
+                {
+                    let location = config.filesystem_to_path(entry.path());
+                    match location {
+                        Ok(path) if path <= *offset => return None,
+                        Err(e) => return Some(Err(e)),
+                        _ => {}
+                    }
                 }
-            }
 
-            let entry = match convert_walkdir_result(result_dir_entry).transpose()? {
-                Ok(entry) => entry,
-                Err(e) => return Some(Err(e)),
-            };
+                let entry = match convert_walkdir_result(result_dir_entry).transpose()? {
+                    Ok(entry) => entry,
+                    Err(e) => return Some(Err(e)),
+                };
 
-            if !entry.path().is_file() {
-                return None;
-            }
+                if !entry.path().is_file() {
+                    return None;
+                }
 
-            match config.filesystem_to_path(entry.path()) {
-                Ok(path) => match is_valid_file_path(&path) {
-                    true => convert_entry(entry, path).transpose(),
-                    false => None,
-                },
-                Err(e) => Some(Err(e)),
-            }
-        });
+                match config.filesystem_to_path(entry.path()) {
+                    Ok(path) => match is_valid_file_path(&path, config.attribute_sidecars) {
+                        true => convert_entry(entry, path).transpose(),
+                        false => None,
+                    },
+                    Err(e) => Some(Err(e)),
+                }
+            });
 
         // If no tokio context, return iterator directly as no
         // need to perform chunked spawn_blocking reads
@@ -721,101 +1285,596 @@ impl LocalFileSystem {
     }
 }
 
-/// Creates the parent directories of `path` or returns an error based on `source` if no parent
-fn create_parent_dirs(path: &std::path::Path, source: io::Error) -> Result<()> {
-    let parent = path.parent().ok_or_else(|| {
-        let path = path.to_path_buf();
-        Error::UnableToCreateFile { path, source }
-    })?;
-
-    std::fs::create_dir_all(parent).map_err(|source| {
-        let path = parent.into();
-        Error::UnableToCreateDir { source, path }
-    })?;
-    Ok(())
+/// Creates the parent directories of `path` or returns an error based on `source` if no parent
+fn create_parent_dirs(path: &std::path::Path, source: io::Error) -> Result<()> {
+    let parent = path.parent().ok_or_else(|| {
+        let path = path.to_path_buf();
+        Error::UnableToCreateFile { path, source }
+    })?;
+
+    std::fs::create_dir_all(parent).map_err(|source| {
+        let path = parent.into();
+        Error::UnableToCreateDir { source, path }
+    })?;
+    Ok(())
+}
+
+/// Generates a unique file path `{base}#{suffix}`, returning the opened `File` and `path`
+///
+/// Creates any directories if necessary
+fn new_staged_upload(base: &std::path::Path) -> Result<(File, PathBuf)> {
+    let mut multipart_id = 1;
+    loop {
+        let suffix = multipart_id.to_string();
+        let path = staged_upload_path(base, &suffix);
+        let mut options = OpenOptions::new();
+        match options.read(true).write(true).create_new(true).open(&path) {
+            Ok(f) => return Ok((f, path)),
+            Err(source) => match source.kind() {
+                ErrorKind::AlreadyExists => multipart_id += 1,
+                ErrorKind::NotFound => create_parent_dirs(&path, source)?,
+                _ => return Err(Error::UnableToOpenFile { source, path }.into()),
+            },
+        }
+    }
+}
+
+/// THIS FUNCTION IS ARAS ONLY
+///
+/// Creates a new staging file by copying from `base` if it exists,
+/// and seeks to the end so we can append new data.
+fn new_staged_upload_copy_and_append(base: &std::path::Path) -> Result<(File, PathBuf, u64)> {
+    let multipart_id = 1;
+    loop {
+        let suffix = multipart_id.to_string();
+        let path = staged_upload_path(base, &suffix);
+
+        let offset = if base.exists() {
+            let metadata = std::fs::metadata(base).map_err(|source| Error::Metadata {
+                source: source.into(),
+                path: base.display().to_string(),
+            })?;
+            let offset = metadata.len();
+
+            std::fs::copy(base, &path).map_err(|source| Error::UnableToCopyFile {
+                from: base.to_path_buf(),
+                to: path.clone(),
+                source,
+            })?;
+
+            offset
+        } else {
+            0
+        };
+
+        let mut options = OpenOptions::new();
+        match options.read(true).write(true).create(true).open(&path) {
+            Ok(mut f) => {
+                f.seek(SeekFrom::Start(offset))
+                    .map_err(|source| Error::Seek {
+                        source,
+                        path: path.clone(),
+                    })?;
+                return Ok((f, path, offset));
+            }
+            Err(source) => match source.kind() {
+                ErrorKind::AlreadyExists => {
+                    return Err(Error::AlreadyExists {
+                        path: path.to_string_lossy().to_string(),
+                        source,
+                    }
+                    .into())
+                }
+                ErrorKind::NotFound => {
+                    create_parent_dirs(&path, source)?;
+                }
+                _ => return Err(Error::UnableToOpenFile { source, path }.into()),
+            },
+        }
+    }
+}
+
+/// THIS CONSTANT IS ARAS ONLY
+///
+/// The file marking a directory as the staging directory of a [`MultipartStore`] upload,
+/// distinguishing it from user directories with the same name
+///
+/// Staged files never have the suffix `#0`, and so this cannot be an object
+const UPLOAD_MARKER: &str = "upload#0";
+
+/// THIS FUNCTION IS ARAS ONLY
+///
+/// Creates a new staging directory `{base}#{id}` for a [`MultipartStore`] upload, returning `id`
+///
+/// Creates any directories if necessary
+fn new_upload_dir(base: &std::path::Path) -> Result<MultipartId> {
+    let mut multipart_id = 1;
+    loop {
+        let id = multipart_id.to_string();
+        let path = staged_upload_path(base, &id);
+        match std::fs::create_dir(&path) {
+            Ok(_) => {
+                let marker = path.join(UPLOAD_MARKER);
+                File::create(&marker).map_err(|source| {
+                    let _ = std::fs::remove_dir(&path); // Attempt to cleanup
+                    Error::UnableToCreateFile {
+                        path: marker,
+                        source,
+                    }
+                })?;
+                return Ok(id);
+            }
+            Err(source) => match source.kind() {
+                ErrorKind::AlreadyExists => multipart_id += 1,
+                ErrorKind::NotFound => create_parent_dirs(&path, source)?,
+                _ => return Err(Error::UnableToCreateDir { source, path }.into()),
+            },
+        }
+    }
+}
+
+/// THIS FUNCTION IS ARAS ONLY
+///
+/// Returns the staging directory of the [`MultipartStore`] upload `id` to `dest`, returning
+/// [`Error::NotFound`] if it does not exist
+fn upload_dir(dest: &std::path::Path, id: &MultipartId) -> Result<PathBuf, Error> {
+    if !is_upload_id(id) {
+        return Err(Error::InvalidMultipartId { id: id.clone() });
+    }
+    let path = staged_upload_path(dest, id);
+    match path.join(UPLOAD_MARKER).is_file() {
+        true => Ok(path),
+        false => {
+            let source = io::Error::new(ErrorKind::NotFound, "upload not found");
+            Err(Error::NotFound { path, source })
+        }
+    }
+}
+
+/// THIS FUNCTION IS ARAS ONLY
+///
+/// Returns true if `suffix` is the suffix of a staged upload, i.e. `{path}#{suffix}`
+fn is_upload_id(suffix: &str) -> bool {
+    !suffix.is_empty() && suffix.bytes().all(|b| b.is_ascii_digit())
+}
+
+/// THIS FUNCTION IS ARAS ONLY
+///
+/// Returns true if `entry` is the staging directory of a [`MultipartStore`] upload,
+/// which should not be listed
+fn is_upload_dir(entry: &DirEntry) -> bool {
+    entry.file_type().is_dir()
+        && entry
+            .file_name()
+            .to_str()
+            .and_then(|name| name.split_once('#'))
+            .map(|(_, suffix)| is_upload_id(suffix))
+            .unwrap_or(false)
+        && entry.path().join(UPLOAD_MARKER).is_file()
+}
+
+/// THIS FUNCTION IS ARAS ONLY
+///
+/// Returns the incomplete uploads below `root`, i.e. the staging directories of
+/// [`MultipartStore`] uploads and the files staged by [`new_staged_upload`]
+fn list_staged_uploads(config: &Config, root: PathBuf) -> Result<Vec<MultipartUploadInfo>> {
+    let mut uploads = Vec::new();
+    let mut walk = WalkDir::new(root)
+        // Don't include the root directory itself
+        .min_depth(1)
+        .follow_links(true)
+        .into_iter();
+
+    while let Some(result) = walk.next() {
+        let entry = match convert_walkdir_result(result)? {
+            Some(entry) => entry,
+            None => continue,
+        };
+        if is_upload_dir(&entry) {
+            // Parts are listed by `list_parts`
+            walk.skip_current_dir();
+        } else if !entry.file_type().is_file() {
+            continue;
+        }
+
+        let (dest, id) = match staged_upload_dest(entry.path()) {
+            Some(staged) => staged,
+            None => continue,
+        };
+        let metadata = match entry.metadata() {
+            Ok(metadata) => metadata,
+            // Completed or aborted since being walked
+            Err(e) if e.io_error().map(|e| e.kind()) == Some(ErrorKind::NotFound) => continue,
+            Err(e) => {
+                return Err(Error::Metadata {
+                    source: e.into(),
+                    path: entry.path().to_string_lossy().to_string(),
+                }
+                .into())
+            }
+        };
+        uploads.push(MultipartUploadInfo {
+            location: config.filesystem_to_path(&dest)?,
+            id,
+            initiated: Some(last_modified(&metadata)),
+        });
+    }
+    Ok(uploads)
+}
+
+/// THIS FUNCTION IS ARAS ONLY
+///
+/// Splits the path of a staged upload `{dest}#{id}` into `dest` and `id`
+fn staged_upload_dest(path: &std::path::Path) -> Option<(PathBuf, MultipartId)> {
+    let name = path.file_name()?.to_str()?;
+    match name.rsplit_once('#') {
+        Some((base, id)) if !base.is_empty() && is_upload_id(id) => {
+            Some((path.with_file_name(base), id.to_string()))
+        }
+        _ => None,
+    }
+}
+
+/// Returns the unique upload for the given path and suffix
+fn staged_upload_path(dest: &std::path::Path, suffix: &str) -> PathBuf {
+    let mut staging_path = dest.as_os_str().to_owned();
+    staging_path.push("#");
+    staging_path.push(suffix);
+    staging_path.into()
+}
+
+/// THIS CONSTANT IS ARAS ONLY
+///
+/// Suffix of the sidecar file `{path}#attributes` storing the [`Attributes`] of a file
+/// on filesystems without support for extended attributes
+const ATTRIBUTES_SUFFIX: &str = "attributes";
+
+/// THIS CONSTANT IS ARAS ONLY
+///
+/// Prefix of the names of [`Attribute::Metadata`] attributes
+const METADATA_PREFIX: &str = "meta.";
+
+/// THIS CONSTANT IS ARAS ONLY
+///
+/// Namespace of the extended attributes storing [`Attributes`]
+#[cfg(target_os = "linux")]
+const XATTR_PREFIX: &str = "user.";
+
+/// THIS CONSTANT IS ARAS ONLY
+///
+/// Characters escaped in the `name=value` lines of sidecar files
+const SIDECAR_ENCODE_SET: &AsciiSet = &CONTROLS.add(b'%').add(b'=');
+
+/// THIS FUNCTION IS ARAS ONLY
+///
+/// Returns the name under which `attribute` is persisted
+fn attribute_name(attribute: &Attribute) -> Cow<'static, str> {
+    match attribute {
+        Attribute::ContentDisposition => "content-disposition".into(),
+        Attribute::ContentEncoding => "content-encoding".into(),
+        Attribute::ContentLanguage => "content-language".into(),
+        Attribute::ContentType => "content-type".into(),
+        Attribute::CacheControl => "cache-control".into(),
+        Attribute::Metadata(key) => format!("{METADATA_PREFIX}{key}").into(),
+    }
+}
+
+/// THIS FUNCTION IS ARAS ONLY
+///
+/// The inverse of [`attribute_name`], returning `None` for unrecognised names
+fn parse_attribute_name(name: &str) -> Option<Attribute> {
+    match name {
+        "content-disposition" => Some(Attribute::ContentDisposition),
+        "content-encoding" => Some(Attribute::ContentEncoding),
+        "content-language" => Some(Attribute::ContentLanguage),
+        "content-type" => Some(Attribute::ContentType),
+        "cache-control" => Some(Attribute::CacheControl),
+        _ => name
+            .strip_prefix(METADATA_PREFIX)
+            .map(|key| Attribute::Metadata(key.to_string().into())),
+    }
+}
+
+/// THIS FUNCTION IS ARAS ONLY
+///
+/// Stores `attributes` as `user.*` extended attributes of `file`, returning `false`
+/// if the filesystem does not support extended attributes
+#[cfg(target_os = "linux")]
+fn write_native_attributes(file: &File, attributes: &Attributes) -> io::Result<bool> {
+    use nix::libc;
+    use std::ffi::CString;
+    use std::os::unix::io::AsRawFd;
+
+    let fd = file.as_raw_fd();
+    if attributes.is_empty() {
+        // Probe for support, so that callers know whether to clear any sidecar file
+        return match read_xattr(|buf, len| unsafe { libc::flistxattr(fd, buf, len) }) {
+            Ok(_) => Ok(true),
+            Err(e) if e.raw_os_error() == Some(libc::ENOTSUP) => Ok(false),
+            Err(e) => Err(e),
+        };
+    }
+
+    for (attribute, value) in attributes {
+        let name = format!("{XATTR_PREFIX}{}", attribute_name(attribute));
+        let name = CString::new(name).map_err(|e| io::Error::new(ErrorKind::InvalidInput, e))?;
+        let value = value.as_bytes();
+        // SAFETY: `name` is NUL-terminated and `value` is valid for `value.len()` bytes
+        let ret =
+            unsafe { libc::fsetxattr(fd, name.as_ptr(), value.as_ptr().cast(), value.len(), 0) };
+        if ret != 0 {
+            let e = io::Error::last_os_error();
+            return match e.raw_os_error() {
+                Some(libc::ENOTSUP) => Ok(false),
+                _ => Err(e),
+            };
+        }
+    }
+    Ok(true)
+}
+
+/// THIS FUNCTION IS ARAS ONLY
+///
+/// Extended attributes are only supported on Linux, other platforms use sidecar files
+#[cfg(not(target_os = "linux"))]
+fn write_native_attributes(_file: &File, _attributes: &Attributes) -> io::Result<bool> {
+    Ok(false)
+}
+
+/// THIS FUNCTION IS ARAS ONLY
+///
+/// Reads the `user.*` extended attributes of `file`, returning `None` if the filesystem
+/// does not support extended attributes
+#[cfg(target_os = "linux")]
+fn read_native_attributes(file: &File) -> io::Result<Option<Attributes>> {
+    use nix::libc;
+    use std::ffi::CStr;
+    use std::os::unix::io::AsRawFd;
+
+    let fd = file.as_raw_fd();
+    let names = match read_xattr(|buf, len| unsafe { libc::flistxattr(fd, buf, len) }) {
+        Ok(names) => names,
+        Err(e) if e.raw_os_error() == Some(libc::ENOTSUP) => return Ok(None),
+        Err(e) => return Err(e),
+    };
+
+    let mut attributes = Attributes::new();
+    // The list is a sequence of NUL-terminated names
+    for name in names.split_inclusive(|b| *b == 0) {
+        let name = match CStr::from_bytes_with_nul(name) {
+            Ok(name) => name,
+            Err(_) => continue,
+        };
+        let attribute = name
+            .to_str()
+            .ok()
+            .and_then(|n| n.strip_prefix(XATTR_PREFIX))
+            .and_then(parse_attribute_name);
+        let attribute = match attribute {
+            Some(attribute) => attribute,
+            None => continue,
+        };
+        // SAFETY: `name` is NUL-terminated and `buf` is valid for `len` bytes
+        let value = match read_xattr(|buf, len| unsafe {
+            libc::fgetxattr(fd, name.as_ptr(), buf.cast(), len)
+        }) {
+            Ok(value) => value,
+            // Removed concurrently
+            Err(e) if e.raw_os_error() == Some(libc::ENODATA) => continue,
+            Err(e) => return Err(e),
+        };
+        let value =
+            String::from_utf8(value).map_err(|e| io::Error::new(ErrorKind::InvalidData, e))?;
+        attributes.insert(attribute, value.into());
+    }
+    Ok(Some(attributes))
+}
+
+/// THIS FUNCTION IS ARAS ONLY
+///
+/// Extended attributes are only supported on Linux, other platforms use sidecar files
+#[cfg(not(target_os = "linux"))]
+fn read_native_attributes(_file: &File) -> io::Result<Option<Attributes>> {
+    Ok(None)
+}
+
+/// THIS FUNCTION IS ARAS ONLY
+///
+/// Calls the `*xattr` function `f` first to size and then to fill a buffer, retrying
+/// if the value grows in between
+#[cfg(target_os = "linux")]
+fn read_xattr(f: impl Fn(*mut std::ffi::c_char, usize) -> isize) -> io::Result<Vec<u8>> {
+    loop {
+        let len = f(std::ptr::null_mut(), 0);
+        if len < 0 {
+            return Err(io::Error::last_os_error());
+        }
+        let mut buf = vec![0_u8; len as usize];
+        let read = f(buf.as_mut_ptr().cast(), buf.len());
+        if read >= 0 {
+            buf.truncate(read as usize);
+            return Ok(buf);
+        }
+        let e = io::Error::last_os_error();
+        if e.raw_os_error() != Some(nix::libc::ERANGE) {
+            return Err(e);
+        }
+    }
+}
+
+/// THIS FUNCTION IS ARAS ONLY
+///
+/// Returns the [`Attributes`] of the opened `file` at `path`, reading them from its
+/// sidecar file if `attribute_sidecars` is set and extended attributes are not supported
+fn read_attributes(
+    file: &File,
+    path: &std::path::Path,
+    attribute_sidecars: bool,
+) -> Result<Attributes, Error> {
+    match read_native_attributes(file) {
+        Ok(Some(attributes)) => Ok(attributes),
+        Ok(None) if attribute_sidecars => read_sidecar(path),
+        Ok(None) => Ok(Attributes::new()),
+        Err(source) => Err(Error::UnableToReadAttributes {
+            path: path.to_path_buf(),
+            source,
+        }),
+    }
+}
+
+/// THIS FUNCTION IS ARAS ONLY
+///
+/// Returns the [`Attributes`] of the file at `path`, or empty attributes if it does not exist
+fn read_path_attributes(
+    path: &std::path::Path,
+    attribute_sidecars: bool,
+) -> Result<Attributes, Error> {
+    match File::open(path) {
+        Ok(file) => read_attributes(&file, path, attribute_sidecars),
+        Err(e) if e.kind() == ErrorKind::NotFound => Ok(Attributes::new()),
+        Err(source) => Err(Error::UnableToOpenFile {
+            source,
+            path: path.to_path_buf(),
+        }),
+    }
+}
+
+/// THIS FUNCTION IS ARAS ONLY
+///
+/// Returns the path of the sidecar file storing the attributes of `path`
+fn sidecar_path(path: &std::path::Path) -> PathBuf {
+    staged_upload_path(path, ATTRIBUTES_SUFFIX)
 }
 
-/// Generates a unique file path `{base}#{suffix}`, returning the opened `File` and `path`
+/// THIS FUNCTION IS ARAS ONLY
 ///
-/// Creates any directories if necessary
-fn new_staged_upload(base: &std::path::Path) -> Result<(File, PathBuf)> {
-    let mut multipart_id = 1;
-    loop {
-        let suffix = multipart_id.to_string();
-        let path = staged_upload_path(base, &suffix);
-        let mut options = OpenOptions::new();
-        match options.read(true).write(true).create_new(true).open(&path) {
-            Ok(f) => return Ok((f, path)),
-            Err(source) => match source.kind() {
-                ErrorKind::AlreadyExists => multipart_id += 1,
-                ErrorKind::NotFound => create_parent_dirs(&path, source)?,
-                _ => return Err(Error::UnableToOpenFile { source, path }.into()),
-            },
+/// Writes `attributes` to the sidecar file of `path`, removing it if `attributes` is empty
+fn write_sidecar(path: &std::path::Path, attributes: &Attributes) -> Result<(), Error> {
+    let sidecar = sidecar_path(path);
+    let result = match attributes.is_empty() {
+        true => match std::fs::remove_file(&sidecar) {
+            Err(e) if e.kind() == ErrorKind::NotFound => Ok(()),
+            r => r,
+        },
+        false => {
+            let contents: String = attributes
+                .iter()
+                .map(|(attribute, value)| {
+                    let name = attribute_name(attribute);
+                    let name = utf8_percent_encode(&name, SIDECAR_ENCODE_SET);
+                    let value = utf8_percent_encode(value, SIDECAR_ENCODE_SET);
+                    format!("{name}={value}\n")
+                })
+                .collect();
+            std::fs::write(&sidecar, contents)
         }
-    }
+    };
+    result.map_err(|source| Error::UnableToWriteAttributes {
+        path: sidecar,
+        source,
+    })
 }
 
 /// THIS FUNCTION IS ARAS ONLY
 ///
-/// Creates a new staging file by copying from `base` if it exists,
-/// and seeks to the end so we can append new data.
-fn new_staged_upload_copy_and_append(base: &std::path::Path) -> Result<(File, PathBuf, u64)> {
-    let multipart_id = 1;
-    loop {
-        let suffix = multipart_id.to_string();
-        let path = staged_upload_path(base, &suffix);
+/// Removes the sidecar file of the removed or replaced `path`, if `attribute_sidecars` is set
+fn remove_sidecar(path: &std::path::Path, attribute_sidecars: bool) -> Result<(), Error> {
+    match attribute_sidecars {
+        true => write_sidecar(path, &Attributes::new()),
+        false => Ok(()),
+    }
+}
 
-        let offset = if base.exists() {
-            let metadata = std::fs::metadata(base).map_err(|source| Error::Metadata {
-                source: source.into(),
-                path: base.display().to_string(),
-            })?;
-            let offset = metadata.len();
+/// THIS FUNCTION IS ARAS ONLY
+///
+/// Writes `attributes` to the staged `file` at `staging_path`, as extended attributes where
+/// supported and otherwise to the sidecar file of `staging_path` if `attribute_sidecars`
+/// is set, discarding them if not
+///
+/// Returns `true` if the sidecar file must be moved into place with [`rename_sidecar`]
+/// before the staged file
+fn stage_attributes(
+    file: &File,
+    staging_path: &std::path::Path,
+    attributes: &Attributes,
+    attribute_sidecars: bool,
+) -> Result<bool, Error> {
+    let native = write_native_attributes(file, attributes).map_err(|source| {
+        Error::UnableToWriteAttributes {
+            path: staging_path.to_path_buf(),
+            source,
+        }
+    })?;
+    let sidecar = !native && attribute_sidecars;
+    if sidecar {
+        write_sidecar(staging_path, attributes)?;
+    }
+    Ok(sidecar)
+}
 
-            std::fs::copy(base, &path).map_err(|source| Error::UnableToCopyFile {
-                from: base.to_path_buf(),
-                to: path.clone(),
+/// THIS FUNCTION IS ARAS ONLY
+///
+/// Reads the attributes from the sidecar file of `path`, if any
+fn read_sidecar(path: &std::path::Path) -> Result<Attributes, Error> {
+    let sidecar = sidecar_path(path);
+    let contents = match std::fs::read_to_string(&sidecar) {
+        Ok(contents) => contents,
+        Err(e) if e.kind() == ErrorKind::NotFound => return Ok(Attributes::new()),
+        Err(source) => {
+            return Err(Error::UnableToReadAttributes {
+                path: sidecar,
                 source,
-            })?;
-
-            offset
-        } else {
-            0
-        };
+            })
+        }
+    };
+
+    Ok(contents
+        .lines()
+        .filter_map(|line| {
+            let (name, value) = line.split_once('=')?;
+            let name = percent_decode_str(name).decode_utf8_lossy();
+            let value = percent_decode_str(value).decode_utf8_lossy();
+            Some((parse_attribute_name(&name)?, value.into_owned()))
+        })
+        .collect())
+}
 
-        let mut options = OpenOptions::new();
-        match options.read(true).write(true).create(true).open(&path) {
-            Ok(mut f) => {
-                f.seek(SeekFrom::Start(offset))
-                    .map_err(|source| Error::Seek {
-                        source,
-                        path: path.clone(),
-                    })?;
-                return Ok((f, path, offset));
+/// THIS FUNCTION IS ARAS ONLY
+///
+/// Replaces the sidecar file of `to` with a copy of that of `from`
+fn copy_sidecar(from: &std::path::Path, to: &std::path::Path) -> Result<(), Error> {
+    let (from, to) = (sidecar_path(from), sidecar_path(to));
+    match std::fs::copy(&from, &to) {
+        Ok(_) => Ok(()),
+        Err(e) if e.kind() == ErrorKind::NotFound && !from.exists() => {
+            match std::fs::remove_file(&to) {
+                Err(e) if e.kind() != ErrorKind::NotFound => Err(Error::UnableToWriteAttributes {
+                    path: to,
+                    source: e,
+                }),
+                _ => Ok(()),
             }
-            Err(source) => match source.kind() {
-                ErrorKind::AlreadyExists => {
-                    return Err(Error::AlreadyExists {
-                        path: path.to_string_lossy().to_string(),
-                        source,
-                    }
-                    .into())
-                }
-                ErrorKind::NotFound => {
-                    create_parent_dirs(&path, source)?;
-                }
-                _ => return Err(Error::UnableToOpenFile { source, path }.into()),
-            },
         }
+        Err(source) => Err(Error::UnableToCopyFile { from, to, source }),
     }
 }
 
-/// Returns the unique upload for the given path and suffix
-fn staged_upload_path(dest: &std::path::Path, suffix: &str) -> PathBuf {
-    let mut staging_path = dest.as_os_str().to_owned();
-    staging_path.push("#");
-    staging_path.push(suffix);
-    staging_path.into()
+/// THIS FUNCTION IS ARAS ONLY
+///
+/// Replaces the sidecar file of `to` with that of `from`
+fn rename_sidecar(from: &std::path::Path, to: &std::path::Path) -> Result<(), Error> {
+    let (from, to) = (sidecar_path(from), sidecar_path(to));
+    match std::fs::rename(&from, &to) {
+        Ok(_) => Ok(()),
+        Err(e) if e.kind() == ErrorKind::NotFound => match std::fs::remove_file(&to) {
+            Err(e) if e.kind() != ErrorKind::NotFound => Err(Error::UnableToWriteAttributes {
+                path: to,
+                source: e,
+            }),
+            _ => Ok(()),
+        },
+        Err(source) => Err(Error::UnableToCopyFile { from, to, source }),
+    }
 }
 
 #[derive(Debug)]
@@ -826,6 +1885,14 @@ struct LocalUpload {
     src: Option<PathBuf>,
     /// The next offset to write into the file
     offset: u64,
+    /// THIS FIELD IS ARAS ONLY
+    ///
+    /// The attributes to store on completion
+    attributes: Attributes,
+    /// THIS FIELD IS ARAS ONLY
+    ///
+    /// Whether the attributes may be stored in a sidecar file
+    attribute_sidecars: bool,
 }
 
 #[derive(Debug)]
@@ -836,7 +1903,14 @@ struct UploadState {
 
 impl LocalUpload {
     /// THIS METHOD IS COMMON, MODIFIED BY ARAS
-    pub(crate) fn new(src: PathBuf, dest: PathBuf, file: File, offset: u64) -> Self {
+    pub(crate) fn new(
+        src: PathBuf,
+        dest: PathBuf,
+        file: File,
+        offset: u64,
+        attributes: Attributes,
+        attribute_sidecars: bool,
+    ) -> Self {
         Self {
             state: Arc::new(UploadState {
                 dest,
@@ -844,6 +1918,8 @@ impl LocalUpload {
             }),
             src: Some(src),
             offset,
+            attributes,
+            attribute_sidecars,
         }
     }
 }
@@ -871,12 +1947,18 @@ impl MultipartUpload for LocalUpload {
         .boxed()
     }
 
+    /// THIS METHOD IS COMMON, MODIFIED BY ARAS
     async fn complete(&mut self) -> Result<PutResult> {
         let src = self.src.take().ok_or(Error::Aborted)?;
         let s = Arc::clone(&self.state);
+        let attributes = std::mem::take(&mut self.attributes);
+        let sidecars = self.attribute_sidecars;
         maybe_spawn_blocking(move || {
             // Ensure no inflight writes
             let file = s.file.lock();
+            if stage_attributes(&file, &src, &attributes, sidecars)? {
+                rename_sidecar(&src, &s.dest)?;
+            }
             std::fs::rename(&src, &s.dest)
                 .map_err(|source| Error::UnableToRenameFile { source })?;
             let metadata = file.metadata().map_err(|e| Error::Metadata {
@@ -1059,6 +2141,91 @@ fn last_modified(metadata: &Metadata) -> DateTime<Utc> {
         .into()
 }
 
+/// THIS FUNCTION IS ARAS ONLY
+///
+/// Checks the file at `path` has the e-tag `expected`, returning [`Error::Precondition`]
+/// if it does not or if the file does not exist
+fn check_etag(path: &std::path::Path, expected: &str) -> Result<(), Error> {
+    let precondition = |message: String| Error::Precondition {
+        path: path.to_string_lossy().to_string(),
+        message,
+    };
+    let existing = match std::fs::metadata(path) {
+        Ok(metadata) if !metadata.is_dir() => get_etag(&metadata),
+        Ok(_) => return Err(precondition(format!("{} is a directory", path.display()))),
+        Err(e) if e.kind() == ErrorKind::NotFound => {
+            return Err(precondition(format!("{} not found", path.display())))
+        }
+        Err(e) => {
+            return Err(Error::Metadata {
+                source: e.into(),
+                path: path.to_string_lossy().to_string(),
+            })
+        }
+    };
+    match existing == expected {
+        true => Ok(()),
+        false => Err(precondition(format!(
+            "{existing} does not match {expected}"
+        ))),
+    }
+}
+
+/// THIS FUNCTION IS ARAS ONLY
+///
+/// Atomically replaces `path` with `staging_path` if the e-tag of `path` matches `e_tag`,
+/// first moving the staged sidecar file of `staging_path` into place if `sidecar` is set
+fn update_file(
+    staging_path: &std::path::Path,
+    path: &std::path::Path,
+    e_tag: Option<&str>,
+    sidecar: bool,
+) -> Result<(), Error> {
+    let e_tag = e_tag.ok_or(Error::MissingETag)?;
+    let _lock = lock_parent(path)?;
+    check_etag(path, e_tag)?;
+    if sidecar {
+        rename_sidecar(staging_path, path)?;
+    }
+    std::fs::rename(staging_path, path).map_err(|source| Error::UnableToRenameFile { source })
+}
+
+/// THIS FUNCTION IS ARAS ONLY
+///
+/// Takes an exclusive advisory lock on the parent directory of `path`, which is released
+/// when the returned guard is dropped
+///
+/// This serializes conditional updates and deletes of files within the same directory,
+/// but does not protect against concurrent unconditional writes
+#[cfg(unix)]
+fn lock_parent(path: &std::path::Path) -> Result<nix::fcntl::Flock<File>, Error> {
+    use nix::fcntl::{Flock, FlockArg};
+
+    let parent = path.parent().unwrap_or(path);
+    let dir = File::open(parent).map_err(|source| Error::UnableToOpenFile {
+        path: parent.to_path_buf(),
+        source,
+    })?;
+    Flock::lock(dir, FlockArg::LockExclusive).map_err(|(_, errno)| Error::UnableToLockDirectory {
+        path: parent.to_path_buf(),
+        source: errno.into(),
+    })
+}
+
+/// THIS FUNCTION IS ARAS ONLY
+///
+/// On platforms without advisory directory locks, conditional updates are not supported
+#[cfg(not(unix))]
+fn lock_parent(path: &std::path::Path) -> Result<(), Error> {
+    Err(Error::UnableToLockDirectory {
+        path: path.to_path_buf(),
+        source: io::Error::new(
+            ErrorKind::Unsupported,
+            "advisory locking is not supported on this platform",
+        ),
+    })
+}
+
 fn get_etag(metadata: &Metadata) -> String {
     let inode = get_inode(metadata);
     let size = metadata.len();
@@ -1175,7 +2342,67 @@ mod tests {
         copy_if_not_exists(&integration).await;
         copy_rename_nonexistent_object(&integration).await;
         stream_get(&integration).await;
-        put_opts(&integration, false).await;
+        put_opts(&integration, true).await;
+        // Attributes are only kept without extended attributes if stored in sidecar files
+        let sidecars = LocalFileSystem::new_with_prefix(root.path())
+            .unwrap()
+            .with_attribute_sidecars(true);
+        put_get_attributes(&sidecars).await;
+        append(&integration).await;
+        delete_opts(&integration).await;
+        compose(&integration).await;
+        multipart(&integration, &integration).await;
+        list_multipart_uploads(&integration).await;
+    }
+
+    /// THIS TEST IS ARAS ONLY
+    #[tokio::test]
+    async fn file_test_paginated() {
+        // Empty directories are listed as common prefixes, and so use a new root
+        let root = TempDir::new().unwrap();
+        let integration = LocalFileSystem::new_with_prefix(root.path()).unwrap();
+        list_paginated(&integration, &integration).await;
+
+        // Pages are in key order, where directories include the delimiter
+        let root = TempDir::new().unwrap();
+        let integration = LocalFileSystem::new_with_prefix(root.path()).unwrap();
+        for path in ["a-b", "a/c", "a/d/e", "a0"] {
+            integration.put(&path.into(), "data".into()).await.unwrap();
+        }
+        let mut opts = PaginatedListOptions {
+            max_keys: Some(2),
+            offset: Some("a".to_string()),
+            ..Default::default()
+        };
+        let mut listed = vec![];
+        loop {
+            let page = integration
+                .list_paginated(Some("a"), opts.clone())
+                .await
+                .unwrap();
+            listed.extend(page.result.objects.into_iter().map(|m| m.location));
+            match page.page_token {
+                Some(token) => opts.page_token = Some(token),
+                None => break,
+            }
+        }
+        let expected: Vec<Path> = vec!["a-b".into(), "a/c".into(), "a/d/e".into(), "a0".into()];
+        assert_eq!(listed, expected);
+
+        opts.page_token = None;
+        opts.delimiter = Some("/".into());
+        let page = integration.list_paginated(None, opts).await.unwrap();
+        assert_eq!(page.result.objects[0].location, Path::from("a-b"));
+        assert_eq!(page.result.common_prefixes, vec![Path::from("a")]);
+        let opts = PaginatedListOptions {
+            page_token: page.page_token,
+            delimiter: Some("/".into()),
+            ..Default::default()
+        };
+        let page = integration.list_paginated(None, opts).await.unwrap();
+        assert_eq!(page.result.objects[0].location, Path::from("a0"));
+        assert!(page.result.common_prefixes.is_empty());
+        assert!(page.page_token.is_none());
     }
 
     #[test]
@@ -1288,6 +2515,44 @@ mod tests {
         assert_eq!(&*read_data, data);
     }
 
+    /// THIS TEST IS ARAS ONLY
+    #[tokio::test]
+    async fn get_ranges_with_policy() {
+        let root = TempDir::new().unwrap();
+        let policy = RangeCoalescePolicy {
+            max_gap: 2,
+            max_request_size: 4,
+            parallelism: 2,
+        };
+        let integration = LocalFileSystem::new_with_prefix(root.path())
+            .unwrap()
+            .with_range_coalesce_policy(policy);
+
+        let location = Path::from("some_file");
+        let data = Bytes::from("arbitrary data");
+        integration
+            .put(&location, data.clone().into())
+            .await
+            .unwrap();
+
+        let ranges = [0..9, 2..3, 10..12, 13..14];
+        let read = integration.get_ranges(&location, &ranges).await.unwrap();
+        assert_eq!(read, vec!["arbitrary", "b", "da", "a"]);
+
+        let policy = RangeCoalescePolicy::default();
+        let read = integration
+            .get_ranges_opts(&location, &ranges, policy)
+            .await
+            .unwrap();
+        assert_eq!(read, vec!["arbitrary", "b", "da", "a"]);
+
+        // Ranges starting beyond the end of the file are still an error
+        integration
+            .get_ranges(&location, &[0..1, 100..101])
+            .await
+            .unwrap_err();
+    }
+
     #[tokio::test]
     #[cfg(target_family = "unix")]
     // Fails on github actions runner (which runs the tests as root)
@@ -1558,6 +2823,151 @@ mod tests {
         );
     }
 
+    /// THIS TEST IS ARAS ONLY
+    #[tokio::test]
+    async fn test_multipart_store() {
+        let root = TempDir::new().unwrap();
+        let store = LocalFileSystem::new_with_prefix(root.path()).unwrap();
+        // Parts may be uploaded by other processes sharing the filesystem
+        let other = LocalFileSystem::new_with_prefix(root.path()).unwrap();
+        let location = Path::from("dir/file");
+
+        let id = store.create_multipart(&location).await.unwrap();
+        let p1 = other.put_part(&location, &id, 1, "world".into()).await;
+        let p0 = store.put_part(&location, &id, 0, "hello ".into()).await;
+        let parts = vec![p0.unwrap(), p1.unwrap()];
+
+        // Staged parts are not listed
+        assert!(flatten_list_stream(&store, None).await.unwrap().is_empty());
+        let list = store
+            .list_with_delimiter(Some(&"dir".into()))
+            .await
+            .unwrap();
+        assert!(list.objects.is_empty() && list.common_prefixes.is_empty());
+
+        // Overwritten parts no longer match their PartId
+        let mut stale = parts.clone();
+        other
+            .put_part(&location, &id, 0, "hello ".into())
+            .await
+            .unwrap();
+        stale[0].content_id.push('0');
+        let err = store.complete_multipart(&location, &id, stale).await;
+        assert!(err.unwrap_err().to_string().contains("Part 0"));
+
+        let parts = vec![
+            other
+                .put_part(&location, &id, 0, "hello ".into())
+                .await
+                .unwrap(),
+            parts[1].clone(),
+        ];
+        let result = other
+            .complete_multipart(&location, &id, parts)
+            .await
+            .unwrap();
+        let get = store.get(&location).await.unwrap();
+        assert_eq!(get.meta.e_tag, result.e_tag);
+        assert_eq!(get.bytes().await.unwrap(), "hello world");
+
+        // The upload is removed on completion
+        let err = store.put_part(&location, &id, 0, "data".into()).await;
+        assert!(matches!(err, Err(crate::Error::NotFound { .. })));
+
+        let id = store.create_multipart(&location).await.unwrap();
+        store
+            .put_part(&location, &id, 0, "data".into())
+            .await
+            .unwrap();
+        store.abort_multipart(&location, &id).await.unwrap();
+        let err = store.abort_multipart(&location, &id).await.unwrap_err();
+        assert!(matches!(err, crate::Error::NotFound { .. }), "{err}");
+        assert_eq!(store.get(&location).await.unwrap().meta.size, 11);
+
+        // Directories with the name of a staging directory are not uploads
+        let user = Path::from("file#2/data");
+        store.put(&user, "data".into()).await.unwrap();
+        let err = store
+            .abort_multipart(&"file".into(), &"2".to_string())
+            .await;
+        assert!(matches!(err, Err(crate::Error::NotFound { .. })));
+        assert_eq!(store.get(&user).await.unwrap().meta.size, 4);
+
+        // Ids are validated to not escape the staging directory
+        let id = "1/../../other".to_string();
+        let err = store.put_part(&location, &id, 0, "data".into()).await;
+        assert!(err
+            .unwrap_err()
+            .to_string()
+            .contains("Invalid multipart upload id"));
+    }
+
+    /// THIS TEST IS ARAS ONLY
+    #[tokio::test]
+    async fn test_abort_stale_uploads() {
+        use crate::multipart::abort_stale_multipart_uploads;
+        use std::time::Duration;
+
+        let root = TempDir::new().unwrap();
+        let store = LocalFileSystem::new_with_prefix(root.path()).unwrap();
+
+        let location = Path::from("a/upload");
+        let id = store.create_multipart(&location).await.unwrap();
+        store
+            .put_part(&location, &id, 1, "world".into())
+            .await
+            .unwrap();
+        store
+            .put_part(&location, &id, 0, "hello ".into())
+            .await
+            .unwrap();
+
+        // A file staged by a write that never completed
+        let staged = Path::from("a/staged");
+        fs::write(root.path().join("a/staged#3"), "partial").unwrap();
+        // Directories with the name of a staged file are not uploads
+        fs::create_dir_all(root.path().join("b/dir#4")).unwrap();
+        fs::write(root.path().join("b/dir#4/file"), "data").unwrap();
+
+        let mut uploads: Vec<_> = store
+            .list_multipart_uploads(None)
+            .try_collect()
+            .await
+            .unwrap();
+        uploads.sort_by(|a, b| a.location.cmp(&b.location));
+        let listed: Vec<_> = uploads
+            .iter()
+            .map(|u| (u.location.as_ref(), u.id.as_str()))
+            .collect();
+        assert_eq!(listed, vec![("a/staged", "3"), ("a/upload", id.as_str())]);
+
+        let prefix = Path::from("b");
+        let uploads = store.list_multipart_uploads(Some(&prefix));
+        assert!(uploads.try_collect::<Vec<_>>().await.unwrap().is_empty());
+
+        let parts = store.list_parts(&location, &id).await.unwrap();
+        let sizes: Vec<_> = parts.iter().map(|p| (p.part_idx, p.size)).collect();
+        assert_eq!(sizes, vec![(0, 6), (1, 5)]);
+        let id3 = "3".to_string();
+        assert!(store.list_parts(&staged, &id3).await.unwrap().is_empty());
+
+        let hour = Duration::from_secs(60 * 60);
+        let aborted = abort_stale_multipart_uploads(&store, None, hour).await;
+        assert_eq!(aborted.unwrap(), 0);
+
+        // Ensure the uploads were modified before the cutoff
+        tokio::time::sleep(Duration::from_millis(10)).await;
+        let aborted = abort_stale_multipart_uploads(&store, None, Duration::ZERO).await;
+        assert_eq!(aborted.unwrap(), 2);
+
+        let uploads = store.list_multipart_uploads(None);
+        assert!(uploads.try_collect::<Vec<_>>().await.unwrap().is_empty());
+        assert!(!root.path().join("a/staged#3").exists());
+        assert!(root.path().join("b/dir#4/file").exists());
+        let err = store.abort_multipart(&staged, &id3).await.unwrap_err();
+        assert!(matches!(err, crate::Error::NotFound { .. }), "{err}");
+    }
+
     #[tokio::test]
     async fn test_path_with_offset() {
         let root = TempDir::new().unwrap();
@@ -1658,8 +3068,128 @@ mod tests {
 
         for (case, expected) in cases {
             let path = Path::parse(case).unwrap();
-            assert_eq!(is_valid_file_path(&path), expected);
+            assert_eq!(is_valid_file_path(&path, false), expected);
+            assert_eq!(is_valid_file_path(&path, true), expected);
+        }
+
+        // THESE CASES ARE ARAS ONLY
+        let cases = [
+            ("foo/test#attributes", true, false),
+            ("foo/test#1#attributes", true, false),
+            ("foo/test#attributes.txt", true, true),
+        ];
+
+        for (case, expected, expected_sidecars) in cases {
+            let path = Path::parse(case).unwrap();
+            assert_eq!(is_valid_file_path(&path, false), expected);
+            assert_eq!(is_valid_file_path(&path, true), expected_sidecars);
+        }
+    }
+
+    /// THIS TEST IS ARAS ONLY
+    #[tokio::test]
+    async fn test_attributes_sidecar() {
+        let root = TempDir::new().unwrap();
+        let integration = LocalFileSystem::new_with_prefix(root.path())
+            .unwrap()
+            .with_attribute_sidecars(true);
+
+        let a = Path::from("a");
+        integration.put(&a, "test".into()).await.unwrap();
+        let path = integration.path_to_filesystem(&a).unwrap();
+
+        // Simulate a filesystem without extended attributes
+        let attributes = Attributes::from_iter([
+            (Attribute::ContentType, "text/plain"),
+            (Attribute::Metadata("k=v%".into()), "line\nbreak"),
+        ]);
+        write_sidecar(&path, &attributes).unwrap();
+        assert_eq!(read_sidecar(&path).unwrap(), attributes);
+
+        let r = integration.get(&a).await.unwrap();
+        if read_native_attributes(&File::open(&path).unwrap())
+            .unwrap()
+            .is_none()
+        {
+            assert_eq!(r.attributes, attributes);
+        }
+
+        // Sidecar files are not listed, and follow their file
+        let list = flatten_list_stream(&integration, None).await.unwrap();
+        assert_eq!(list, vec![a.clone()]);
+
+        let b = Path::from("b");
+        integration.copy(&a, &b).await.unwrap();
+        let b_path = integration.path_to_filesystem(&b).unwrap();
+        assert_eq!(read_sidecar(&b_path).unwrap(), attributes);
+
+        let c = Path::from("c");
+        integration.rename(&b, &c).await.unwrap();
+        let c_path = integration.path_to_filesystem(&c).unwrap();
+        assert_eq!(read_sidecar(&c_path).unwrap(), attributes);
+        assert!(!sidecar_path(&b_path).exists());
+
+        integration.delete(&a).await.unwrap();
+        integration.delete(&c).await.unwrap();
+        assert!(!sidecar_path(&path).exists());
+        assert!(!sidecar_path(&c_path).exists());
+
+        // Without sidecars, files ending in `#attributes` are regular files
+        let integration = LocalFileSystem::new_with_prefix(root.path()).unwrap();
+        let d = Path::from("d#attributes");
+        integration.put(&d, "test".into()).await.unwrap();
+        let list = flatten_list_stream(&integration, None).await.unwrap();
+        assert_eq!(list, vec![d.clone()]);
+
+        // And are not touched when deleting their namesake
+        let e = Path::from("d");
+        integration.put(&e, "test".into()).await.unwrap();
+        integration.delete(&e).await.unwrap();
+        integration.head(&d).await.unwrap();
+    }
+
+    /// THIS TEST IS ARAS ONLY
+    #[tokio::test]
+    async fn test_put_failure_cleanup() {
+        let root = TempDir::new().unwrap();
+        let integration = LocalFileSystem::new_with_prefix(root.path())
+            .unwrap()
+            .with_attribute_sidecars(true);
+
+        let a = Path::from("a");
+        let result = integration.put(&a, "test".into()).await.unwrap();
+
+        let attributes = Attributes::from_iter([(Attribute::ContentType, "text/plain")]);
+        let opts = PutOptions {
+            mode: PutMode::Create,
+            attributes: attributes.clone(),
+            ..Default::default()
+        };
+        let err = integration.put_opts(&a, "new".into(), opts).await;
+        assert!(matches!(err, Err(crate::Error::AlreadyExists { .. })));
+
+        if cfg!(unix) {
+            let opts = PutOptions {
+                mode: PutMode::Update(crate::UpdateVersion {
+                    e_tag: Some("invalid".to_string()),
+                    version: None,
+                }),
+                attributes,
+                ..Default::default()
+            };
+            let err = integration.put_opts(&a, "new".into(), opts).await;
+            assert!(matches!(err, Err(crate::Error::Precondition { .. })));
         }
+
+        // Neither the staged file nor its attributes are left behind
+        let files: Vec<_> = std::fs::read_dir(root.path())
+            .unwrap()
+            .map(|e| e.unwrap().file_name())
+            .collect();
+        assert_eq!(files, ["a"]);
+        let r = integration.get(&a).await.unwrap();
+        assert_eq!(r.meta.e_tag, result.e_tag);
+        assert!(r.attributes.is_empty());
     }
 
     #[tokio::test]
@@ -1681,7 +3211,7 @@ mod tests {
 
         let b = Path::parse("bar#123").unwrap();
         let err = integration.get(&b).await.unwrap_err().to_string();
-        assert_eq!(err, "Generic LocalFileSystem error: Filenames containing trailing '/#\\d+/' are not supported: bar#123");
+        assert_eq!(err, "Generic LocalFileSystem error: Filenames containing trailing '/#\\d+/', or '#attributes' with attribute sidecars, are not supported: bar#123");
 
         let c = Path::parse("foo#123.txt").unwrap();
         integration.put(&c, "test".into()).await.unwrap();
@@ -26,14 +26,20 @@ use chrono::{DateTime, Utc};
 use futures::{stream::BoxStream, StreamExt};
 use parking_lot::RwLock;
 
+use crate::list::{
+    is_delimited, page_start, paginate_sorted, prefix_directory, ListEntry, PaginatedListOptions,
+    PaginatedListResult, PaginatedListStore,
+};
 use crate::multipart::{MultipartStore, PartId};
 use crate::util::InvalidGetRange;
+use crate::version::{ObjectVersion, VersionedStore};
 use crate::{
-    path::Path, Attributes, GetRange, GetResult, GetResultPayload, ListResult, MultipartId,
-    MultipartUpload, ObjectMeta, ObjectStore, PutMode, PutMultipartOpts, PutOptions, PutResult,
-    Result, UpdateVersion, UploadPart,
+    path::{Path, DELIMITER},
+    Attributes, GetRange, GetResult, GetResultPayload, ListResult, MultipartId, MultipartUpload,
+    ObjectMeta, ObjectStore, PutMode, PutMultipartOpts, PutOptions, PutResult, Result, TagSet,
+    TaggingStore, UpdateVersion, UploadPart,
 };
-use crate::{GetOptions, PutPayload};
+use crate::{DeleteOptions, GetOptions, PutPayload};
 
 /// A specialized `Error` for in-memory object store-related errors
 #[derive(Debug, thiserror::Error)]
@@ -55,6 +61,9 @@ enum Error {
 
     #[error("Missing part at index: {part}")]
     MissingPart { part: usize },
+
+    #[error("Version {version} not found. Location: {path}")]
+    VersionNotFound { path: String, version: String },
 }
 
 impl From<Error> for super::Error {
@@ -68,6 +77,10 @@ impl From<Error> for super::Error {
                 path: path.into(),
                 source: source.into(),
             },
+            Error::VersionNotFound { ref path, .. } => Self::NotFound {
+                path: path.into(),
+                source: source.into(),
+            },
             _ => Self::Generic {
                 store: "InMemory",
                 source: Box::new(source),
@@ -78,40 +91,68 @@ impl From<Error> for super::Error {
 
 /// In-memory storage suitable for testing or for opting out of using a cloud
 /// storage provider.
+///
+/// By default only the current version of each object is retained, see
+/// [`InMemory::with_versioning`] to retain every version for use with [`VersionedStore`]
 #[derive(Debug, Default)]
 pub struct InMemory {
     storage: SharedStorage,
 }
 
+/// THIS STRUCT IS COMMON, MODIFIED BY ARAS
 #[derive(Debug, Clone)]
 struct Entry {
     data: Bytes,
     last_modified: DateTime<Utc>,
     attributes: Attributes,
     e_tag: usize,
+    /// THIS FIELD IS ARAS ONLY
+    tags: TagSet,
 }
 
 impl Entry {
+    /// THIS METHOD IS COMMON, MODIFIED BY ARAS
     fn new(
         data: Bytes,
         last_modified: DateTime<Utc>,
         e_tag: usize,
         attributes: Attributes,
+        tags: TagSet,
     ) -> Self {
         Self {
             data,
             last_modified,
             e_tag,
             attributes,
+            tags,
         }
     }
 }
 
+/// THIS STRUCT IS ARAS ONLY
+///
+/// A version of an object, with an `entry` of `None` for a delete marker
+#[derive(Debug, Clone)]
+struct Version {
+    id: usize,
+    last_modified: DateTime<Utc>,
+    entry: Option<Entry>,
+}
+
+/// THIS STRUCT IS COMMON, MODIFIED BY ARAS
 #[derive(Debug, Default, Clone)]
 struct Storage {
     next_etag: usize,
     map: BTreeMap<Path, Entry>,
     uploads: HashMap<usize, PartStorage>,
+    /// THIS FIELD IS ARAS ONLY
+    ///
+    /// The version history of each object, oldest first
+    versions: BTreeMap<Path, Vec<Version>>,
+    /// THIS FIELD IS ARAS ONLY
+    ///
+    /// Whether to record the version history in `versions`
+    versioning: bool,
 }
 
 #[derive(Debug, Default, Clone)]
@@ -122,18 +163,48 @@ struct PartStorage {
 type SharedStorage = Arc<RwLock<Storage>>;
 
 impl Storage {
-    fn insert(&mut self, location: &Path, bytes: Bytes, attributes: Attributes) -> usize {
+    /// THIS METHOD IS COMMON, MODIFIED BY ARAS
+    fn insert(
+        &mut self,
+        location: &Path,
+        bytes: Bytes,
+        attributes: Attributes,
+        tags: TagSet,
+    ) -> usize {
         let etag = self.next_etag;
         self.next_etag += 1;
-        let entry = Entry::new(bytes, Utc::now(), etag, attributes);
+        let entry = Entry::new(bytes, Utc::now(), etag, attributes, tags);
         self.overwrite(location, entry);
         etag
     }
 
+    /// THIS METHOD IS COMMON, MODIFIED BY ARAS
     fn overwrite(&mut self, location: &Path, entry: Entry) {
+        self.push_version(location, Some(entry.clone()));
         self.map.insert(location.clone(), entry);
     }
 
+    /// THIS METHOD IS ARAS ONLY
+    ///
+    /// Appends the data of `entry` to any existing object at `location`, retaining
+    /// the existing attributes and tags if `entry` does not provide any
+    fn append(&mut self, location: &Path, mut entry: Entry) {
+        if let Some(existing) = self.map.get(location) {
+            let mut buf = Vec::with_capacity(existing.data.len() + entry.data.len());
+            buf.extend_from_slice(&existing.data);
+            buf.extend_from_slice(&entry.data);
+            entry.data = buf.into();
+            if entry.attributes.is_empty() {
+                entry.attributes = existing.attributes.clone();
+            }
+            if entry.tags.is_empty() {
+                entry.tags = existing.tags.clone();
+            }
+        }
+        self.overwrite(location, entry)
+    }
+
+    /// THIS METHOD IS COMMON, MODIFIED BY ARAS
     fn create(&mut self, location: &Path, entry: Entry) -> Result<()> {
         use std::collections::btree_map;
         match self.map.entry(location.clone()) {
@@ -142,12 +213,14 @@ impl Storage {
             }
             .into()),
             btree_map::Entry::Vacant(v) => {
-                v.insert(entry);
+                v.insert(entry.clone());
+                self.push_version(location, Some(entry));
                 Ok(())
             }
         }
     }
 
+    /// THIS METHOD IS COMMON, MODIFIED BY ARAS
     fn update(&mut self, location: &Path, v: UpdateVersion, entry: Entry) -> Result<()> {
         match self.map.get_mut(location) {
             // Return Precondition instead of NotFound for consistency with stores
@@ -159,7 +232,8 @@ impl Storage {
                 let existing = e.e_tag.to_string();
                 let expected = v.e_tag.ok_or(Error::MissingETag)?;
                 if existing == expected {
-                    *e = entry;
+                    *e = entry.clone();
+                    self.push_version(location, Some(entry));
                     Ok(())
                 } else {
                     Err(crate::Error::Precondition {
@@ -171,6 +245,131 @@ impl Storage {
         }
     }
 
+    /// THIS METHOD IS ARAS ONLY
+    ///
+    /// Removes the object at `location`, recording a delete marker if it existed
+    fn delete(&mut self, location: &Path) {
+        if self.map.remove(location).is_some() {
+            self.push_version(location, None);
+        }
+    }
+
+    /// THIS METHOD IS ARAS ONLY
+    ///
+    /// Removes the object at `location` if it matches the preconditions in `opts`
+    fn delete_opts(&mut self, location: &Path, opts: &DeleteOptions) -> Result<()> {
+        let expected = [&opts.if_match, &opts.version];
+        if expected.iter().all(|x| x.is_none()) {
+            self.delete(location);
+            return Ok(());
+        }
+
+        let existing = match self.map.get(location) {
+            Some(e) => e.e_tag.to_string(),
+            // Return Precondition instead of NotFound for consistency with stores
+            None => {
+                return Err(crate::Error::Precondition {
+                    path: location.to_string(),
+                    source: format!("Object at location {location} not found").into(),
+                })
+            }
+        };
+        for expected in expected.into_iter().flatten() {
+            if &existing != expected {
+                return Err(crate::Error::Precondition {
+                    path: location.to_string(),
+                    source: format!("{existing} does not match {expected}").into(),
+                });
+            }
+        }
+        self.delete(location);
+        Ok(())
+    }
+
+    /// THIS METHOD IS ARAS ONLY
+    ///
+    /// Returns the version id reported for the entry with `e_tag`, if versioning is enabled
+    fn version_id(&self, e_tag: usize) -> Option<String> {
+        self.versioning.then(|| e_tag.to_string())
+    }
+
+    /// Records a new current version of the object at `location`, if versioning is enabled
+    fn push_version(&mut self, location: &Path, entry: Option<Entry>) {
+        if !self.versioning {
+            return;
+        }
+        let (id, last_modified) = match &entry {
+            Some(e) => (e.e_tag, e.last_modified),
+            None => {
+                let id = self.next_etag;
+                self.next_etag += 1;
+                (id, Utc::now())
+            }
+        };
+        let version = Version {
+            id,
+            last_modified,
+            entry,
+        };
+        self.versions
+            .entry(location.clone())
+            .or_default()
+            .push(version);
+    }
+
+    /// THIS METHOD IS ARAS ONLY
+    ///
+    /// Returns the [`Entry`] for the specified version of the object at `location`
+    ///
+    /// If versioning is disabled, only the current version can be returned
+    fn version(&self, location: &Path, version: &str) -> Result<Entry> {
+        let entry = match self.versioning {
+            true => self
+                .versions
+                .get(location)
+                .and_then(|versions| versions.iter().find(|v| v.id.to_string() == version))
+                .and_then(|v| v.entry.clone()),
+            false => (self.map.get(location))
+                .filter(|e| e.e_tag.to_string() == version)
+                .cloned(),
+        };
+        entry.ok_or_else(|| {
+            Error::VersionNotFound {
+                path: location.to_string(),
+                version: version.to_string(),
+            }
+            .into()
+        })
+    }
+
+    /// THIS METHOD IS ARAS ONLY
+    ///
+    /// Permanently removes the specified version of the object at `location`, making
+    /// the previous version current if `version` was the current version
+    fn delete_version(&mut self, location: &Path, version: &str) -> Result<()> {
+        let not_found = || Error::VersionNotFound {
+            path: location.to_string(),
+            version: version.to_string(),
+        };
+        let versions = self.versions.get_mut(location).ok_or_else(not_found)?;
+        let idx = versions
+            .iter()
+            .position(|v| v.id.to_string() == version)
+            .ok_or_else(not_found)?;
+        versions.remove(idx);
+
+        if idx == versions.len() {
+            match versions.last().and_then(|v| v.entry.clone()) {
+                Some(entry) => self.map.insert(location.clone(), entry),
+                None => self.map.remove(location),
+            };
+        }
+        if versions.is_empty() {
+            self.versions.remove(location);
+        }
+        Ok(())
+    }
+
     fn upload_mut(&mut self, id: &MultipartId) -> Result<&mut PartStorage> {
         let parts = id
             .parse()
@@ -206,18 +405,19 @@ impl ObjectStore for InMemory {
     ) -> Result<PutResult> {
         let mut storage = self.storage.write();
         let etag = storage.next_etag;
-        let entry = Entry::new(payload.into(), Utc::now(), etag, opts.attributes);
+        let entry = Entry::new(payload.into(), Utc::now(), etag, opts.attributes, opts.tags);
 
         match opts.mode {
             PutMode::Overwrite => storage.overwrite(location, entry),
             PutMode::Create => storage.create(location, entry)?,
             PutMode::Update(v) => storage.update(location, v, entry)?,
+            PutMode::Append => storage.append(location, entry),
         }
         storage.next_etag += 1;
 
         Ok(PutResult {
             e_tag: Some(etag.to_string()),
-            version: None,
+            version: storage.version_id(etag),
         })
     }
 
@@ -229,21 +429,31 @@ impl ObjectStore for InMemory {
         Ok(Box::new(InMemoryUpload {
             location: location.clone(),
             attributes: opts.attributes,
+            tags: opts.tags,
+            copy_and_append: match opts.mode {
+                PutMode::Overwrite => false,
+                PutMode::Append => true,
+                PutMode::Create | PutMode::Update(_) => return Err(crate::Error::NotImplemented),
+            },
             parts: vec![],
             storage: Arc::clone(&self.storage),
         }))
     }
 
+    /// THIS METHOD IS COMMON, MODIFIED BY ARAS
     async fn get_opts(&self, location: &Path, options: GetOptions) -> Result<GetResult> {
-        let entry = self.entry(location)?;
-        let e_tag = entry.e_tag.to_string();
+        let entry = match &options.version {
+            Some(version) => self.storage.read().version(location, version)?,
+            None => self.entry(location)?,
+        };
+        let version = self.storage.read().version_id(entry.e_tag);
 
         let meta = ObjectMeta {
             location: location.clone(),
             last_modified: entry.last_modified,
             size: entry.data.len() as u64,
-            e_tag: Some(e_tag),
-            version: None,
+            e_tag: Some(entry.e_tag.to_string()),
+            version,
         };
         options.check_preconditions(&meta)?;
 
@@ -294,6 +504,7 @@ impl ObjectStore for InMemory {
             .collect()
     }
 
+    /// THIS METHOD IS COMMON, MODIFIED BY ARAS
     async fn head(&self, location: &Path) -> Result<ObjectMeta> {
         let entry = self.entry(location)?;
 
@@ -302,15 +513,21 @@ impl ObjectStore for InMemory {
             last_modified: entry.last_modified,
             size: entry.data.len() as u64,
             e_tag: Some(entry.e_tag.to_string()),
-            version: None,
+            version: self.storage.read().version_id(entry.e_tag),
         })
     }
 
+    /// THIS METHOD IS COMMON, MODIFIED BY ARAS
     async fn delete(&self, location: &Path) -> Result<()> {
-        self.storage.write().map.remove(location);
+        self.storage.write().delete(location);
         Ok(())
     }
 
+    /// THIS METHOD IS ARAS ONLY
+    async fn delete_opts(&self, location: &Path, opts: DeleteOptions) -> Result<()> {
+        self.storage.write().delete_opts(location, &opts)
+    }
+
     fn list(&self, prefix: Option<&Path>) -> BoxStream<'static, Result<ObjectMeta>> {
         let root = Path::default();
         let prefix = prefix.unwrap_or(&root);
@@ -332,7 +549,7 @@ impl ObjectStore for InMemory {
                     last_modified: value.last_modified,
                     size: value.data.len() as u64,
                     e_tag: Some(value.e_tag.to_string()),
-                    version: None,
+                    version: storage.version_id(value.e_tag),
                 })
             })
             .collect();
@@ -352,7 +569,8 @@ impl ObjectStore for InMemory {
         // Only objects in this base level should be returned in the
         // response. Otherwise, we just collect the common prefixes.
         let mut objects = vec![];
-        for (k, v) in self.storage.read().map.range((prefix)..) {
+        let storage = self.storage.read();
+        for (k, v) in storage.map.range((prefix)..) {
             if !k.as_ref().starts_with(prefix.as_ref()) {
                 break;
             }
@@ -377,7 +595,7 @@ impl ObjectStore for InMemory {
                     last_modified: v.last_modified,
                     size: v.data.len() as u64,
                     e_tag: Some(v.e_tag.to_string()),
-                    version: None,
+                    version: storage.version_id(v.e_tag),
                 };
                 objects.push(object);
             }
@@ -393,7 +611,7 @@ impl ObjectStore for InMemory {
         let entry = self.entry(from)?;
         self.storage
             .write()
-            .insert(to, entry.data, entry.attributes);
+            .insert(to, entry.data, entry.attributes, entry.tags);
         Ok(())
     }
 
@@ -406,11 +624,172 @@ impl ObjectStore for InMemory {
             }
             .into());
         }
-        storage.insert(to, entry.data, entry.attributes);
+        storage.insert(to, entry.data, entry.attributes, entry.tags);
+        Ok(())
+    }
+
+    /// THIS METHOD IS ARAS ONLY
+    async fn compose(&self, sources: &[Path], dest: &Path) -> Result<PutResult> {
+        let mut storage = self.storage.write();
+        let mut buf = Vec::new();
+        for source in sources {
+            let entry = storage
+                .map
+                .get(source)
+                .ok_or_else(|| Error::NoDataInMemory {
+                    path: source.to_string(),
+                })?;
+            buf.extend_from_slice(&entry.data);
+        }
+        let etag = storage.insert(dest, buf.into(), Attributes::new(), TagSet::default());
+        Ok(PutResult {
+            e_tag: Some(etag.to_string()),
+            version: storage.version_id(etag),
+        })
+    }
+}
+
+/// THIS IMPL IS ARAS ONLY
+#[async_trait]
+impl TaggingStore for InMemory {
+    async fn get_tags(&self, location: &Path) -> Result<TagSet> {
+        Ok(self.entry(location)?.tags)
+    }
+
+    async fn put_tags(&self, location: &Path, tags: TagSet) -> Result<()> {
+        let mut storage = self.storage.write();
+        let entry = storage
+            .map
+            .get_mut(location)
+            .ok_or_else(|| Error::NoDataInMemory {
+                path: location.to_string(),
+            })?;
+        entry.tags = tags;
+        Ok(())
+    }
+}
+
+/// THIS IMPL IS ARAS ONLY
+///
+/// Previous versions are only retained if created with [`InMemory::with_versioning`]
+#[async_trait]
+impl VersionedStore for InMemory {
+    fn list_versions(&self, prefix: Option<&Path>) -> BoxStream<'static, Result<ObjectVersion>> {
+        let root = Path::default();
+        let prefix = prefix.unwrap_or(&root);
+
+        let storage = self.storage.read();
+        let values: Vec<_> = storage
+            .versions
+            .range((prefix)..)
+            .take_while(|(key, _)| key.as_ref().starts_with(prefix.as_ref()))
+            .filter(|(key, _)| {
+                // Don't return for exact prefix match
+                key.prefix_match(prefix)
+                    .map(|mut x| x.next().is_some())
+                    .unwrap_or(false)
+            })
+            .flat_map(|(key, versions)| {
+                versions.iter().rev().enumerate().map(|(idx, v)| {
+                    let (size, e_tag) = match &v.entry {
+                        Some(e) => (e.data.len() as u64, Some(e.e_tag.to_string())),
+                        None => (0, None),
+                    };
+                    Ok(ObjectVersion {
+                        meta: ObjectMeta {
+                            location: key.clone(),
+                            last_modified: v.last_modified,
+                            size,
+                            e_tag,
+                            version: Some(v.id.to_string()),
+                        },
+                        is_latest: idx == 0,
+                        is_delete_marker: v.entry.is_none(),
+                    })
+                })
+            })
+            .collect();
+
+        futures::stream::iter(values).boxed()
+    }
+
+    async fn delete_version(&self, location: &Path, version: &str) -> Result<()> {
+        self.storage.write().delete_version(location, version)
+    }
+
+    async fn copy_version(&self, from: &Path, version: &str, to: &Path) -> Result<()> {
+        let mut storage = self.storage.write();
+        let entry = storage.version(from, version)?;
+        storage.insert(to, entry.data, entry.attributes, entry.tags);
         Ok(())
     }
 }
 
+/// THIS IMPL IS ARAS ONLY
+///
+/// Pages are read with a range scan of the objects, with page tokens encoding the key
+/// of the last entry. Only a `/` delimiter is supported
+#[async_trait]
+impl PaginatedListStore for InMemory {
+    async fn list_paginated(
+        &self,
+        prefix: Option<&str>,
+        opts: PaginatedListOptions,
+    ) -> Result<PaginatedListResult> {
+        let prefix = prefix.unwrap_or_default();
+        let delimited = is_delimited("InMemory", &opts)?;
+        let directory = prefix_directory(prefix)?;
+        let directory_len = match directory.as_ref().len() {
+            0 => 0,
+            len => len + 1,
+        };
+
+        // Start the scan from the start of the page, if it is a valid path
+        let mut from = directory;
+        if let Some(start) = page_start("InMemory", &opts)? {
+            match Path::parse(&start) {
+                Ok(path) if path > from && path.as_ref() <= start.as_str() => from = path,
+                _ => {}
+            }
+        }
+
+        let storage = self.storage.read();
+        let mut common_prefix: Option<Path> = None;
+        let entries = storage
+            .map
+            .range(from..)
+            .take_while(|(key, _)| key.as_ref() < prefix || key.as_ref().starts_with(prefix))
+            .filter_map(|(key, value)| {
+                if !key.as_ref().starts_with(prefix) {
+                    return None;
+                }
+                if delimited {
+                    if let Some(idx) = key.as_ref()[directory_len..].find(DELIMITER) {
+                        // Keys of a common prefix are contiguous
+                        let path = Path::parse(&key.as_ref()[..directory_len + idx]);
+                        return match path {
+                            Ok(path) if common_prefix.as_ref() == Some(&path) => None,
+                            Ok(path) => {
+                                common_prefix = Some(path.clone());
+                                Some(Ok(ListEntry::Prefix(path)))
+                            }
+                            Err(e) => Some(Err(e.into())),
+                        };
+                    }
+                }
+                Some(Ok(ListEntry::Object(ObjectMeta {
+                    location: key.clone(),
+                    last_modified: value.last_modified,
+                    size: value.data.len() as u64,
+                    e_tag: Some(value.e_tag.to_string()),
+                    version: storage.version_id(value.e_tag),
+                })))
+            });
+
+        paginate_sorted("InMemory", entries, prefix, &opts)
+    }
+}
+
 #[async_trait]
 impl MultipartStore for InMemory {
     async fn create_multipart(&self, _path: &Path) -> Result<MultipartId> {
@@ -456,10 +835,10 @@ impl MultipartStore for InMemory {
         for x in &upload.parts {
             buf.extend_from_slice(x.as_ref().unwrap())
         }
-        let etag = storage.insert(path, buf.into(), Default::default());
+        let etag = storage.insert(path, buf.into(), Default::default(), Default::default());
         Ok(PutResult {
             e_tag: Some(etag.to_string()),
-            version: None,
+            version: storage.version_id(etag),
         })
     }
 
@@ -475,6 +854,17 @@ impl InMemory {
         Self::default()
     }
 
+    /// THIS METHOD IS ARAS ONLY
+    ///
+    /// Retain every version of every object written from now on, including delete
+    /// markers, for use with [`VersionedStore`]
+    ///
+    /// As versions are never expired, the memory used grows with every write
+    pub fn with_versioning(self) -> Self {
+        self.storage.write().versioning = true;
+        self
+    }
+
     /// Creates a fork of the store, with the current content copied into the
     /// new store.
     pub fn fork(&self) -> Self {
@@ -501,6 +891,12 @@ impl InMemory {
 struct InMemoryUpload {
     location: Path,
     attributes: Attributes,
+    /// THIS FIELD IS ARAS ONLY
+    tags: TagSet,
+    /// THIS FIELD IS ARAS ONLY
+    ///
+    /// Append the uploaded parts to any existing object at `location`
+    copy_and_append: bool,
     parts: Vec<PutPayload>,
     storage: Arc<RwLock<Storage>>,
 }
@@ -517,15 +913,23 @@ impl MultipartUpload for InMemoryUpload {
         let mut buf = Vec::with_capacity(cap);
         let parts = self.parts.iter().flatten();
         parts.for_each(|x| buf.extend_from_slice(x));
-        let etag = self.storage.write().insert(
-            &self.location,
-            buf.into(),
-            std::mem::take(&mut self.attributes),
-        );
+        let attributes = std::mem::take(&mut self.attributes);
+        let tags = std::mem::take(&mut self.tags);
+        let mut storage = self.storage.write();
+        let etag = match self.copy_and_append {
+            true => {
+                let etag = storage.next_etag;
+                storage.next_etag += 1;
+                let entry = Entry::new(buf.into(), Utc::now(), etag, attributes, tags);
+                storage.append(&self.location, entry);
+                etag
+            }
+            false => storage.insert(&self.location, buf.into(), attributes, tags),
+        };
 
         Ok(PutResult {
             e_tag: Some(etag.to_string()),
-            version: None,
+            version: storage.version_id(etag),
         })
     }
 
@@ -554,6 +958,40 @@ mod tests {
         put_opts(&integration, true).await;
         multipart(&integration, &integration).await;
         put_get_attributes(&integration).await;
+        append(&integration).await;
+        delete_opts(&integration).await;
+        compose(&integration).await;
+        tags(&integration).await;
+        list_paginated(&integration, &integration).await;
+    }
+
+    /// THIS TEST IS ARAS ONLY
+    #[tokio::test]
+    async fn in_memory_versions() {
+        let integration = InMemory::new().with_versioning();
+        versions(&integration).await;
+
+        // Without versioning only the current version is retained
+        let integration = InMemory::new();
+        let location = Path::from("data");
+        let first = integration.put(&location, "1".into()).await.unwrap();
+        let second = integration.put(&location, "2".into()).await.unwrap();
+        assert!(integration.storage.read().versions.is_empty());
+        assert_eq!(second.version, None);
+
+        let opts = GetOptions {
+            version: second.e_tag,
+            ..Default::default()
+        };
+        let result = integration.get_opts(&location, opts).await.unwrap();
+        assert_eq!(result.bytes().await.unwrap().as_ref(), b"2");
+        let opts = GetOptions {
+            version: first.e_tag,
+            ..Default::default()
+        };
+        let err = integration.get_opts(&location, opts).await.unwrap_err();
+        assert!(matches!(err, crate::Error::NotFound { .. }), "{err}");
+        assert_eq!(integration.list_versions(None).count().await, 0);
     }
 
     #[tokio::test]
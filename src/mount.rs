@@ -0,0 +1,590 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! THIS MODULE IS ARAS ONLY
+//!
+//! An object store routing paths to the stores mounted at their prefixes
+
+use crate::path::{Path, DELIMITER};
+use crate::registry::PathEntry;
+use crate::transfer::{copy_between_opts, TransferOptions};
+use crate::{
+    BoxStream, DeleteOptions, GetOptions, GetResult, ListResult, MultipartUpload, ObjectMeta,
+    ObjectStore, PutMultipartOpts, PutOptions, PutPayload, PutResult, RangeCoalescePolicy, Result,
+    StreamExt,
+};
+use async_trait::async_trait;
+use bytes::Bytes;
+use futures::TryStreamExt;
+use std::collections::BTreeSet;
+use std::ops::Range;
+use std::sync::Arc;
+
+/// A specialized `Error` for mount-related errors
+#[derive(Debug, thiserror::Error)]
+enum Error {
+    #[error("No store is mounted at {path}")]
+    NotMounted { path: String },
+
+    #[error("Cannot {operation} from {from} to {to} as they are on different mounts")]
+    CrossMount {
+        operation: &'static str,
+        from: String,
+        to: String,
+    },
+
+    #[error("Object at {path} already exists")]
+    AlreadyExists { path: String },
+}
+
+impl From<Error> for crate::Error {
+    fn from(source: Error) -> Self {
+        match source {
+            Error::NotMounted { ref path } => Self::NotFound {
+                path: path.clone(),
+                source: Box::new(source),
+            },
+            Error::CrossMount { .. } => Self::NotSupported {
+                source: Box::new(source),
+            },
+            Error::AlreadyExists { ref path } => Self::AlreadyExists {
+                path: path.clone(),
+                source: Box::new(source),
+            },
+        }
+    }
+}
+
+/// Store routing each [`Path`] to the store mounted at its longest prefix
+///
+/// Prefixes are matched on a path segment basis, and the mount point is stripped from
+/// the paths passed to the mounted store, in the same way as [`PrefixStore`] adds one.
+/// Paths not under any mount point return [`Error::NotFound`](crate::Error::NotFound).
+///
+/// Listing merges the results of every mount under the listed prefix, ignoring any
+/// objects of a store that are hidden by a store mounted at a longer prefix. Mount
+/// points are returned as [`ListResult::common_prefixes`] even if their store is empty.
+///
+/// [`ObjectStore::copy`], [`ObjectStore::rename`] and their `if_not_exists` variants
+/// between paths on different mounts return [`Error::NotSupported`](crate::Error::NotSupported),
+/// unless a streaming fallback is enabled with [`MountStore::with_streaming_fallback`].
+///
+/// ```
+/// # use std::sync::Arc;
+/// # use object_store::memory::InMemory;
+/// # use object_store::mount::MountStore;
+/// let store = MountStore::new()
+///     .with_mount("", Arc::new(InMemory::new()))
+///     .with_mount("scratch", Arc::new(InMemory::new()));
+/// ```
+///
+/// [`PrefixStore`]: crate::prefix::PrefixStore
+#[derive(Debug, Default)]
+pub struct MountStore {
+    /// The tree of mounted stores
+    mounts: PathEntry,
+    /// The mount points
+    points: BTreeSet<Path>,
+    /// The options of cross-mount copies, if enabled
+    fallback: Option<TransferOptions>,
+}
+
+impl MountStore {
+    /// Create a new [`MountStore`] with no mounts
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Mount `store` at `prefix`, replacing any store previously mounted there
+    pub fn with_mount(mut self, prefix: impl Into<Path>, store: Arc<dyn ObjectStore>) -> Self {
+        let prefix = prefix.into();
+        self.mounts.insert(prefix.parts(), store);
+        self.points.insert(prefix);
+        self
+    }
+
+    /// Copy objects between mounts by streaming them with [`copy_between_opts`],
+    /// instead of returning an error
+    ///
+    /// Such copies are not atomic, and [`ObjectStore::copy_if_not_exists`] only
+    /// checks that the destination does not exist before copying
+    pub fn with_streaming_fallback(self, options: TransferOptions) -> Self {
+        Self {
+            fallback: Some(options),
+            ..self
+        }
+    }
+
+    /// Returns the store mounted at the longest prefix of `location`, along with
+    /// its mount point and the path of `location` within it
+    fn resolve(&self, location: &Path) -> Result<(&Arc<dyn ObjectStore>, Path, Path)> {
+        match self.mounts.lookup_segments(location.parts()) {
+            Some((store, depth)) => Ok((
+                store,
+                location.parts().take(depth).collect(),
+                location.parts().skip(depth).collect(),
+            )),
+            None => Err(Error::NotMounted {
+                path: location.to_string(),
+            }
+            .into()),
+        }
+    }
+
+    /// Returns the options of a copy between mounts, or an error if not enabled
+    fn cross_mount(
+        &self,
+        operation: &'static str,
+        from: &Path,
+        to: &Path,
+    ) -> Result<TransferOptions> {
+        self.fallback.ok_or_else(|| {
+            Error::CrossMount {
+                operation,
+                from: from.to_string(),
+                to: to.to_string(),
+            }
+            .into()
+        })
+    }
+
+    /// Returns the mount points strictly below `prefix`
+    fn nested(&self, prefix: &Path) -> Vec<Path> {
+        self.points
+            .iter()
+            .filter(|p| *p != prefix && p.prefix_matches(prefix))
+            .cloned()
+            .collect()
+    }
+
+    /// Returns the listing of the mount of `location`, if any
+    fn listing(&self, location: &Path) -> Option<Listing> {
+        let (store, mount, path) = self.resolve(location).ok()?;
+        Some(Listing {
+            store: Arc::clone(store),
+            hidden: self.nested(&mount),
+            prefix: (!path.as_ref().is_empty()).then_some(path),
+            mount,
+        })
+    }
+
+    /// Returns the listings of the mounts under `prefix`
+    fn listings(&self, prefix: Option<&Path>) -> Vec<Listing> {
+        let prefix = prefix.cloned().unwrap_or_default();
+        let nested = self.nested(&prefix);
+        let nested = nested.iter().filter_map(|mount| self.listing(mount));
+        self.listing(&prefix).into_iter().chain(nested).collect()
+    }
+}
+
+/// A listing of a single mount
+struct Listing {
+    store: Arc<dyn ObjectStore>,
+    /// The mount point
+    mount: Path,
+    /// The prefix to list within the store
+    prefix: Option<Path>,
+    /// The mount points below the mount point, hiding the objects below them
+    hidden: Vec<Path>,
+}
+
+impl Listing {
+    /// Returns the path of `location` within the [`MountStore`]
+    fn full_path(&self, location: &Path) -> Path {
+        self.mount.parts().chain(location.parts()).collect()
+    }
+
+    /// Returns true if `location` is hidden by a store mounted below this mount
+    fn is_hidden(&self, location: &Path) -> bool {
+        self.hidden.iter().any(|p| location.prefix_matches(p))
+    }
+
+    fn list(self) -> BoxStream<'static, Result<ObjectMeta>> {
+        let stream = self.store.list(self.prefix.as_ref());
+        self.filter(stream)
+    }
+
+    /// Lists the objects of the mount after `offset`, a path within the [`MountStore`]
+    fn list_with_offset(self, offset: &Path) -> BoxStream<'static, Result<ObjectMeta>> {
+        let stream = match offset.prefix_match(&self.mount) {
+            Some(parts) => {
+                let offset: Path = parts.collect();
+                self.store.list_with_offset(self.prefix.as_ref(), &offset)
+            }
+            // All objects of the mount sort after the offset
+            None if format!("{}{DELIMITER}", self.mount).as_str() > offset.as_ref() => {
+                self.store.list(self.prefix.as_ref())
+            }
+            None => return futures::stream::empty().boxed(),
+        };
+        self.filter(stream)
+    }
+
+    /// Maps the objects listed from the store to their paths, skipping hidden objects
+    fn filter(
+        self,
+        stream: BoxStream<'static, Result<ObjectMeta>>,
+    ) -> BoxStream<'static, Result<ObjectMeta>> {
+        stream
+            .try_filter_map(move |meta| {
+                let location = self.full_path(&meta.location);
+                let meta = (!self.is_hidden(&location)).then(|| with_location(meta, &location));
+                futures::future::ready(Ok(meta))
+            })
+            .boxed()
+    }
+}
+
+fn with_location(mut meta: ObjectMeta, location: &Path) -> ObjectMeta {
+    meta.location = location.clone();
+    meta
+}
+
+impl std::fmt::Display for MountStore {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "MountStore")
+    }
+}
+
+#[async_trait]
+impl ObjectStore for MountStore {
+    async fn put_opts(
+        &self,
+        location: &Path,
+        payload: PutPayload,
+        opts: PutOptions,
+    ) -> Result<PutResult> {
+        let (store, _, path) = self.resolve(location)?;
+        store.put_opts(&path, payload, opts).await
+    }
+
+    async fn put_multipart_opts(
+        &self,
+        location: &Path,
+        opts: PutMultipartOpts,
+    ) -> Result<Box<dyn MultipartUpload>> {
+        let (store, _, path) = self.resolve(location)?;
+        store.put_multipart_opts(&path, opts).await
+    }
+
+    async fn get_opts(&self, location: &Path, options: GetOptions) -> Result<GetResult> {
+        let (store, _, path) = self.resolve(location)?;
+        let mut result = store.get_opts(&path, options).await?;
+        result.meta = with_location(result.meta, location);
+        Ok(result)
+    }
+
+    async fn get_range(&self, location: &Path, range: Range<u64>) -> Result<Bytes> {
+        let (store, _, path) = self.resolve(location)?;
+        store.get_range(&path, range).await
+    }
+
+    async fn get_ranges(&self, location: &Path, ranges: &[Range<u64>]) -> Result<Vec<Bytes>> {
+        let (store, _, path) = self.resolve(location)?;
+        store.get_ranges(&path, ranges).await
+    }
+
+    /// THIS METHOD IS ARAS ONLY
+    async fn get_ranges_opts(
+        &self,
+        location: &Path,
+        ranges: &[Range<u64>],
+        policy: RangeCoalescePolicy,
+    ) -> Result<Vec<Bytes>> {
+        let (store, _, path) = self.resolve(location)?;
+        store.get_ranges_opts(&path, ranges, policy).await
+    }
+
+    async fn head(&self, location: &Path) -> Result<ObjectMeta> {
+        let (store, _, path) = self.resolve(location)?;
+        let meta = store.head(&path).await?;
+        Ok(with_location(meta, location))
+    }
+
+    async fn delete(&self, location: &Path) -> Result<()> {
+        let (store, _, path) = self.resolve(location)?;
+        store.delete(&path).await
+    }
+
+    async fn delete_opts(&self, location: &Path, opts: DeleteOptions) -> Result<()> {
+        let (store, _, path) = self.resolve(location)?;
+        store.delete_opts(&path, opts).await
+    }
+
+    fn list(&self, prefix: Option<&Path>) -> BoxStream<'static, Result<ObjectMeta>> {
+        let listings = self.listings(prefix).into_iter().map(Listing::list);
+        futures::stream::iter(listings).flatten().boxed()
+    }
+
+    fn list_with_offset(
+        &self,
+        prefix: Option<&Path>,
+        offset: &Path,
+    ) -> BoxStream<'static, Result<ObjectMeta>> {
+        let offset = offset.clone();
+        let listings = self.listings(prefix).into_iter();
+        let listings = listings.map(move |listing| listing.list_with_offset(&offset));
+        futures::stream::iter(listings).flatten().boxed()
+    }
+
+    async fn list_with_delimiter(&self, prefix: Option<&Path>) -> Result<ListResult> {
+        let root = Path::default();
+        let prefix_path = prefix.unwrap_or(&root);
+
+        // Mount points below the prefix are surfaced as common prefixes
+        let mut common_prefixes: BTreeSet<Path> = self
+            .nested(prefix_path)
+            .iter()
+            .filter_map(|p| p.prefix_match(prefix_path)?.next())
+            .map(|part| prefix_path.child(part))
+            .collect();
+
+        let mut objects = vec![];
+        if let Some(listing) = self.listing(prefix_path) {
+            let store = &listing.store;
+            let result = store.list_with_delimiter(listing.prefix.as_ref()).await?;
+            for meta in result.objects {
+                let location = listing.full_path(&meta.location);
+                if !listing.is_hidden(&location) {
+                    objects.push(with_location(meta, &location));
+                }
+            }
+            let prefixes = result.common_prefixes.iter();
+            common_prefixes.extend(prefixes.map(|p| listing.full_path(p)));
+        }
+        objects.sort_by(|a, b| a.location.cmp(&b.location));
+
+        Ok(ListResult {
+            common_prefixes: common_prefixes.into_iter().collect(),
+            objects,
+        })
+    }
+
+    async fn copy(&self, from: &Path, to: &Path) -> Result<()> {
+        let (src, _, src_path) = self.resolve(from)?;
+        let (dst, _, dst_path) = self.resolve(to)?;
+        if Arc::ptr_eq(src, dst) {
+            return src.copy(&src_path, &dst_path).await;
+        }
+        let options = self.cross_mount("copy", from, to)?;
+        copy_between_opts(src.as_ref(), &src_path, dst.as_ref(), &dst_path, options).await?;
+        Ok(())
+    }
+
+    async fn rename(&self, from: &Path, to: &Path) -> Result<()> {
+        let (src, _, src_path) = self.resolve(from)?;
+        let (dst, _, dst_path) = self.resolve(to)?;
+        if Arc::ptr_eq(src, dst) {
+            return src.rename(&src_path, &dst_path).await;
+        }
+        let options = self.cross_mount("rename", from, to)?;
+        copy_between_opts(src.as_ref(), &src_path, dst.as_ref(), &dst_path, options).await?;
+        src.delete(&src_path).await
+    }
+
+    async fn copy_if_not_exists(&self, from: &Path, to: &Path) -> Result<()> {
+        let (src, _, src_path) = self.resolve(from)?;
+        let (dst, _, dst_path) = self.resolve(to)?;
+        if Arc::ptr_eq(src, dst) {
+            return src.copy_if_not_exists(&src_path, &dst_path).await;
+        }
+        let options = self.cross_mount("copy_if_not_exists", from, to)?;
+        match dst.head(&dst_path).await {
+            Ok(_) => {
+                let path = to.to_string();
+                return Err(Error::AlreadyExists { path }.into());
+            }
+            Err(crate::Error::NotFound { .. }) => {}
+            Err(e) => return Err(e),
+        }
+        copy_between_opts(src.as_ref(), &src_path, dst.as_ref(), &dst_path, options).await?;
+        Ok(())
+    }
+
+    async fn rename_if_not_exists(&self, from: &Path, to: &Path) -> Result<()> {
+        let (src, _, src_path) = self.resolve(from)?;
+        let (dst, _, dst_path) = self.resolve(to)?;
+        if Arc::ptr_eq(src, dst) {
+            return src.rename_if_not_exists(&src_path, &dst_path).await;
+        }
+        self.copy_if_not_exists(from, to).await?;
+        src.delete(&src_path).await
+    }
+
+    async fn compose(&self, sources: &[Path], dest: &Path) -> Result<PutResult> {
+        let (store, _, dest_path) = self.resolve(dest)?;
+        let mut paths = Vec::with_capacity(sources.len());
+        for source in sources {
+            let (src, _, path) = self.resolve(source)?;
+            if !Arc::ptr_eq(src, store) {
+                return Err(Error::CrossMount {
+                    operation: "compose",
+                    from: source.to_string(),
+                    to: dest.to_string(),
+                }
+                .into());
+            }
+            paths.push(path);
+        }
+        store.compose(&paths, &dest_path).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::integration::*;
+    use crate::memory::InMemory;
+
+    /// THIS TEST IS ARAS ONLY
+    #[tokio::test]
+    async fn mount_test() {
+        let integration = MountStore::new().with_mount("", Arc::new(InMemory::new()));
+
+        put_get_delete_list(&integration).await;
+        get_opts(&integration).await;
+        list_uses_directories_correctly(&integration).await;
+        list_with_delimiter(&integration).await;
+        rename_and_copy(&integration).await;
+        copy_if_not_exists(&integration).await;
+        stream_get(&integration).await;
+        put_get_attributes(&integration).await;
+    }
+
+    /// THIS TEST IS ARAS ONLY
+    #[tokio::test]
+    async fn test_mounts() {
+        let root = Arc::new(InMemory::new());
+        let data = Arc::new(InMemory::new());
+        let nested = Arc::new(InMemory::new());
+        let store = MountStore::new()
+            .with_mount("", Arc::<InMemory>::clone(&root))
+            .with_mount("data", Arc::<InMemory>::clone(&data))
+            .with_mount("data/a/nested", Arc::<InMemory>::clone(&nested));
+
+        // Paths are routed to the longest matching mount on a segment basis
+        for path in ["foo", "data_x/y", "data/a/b", "data/a/nested/c"] {
+            let path = Path::from(path);
+            store.put(&path, path.to_string().into()).await.unwrap();
+            let meta = store.head(&path).await.unwrap();
+            assert_eq!(meta.location, path);
+        }
+        assert!(root.head(&Path::from("data_x/y")).await.is_ok());
+        assert!(data.head(&Path::from("a/b")).await.is_ok());
+        assert!(nested.head(&Path::from("c")).await.is_ok());
+
+        // Objects hidden by a nested mount are not listed
+        root.put(&Path::from("data"), "".into()).await.unwrap();
+        root.put(&Path::from("data/hidden"), "".into())
+            .await
+            .unwrap();
+        data.put(&Path::from("a/nested/hidden"), "".into())
+            .await
+            .unwrap();
+
+        let mut listed: Vec<_> = store
+            .list(None)
+            .map_ok(|m| m.location)
+            .try_collect()
+            .await
+            .unwrap();
+        listed.sort();
+        let expected = ["data/a/b", "data/a/nested/c", "data_x/y", "foo"];
+        assert_eq!(listed, expected.map(Path::from));
+
+        let listed: Vec<_> = store
+            .list(Some(&Path::from("data/a")))
+            .map_ok(|m| m.location)
+            .try_collect()
+            .await
+            .unwrap();
+        assert_eq!(listed.len(), 2);
+
+        // Offsets are forwarded to the mounts they fall within
+        for (offset, expected) in [
+            (
+                "data",
+                &["data/a/b", "data/a/nested/c", "data_x/y", "foo"][..],
+            ),
+            (
+                "data-x",
+                &["data/a/b", "data/a/nested/c", "data_x/y", "foo"],
+            ),
+            ("data/a/b", &["data/a/nested/c", "data_x/y", "foo"]),
+            ("data/a/nested", &["data/a/nested/c", "data_x/y", "foo"]),
+            ("data_x", &["data_x/y", "foo"]),
+        ] {
+            let mut listed: Vec<_> = store
+                .list_with_offset(None, &Path::from(offset))
+                .map_ok(|m| m.location)
+                .try_collect()
+                .await
+                .unwrap();
+            listed.sort();
+            let expected: Vec<_> = expected.iter().map(|p| Path::from(*p)).collect();
+            assert_eq!(listed, expected, "{offset}");
+        }
+
+        // Mount points are surfaced as common prefixes
+        let result = store.list_with_delimiter(None).await.unwrap();
+        let prefixes = ["data", "data_x"].map(Path::from);
+        assert_eq!(result.common_prefixes, prefixes);
+        assert_eq!(result.objects.len(), 1);
+        assert_eq!(result.objects[0].location, Path::from("foo"));
+
+        let result = store
+            .list_with_delimiter(Some(&Path::from("data/a")))
+            .await
+            .unwrap();
+        let prefixes = ["data/a/nested"].map(Path::from);
+        assert_eq!(result.common_prefixes, prefixes);
+        assert_eq!(result.objects[0].location, Path::from("data/a/b"));
+
+        // Cross-mount copies require the streaming fallback
+        let (from, to) = (Path::from("foo"), Path::from("data/foo"));
+        let err = store.copy_if_not_exists(&from, &to).await.unwrap_err();
+        assert!(matches!(err, crate::Error::NotSupported { .. }), "{err}");
+        let err = store.rename(&from, &to).await.unwrap_err();
+        assert!(matches!(err, crate::Error::NotSupported { .. }), "{err}");
+
+        let store = store.with_streaming_fallback(TransferOptions::default());
+        store.copy_if_not_exists(&from, &to).await.unwrap();
+        let err = store.copy_if_not_exists(&from, &to).await.unwrap_err();
+        assert!(matches!(err, crate::Error::AlreadyExists { .. }), "{err}");
+
+        store
+            .rename(&from, &Path::from("data/a/nested/foo"))
+            .await
+            .unwrap();
+        assert!(root.head(&from).await.is_err());
+        let bytes = nested
+            .get(&Path::from("foo"))
+            .await
+            .unwrap()
+            .bytes()
+            .await
+            .unwrap();
+        assert_eq!(bytes.as_ref(), b"foo");
+
+        // Paths without a mount are not found
+        let store = MountStore::new().with_mount("data", data);
+        let err = store.get(&Path::from("foo")).await.unwrap_err();
+        assert!(matches!(err, crate::Error::NotFound { .. }), "{err}");
+        let result = store.list_with_delimiter(None).await.unwrap();
+        assert_eq!(result.common_prefixes, [Path::from("data")]);
+    }
+}
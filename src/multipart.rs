@@ -20,19 +20,60 @@
 //! This crate provides an asynchronous interface for multipart file uploads to
 //! cloud storage services. It's designed to offer efficient, non-blocking operations,
 //! especially useful when dealing with large files or high-throughput systems.
+//!
+//! Uploads that should survive a restart of the uploading process can be performed with
+//! [`ResumableUpload`], whose [`MultipartUploadState`] can be persisted and resumed from.
 
 use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use futures::stream::BoxStream;
+use futures::{FutureExt, StreamExt, TryStreamExt};
+use parking_lot::Mutex;
+use std::sync::Arc;
+use std::time::Duration;
 
 use crate::path::Path;
-use crate::{MultipartId, PutPayload, PutResult, Result};
+use crate::{Error, MultipartId, MultipartUpload, PutPayload, PutResult, Result, UploadPart};
 
+/// THIS STRUCT IS COMMON, MODIFIED BY ARAS
+///
 /// Represents a part of a file that has been successfully uploaded in a multipart upload process.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct PartId {
     /// Id of this part
     pub content_id: String,
 }
 
+/// THIS STRUCT IS ARAS ONLY
+///
+/// A multipart upload that has been created but not yet completed or aborted, as returned
+/// by [`MultipartStore::list_multipart_uploads`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MultipartUploadInfo {
+    /// The path the upload will be written to
+    pub location: Path,
+    /// The id of the upload
+    pub id: MultipartId,
+    /// When the upload was initiated, if known
+    pub initiated: Option<DateTime<Utc>>,
+}
+
+/// THIS STRUCT IS ARAS ONLY
+///
+/// A part uploaded to a multipart upload, as returned by [`MultipartStore::list_parts`]
+#[derive(Debug, Clone)]
+pub struct PartInfo {
+    /// The `part_idx` the part was uploaded with
+    pub part_idx: usize,
+    /// The [`PartId`] of the part, which can be passed to [`MultipartStore::complete_multipart`]
+    pub part_id: PartId,
+    /// The size of the part in bytes
+    pub size: u64,
+    /// When the part was uploaded, if known
+    pub last_modified: Option<DateTime<Utc>>,
+}
+
 /// A low-level interface for interacting with multipart upload APIs
 ///
 /// Most use-cases should prefer [`ObjectStore::put_multipart`] as this is supported by more
@@ -81,4 +122,500 @@ pub trait MultipartStore: Send + Sync + 'static {
 
     /// Aborts a multipart upload
     async fn abort_multipart(&self, path: &Path, id: &MultipartId) -> Result<()>;
+
+    /// THIS METHOD IS ARAS ONLY
+    ///
+    /// Returns `false` if [`Self::abort_multipart`] is a no-op, leaving the uploaded parts
+    /// to expire, as is the case for Azure. Defaults to `true`
+    fn abort_removes_parts(&self) -> bool {
+        true
+    }
+
+    /// THIS METHOD IS ARAS ONLY
+    ///
+    /// List the multipart uploads to paths below `prefix` that have been created but not
+    /// yet completed or aborted, in an unspecified order
+    ///
+    /// Parts of abandoned uploads are typically billed until the upload is aborted, see
+    /// [`abort_stale_multipart_uploads`]. The default implementation returns
+    /// [`Error::NotImplemented`]
+    fn list_multipart_uploads(
+        &self,
+        prefix: Option<&Path>,
+    ) -> BoxStream<'static, Result<MultipartUploadInfo>> {
+        let _ = prefix;
+        futures::stream::once(async { Err(Error::NotImplemented) }).boxed()
+    }
+
+    /// THIS METHOD IS ARAS ONLY
+    ///
+    /// List the parts uploaded to the multipart upload `id` to `path`, ordered by `part_idx`
+    ///
+    /// The default implementation returns [`Error::NotImplemented`]
+    async fn list_parts(&self, path: &Path, id: &MultipartId) -> Result<Vec<PartInfo>> {
+        let _ = (path, id);
+        Err(Error::NotImplemented)
+    }
+}
+
+/// THIS FUNCTION IS ARAS ONLY
+///
+/// Aborts the multipart uploads to paths below `prefix` initiated more than `age` ago,
+/// returning the number of uploads aborted
+///
+/// Uploads with an unknown initiation time are left untouched, as are uploads completed
+/// or aborted while this runs. Stores where aborting is a no-op, see
+/// [`MultipartStore::abort_removes_parts`], are left untouched and return `0`
+///
+/// ```
+/// # use object_store::local::LocalFileSystem;
+/// # use object_store::multipart::abort_stale_multipart_uploads;
+/// # use std::time::Duration;
+/// # async fn example() -> object_store::Result<()> {
+/// let store = LocalFileSystem::new_with_prefix("/tmp")?;
+/// let day = Duration::from_secs(24 * 60 * 60);
+/// let aborted = abort_stale_multipart_uploads(&store, None, day).await?;
+/// # Ok(())
+/// # }
+/// ```
+pub async fn abort_stale_multipart_uploads<S: MultipartStore + ?Sized>(
+    store: &S,
+    prefix: Option<&Path>,
+    age: Duration,
+) -> Result<usize> {
+    if !store.abort_removes_parts() {
+        return Ok(0);
+    }
+    let cutoff = match chrono::Duration::from_std(age)
+        .ok()
+        .and_then(|age| Utc::now().checked_sub_signed(age))
+    {
+        Some(cutoff) => cutoff,
+        None => return Ok(0),
+    };
+
+    store
+        .list_multipart_uploads(prefix)
+        .try_filter(|upload| {
+            futures::future::ready(matches!(upload.initiated, Some(t) if t < cutoff))
+        })
+        .map_ok(|upload| async move {
+            match store.abort_multipart(&upload.location, &upload.id).await {
+                Ok(_) => Ok(1),
+                Err(Error::NotFound { .. }) => Ok(0),
+                Err(e) => Err(e),
+            }
+        })
+        .try_buffer_unordered(10)
+        .try_fold(0, |acc, aborted| futures::future::ready(Ok(acc + aborted)))
+        .await
+}
+
+/// THIS STRUCT IS ARAS ONLY
+///
+/// A part uploaded by a [`ResumableUpload`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct UploadedPart {
+    /// The `part_idx` of the part
+    pub part_idx: usize,
+    /// The [`PartId`] returned by [`MultipartStore::put_part`]
+    pub part_id: PartId,
+    /// The size of the part in bytes
+    pub size: u64,
+}
+
+/// THIS STRUCT IS ARAS ONLY
+///
+/// The state of a [`ResumableUpload`], which can be persisted and passed to
+/// [`ResumableUpload::resume`] to continue the upload after a restart
+///
+/// Implements `serde::Serialize` and `serde::Deserialize` if the `serde` feature is
+/// enabled, as it is by the cloud store features
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct MultipartUploadState {
+    /// The path being uploaded to
+    pub location: Path,
+    /// The id returned by [`MultipartStore::create_multipart`]
+    pub id: MultipartId,
+    /// The size in bytes of all parts excluding the last
+    pub part_size: usize,
+    /// The uploaded parts, ordered by `part_idx`
+    pub parts: Vec<UploadedPart>,
+}
+
+impl MultipartUploadState {
+    /// Returns the number of leading parts, i.e. with `part_idx` in `0..N`, that have
+    /// been uploaded
+    pub fn completed_parts(&self) -> usize {
+        self.parts
+            .iter()
+            .enumerate()
+            .take_while(|(idx, part)| part.part_idx == *idx)
+            .count()
+    }
+
+    /// Returns the number of bytes uploaded by the leading parts, i.e. the offset in
+    /// the source data a resumed upload should continue from
+    pub fn completed_bytes(&self) -> u64 {
+        self.parts[..self.completed_parts()]
+            .iter()
+            .map(|part| part.size)
+            .sum()
+    }
+}
+
+/// THIS TYPE IS ARAS ONLY
+///
+/// A callback invoked by a [`ResumableUpload`] with its state after each part is uploaded
+pub type UploadCheckpoint = Arc<dyn Fn(&MultipartUploadState) + Send + Sync>;
+
+/// THIS STRUCT IS ARAS ONLY
+///
+/// A [`MultipartUpload`] to a [`MultipartStore`] that can be resumed after a restart
+///
+/// The [`MultipartUploadState`] passed to the [checkpoint](Self::with_checkpoint) after
+/// each part is uploaded can be persisted, and later passed to [`Self::resume`] to
+/// continue the upload from [`MultipartUploadState::completed_bytes`] of the source data.
+/// Parts must be written in chunks of [`MultipartUploadState::part_size`], e.g. with
+/// [`Self::into_writer`], so that the parts can be identified by their offset.
+///
+/// ```
+/// # use object_store::memory::InMemory;
+/// # use object_store::multipart::{MultipartUploadState, ResumableUpload};
+/// # use object_store::path::Path;
+/// # use std::sync::{Arc, Mutex};
+/// # async fn example() -> object_store::Result<()> {
+/// let store = Arc::new(InMemory::new());
+/// let data = vec![0; 10 * 1024 * 1024];
+/// let saved: Arc<Mutex<Option<MultipartUploadState>>> = Default::default();
+///
+/// let checkpoint = Arc::clone(&saved);
+/// let upload = ResumableUpload::new(store.clone(), &Path::from("data"), 5 * 1024 * 1024)
+///     .await?
+///     .with_checkpoint(Arc::new(move |s| *checkpoint.lock().unwrap() = Some(s.clone())));
+/// let mut writer = upload.into_writer();
+/// writer.write(&data[..6 * 1024 * 1024]);
+/// writer.wait_for_capacity(0).await?;
+/// // The process restarts, losing the writer
+///
+/// let state = saved.lock().unwrap().clone().unwrap();
+/// let upload = ResumableUpload::resume(store, state);
+/// let offset = upload.state().completed_bytes() as usize;
+/// let mut writer = upload.into_writer();
+/// writer.write(&data[offset..]);
+/// writer.finish().await?;
+/// # Ok(())
+/// # }
+/// ```
+pub struct ResumableUpload {
+    store: Arc<dyn MultipartStore>,
+    state: Arc<Mutex<MultipartUploadState>>,
+    checkpoint: Option<UploadCheckpoint>,
+    next_part: usize,
+}
+
+impl std::fmt::Debug for ResumableUpload {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ResumableUpload")
+            .field("state", &self.state)
+            .field("next_part", &self.next_part)
+            .finish()
+    }
+}
+
+impl ResumableUpload {
+    /// Creates a new multipart upload to `location` with [`MultipartStore::create_multipart`],
+    /// uploading parts of `part_size` bytes
+    pub async fn new(
+        store: Arc<dyn MultipartStore>,
+        location: &Path,
+        part_size: usize,
+    ) -> Result<Self> {
+        let id = store.create_multipart(location).await?;
+        let state = MultipartUploadState {
+            location: location.clone(),
+            id,
+            part_size,
+            parts: vec![],
+        };
+        Ok(Self::resume(store, state))
+    }
+
+    /// Resumes the upload with the persisted `state`
+    ///
+    /// Uploading continues after the leading uploaded parts, any later parts are
+    /// discarded and uploaded again
+    pub fn resume(store: Arc<dyn MultipartStore>, mut state: MultipartUploadState) -> Self {
+        state.parts.sort_by_key(|part| part.part_idx);
+        state.parts.dedup_by_key(|part| part.part_idx);
+        let next_part = state.completed_parts();
+        state.parts.truncate(next_part);
+        Self {
+            store,
+            state: Arc::new(Mutex::new(state)),
+            checkpoint: None,
+            next_part,
+        }
+    }
+
+    /// Sets a callback invoked with the state of the upload after each part is uploaded
+    ///
+    /// Calls are serialized, so that the last state passed is always the most recent
+    pub fn with_checkpoint(mut self, checkpoint: UploadCheckpoint) -> Self {
+        self.checkpoint = Some(checkpoint);
+        self
+    }
+
+    /// Returns the current state of the upload
+    pub fn state(&self) -> MultipartUploadState {
+        self.state.lock().clone()
+    }
+
+    /// Returns a [`WriteMultipart`] writing parts of [`MultipartUploadState::part_size`]
+    /// to this upload
+    ///
+    /// [`WriteMultipart`]: crate::WriteMultipart
+    pub fn into_writer(self) -> crate::WriteMultipart {
+        let part_size = self.state.lock().part_size;
+        crate::WriteMultipart::new_with_chunk_size(Box::new(self), part_size)
+    }
+}
+
+#[async_trait]
+impl MultipartUpload for ResumableUpload {
+    fn put_part(&mut self, data: PutPayload) -> UploadPart {
+        let part_idx = self.next_part;
+        self.next_part += 1;
+
+        let store = Arc::clone(&self.store);
+        let state = Arc::clone(&self.state);
+        let checkpoint = self.checkpoint.clone();
+        let (location, id) = {
+            let state = state.lock();
+            (state.location.clone(), state.id.clone())
+        };
+        async move {
+            let size = data.content_length() as u64;
+            let part_id = store.put_part(&location, &id, part_idx, data).await?;
+
+            let mut state = state.lock();
+            let part = UploadedPart {
+                part_idx,
+                part_id,
+                size,
+            };
+            match state.parts.binary_search_by_key(&part_idx, |p| p.part_idx) {
+                Ok(idx) => state.parts[idx] = part,
+                Err(idx) => state.parts.insert(idx, part),
+            }
+            if let Some(checkpoint) = checkpoint {
+                checkpoint(&state);
+            }
+            Ok(())
+        }
+        .boxed()
+    }
+
+    async fn complete(&mut self) -> Result<PutResult> {
+        let (location, id, parts) = {
+            let state = self.state.lock();
+            if state.completed_parts() != self.next_part {
+                return Err(Error::Generic {
+                    store: "MultipartStore",
+                    source: format!(
+                        "Cannot complete upload to {} as part {} has not been uploaded",
+                        state.location,
+                        state.completed_parts()
+                    )
+                    .into(),
+                });
+            }
+            let parts = state.parts.iter().map(|p| p.part_id.clone()).collect();
+            (state.location.clone(), state.id.clone(), parts)
+        };
+        self.store.complete_multipart(&location, &id, parts).await
+    }
+
+    async fn abort(&mut self) -> Result<()> {
+        let (location, id) = {
+            let state = self.state.lock();
+            (state.location.clone(), state.id.clone())
+        };
+        self.store.abort_multipart(&location, &id).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::memory::InMemory;
+    use crate::ObjectStore;
+
+    /// THIS TEST IS ARAS ONLY
+    #[tokio::test]
+    async fn test_resumable_upload() {
+        let store = Arc::new(InMemory::new());
+        let location = Path::from("resumed");
+        let data: Vec<u8> = (0..35).collect();
+
+        let checkpoints = Arc::new(Mutex::new(vec![]));
+        let captured = Arc::clone(&checkpoints);
+        let upload = ResumableUpload::new(Arc::<InMemory>::clone(&store), &location, 10)
+            .await
+            .unwrap()
+            .with_checkpoint(Arc::new(move |s| captured.lock().push(s.clone())));
+        let mut writer = upload.into_writer();
+        writer.write(&data[..25]);
+        writer.wait_for_capacity(0).await.unwrap();
+
+        let mut state = checkpoints.lock().last().cloned().unwrap();
+        assert_eq!(state.parts.len(), 2);
+        assert_eq!(state.completed_bytes(), 20);
+
+        // Parts after a missing part are uploaded again
+        state.parts.remove(0);
+        let upload = ResumableUpload::resume(Arc::<InMemory>::clone(&store), state.clone());
+        assert_eq!(upload.state().completed_bytes(), 0);
+
+        let upload = ResumableUpload::resume(
+            Arc::<InMemory>::clone(&store),
+            checkpoints.lock()[1].clone(),
+        );
+        let offset = upload.state().completed_bytes() as usize;
+        let mut writer = upload.into_writer();
+        writer.write(&data[offset..]);
+        writer.finish().await.unwrap();
+
+        let read = store.get(&location).await.unwrap().bytes().await.unwrap();
+        assert_eq!(read.as_ref(), data.as_slice());
+    }
+
+    /// THIS TEST IS ARAS ONLY
+    #[tokio::test]
+    async fn test_resumable_upload_missing_part() {
+        let store = Arc::new(InMemory::new());
+        let location = Path::from("missing");
+        let mut upload = ResumableUpload::new(store, &location, 10).await.unwrap();
+
+        // The failed part is not recorded, and so the upload cannot be completed
+        drop(upload.put_part(vec![0; 10].into()));
+        let err = upload.complete().await.unwrap_err().to_string();
+        assert!(err.contains("part 0 has not been uploaded"), "{err}");
+    }
+
+    /// THIS STRUCT IS ARAS ONLY
+    ///
+    /// Store listing a stale, a recent and an undated upload
+    #[derive(Debug, Default)]
+    struct StaleUploads {
+        noop: bool,
+        aborted: Mutex<Vec<MultipartId>>,
+    }
+
+    #[async_trait]
+    impl MultipartStore for StaleUploads {
+        async fn create_multipart(&self, _: &Path) -> Result<MultipartId> {
+            Err(Error::NotImplemented)
+        }
+
+        async fn put_part(
+            &self,
+            _: &Path,
+            _: &MultipartId,
+            _: usize,
+            _: PutPayload,
+        ) -> Result<PartId> {
+            Err(Error::NotImplemented)
+        }
+
+        async fn complete_multipart(
+            &self,
+            _: &Path,
+            _: &MultipartId,
+            _: Vec<PartId>,
+        ) -> Result<PutResult> {
+            Err(Error::NotImplemented)
+        }
+
+        async fn abort_multipart(&self, _: &Path, id: &MultipartId) -> Result<()> {
+            if !self.noop {
+                self.aborted.lock().push(id.clone());
+            }
+            Ok(())
+        }
+
+        fn abort_removes_parts(&self) -> bool {
+            !self.noop
+        }
+
+        fn list_multipart_uploads(
+            &self,
+            _: Option<&Path>,
+        ) -> BoxStream<'static, Result<MultipartUploadInfo>> {
+            let now = Utc::now();
+            let uploads = [
+                ("stale", Some(now - chrono::Duration::hours(2))),
+                ("recent", Some(now)),
+                ("undated", None),
+            ];
+            let uploads = uploads.map(|(id, initiated)| {
+                Ok(MultipartUploadInfo {
+                    location: Path::from(id),
+                    id: id.to_string(),
+                    initiated,
+                })
+            });
+            futures::stream::iter(uploads).boxed()
+        }
+    }
+
+    /// THIS TEST IS ARAS ONLY
+    #[tokio::test]
+    async fn test_abort_stale_multipart_uploads() {
+        let hour = Duration::from_secs(60 * 60);
+        let store = StaleUploads::default();
+        let aborted = abort_stale_multipart_uploads(&store, None, hour).await;
+        assert_eq!(aborted.unwrap(), 1);
+        assert_eq!(*store.aborted.lock(), ["stale"]);
+
+        // Uploads are not reported as aborted if aborting is a no-op
+        let store = StaleUploads {
+            noop: true,
+            ..Default::default()
+        };
+        let aborted = abort_stale_multipart_uploads(&store, None, hour).await;
+        assert_eq!(aborted.unwrap(), 0);
+    }
+
+    /// THIS TEST IS ARAS ONLY
+    #[cfg(feature = "cloud")]
+    #[test]
+    fn test_state_serde() {
+        let state = MultipartUploadState {
+            location: Path::from("a/b"),
+            id: "id".to_string(),
+            part_size: 10,
+            parts: vec![UploadedPart {
+                part_idx: 0,
+                part_id: PartId {
+                    content_id: "etag".to_string(),
+                },
+                size: 10,
+            }],
+        };
+        let json = serde_json::to_string(&state).unwrap();
+        assert_eq!(
+            json,
+            r#"{"location":"a/b","id":"id","part_size":10,"parts":[{"part_idx":0,"part_id":{"content_id":"etag"},"size":10}]}"#
+        );
+        assert_eq!(
+            serde_json::from_str::<MultipartUploadState>(&json).unwrap(),
+            state
+        );
+
+        let invalid = json.replace("a/b", "a//b");
+        assert!(serde_json::from_str::<MultipartUploadState>(&invalid).is_err());
+    }
 }
@@ -343,6 +343,27 @@ impl std::fmt::Display for Path {
     }
 }
 
+/// THIS IMPL IS ARAS ONLY
+///
+/// Paths are serialized as their string representation
+#[cfg(feature = "serde")]
+impl serde::Serialize for Path {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&self.raw)
+    }
+}
+
+/// THIS IMPL IS ARAS ONLY
+///
+/// Paths are validated with [`Path::parse`] when deserialized
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for Path {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let raw = <String as serde::Deserialize<'de>>::deserialize(deserializer)?;
+        Self::parse(raw).map_err(serde::de::Error::custom)
+    }
+}
+
 impl<'a, I> FromIterator<I> for Path
 where
     I: Into<PathPart<'a>>,
@@ -22,8 +22,8 @@ use std::ops::Range;
 
 use crate::path::Path;
 use crate::{
-    GetOptions, GetResult, ListResult, MultipartUpload, ObjectMeta, ObjectStore, PutMultipartOpts,
-    PutOptions, PutPayload, PutResult, Result,
+    DeleteOptions, GetOptions, GetResult, ListResult, MultipartUpload, ObjectMeta, ObjectStore,
+    PutMultipartOpts, PutOptions, PutPayload, PutResult, RangeCoalescePolicy, Result,
 };
 
 /// Store wrapper that applies a constant prefix to all paths handled by the store.
@@ -147,6 +147,17 @@ impl<T: ObjectStore> ObjectStore for PrefixStore<T> {
         self.inner.get_ranges(&full_path, ranges).await
     }
 
+    /// THIS METHOD IS ARAS ONLY
+    async fn get_ranges_opts(
+        &self,
+        location: &Path,
+        ranges: &[Range<u64>],
+        policy: RangeCoalescePolicy,
+    ) -> Result<Vec<Bytes>> {
+        let full_path = self.full_path(location);
+        self.inner.get_ranges_opts(&full_path, ranges, policy).await
+    }
+
     async fn head(&self, location: &Path) -> Result<ObjectMeta> {
         let full_path = self.full_path(location);
         let meta = self.inner.head(&full_path).await?;
@@ -158,6 +169,12 @@ impl<T: ObjectStore> ObjectStore for PrefixStore<T> {
         self.inner.delete(&full_path).await
     }
 
+    /// THIS METHOD IS ARAS ONLY
+    async fn delete_opts(&self, location: &Path, opts: DeleteOptions) -> Result<()> {
+        let full_path = self.full_path(location);
+        self.inner.delete_opts(&full_path, opts).await
+    }
+
     fn list(&self, prefix: Option<&Path>) -> BoxStream<'static, Result<ObjectMeta>> {
         let prefix = self.full_path(prefix.unwrap_or(&Path::default()));
         let s = self.inner.list(Some(&prefix));
@@ -219,6 +236,13 @@ impl<T: ObjectStore> ObjectStore for PrefixStore<T> {
         let full_to = self.full_path(to);
         self.inner.rename_if_not_exists(&full_from, &full_to).await
     }
+
+    /// THIS METHOD IS ARAS ONLY
+    async fn compose(&self, sources: &[Path], dest: &Path) -> Result<PutResult> {
+        let full_sources: Vec<_> = sources.iter().map(|p| self.full_path(p)).collect();
+        let full_dest = self.full_path(dest);
+        self.inner.compose(&full_sources, &full_dest).await
+    }
 }
 
 #[cfg(not(target_arch = "wasm32"))]
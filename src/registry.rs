@@ -114,6 +114,8 @@ pub struct DefaultObjectStoreRegistry {
     map: RwLock<HashMap<String, PathEntry>>,
 }
 
+/// THIS STRUCT IS COMMON, MODIFIED BY ARAS
+///
 /// [`PathEntry`] construct a tree of path segments starting from the root
 ///
 /// For example the following paths
@@ -134,7 +136,7 @@ pub struct DefaultObjectStoreRegistry {
 /// ```
 ///
 #[derive(Debug, Default)]
-struct PathEntry {
+pub(crate) struct PathEntry {
     /// Store, if defined at this path
     store: Option<Arc<dyn ObjectStore>>,
     /// Child [`PathEntry`], keyed by the next path segment in their path
@@ -142,16 +144,31 @@ struct PathEntry {
 }
 
 impl PathEntry {
+    /// THIS METHOD IS COMMON, MODIFIED BY ARAS
+    ///
     /// Lookup a store based on URL path
     ///
     /// Returns the store and its path segment depth
     fn lookup(&self, to_resolve: &Url) -> Option<(&Arc<dyn ObjectStore>, usize)> {
+        self.lookup_segments(path_segments(to_resolve.path()))
+    }
+
+    /// THIS METHOD IS ARAS ONLY
+    ///
+    /// Lookup a store based on path segments
+    ///
+    /// Returns the store and its path segment depth
+    pub(crate) fn lookup_segments<I>(&self, segments: I) -> Option<(&Arc<dyn ObjectStore>, usize)>
+    where
+        I: IntoIterator,
+        I::Item: AsRef<str>,
+    {
         let mut current = self;
         let mut ret = self.store.as_ref().map(|store| (store, 0));
         let mut depth = 0;
         // Traverse the PathEntry tree to find the longest match
-        for segment in path_segments(to_resolve.path()) {
-            match current.children.get(segment) {
+        for segment in segments {
+            match current.children.get(segment.as_ref()) {
                 Some(e) => {
                     current = e;
                     depth += 1;
@@ -164,6 +181,30 @@ impl PathEntry {
         }
         ret
     }
+
+    /// THIS METHOD IS ARAS ONLY
+    ///
+    /// Register a store at the given path segments
+    ///
+    /// If a store existed at the same path before, it is replaced and returned
+    pub(crate) fn insert<I>(
+        &mut self,
+        segments: I,
+        store: Arc<dyn ObjectStore>,
+    ) -> Option<Arc<dyn ObjectStore>>
+    where
+        I: IntoIterator,
+        I::Item: AsRef<str>,
+    {
+        let mut entry = self;
+        for segment in segments {
+            entry = entry
+                .children
+                .entry(segment.as_ref().to_string())
+                .or_default();
+        }
+        entry.store.replace(store)
+    }
 }
 
 impl DefaultObjectStoreRegistry {
@@ -174,15 +215,12 @@ impl DefaultObjectStoreRegistry {
 }
 
 impl ObjectStoreRegistry for DefaultObjectStoreRegistry {
+    /// THIS METHOD IS COMMON, MODIFIED BY ARAS
     fn register(&self, url: Url, store: Arc<dyn ObjectStore>) -> Option<Arc<dyn ObjectStore>> {
         let mut map = self.map.write();
         let key = url_key(&url);
-        let mut entry = map.entry(key.to_string()).or_default();
-
-        for segment in path_segments(url.path()) {
-            entry = entry.children.entry(segment.to_string()).or_default();
-        }
-        entry.store.replace(store)
+        let entry = map.entry(key.to_string()).or_default();
+        entry.insert(path_segments(url.path()), store)
     }
 
     fn resolve(&self, to_resolve: &Url) -> crate::Result<(Arc<dyn ObjectStore>, Path)> {
@@ -15,7 +15,13 @@
 // specific language governing permissions and limitations
 // under the License.
 
-use url::form_urlencoded::Serializer;
+use std::borrow::Cow;
+
+use async_trait::async_trait;
+use url::form_urlencoded::{parse, Serializer};
+
+use crate::path::Path;
+use crate::{ObjectStore, Result};
 
 /// A collection of key value pairs used to annotate objects
 ///
@@ -41,6 +47,142 @@ impl TagSet {
     pub fn encoded(&self) -> &str {
         &self.0
     }
+
+    /// THIS METHOD IS ARAS ONLY
+    ///
+    /// Parse a URL-encoded string, as returned by [`Self::encoded`], into a [`TagSet`]
+    pub fn parse(encoded: &str) -> Self {
+        parse(encoded.as_bytes()).collect()
+    }
+
+    /// THIS METHOD IS ARAS ONLY
+    ///
+    /// Returns an iterator over the decoded key value pairs of this [`TagSet`],
+    /// in the order they were pushed
+    pub fn iter(&self) -> impl Iterator<Item = (Cow<'_, str>, Cow<'_, str>)> {
+        parse(self.0.as_bytes())
+    }
+
+    /// THIS METHOD IS ARAS ONLY
+    ///
+    /// Returns the value of the first tag with the given key, if any
+    pub fn get(&self, key: &str) -> Option<Cow<'_, str>> {
+        self.iter().find(|(k, _)| k == key).map(|(_, v)| v)
+    }
+
+    /// THIS METHOD IS ARAS ONLY
+    ///
+    /// Returns the number of tags in this [`TagSet`]
+    pub fn len(&self) -> usize {
+        self.iter().count()
+    }
+
+    /// THIS METHOD IS ARAS ONLY
+    ///
+    /// Returns true if this [`TagSet`] contains no tags
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+}
+
+/// THIS IMPL IS ARAS ONLY
+impl<K, V> FromIterator<(K, V)> for TagSet
+where
+    K: AsRef<str>,
+    V: AsRef<str>,
+{
+    fn from_iter<T: IntoIterator<Item = (K, V)>>(iter: T) -> Self {
+        let mut set = Self::default();
+        for (k, v) in iter {
+            set.push(k.as_ref(), v.as_ref());
+        }
+        set
+    }
+}
+
+/// THIS TRAIT IS ARAS ONLY
+///
+/// An interface for stores that can read and update the [`TagSet`] of an existing object
+///
+/// Tags are initially written with [`PutOptions::tags`](crate::PutOptions::tags) and
+/// [`PutMultipartOpts::tags`](crate::PutMultipartOpts::tags). Unlike a put, updating
+/// tags does not rewrite the object's data
+#[async_trait]
+pub trait TaggingStore: ObjectStore {
+    /// Return the tags of the object at `location`
+    ///
+    /// The order of the returned tags is store-dependent
+    async fn get_tags(&self, location: &Path) -> Result<TagSet>;
+
+    /// Replace the tags of the object at `location` with `tags`
+    async fn put_tags(&self, location: &Path, tags: TagSet) -> Result<()>;
+
+    /// Remove all tags from the object at `location`
+    async fn delete_tags(&self, location: &Path) -> Result<()> {
+        self.put_tags(location, TagSet::default()).await
+    }
+}
+
+/// THIS STRUCT IS ARAS ONLY
+///
+/// The XML representation of a [`TagSet`] used by S3 and Azure
+///
+/// <https://docs.aws.amazon.com/AmazonS3/latest/API/API_GetObjectTagging.html>
+/// <https://docs.aws.amazon.com/AmazonS3/latest/API/API_PutObjectTagging.html>
+/// <https://learn.microsoft.com/en-us/rest/api/storageservices/get-blob-tags>
+/// <https://learn.microsoft.com/en-us/rest/api/storageservices/set-blob-tags>
+#[cfg(any(feature = "aws", feature = "azure"))]
+#[derive(Debug, serde::Deserialize, serde::Serialize)]
+pub(crate) struct Tagging {
+    #[serde(rename = "TagSet")]
+    tag_set: TagList,
+}
+
+/// THIS STRUCT IS ARAS ONLY
+#[cfg(any(feature = "aws", feature = "azure"))]
+#[derive(Debug, serde::Deserialize, serde::Serialize)]
+struct TagList {
+    #[serde(rename = "Tag", default)]
+    tags: Vec<Tag>,
+}
+
+/// THIS STRUCT IS ARAS ONLY
+#[cfg(any(feature = "aws", feature = "azure"))]
+#[derive(Debug, serde::Deserialize, serde::Serialize)]
+#[serde(rename_all = "PascalCase")]
+struct Tag {
+    key: String,
+    value: String,
+}
+
+/// THIS IMPL IS ARAS ONLY
+#[cfg(any(feature = "aws", feature = "azure"))]
+impl From<&TagSet> for Tagging {
+    fn from(value: &TagSet) -> Self {
+        let tags = value
+            .iter()
+            .map(|(k, v)| Tag {
+                key: k.into_owned(),
+                value: v.into_owned(),
+            })
+            .collect();
+        Self {
+            tag_set: TagList { tags },
+        }
+    }
+}
+
+/// THIS IMPL IS ARAS ONLY
+#[cfg(any(feature = "aws", feature = "azure"))]
+impl From<Tagging> for TagSet {
+    fn from(value: Tagging) -> Self {
+        value
+            .tag_set
+            .tags
+            .into_iter()
+            .map(|t| (t.key, t.value))
+            .collect()
+    }
 }
 
 #[cfg(test)]
@@ -57,4 +199,39 @@ mod tests {
             "test%2Ffoo=value+sdlks&foo=+sdf+_+%2F%2B.%2Fsd"
         );
     }
+
+    /// THIS TEST IS ARAS ONLY
+    #[test]
+    fn test_tag_set_parse() {
+        let set = TagSet::parse("test%2Ffoo=value+sdlks&foo=+sdf+_+%2F%2B.%2Fsd");
+        assert_eq!(set.len(), 2);
+        assert_eq!(set.get("test/foo").unwrap(), "value sdlks");
+        assert_eq!(set.get("foo").unwrap(), " sdf _ /+./sd");
+        assert!(set.get("bar").is_none());
+
+        let pairs: Vec<_> = set.iter().collect();
+        assert_eq!(pairs[0], ("test/foo".into(), "value sdlks".into()));
+
+        let round_trip: TagSet = set.iter().collect();
+        assert_eq!(round_trip, set);
+        assert!(TagSet::parse("").is_empty());
+    }
+
+    /// THIS TEST IS ARAS ONLY
+    #[cfg(any(feature = "aws", feature = "azure"))]
+    #[test]
+    fn test_tagging_xml() {
+        let set = TagSet::from_iter([("a/b", "c d"), ("e", "")]);
+        let xml = quick_xml::se::to_string(&Tagging::from(&set)).unwrap();
+        assert_eq!(
+            xml,
+            "<Tagging><TagSet><Tag><Key>a/b</Key><Value>c d</Value></Tag><Tag><Key>e</Key><Value/></Tag></TagSet></Tagging>"
+        );
+
+        let tagging: Tagging = quick_xml::de::from_str(&xml).unwrap();
+        assert_eq!(TagSet::from(tagging), set);
+
+        let empty: Tagging = quick_xml::de::from_str("<Tags><TagSet/></Tags>").unwrap();
+        assert!(TagSet::from(empty).is_empty());
+    }
 }
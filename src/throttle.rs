@@ -20,12 +20,12 @@ use parking_lot::Mutex;
 use std::ops::Range;
 use std::{convert::TryInto, sync::Arc};
 
-use crate::multipart::{MultipartStore, PartId};
+use crate::multipart::{MultipartStore, MultipartUploadInfo, PartId, PartInfo};
 use crate::{
     path::Path, GetResult, GetResultPayload, ListResult, MultipartId, MultipartUpload, ObjectMeta,
     ObjectStore, PutMultipartOpts, PutOptions, PutPayload, PutResult, Result,
 };
-use crate::{GetOptions, UploadPart};
+use crate::{DeleteOptions, GetOptions, RangeCoalescePolicy, UploadPart};
 use async_trait::async_trait;
 use bytes::Bytes;
 use futures::{stream::BoxStream, FutureExt, StreamExt};
@@ -226,6 +226,24 @@ impl<T: ObjectStore> ObjectStore for ThrottledStore<T> {
         self.inner.get_ranges(location, ranges).await
     }
 
+    /// THIS METHOD IS ARAS ONLY
+    async fn get_ranges_opts(
+        &self,
+        location: &Path,
+        ranges: &[Range<u64>],
+        policy: RangeCoalescePolicy,
+    ) -> Result<Vec<Bytes>> {
+        let config = self.config();
+
+        let total_bytes: u64 = ranges.iter().map(|range| range.end - range.start).sum();
+        let sleep_duration =
+            config.wait_get_per_call + config.wait_get_per_byte * total_bytes as u32;
+
+        sleep(sleep_duration).await;
+
+        self.inner.get_ranges_opts(location, ranges, policy).await
+    }
+
     async fn head(&self, location: &Path) -> Result<ObjectMeta> {
         sleep(self.config().wait_put_per_call).await;
         self.inner.head(location).await
@@ -237,6 +255,13 @@ impl<T: ObjectStore> ObjectStore for ThrottledStore<T> {
         self.inner.delete(location).await
     }
 
+    /// THIS METHOD IS ARAS ONLY
+    async fn delete_opts(&self, location: &Path, opts: DeleteOptions) -> Result<()> {
+        sleep(self.config().wait_delete_per_call).await;
+
+        self.inner.delete_opts(location, opts).await
+    }
+
     fn list(&self, prefix: Option<&Path>) -> BoxStream<'static, Result<ObjectMeta>> {
         let stream = self.inner.list(prefix);
         let config = Arc::clone(&self.config);
@@ -303,6 +328,13 @@ impl<T: ObjectStore> ObjectStore for ThrottledStore<T> {
 
         self.inner.rename_if_not_exists(from, to).await
     }
+
+    /// THIS METHOD IS ARAS ONLY
+    async fn compose(&self, sources: &[Path], dest: &Path) -> Result<PutResult> {
+        sleep(self.config().wait_put_per_call).await;
+
+        self.inner.compose(sources, dest).await
+    }
 }
 
 /// Saturated `usize` to `u32` cast.
@@ -373,6 +405,24 @@ impl<T: MultipartStore> MultipartStore for ThrottledStore<T> {
     async fn abort_multipart(&self, path: &Path, id: &MultipartId) -> Result<()> {
         self.inner.abort_multipart(path, id).await
     }
+
+    /// THIS METHOD IS ARAS ONLY
+    fn list_multipart_uploads(
+        &self,
+        prefix: Option<&Path>,
+    ) -> BoxStream<'static, Result<MultipartUploadInfo>> {
+        self.inner.list_multipart_uploads(prefix)
+    }
+
+    /// THIS METHOD IS ARAS ONLY
+    async fn list_parts(&self, path: &Path, id: &MultipartId) -> Result<Vec<PartInfo>> {
+        self.inner.list_parts(path, id).await
+    }
+
+    /// THIS METHOD IS ARAS ONLY
+    fn abort_removes_parts(&self) -> bool {
+        self.inner.abort_removes_parts()
+    }
 }
 
 #[derive(Debug)]
@@ -0,0 +1,483 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! THIS MODULE IS ARAS ONLY
+//!
+//! Streaming copies of objects between [`ObjectStore`]s
+//!
+//! Unlike [`ObjectStore::copy`], which copies objects within a single store, the
+//! functions in this module copy objects between any two stores, such as from a local
+//! filesystem to a cloud object store, by streaming the source object into a multipart
+//! upload to the destination.
+
+use crate::path::Path;
+use crate::{
+    ObjectMeta, ObjectStore, PutMultipartOpts, PutOptions, PutResult, Result, WriteMultipart,
+};
+use futures::stream::BoxStream;
+use futures::{StreamExt, TryStreamExt};
+use std::collections::BTreeMap;
+
+/// Options for [`copy_between_opts`]
+#[derive(Debug, Clone, Copy)]
+pub struct TransferOptions {
+    /// The size of each part uploaded to the destination
+    ///
+    /// Objects no larger than this are uploaded with a single [`ObjectStore::put_opts`]
+    pub chunk_size: usize,
+
+    /// The maximum number of parts of an object uploaded concurrently
+    pub max_concurrency: usize,
+}
+
+impl Default for TransferOptions {
+    fn default() -> Self {
+        Self {
+            chunk_size: 10 * 1024 * 1024,
+            max_concurrency: 8,
+        }
+    }
+}
+
+/// Copy the object at `src_path` in `src` to `dst_path` in `dst`
+///
+/// The object is streamed, and so the whole object is never buffered in memory.
+/// The [`Attributes`](crate::Attributes) of the source object are retained.
+///
+/// ```
+/// # use object_store::memory::InMemory;
+/// # use object_store::path::Path;
+/// # use object_store::ObjectStore;
+/// # use object_store::transfer::copy_between;
+/// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+/// let src = InMemory::new();
+/// let dst = InMemory::new();
+/// let path = Path::from("data.parquet");
+/// src.put(&path, "data".into()).await?;
+///
+/// copy_between(&src, &path, &dst, &path).await?;
+/// # Ok(())
+/// # }
+/// ```
+pub async fn copy_between(
+    src: &dyn ObjectStore,
+    src_path: &Path,
+    dst: &dyn ObjectStore,
+    dst_path: &Path,
+) -> Result<PutResult> {
+    copy_between_opts(src, src_path, dst, dst_path, TransferOptions::default()).await
+}
+
+/// Copy the object at `src_path` in `src` to `dst_path` in `dst` with the given options
+///
+/// See [`copy_between`] for more information
+pub async fn copy_between_opts(
+    src: &dyn ObjectStore,
+    src_path: &Path,
+    dst: &dyn ObjectStore,
+    dst_path: &Path,
+    options: TransferOptions,
+) -> Result<PutResult> {
+    let result = src.get(src_path).await?;
+    let attributes = result.attributes.clone();
+
+    if result.meta.size <= options.chunk_size as u64 {
+        let payload = result.bytes().await?;
+        let opts = PutOptions {
+            attributes,
+            ..Default::default()
+        };
+        return dst.put_opts(dst_path, payload.into(), opts).await;
+    }
+
+    let opts = PutMultipartOpts {
+        attributes,
+        ..Default::default()
+    };
+    let upload = dst.put_multipart_opts(dst_path, opts).await?;
+    let mut write = WriteMultipart::new_with_chunk_size(upload, options.chunk_size);
+
+    let mut stream = result.into_stream();
+    let streamed = async {
+        while let Some(bytes) = stream.try_next().await? {
+            write.wait_for_capacity(options.max_concurrency).await?;
+            write.put(bytes);
+        }
+        Ok(())
+    }
+    .await;
+
+    match streamed {
+        Ok(()) => write.finish().await,
+        Err(e) => {
+            let _ = write.abort().await; // Attempt to cleanup
+            Err(e)
+        }
+    }
+}
+
+/// Options for [`sync_prefix`]
+#[derive(Debug, Clone, Copy)]
+pub struct SyncOptions {
+    /// Delete objects in the destination that do not exist in the source
+    ///
+    /// Defaults to `false`
+    pub delete: bool,
+
+    /// The maximum number of objects copied or deleted concurrently
+    pub max_concurrency: usize,
+
+    /// The options used to copy each object
+    pub transfer: TransferOptions,
+}
+
+impl Default for SyncOptions {
+    fn default() -> Self {
+        Self {
+            delete: false,
+            max_concurrency: 4,
+            transfer: Default::default(),
+        }
+    }
+}
+
+/// The progress of a [`sync_prefix`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SyncEvent {
+    /// The listings of the source and destination have been compared
+    ///
+    /// This is always the first event, and is followed by a [`SyncEvent::Copied`] or
+    /// [`SyncEvent::Deleted`] for each object to be copied or deleted
+    Planned {
+        /// The number of objects to be copied
+        copies: usize,
+        /// The total size in bytes of the objects to be copied
+        bytes: u64,
+        /// The number of objects to be deleted
+        deletes: usize,
+        /// The number of objects that are already up to date
+        unchanged: usize,
+    },
+    /// An object has been copied
+    Copied {
+        /// The location of the object in the destination
+        location: Path,
+        /// The size of the object in bytes
+        size: u64,
+    },
+    /// An object has been deleted from the destination
+    Deleted {
+        /// The location of the deleted object
+        location: Path,
+    },
+}
+
+/// An action of a [`sync_prefix`]
+#[derive(Debug)]
+enum SyncAction {
+    Copy { from: Path, to: Path, size: u64 },
+    Delete { location: Path },
+}
+
+/// Mirror the objects under `src_prefix` in `src` to `dst_prefix` in `dst`
+///
+/// Objects are copied with [`copy_between_opts`] if they do not exist in the destination,
+/// or if they differ in size, or if they differ in e-tag and the destination was last
+/// modified before the source. Objects in the destination that do not exist in the source
+/// are deleted if [`SyncOptions::delete`] is set.
+///
+/// Returns a stream reporting the progress of the sync, which must be polled to completion
+/// to perform it, see [`SyncEvent`]. Copies and deletes are performed concurrently, and so
+/// the order of events after [`SyncEvent::Planned`] is not guaranteed.
+///
+/// ```
+/// # use futures::TryStreamExt;
+/// # use object_store::memory::InMemory;
+/// # use object_store::path::Path;
+/// # use object_store::ObjectStore;
+/// # use object_store::transfer::{sync_prefix, SyncEvent, SyncOptions};
+/// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+/// let src = InMemory::new();
+/// let dst = InMemory::new();
+/// src.put(&Path::from("data/a.parquet"), "a".into()).await?;
+///
+/// let (src_prefix, dst_prefix) = (Path::from("data"), Path::from("backup"));
+/// let options = SyncOptions::default();
+/// let mut events = sync_prefix(&src, &src_prefix, &dst, &dst_prefix, options);
+/// while let Some(event) = events.try_next().await? {
+///     if let SyncEvent::Copied { location, size } = event {
+///         println!("copied {location} ({size} bytes)");
+///     }
+/// }
+/// # Ok(())
+/// # }
+/// ```
+pub fn sync_prefix<'a>(
+    src: &'a dyn ObjectStore,
+    src_prefix: &'a Path,
+    dst: &'a dyn ObjectStore,
+    dst_prefix: &'a Path,
+    options: SyncOptions,
+) -> BoxStream<'a, Result<SyncEvent>> {
+    futures::stream::once(plan_sync(src, src_prefix, dst, dst_prefix, options))
+        .map_ok(move |(actions, unchanged)| {
+            let (mut copies, mut bytes, mut deletes) = (0, 0, 0);
+            for action in &actions {
+                match action {
+                    SyncAction::Copy { size, .. } => {
+                        copies += 1;
+                        bytes += size;
+                    }
+                    SyncAction::Delete { .. } => deletes += 1,
+                }
+            }
+            let planned = SyncEvent::Planned {
+                copies,
+                bytes,
+                deletes,
+                unchanged,
+            };
+
+            let performed = futures::stream::iter(actions)
+                .map(move |action| perform_sync(src, dst, action, options.transfer))
+                .buffer_unordered(options.max_concurrency.max(1));
+            futures::stream::once(futures::future::ready(Ok(planned))).chain(performed)
+        })
+        .try_flatten()
+        .boxed()
+}
+
+/// Compares the listings of the source and destination, returning the actions to
+/// perform and the number of objects that are already up to date
+async fn plan_sync(
+    src: &dyn ObjectStore,
+    src_prefix: &Path,
+    dst: &dyn ObjectStore,
+    dst_prefix: &Path,
+    options: SyncOptions,
+) -> Result<(Vec<SyncAction>, usize)> {
+    let (src_objects, mut dst_objects) = futures::try_join!(
+        list_relative(src, src_prefix),
+        list_relative(dst, dst_prefix)
+    )?;
+
+    let mut actions = vec![];
+    let mut unchanged = 0;
+    for (relative, src_meta) in src_objects {
+        let dst_meta = dst_objects.remove(&relative);
+        if dst_meta
+            .map(|d| is_unchanged(&src_meta, &d))
+            .unwrap_or(false)
+        {
+            unchanged += 1;
+            continue;
+        }
+        actions.push(SyncAction::Copy {
+            from: src_meta.location,
+            to: join(dst_prefix, &relative),
+            size: src_meta.size,
+        });
+    }
+
+    if options.delete {
+        actions.extend(dst_objects.into_values().map(|meta| SyncAction::Delete {
+            location: meta.location,
+        }));
+    }
+    Ok((actions, unchanged))
+}
+
+/// Lists the objects under `prefix`, keyed by their location relative to `prefix`
+async fn list_relative(
+    store: &dyn ObjectStore,
+    prefix: &Path,
+) -> Result<BTreeMap<Path, ObjectMeta>> {
+    store
+        .list(Some(prefix))
+        .try_filter_map(|meta| async move {
+            let relative = match meta.location.prefix_match(prefix) {
+                Some(parts) => Path::from_iter(parts),
+                None => return Ok(None),
+            };
+            Ok(Some((relative, meta)))
+        })
+        .try_collect()
+        .await
+}
+
+/// Returns true if the object described by `dst` is an up to date copy of `src`
+fn is_unchanged(src: &ObjectMeta, dst: &ObjectMeta) -> bool {
+    if src.size != dst.size {
+        return false;
+    }
+    match (&src.e_tag, &dst.e_tag) {
+        (Some(s), Some(d)) if s == d => true,
+        _ => dst.last_modified >= src.last_modified,
+    }
+}
+
+fn join(prefix: &Path, relative: &Path) -> Path {
+    Path::from_iter(prefix.parts().chain(relative.parts()))
+}
+
+async fn perform_sync(
+    src: &dyn ObjectStore,
+    dst: &dyn ObjectStore,
+    action: SyncAction,
+    options: TransferOptions,
+) -> Result<SyncEvent> {
+    match action {
+        SyncAction::Copy { from, to, size } => {
+            copy_between_opts(src, &from, dst, &to, options).await?;
+            Ok(SyncEvent::Copied { location: to, size })
+        }
+        SyncAction::Delete { location } => {
+            dst.delete(&location).await?;
+            Ok(SyncEvent::Deleted { location })
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::memory::InMemory;
+    use crate::{Attribute, Attributes};
+
+    /// THIS TEST IS ARAS ONLY
+    #[tokio::test]
+    async fn test_copy_between() {
+        let src = InMemory::new();
+        let dst = InMemory::new();
+        let options = TransferOptions {
+            chunk_size: 5,
+            max_concurrency: 2,
+        };
+
+        let attributes = Attributes::from_iter([(Attribute::ContentType, "text/plain")]);
+        let path = Path::from("a/b");
+        let opts = PutOptions {
+            attributes: attributes.clone(),
+            ..Default::default()
+        };
+        src.put_opts(&path, "hello world".into(), opts)
+            .await
+            .unwrap();
+        src.put(&Path::from("small"), "hi".into()).await.unwrap();
+
+        for (from, to) in [("a/b", "c"), ("small", "d")] {
+            let (from, to) = (Path::from(from), Path::from(to));
+            copy_between_opts(&src, &from, &dst, &to, options)
+                .await
+                .unwrap();
+            let expected = src.get(&from).await.unwrap().bytes().await.unwrap();
+            let actual = dst.get(&to).await.unwrap().bytes().await.unwrap();
+            assert_eq!(actual, expected);
+        }
+        let result = dst.get(&Path::from("c")).await.unwrap();
+        assert_eq!(result.attributes, attributes);
+
+        let err = copy_between(&src, &Path::from("missing"), &dst, &path)
+            .await
+            .unwrap_err();
+        assert!(matches!(err, crate::Error::NotFound { .. }), "{err}");
+    }
+
+    /// THIS TEST IS ARAS ONLY
+    #[tokio::test]
+    async fn test_sync_prefix() {
+        let src = InMemory::new();
+        let dst = InMemory::new();
+        for (path, data) in [("src/a", "a"), ("src/b", "b"), ("src/c/d", "cd")] {
+            src.put(&Path::from(path), data.into()).await.unwrap();
+        }
+        dst.put(&Path::from("dst/b"), "b".into()).await.unwrap();
+        dst.put(&Path::from("dst/c/d"), "stale".into())
+            .await
+            .unwrap();
+        dst.put(&Path::from("dst/e"), "e".into()).await.unwrap();
+        dst.put(&Path::from("other"), "other".into()).await.unwrap();
+
+        let (src_prefix, dst_prefix) = (Path::from("src"), Path::from("dst"));
+        let options = SyncOptions::default();
+        let events: Vec<_> = sync_prefix(&src, &src_prefix, &dst, &dst_prefix, options)
+            .try_collect()
+            .await
+            .unwrap();
+
+        // Objects missing from the source are not deleted by default
+        let expected = SyncEvent::Planned {
+            copies: 2,
+            bytes: 3,
+            deletes: 0,
+            unchanged: 1,
+        };
+        assert_eq!(events[0], expected);
+        assert_eq!(events.len(), 3);
+        assert!(events.contains(&SyncEvent::Copied {
+            location: Path::from("dst/c/d"),
+            size: 2
+        }));
+        dst.head(&Path::from("dst/e")).await.unwrap();
+
+        let options = SyncOptions {
+            delete: true,
+            ..Default::default()
+        };
+        let events: Vec<_> = sync_prefix(&src, &src_prefix, &dst, &dst_prefix, options)
+            .try_collect()
+            .await
+            .unwrap();
+        let expected = SyncEvent::Planned {
+            copies: 0,
+            bytes: 0,
+            deletes: 1,
+            unchanged: 3,
+        };
+        assert_eq!(events[0], expected);
+        assert_eq!(
+            events[1..],
+            [SyncEvent::Deleted {
+                location: Path::from("dst/e")
+            }]
+        );
+
+        let mut listed: Vec<_> = dst
+            .list(None)
+            .map_ok(|meta| meta.location.to_string())
+            .try_collect()
+            .await
+            .unwrap();
+        listed.sort_unstable();
+        assert_eq!(listed, ["dst/a", "dst/b", "dst/c/d", "other"]);
+        let data = dst.get(&Path::from("dst/c/d")).await.unwrap();
+        assert_eq!(data.bytes().await.unwrap().as_ref(), b"cd");
+
+        // A second sync has nothing to do
+        let events: Vec<_> = sync_prefix(&src, &src_prefix, &dst, &dst_prefix, options)
+            .try_collect()
+            .await
+            .unwrap();
+        let expected = SyncEvent::Planned {
+            copies: 0,
+            bytes: 0,
+            deletes: 0,
+            unchanged: 3,
+        };
+        assert_eq!(events, [expected]);
+    }
+}
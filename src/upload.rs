@@ -15,12 +15,18 @@
 // specific language governing permissions and limitations
 // under the License.
 
+#[cfg(any(feature = "aws", feature = "azure", feature = "gcp"))]
+use std::sync::Arc;
 use std::task::{Context, Poll};
 
+#[cfg(any(feature = "aws", feature = "azure", feature = "gcp"))]
+use crate::{path::Path, ObjectStore, PutMode, PutMultipartOpts, PutOptions};
 use crate::{PutPayload, PutPayloadMut, PutResult, Result};
 use async_trait::async_trait;
 use bytes::Bytes;
 use futures::future::BoxFuture;
+#[cfg(any(feature = "aws", feature = "azure", feature = "gcp"))]
+use futures::future::{FutureExt, Shared};
 use futures::ready;
 use tokio::task::JoinSet;
 
@@ -107,6 +113,114 @@ impl<W: MultipartUpload + ?Sized> MultipartUpload for Box<W> {
     }
 }
 
+/// THIS STRUCT IS ARAS ONLY
+///
+/// A [`MultipartUpload`] that writes each part to the destination with a [`PutMode::Append`]
+/// request as soon as the previous part has been appended
+///
+/// Used by stores whose append primitive cannot be combined with their multipart
+/// upload API, ensuring parts are appended in order regardless of the order in
+/// which their futures are polled. Parts are therefore visible before the upload is
+/// completed, and are not removed by [`MultipartUpload::abort`]
+#[cfg(any(feature = "aws", feature = "azure", feature = "gcp"))]
+pub(crate) struct AppendUpload {
+    store: Arc<dyn ObjectStore>,
+    location: Path,
+    opts: PutOptions,
+    /// The append of the last part, which completes once all parts have been appended
+    last: Option<SharedAppend>,
+}
+
+/// The append of a part, awaited by both its caller and the append of the next part
+#[cfg(any(feature = "aws", feature = "azure", feature = "gcp"))]
+type SharedAppend = Shared<BoxFuture<'static, Result<PutResult, Arc<crate::Error>>>>;
+
+#[cfg(any(feature = "aws", feature = "azure", feature = "gcp"))]
+impl std::fmt::Debug for AppendUpload {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("AppendUpload")
+            .field("store", &self.store)
+            .field("location", &self.location)
+            .field("opts", &self.opts)
+            .finish()
+    }
+}
+
+#[cfg(any(feature = "aws", feature = "azure", feature = "gcp"))]
+impl AppendUpload {
+    pub(crate) fn new(store: Arc<dyn ObjectStore>, location: Path, opts: PutMultipartOpts) -> Self {
+        let PutMultipartOpts {
+            tags,
+            attributes,
+            extensions,
+            // Always PutMode::Append, see the callers
+            mode: _,
+        } = opts;
+
+        Self {
+            store,
+            location,
+            opts: PutOptions {
+                mode: PutMode::Append,
+                tags,
+                attributes,
+                extensions,
+            },
+            last: None,
+        }
+    }
+}
+
+/// Returns the error of a part shared with the parts after it
+#[cfg(any(feature = "aws", feature = "azure", feature = "gcp"))]
+fn unshare(e: Arc<crate::Error>) -> crate::Error {
+    Arc::try_unwrap(e).unwrap_or_else(|e| crate::Error::Generic {
+        store: "AppendUpload",
+        source: Box::new(e),
+    })
+}
+
+#[cfg(any(feature = "aws", feature = "azure", feature = "gcp"))]
+#[async_trait]
+impl MultipartUpload for AppendUpload {
+    fn put_part(&mut self, data: PutPayload) -> UploadPart {
+        let previous = self.last.take();
+        let store = Arc::clone(&self.store);
+        let location = self.location.clone();
+        let opts = self.opts.clone();
+        let append = async move {
+            if let Some(previous) = previous {
+                previous.await?;
+            }
+            store
+                .put_opts(&location, data, opts)
+                .await
+                .map_err(Arc::new)
+        }
+        .boxed()
+        .shared();
+        self.last = Some(append.clone());
+        Box::pin(async move { append.await.map(|_| ()).map_err(unshare) })
+    }
+
+    async fn complete(&mut self) -> Result<PutResult> {
+        match self.last.take() {
+            Some(last) => last.await.map_err(unshare),
+            // Create the object if it does not exist
+            None => {
+                let opts = self.opts.clone();
+                let payload = PutPayload::default();
+                self.store.put_opts(&self.location, payload, opts).await
+            }
+        }
+    }
+
+    async fn abort(&mut self) -> Result<()> {
+        self.last = None;
+        Ok(())
+    }
+}
+
 /// A synchronous write API for uploading data in parallel in fixed size chunks
 ///
 /// Uses multiple tokio tasks in a [`JoinSet`] to multiplex upload tasks in parallel
@@ -338,4 +452,36 @@ mod tests {
             }
         }
     }
+
+    /// THIS TEST IS ARAS ONLY
+    #[cfg(any(feature = "aws", feature = "azure", feature = "gcp"))]
+    #[tokio::test]
+    async fn test_append_upload() {
+        let store = Arc::new(InMemory::new());
+        let path = Path::from("append");
+        store.put(&path, "a".into()).await.unwrap();
+
+        let opts = PutMultipartOpts {
+            mode: PutMode::Append,
+            ..Default::default()
+        };
+        let inner = Arc::<InMemory>::clone(&store);
+        let mut upload = AppendUpload::new(inner, path.clone(), opts);
+        let parts: Vec<_> = ["b", "c", "d"]
+            .into_iter()
+            .map(|x| upload.put_part(x.into()))
+            .collect();
+
+        // Parts are appended in order as soon as they are polled
+        for part in parts.into_iter().rev() {
+            part.await.unwrap();
+        }
+        let data = store.get(&path).await.unwrap().bytes().await.unwrap();
+        assert_eq!(data.as_ref(), b"abcd");
+
+        upload.put_part("e".into()).await.unwrap();
+        let data = store.get(&path).await.unwrap().bytes().await.unwrap();
+        assert_eq!(data.as_ref(), b"abcde");
+        upload.complete().await.unwrap();
+    }
 }
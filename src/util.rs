@@ -22,7 +22,7 @@ use std::{
 };
 
 use super::Result;
-use bytes::Bytes;
+use bytes::{Bytes, BytesMut};
 use futures::{stream::StreamExt, Stream, TryStreamExt};
 
 #[cfg(any(feature = "azure", feature = "http"))]
@@ -94,6 +94,45 @@ pub const OBJECT_STORE_COALESCE_DEFAULT: u64 = 1024 * 1024;
 /// Up to this number of range requests will be performed in parallel by [`coalesce_ranges`]
 pub(crate) const OBJECT_STORE_COALESCE_PARALLEL: usize = 10;
 
+/// THIS STRUCT IS ARAS ONLY
+///
+/// Controls how [`coalesce_ranges_opts`] turns a list of byte ranges into requests
+///
+/// Can be configured per store with [`ClientOptions::with_range_coalesce_policy`], or
+/// per call with [`ObjectStore::get_ranges_opts`]
+///
+/// [`ClientOptions::with_range_coalesce_policy`]: crate::ClientOptions::with_range_coalesce_policy
+/// [`ObjectStore::get_ranges_opts`]: crate::ObjectStore::get_ranges_opts
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RangeCoalescePolicy {
+    /// Ranges with a gap less than or equal to this many bytes are merged into a single request
+    pub max_gap: u64,
+
+    /// The maximum size in bytes of a single request
+    ///
+    /// Ranges are not merged beyond this size, and ranges larger than this
+    /// are split into multiple requests
+    ///
+    /// As the size of the object is not known in advance, ranges extending beyond the
+    /// end of the object may be split into requests starting beyond its end, which fail
+    pub max_request_size: u64,
+
+    /// The maximum number of requests to perform in parallel
+    pub parallelism: usize,
+}
+
+impl Default for RangeCoalescePolicy {
+    fn default() -> Self {
+        Self {
+            max_gap: OBJECT_STORE_COALESCE_DEFAULT,
+            max_request_size: u64::MAX,
+            parallelism: OBJECT_STORE_COALESCE_PARALLEL,
+        }
+    }
+}
+
+/// THIS FUNCTION IS COMMON, MODIFIED BY ARAS
+///
 /// Takes a function `fetch` that can fetch a range of bytes and uses this to
 /// fetch the provided byte `ranges`
 ///
@@ -102,6 +141,7 @@ pub(crate) const OBJECT_STORE_COALESCE_PARALLEL: usize = 10;
 /// * Combine ranges less than `coalesce` bytes apart into a single call to `fetch`
 /// * Make multiple `fetch` requests in parallel (up to maximum of 10)
 ///
+/// See [`coalesce_ranges_opts`] for finer control
 pub async fn coalesce_ranges<F, E, Fut>(
     ranges: &[Range<u64>],
     fetch: F,
@@ -112,11 +152,37 @@ where
     E: Send,
     Fut: std::future::Future<Output = Result<Bytes, E>> + Send,
 {
-    let fetch_ranges = merge_ranges(ranges, coalesce);
+    let policy = RangeCoalescePolicy {
+        max_gap: coalesce,
+        ..Default::default()
+    };
+    coalesce_ranges_opts(ranges, fetch, policy).await
+}
+
+/// THIS FUNCTION IS ARAS ONLY
+///
+/// Takes a function `fetch` that can fetch a range of bytes and uses this to
+/// fetch the provided byte `ranges` according to `policy`
+///
+/// Ranges at most [`RangeCoalescePolicy::max_gap`] bytes apart are combined into a single
+/// call to `fetch`, unless this would exceed [`RangeCoalescePolicy::max_request_size`].
+/// Ranges larger than this are split into multiple calls, and up to
+/// [`RangeCoalescePolicy::parallelism`] calls are performed in parallel
+pub async fn coalesce_ranges_opts<F, E, Fut>(
+    ranges: &[Range<u64>],
+    fetch: F,
+    policy: RangeCoalescePolicy,
+) -> Result<Vec<Bytes>, E>
+where
+    F: Send + FnMut(Range<u64>) -> Fut,
+    E: Send,
+    Fut: std::future::Future<Output = Result<Bytes, E>> + Send,
+{
+    let fetch_ranges = plan_ranges(ranges, &policy);
 
     let fetched: Vec<_> = futures::stream::iter(fetch_ranges.iter().cloned())
         .map(fetch)
-        .buffered(OBJECT_STORE_COALESCE_PARALLEL)
+        .buffered(policy.parallelism.max(1))
         .try_collect()
         .await?;
 
@@ -127,16 +193,58 @@ where
             let fetch_range = &fetch_ranges[idx];
             let fetch_bytes = &fetched[idx];
 
-            let start = range.start - fetch_range.start;
-            let end = range.end - fetch_range.start;
-            let range = (start as usize)..(end as usize).min(fetch_bytes.len());
-            fetch_bytes.slice(range)
+            if range.end <= fetch_range.end {
+                let start = range.start - fetch_range.start;
+                let end = range.end - fetch_range.start;
+                let range = (start as usize)..(end as usize).min(fetch_bytes.len());
+                return fetch_bytes.slice(range);
+            }
+
+            // The range was split across multiple requests
+            let mut buf = BytesMut::with_capacity((range.end - range.start) as usize);
+            let split = fetch_ranges[idx..].iter().zip(&fetched[idx..]);
+            for (fetch_range, fetch_bytes) in split {
+                if fetch_range.start >= range.end {
+                    break;
+                }
+                let start = (range.start.max(fetch_range.start) - fetch_range.start) as usize;
+                let end = (range.end.min(fetch_range.end) - fetch_range.start) as usize;
+                let len = fetch_bytes.len();
+                buf.extend_from_slice(&fetch_bytes[start.min(len)..end.min(len)]);
+            }
+            buf.freeze()
         })
         .collect())
 }
 
+/// THIS FUNCTION IS ARAS ONLY
+///
+/// Returns a sorted list of ranges that cover `ranges`, to fetch according to `policy`
+pub(crate) fn plan_ranges(ranges: &[Range<u64>], policy: &RangeCoalescePolicy) -> Vec<Range<u64>> {
+    let max_size = policy.max_request_size.max(1);
+    let merged = merge_ranges(ranges, policy.max_gap, max_size);
+    if merged.iter().all(|r| r.end - r.start <= max_size) {
+        return merged;
+    }
+
+    let mut ret = Vec::with_capacity(merged.len());
+    for range in merged {
+        let mut start = range.start;
+        while start < range.end {
+            let end = range.end.min(start.saturating_add(max_size));
+            ret.push(start..end);
+            start = end;
+        }
+    }
+    ret
+}
+
+/// THIS FUNCTION IS COMMON, MODIFIED BY ARAS
+///
 /// Returns a sorted list of ranges that cover `ranges`
-fn merge_ranges(ranges: &[Range<u64>], coalesce: u64) -> Vec<Range<u64>> {
+///
+/// Ranges are not merged beyond `max_size` bytes, unless they overlap
+fn merge_ranges(ranges: &[Range<u64>], coalesce: u64, max_size: u64) -> Vec<Range<u64>> {
     if ranges.is_empty() {
         return vec![];
     }
@@ -149,20 +257,22 @@ fn merge_ranges(ranges: &[Range<u64>], coalesce: u64) -> Vec<Range<u64>> {
     let mut end_idx = 1;
 
     while start_idx != ranges.len() {
+        let start = ranges[start_idx].start;
         let mut range_end = ranges[start_idx].end;
 
         while end_idx != ranges.len()
             && ranges[end_idx]
                 .start
                 .checked_sub(range_end)
-                .map(|delta| delta <= coalesce)
+                .map(|delta| {
+                    delta <= coalesce && ranges[end_idx].end.max(range_end) - start <= max_size
+                })
                 .unwrap_or(true)
         {
             range_end = range_end.max(ranges[end_idx].end);
             end_idx += 1;
         }
 
-        let start = ranges[start_idx].start;
         let end = range_end;
         ret.push(start..end);
 
@@ -338,11 +448,27 @@ mod tests {
     ///
     /// Returns the fetched ranges
     async fn do_fetch(ranges: Vec<Range<u64>>, coalesce: u64) -> Vec<Range<u64>> {
+        let policy = RangeCoalescePolicy {
+            max_gap: coalesce,
+            ..Default::default()
+        };
+        do_fetch_opts(ranges, policy).await
+    }
+
+    /// THIS FUNCTION IS ARAS ONLY
+    ///
+    /// Calls coalesce_ranges_opts and validates the returned data is correct
+    ///
+    /// Returns the fetched ranges
+    async fn do_fetch_opts(
+        ranges: Vec<Range<u64>>,
+        policy: RangeCoalescePolicy,
+    ) -> Vec<Range<u64>> {
         let max = ranges.iter().map(|x| x.end).max().unwrap_or(0);
         let src: Vec<_> = (0..max).map(|x| x as u8).collect();
 
         let mut fetches = vec![];
-        let coalesced = coalesce_ranges::<_, Error, _>(
+        let coalesced = coalesce_ranges_opts::<_, Error, _>(
             &ranges,
             |range| {
                 fetches.push(range.clone());
@@ -350,7 +476,7 @@ mod tests {
                 let end = usize::try_from(range.end).unwrap();
                 futures::future::ready(Ok(Bytes::from(src[start..end].to_vec())))
             },
-            coalesce,
+            policy,
         )
         .await
         .unwrap();
@@ -395,6 +521,31 @@ mod tests {
         assert_eq!(fetches, vec![0..1, 6..14]);
     }
 
+    /// THIS TEST IS ARAS ONLY
+    #[tokio::test]
+    async fn test_coalesce_ranges_policy() {
+        let policy = |max_gap, max_request_size| RangeCoalescePolicy {
+            max_gap,
+            max_request_size,
+            parallelism: 2,
+        };
+
+        // Ranges are not merged beyond the maximum request size
+        let fetches = do_fetch_opts(vec![0..4, 5..8, 9..12], policy(1, 8)).await;
+        assert_eq!(fetches, vec![0..8, 9..12]);
+
+        // Unless they overlap
+        let fetches = do_fetch_opts(vec![0..4, 2..8, 7..12], policy(0, 8)).await;
+        assert_eq!(fetches, vec![0..8, 8..12]);
+
+        // Oversized ranges are split into multiple requests
+        let fetches = do_fetch_opts(vec![0..10, 3..5, 20..23], policy(1, 4)).await;
+        assert_eq!(fetches, vec![0..4, 4..8, 8..10, 20..23]);
+
+        let fetches = do_fetch_opts(vec![0..10, 11..12], policy(10, 100)).await;
+        assert_eq!(fetches, vec![0..12]);
+    }
+
     #[tokio::test]
     async fn test_coalesce_fuzz() {
         let mut rand = rng();
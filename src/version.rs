@@ -0,0 +1,71 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+//! THIS MODULE IS ARAS ONLY
+//!
+//! Object version listing and version-targeted operations
+
+use crate::path::Path;
+use crate::{GetOptions, ObjectMeta, ObjectStore, Result};
+use async_trait::async_trait;
+use futures::stream::BoxStream;
+
+/// A single version of an object returned by [`VersionedStore::list_versions`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ObjectVersion {
+    /// The metadata of this version, with [`ObjectMeta::version`] set to its version id
+    pub meta: ObjectMeta,
+    /// Whether this is the current version of the object
+    pub is_latest: bool,
+    /// Whether this version is a delete marker, recording the deletion of the object
+    ///
+    /// Delete markers have no content and a `size` of zero
+    pub is_delete_marker: bool,
+}
+
+/// An interface for stores that retain previous versions of objects
+///
+/// The version identifiers yielded by [`VersionedStore::list_versions`] can be passed
+/// to [`GetOptions::version`] to read a specific version, or to the methods below to
+/// inspect, delete or restore it
+#[async_trait]
+pub trait VersionedStore: ObjectStore {
+    /// List all versions of all objects with the given prefix, including delete markers
+    ///
+    /// Versions of the same object are returned together, most recent first
+    fn list_versions(&self, prefix: Option<&Path>) -> BoxStream<'static, Result<ObjectVersion>>;
+
+    /// Return the metadata for the specified version of the object at `location`
+    async fn head_version(&self, location: &Path, version: &str) -> Result<ObjectMeta> {
+        let options = GetOptions {
+            version: Some(version.to_string()),
+            head: true,
+            ..Default::default()
+        };
+        Ok(self.get_opts(location, options).await?.meta)
+    }
+
+    /// Permanently delete the specified version of the object at `location`
+    ///
+    /// Deleting the current version makes the previous version, if any, current
+    async fn delete_version(&self, location: &Path, version: &str) -> Result<()>;
+
+    /// Copy the specified version of the object at `from` to `to`, creating a new
+    /// current version of `to`
+    ///
+    /// Copying an old version onto its own location restores it
+    async fn copy_version(&self, from: &Path, version: &str, to: &Path) -> Result<()>;
+}